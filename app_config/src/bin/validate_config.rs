@@ -0,0 +1,49 @@
+//! Checks the config for problems, listing all of them at once.
+//!
+//! Usage: `validate-config [tracker | nightly-checks | report-generator | backend]...`
+//!
+//! The settings required by the given services are checked too. Every service is checked if none are given.
+
+use app_config::legacy_keys::find_legacy_keys;
+use app_config::validation::Service;
+use app_config::AppConfig;
+
+fn main() {
+  let services: Result<Vec<Service>, String> = std::env::args()
+    .skip(1)
+    .map(|service| service.parse())
+    .collect();
+  let services = match services {
+    Ok(services) if services.is_empty() => Service::ALL.to_vec(),
+    Ok(services) => services,
+    Err(error) => {
+      eprintln!("{error}");
+
+      std::process::exit(2);
+    }
+  };
+  let config_path = AppConfig::config_path();
+
+  println!("Validating {config_path:?} for {services:?}.");
+
+  let problems = match AppConfig::load_from_path(&config_path) {
+    Ok(config) => config.validate(&services),
+    Err(error) => std::iter::once(error.to_full_string())
+      .chain(find_legacy_keys(&config_path))
+      .collect(),
+  };
+
+  if problems.is_empty() {
+    println!("No problems found.");
+
+    return;
+  }
+
+  println!("Found {} problem(s):", problems.len());
+
+  for problem in problems {
+    println!("- {problem}");
+  }
+
+  std::process::exit(1);
+}
@@ -1,82 +1,76 @@
+use crate::cron_schedule::CronSchedule;
+use crate::donation_parser_config::DonationParserConfig;
+use crate::legacy_keys;
 use crate::log_level_wrapper::*;
+use crate::message_content_mode::MessageContentMode;
+use crate::nightly_job_config::NightlyJobConfig;
+use crate::reload::{ReloadableSettings, ReloadCallback};
 use crate::rolling_appender_rotation::*;
 use crate::secret_string::Secret;
-use schematic::{Config, ConfigLoader};
-use std::path::PathBuf;
-use std::sync::OnceLock;
+use crate::sections::*;
+use schematic::{Config, ConfigError, ConfigLoader};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, OnceLock, RwLock};
 
 const CONFIG_PATH_ENV_VAR: &str = "CONFIG_PATH";
 const DEFAULT_CONFIG_FILEPATH: &str = "./config_files/config.yml";
-const MAX_QUERIES_PER_MINUTE: usize = 12;
-const RATE_LIMIT: usize = 500;
+const DEFAULT_TOKEN_STORE_FILEPATH: &str = "./config_files/twitch_tokens.json";
+const DEFAULT_NIGHTLY_CHECKS_SCHEDULE: &str = "0 23 * * *";
 
 static APP_CONFIG: OnceLock<AppConfig> = OnceLock::new();
+/// The settings that can change while running. Kept apart from the rest of the config, which is fixed at startup.
+static RELOADABLE_SETTINGS: LazyLock<RwLock<ReloadableSettings>> =
+  LazyLock::new(|| RwLock::new(ReloadableSettings::from(AppConfig::get_or_set())));
+pub(crate) static RELOAD_CALLBACKS: Mutex<Vec<ReloadCallback>> = Mutex::new(vec![]);
 
 #[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
 pub struct AppConfig {
-  log_level: Option<LoggingConfigLevel>,
-  logging_dir: Option<PathBuf>,
-  #[setting(default = "")]
-  logging_filename_prefix: String,
-  #[setting(default = "daily")]
-  logging_roll_appender: RollingAppenderRotation,
-
-  #[setting(merge = append_vec, validate = min_length(1), validate = max_length(100), env = "TRACKED_CHANNELS")]
-  channels: Vec<String>,
-
-  #[setting(default = 0)]
-  queries_per_minute: usize,
-
-  /// Required for the main app.
-  twitch_nickname: Option<String>,
-  /// Required for the main app.
-  #[setting(env = "TWITCH_ACCESS_TOKEN")]
-  access_token: Option<Secret>,
-  /// Required for the main app.
-  #[setting(env = "TWITCH_CLIENT_ID")]
-  client_id: Option<Secret>,
-
-  #[setting(default = "root", env = "DATABASE_USERNAME")]
-  database_username: String,
-  #[setting(default = "localhost:3306", env = "DATABASE_HOST_ADDRESS")]
-  database_host_address: String,
-  #[setting(default = "twitch_tracker_db")]
-  database: String,
-
-  /// We're not dealing with sensitive data here. So configuring a default is fine.
-  #[setting(default = "password", env = "DATABASE_PASSWORD")]
-  sql_user_password: Option<Secret>,
-
-  /// Obtained from https://pastebin.com/doc_api#1
-  #[setting(env = "PASTEBIN_API_KEY")]
-  pastebin_api_key: Option<Secret>,
-
-  /// Obtained from https://app.exchangerate-api.com
-  #[setting(env = "EXCHANGE_RATE_API_KEY")]
-  exchange_rate_api_key: Option<Secret>,
+  #[setting(nested)]
+  pub(crate) logging: LoggingConfig,
+  #[setting(nested)]
+  pub(crate) twitch: TwitchConfig,
+  #[setting(nested)]
+  pub(crate) database: DatabaseConfig,
+  #[setting(nested)]
+  pub(crate) tracker: TrackerConfig,
+  #[setting(nested)]
+  pub(crate) reports: ReportConfig,
+  #[setting(nested)]
+  pub(crate) nightly_checks: NightlyChecksConfig,
+  #[setting(nested)]
+  pub(crate) backend: BackendConfig,
 }
 
 impl AppConfig {
   pub const TEST_CHANNELS: &[&str] = &["fallenshadow", "shadowchama"];
 
   fn new() -> Self {
+    let config_path = get_config_path();
+
+    match Self::load_from_path(&config_path) {
+      Ok(config) => config,
+      Err(error) => panic!(
+        "Failed to load the config at {config_path:?}. Run validate-config for details.\n{error}{}",
+        legacy_keys::describe_legacy_keys(&config_path)
+      ),
+    }
+  }
+
+  /// Loads the config at the given path along with the environment.
+  pub fn load_from_path(config_path: &Path) -> Result<Self, ConfigError> {
     let mut config = ConfigLoader::<AppConfig>::new()
-      .file_optional(get_config_path())
-      .unwrap()
-      .load()
-      .unwrap()
+      .file_optional(config_path)?
+      .load()?
       .config;
 
     if cfg!(test) || cfg!(feature = "__test_hook") {
-      config.channels = Self::TEST_CHANNELS
+      config.tracker.channels = Self::TEST_CHANNELS
         .iter()
         .map(|channel_name| channel_name.to_string())
         .collect();
     }
 
-    config //
-      .extend_channels_from_environment()
-      .set_ratelimit()
+    Ok(config.extend_channels_from_environment())
   }
 
   fn extend_channels_from_environment(mut self) -> Self {
@@ -86,91 +80,159 @@ impl AppConfig {
         .filter_map(|name| (!name.is_empty()).then_some(name.trim().to_string()))
         .collect();
 
-      self.channels.extend(additional);
+      self.tracker.channels.extend(additional);
     }
 
     self
   }
 
-  fn set_ratelimit(mut self) -> Self {
-    if self.queries_per_minute == 0 {
-      let max_queries_per_minute =
-        (RATE_LIMIT / self.channels.len().max(1)).min(MAX_QUERIES_PER_MINUTE);
-
-      self.queries_per_minute = max_queries_per_minute;
-    }
-
-    if self.channels.len() * self.queries_per_minute > RATE_LIMIT {
-      panic!("The amount of channels being queried each minute exceeds the limit of 800. channel_count * quieries_per_minute must be <= 800.");
-    }
+  fn get_or_set() -> &'static Self {
+    APP_CONFIG.get_or_init(Self::new)
+  }
 
-    self
+  pub(crate) fn reloadable_settings() -> &'static RwLock<ReloadableSettings> {
+    &RELOADABLE_SETTINGS
   }
 
-  fn get_or_set() -> &'static Self {
-    APP_CONFIG.get_or_init(Self::new)
+  pub fn config_path() -> PathBuf {
+    get_config_path()
   }
 
-  pub fn log_level() -> Option<&'static LoggingConfigLevel> {
-    Self::get_or_set().log_level.as_ref()
+  /// Can change while running if the config file is [`watched`](Self::watch_for_changes).
+  pub fn log_level() -> Option<LoggingConfigLevel> {
+    RELOADABLE_SETTINGS.read().unwrap().log_level
   }
 
   pub fn logging_dir() -> Option<&'static PathBuf> {
-    Self::get_or_set().logging_dir.as_ref()
+    Self::get_or_set().logging.dir.as_ref()
   }
 
   pub fn logging_filename_prefix() -> &'static str {
-    &Self::get_or_set().logging_filename_prefix
+    &Self::get_or_set().logging.filename_prefix
   }
 
   pub fn logging_file_roll_appender() -> &'static RollingAppenderRotation {
-    &Self::get_or_set().logging_roll_appender
+    &Self::get_or_set().logging.roll_appender
   }
 
-  pub fn channels() -> &'static Vec<String> {
-    &Self::get_or_set().channels
+  /// Can change while running if the config file is [`watched`](Self::watch_for_changes).
+  pub fn channels() -> Vec<String> {
+    RELOADABLE_SETTINGS.read().unwrap().channels.clone()
   }
 
-  pub fn queries_per_minute() -> usize {
-    Self::get_or_set().queries_per_minute
+  pub fn message_content_mode() -> MessageContentMode {
+    Self::get_or_set().tracker.message_content_mode
+  }
+
+  /// Required for the hashed message content mode.
+  pub fn message_hash_key() -> Option<&'static Secret> {
+    Self::get_or_set().tracker.message_hash_key.as_ref()
+  }
+
+  pub fn donation_parsers() -> &'static [DonationParserConfig] {
+    &Self::get_or_set().tracker.donation_parsers
   }
 
   pub fn twitch_nickname() -> &'static str {
-    Self::get_or_set().twitch_nickname.as_ref().unwrap()
+    Self::get_or_set().twitch.nickname.as_ref().unwrap()
   }
 
-  pub fn access_token() -> &'static Secret {
-    Self::get_or_set().access_token.as_ref().unwrap()
+  pub fn access_token() -> Option<&'static Secret> {
+    Self::get_or_set().twitch.access_token.as_ref()
   }
 
-  pub fn client_id() -> &'static Secret {
-    Self::get_or_set().client_id.as_ref().unwrap()
+  pub fn client_id() -> Option<&'static Secret> {
+    Self::get_or_set().twitch.client_id.as_ref()
+  }
+
+  pub fn client_secret() -> Option<&'static Secret> {
+    Self::get_or_set().twitch.client_secret.as_ref()
+  }
+
+  pub fn refresh_token() -> Option<&'static Secret> {
+    Self::get_or_set().twitch.refresh_token.as_ref()
+  }
+
+  pub fn token_store_path() -> PathBuf {
+    Self::get_or_set()
+      .twitch
+      .token_store_path
+      .clone()
+      .unwrap_or_else(|| PathBuf::from(DEFAULT_TOKEN_STORE_FILEPATH))
   }
 
   pub fn database_username() -> &'static str {
-    &Self::get_or_set().database_username
+    &Self::get_or_set().database.username
   }
 
   pub fn database_address() -> &'static str {
-    &Self::get_or_set().database_host_address
+    &Self::get_or_set().database.host_address
   }
 
   pub fn database() -> &'static str {
-    &Self::get_or_set().database
+    &Self::get_or_set().database.name
   }
 
   pub fn sql_user_password() -> &'static Secret {
-    Self::get_or_set().sql_user_password.as_ref().unwrap()
+    Self::get_or_set().database.password.as_ref().unwrap()
   }
 
   /// Obtained from https://pastebin.com/doc_api#1
   pub fn pastebin_api_key() -> Option<&'static Secret> {
-    Self::get_or_set().pastebin_api_key.as_ref()
+    Self::get_or_set().reports.pastebin_api_key.as_ref()
   }
 
   /// Obtained from https://app.exchangerate-api.com
   pub fn exchange_rate_api_key() -> Option<&'static Secret> {
-    Self::get_or_set().exchange_rate_api_key.as_ref()
+    Self::get_or_set().reports.exchange_rate_api_key.as_ref()
+  }
+
+  pub fn report_currency() -> &'static str {
+    &Self::get_or_set().reports.currency
+  }
+
+  pub fn message_contents_retention_days() -> Option<u32> {
+    Self::get_or_set().nightly_checks.message_contents_retention_days
+  }
+
+  pub fn chatter_presence_retention_days() -> Option<u32> {
+    Self::get_or_set().nightly_checks.chatter_presence_retention_days
+  }
+
+  pub fn name_check_sweep_nights() -> u32 {
+    Self::get_or_set().nightly_checks.name_check_sweep_nights
+  }
+
+  pub fn nightly_checks_schedule() -> CronSchedule {
+    Self::get_or_set()
+      .nightly_checks
+      .schedule
+      .clone()
+      .unwrap_or_else(|| DEFAULT_NIGHTLY_CHECKS_SCHEDULE.parse().unwrap())
+  }
+
+  /// Returns the settings for the nightly check job of the given name, or the defaults if it has none.
+  pub fn nightly_job(job_name: &str) -> NightlyJobConfig {
+    Self::get_or_set()
+      .nightly_checks
+      .jobs
+      .get(job_name)
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  /// The names of every nightly check job with settings in the config.
+  pub fn configured_nightly_jobs() -> impl Iterator<Item = &'static str> {
+    Self::get_or_set()
+      .nightly_checks
+      .jobs
+      .keys()
+      .map(String::as_str)
+  }
+
+  /// Required for the admin API.
+  pub fn admin_api_token() -> Option<&'static Secret> {
+    Self::get_or_set().backend.admin_api_token.as_ref()
   }
 }
 
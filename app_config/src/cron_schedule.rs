@@ -0,0 +1,300 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
+use std::str::FromStr;
+
+/// How far ahead to look for the next run before assuming the schedule never matches, such as `0 0 31 2 *`.
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+/// A cron expression in the form `minute hour day-of-month month day-of-week`, evaluated in UTC.
+///
+/// Each field takes `*`, a value, a range such as `1-5`, a step such as `*/15` or `0-30/10`, or a comma separated list
+/// of those. Days of the week go from 0 (Sunday) to 6, with 7 also being Sunday.
+///
+/// As with cron, when both the day of the month and the day of the week are restricted, matching either is enough.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+  expression: String,
+  minutes: u64,
+  hours: u64,
+  days_of_month: u64,
+  months: u64,
+  days_of_week: u64,
+  day_of_month_restricted: bool,
+  day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+  /// Returns the first time after the given one that matches the schedule, to the minute.
+  ///
+  /// None is returned if the schedule never matches.
+  pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let search_limit = after + Duration::days(MAX_SEARCH_DAYS);
+    let mut time = after
+      .with_second(0)
+      .and_then(|time| time.with_nanosecond(0))?
+      + Duration::minutes(1);
+
+    while time <= search_limit {
+      if !contains(self.months, time.month()) {
+        let (year, month) = match time.month() {
+          12 => (time.year() + 1, 1),
+          month => (time.year(), month + 1),
+        };
+
+        time = NaiveDate::from_ymd_opt(year, month, 1)?
+          .and_hms_opt(0, 0, 0)?
+          .and_utc();
+      } else if !self.matches_day(time) {
+        time = (time.date_naive() + Duration::days(1))
+          .and_hms_opt(0, 0, 0)?
+          .and_utc();
+      } else if !contains(self.hours, time.hour()) {
+        time = time.with_minute(0)? + Duration::hours(1);
+      } else if !contains(self.minutes, time.minute()) {
+        time += Duration::minutes(1);
+      } else {
+        return Some(time);
+      }
+    }
+
+    None
+  }
+
+  fn matches_day(&self, time: DateTime<Utc>) -> bool {
+    let day_of_month_matches = contains(self.days_of_month, time.day());
+    let day_of_week_matches = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+
+    if self.day_of_month_restricted && self.day_of_week_restricted {
+      day_of_month_matches || day_of_week_matches
+    } else {
+      day_of_month_matches && day_of_week_matches
+    }
+  }
+}
+
+impl FromStr for CronSchedule {
+  type Err = String;
+
+  fn from_str(expression: &str) -> Result<Self, Self::Err> {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+
+    let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+      return Err(format!(
+        "`{expression}` isn't a cron expression. Expected 5 fields: minute hour day-of-month month day-of-week."
+      ));
+    };
+    let mut days_of_week_values = parse_field(days_of_week, 0, 7, "day of week")?;
+
+    // Both 0 and 7 are Sunday.
+    if contains(days_of_week_values, 7) {
+      days_of_week_values |= 1;
+    }
+
+    Ok(Self {
+      expression: fields.join(" "),
+      minutes: parse_field(minutes, 0, 59, "minute")?,
+      hours: parse_field(hours, 0, 23, "hour")?,
+      days_of_month: parse_field(days_of_month, 1, 31, "day of month")?,
+      months: parse_field(months, 1, 12, "month")?,
+      days_of_week: days_of_week_values,
+      day_of_month_restricted: !days_of_month.starts_with('*'),
+      day_of_week_restricted: !days_of_week.starts_with('*'),
+    })
+  }
+}
+
+impl TryFrom<String> for CronSchedule {
+  type Error = String;
+
+  fn try_from(expression: String) -> Result<Self, Self::Error> {
+    expression.parse()
+  }
+}
+
+impl From<CronSchedule> for String {
+  fn from(schedule: CronSchedule) -> Self {
+    schedule.expression
+  }
+}
+
+impl std::fmt::Display for CronSchedule {
+  fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(formatter, "{}", self.expression)
+  }
+}
+
+/// Parses a field into a bit set of the values it matches.
+fn parse_field(field: &str, min: u32, max: u32, field_name: &str) -> Result<u64, String> {
+  let mut values = 0;
+
+  for part in field.split(',') {
+    let (range, step) = match part.split_once('/') {
+      Some((range, step)) => {
+        let step = step
+          .parse::<u32>()
+          .ok()
+          .filter(|step| *step > 0)
+          .ok_or_else(|| format!("`{part}` has an invalid step for the {field_name} field."))?;
+
+        (range, step)
+      }
+      None => (part, 1),
+    };
+    let parse_value = |value: &str| {
+      value
+        .parse::<u32>()
+        .ok()
+        .filter(|value| (min..=max).contains(value))
+        .ok_or_else(|| {
+          format!("`{value}` isn't a valid {field_name}. Expected a value from {min} to {max}.")
+        })
+    };
+    let (start, end) = match range {
+      "*" => (min, max),
+      range => match range.split_once('-') {
+        Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+        // A single value with a step, such as `5/15`, runs from that value to the end of the range.
+        None if step > 1 => (parse_value(range)?, max),
+        None => {
+          let value = parse_value(range)?;
+
+          (value, value)
+        }
+      },
+    };
+
+    if start > end {
+      return Err(format!(
+        "`{part}` has a range that ends before it starts for the {field_name} field."
+      ));
+    }
+
+    for value in (start..=end).step_by(step as usize) {
+      values |= 1 << value;
+    }
+  }
+
+  Ok(values)
+}
+
+fn contains(values: u64, value: u32) -> bool {
+  values & (1 << value) != 0
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc
+      .with_ymd_and_hms(year, month, day, hour, minute, 0)
+      .unwrap()
+  }
+
+  fn next_after(expression: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    expression
+      .parse::<CronSchedule>()
+      .unwrap()
+      .next_after(after)
+  }
+
+  #[test]
+  fn steps_ranges_and_lists_are_parsed() {
+    assert_eq!(
+      parse_field("*/15", 0, 59, "minute"),
+      Ok(1 | 1 << 15 | 1 << 30 | 1 << 45)
+    );
+    assert_eq!(
+      parse_field("10-30/10", 0, 59, "minute"),
+      Ok(1 << 10 | 1 << 20 | 1 << 30)
+    );
+    assert_eq!(
+      parse_field("1-3", 1, 12, "month"),
+      Ok(1 << 1 | 1 << 2 | 1 << 3)
+    );
+    assert_eq!(
+      parse_field("5,7,9", 0, 23, "hour"),
+      Ok(1 << 5 | 1 << 7 | 1 << 9)
+    );
+    assert_eq!(parse_field("50/5", 0, 59, "minute"), Ok(1 << 50 | 1 << 55));
+    assert!(parse_field("30-10", 0, 59, "minute").is_err());
+    assert!(parse_field("*/0", 0, 59, "minute").is_err());
+    assert!(parse_field("60", 0, 59, "minute").is_err());
+    assert!("0 23 * *".parse::<CronSchedule>().is_err());
+  }
+
+  #[test]
+  fn next_run_follows_steps_and_lists() {
+    assert_eq!(
+      next_after("*/15 * * * *", utc(2026, 10, 19, 10, 16)),
+      Some(utc(2026, 10, 19, 10, 30))
+    );
+    assert_eq!(
+      next_after("0 6,18 * * *", utc(2026, 10, 19, 6, 0)),
+      Some(utc(2026, 10, 19, 18, 0))
+    );
+  }
+
+  #[test]
+  fn seven_is_sunday() {
+    // 2026-10-19 is a Monday.
+    let monday = utc(2026, 10, 19, 12, 0);
+
+    assert_eq!(
+      next_after("0 0 * * 7", monday),
+      Some(utc(2026, 10, 25, 0, 0))
+    );
+    assert_eq!(
+      next_after("0 0 * * 0", monday),
+      Some(utc(2026, 10, 25, 0, 0))
+    );
+    assert_eq!(
+      next_after("0 0 * * 6-7", monday),
+      Some(utc(2026, 10, 24, 0, 0))
+    );
+  }
+
+  #[test]
+  fn restricted_day_of_month_and_week_match_either() {
+    // The 1st of the month or any Friday. 2026-10-23 is the first Friday after the 19th.
+    assert_eq!(
+      next_after("0 0 1 * 5", utc(2026, 10, 19, 12, 0)),
+      Some(utc(2026, 10, 23, 0, 0))
+    );
+    assert_eq!(
+      next_after("0 0 1 * 5", utc(2026, 10, 30, 12, 0)),
+      Some(utc(2026, 11, 1, 0, 0))
+    );
+    // With an unrestricted day of the week, only the day of the month counts.
+    assert_eq!(
+      next_after("0 0 1 * *", utc(2026, 10, 19, 12, 0)),
+      Some(utc(2026, 11, 1, 0, 0))
+    );
+  }
+
+  #[test]
+  fn next_run_rolls_over_days_months_and_years() {
+    assert_eq!(
+      next_after("30 1 * * *", utc(2026, 10, 19, 23, 59)),
+      Some(utc(2026, 10, 20, 1, 30))
+    );
+    assert_eq!(
+      next_after("0 0 31 * *", utc(2026, 9, 1, 0, 0)),
+      Some(utc(2026, 10, 31, 0, 0))
+    );
+    assert_eq!(
+      next_after("0 0 1 1 *", utc(2026, 12, 31, 23, 59)),
+      Some(utc(2027, 1, 1, 0, 0))
+    );
+    assert_eq!(
+      next_after("0 0 29 2 *", utc(2026, 3, 1, 0, 0)),
+      Some(utc(2028, 2, 29, 0, 0))
+    );
+  }
+
+  #[test]
+  fn impossible_dates_never_match() {
+    assert_eq!(next_after("0 0 31 2 *", utc(2026, 10, 19, 0, 0)), None);
+  }
+}
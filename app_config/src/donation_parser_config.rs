@@ -0,0 +1,22 @@
+/// Describes how to read the donation messages a bot posts in chat.
+///
+/// Either `pattern` or `template` has to be set. If both are, `pattern` is used.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DonationParserConfig {
+  /// The channels the bot posts donations in. Applies to every tracked channel if empty.
+  #[serde(default)]
+  pub channels: Vec<String>,
+  /// The login name of the bot that posts the donation messages.
+  pub bot_login: String,
+  /// A regex with the named captures `name` and `amount`, and optionally `currency` and `message`.
+  pub pattern: Option<String>,
+  /// A message template such as `{name} donated {currency}{amount}: {message}`.
+  ///
+  /// Everything outside of the placeholders has to match exactly.
+  pub template: Option<String>,
+  /// The ISO 4217 code used when a message doesn't contain a currency.
+  ///
+  /// Required if the pattern or template has no `currency` capture.
+  pub default_currency: Option<String>,
+}
@@ -0,0 +1,117 @@
+//! Finds the flat keys used before the config was split into a section per service, so that an old config fails
+//! with where each of its keys moved rather than just an unknown field.
+
+use std::path::Path;
+
+/// Each flat key and the key that replaced it.
+const LEGACY_KEYS: &[(&str, &str)] = &[
+  ("logLevel", "logging.level"),
+  ("loggingDir", "logging.dir"),
+  ("loggingFilenamePrefix", "logging.filenamePrefix"),
+  ("loggingRollAppender", "logging.rollAppender"),
+  ("channels", "tracker.channels"),
+  ("twitchNickname", "twitch.nickname"),
+  ("accessToken", "twitch.accessToken"),
+  ("clientId", "twitch.clientId"),
+  ("databaseUsername", "database.username"),
+  ("databaseHostAddress", "database.hostAddress"),
+  ("sqlUserPassword", "database.password"),
+  ("pastebinApiKey", "reports.pastebinApiKey"),
+  ("exchangeRateApiKey", "reports.exchangeRateApiKey"),
+];
+
+/// Returns where each flat key in the config file moved to. Empty if the file can't be read.
+pub fn find_legacy_keys(config_path: &Path) -> Vec<String> {
+  std::fs::read_to_string(config_path)
+    .map(|config_contents| legacy_keys_in(&config_contents))
+    .unwrap_or_default()
+}
+
+/// Lists the flat keys in the config file, to be appended to an error from loading it.
+pub(crate) fn describe_legacy_keys(config_path: &Path) -> String {
+  let legacy_keys = find_legacy_keys(config_path);
+
+  if legacy_keys.is_empty() {
+    return String::new();
+  }
+
+  let key_list: Vec<String> = legacy_keys
+    .iter()
+    .map(|legacy_key| format!("- {legacy_key}"))
+    .collect();
+
+  format!(
+    "\nThe config uses keys from before it was split into sections:\n{}",
+    key_list.join("\n")
+  )
+}
+
+fn legacy_keys_in(config_contents: &str) -> Vec<String> {
+  config_contents
+    .lines()
+    // Only top level keys can be flat ones, and those aren't indented.
+    .filter(|line| !line.starts_with(char::is_whitespace) && !line.starts_with('#'))
+    .filter_map(|line| {
+      let (key, value) = line.split_once(':')?;
+      let key = key.trim().trim_matches(['"', '\'']);
+      let value = value.split('#').next().unwrap_or_default().trim();
+
+      // `database` is still a key, but it's now a section rather than the database's name.
+      if key == "database" && !value.is_empty() {
+        return Some("`database` has moved to `database.name`.".to_string());
+      }
+
+      if key == "queriesPerMinute" {
+        return Some("`queriesPerMinute` is no longer used and can be removed.".to_string());
+      }
+
+      let (_, new_key) = LEGACY_KEYS
+        .iter()
+        .find(|(legacy_key, _)| *legacy_key == key)?;
+
+      Some(format!("`{key}` has moved to `{new_key}`."))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn flat_keys_are_mapped_to_their_sections() {
+    let config_contents = "\
+# An old config
+twitchNickname: moose
+accessToken: token
+database: twitch_tracker_db
+channels: [\"fallenshadow\"]
+queriesPerMinute: 12
+";
+
+    assert_eq!(
+      legacy_keys_in(config_contents),
+      vec![
+        "`twitchNickname` has moved to `twitch.nickname`.",
+        "`accessToken` has moved to `twitch.accessToken`.",
+        "`database` has moved to `database.name`.",
+        "`channels` has moved to `tracker.channels`.",
+        "`queriesPerMinute` is no longer used and can be removed.",
+      ]
+    );
+  }
+
+  #[test]
+  fn sectioned_keys_are_not_reported() {
+    let config_contents = "\
+twitch:
+  accessToken: token
+database: # The database section
+  name: twitch_tracker_db
+tracker:
+  channels: [\"fallenshadow\"]
+";
+
+    assert!(legacy_keys_in(config_contents).is_empty());
+  }
+}
@@ -1,6 +1,14 @@
 pub mod config;
+pub mod cron_schedule;
+pub mod donation_parser_config;
+pub mod legacy_keys;
 pub mod log_level_wrapper;
+pub mod message_content_mode;
+pub mod nightly_job_config;
+pub mod reload;
 pub mod rolling_appender_rotation;
 pub mod secret_string;
+pub mod sections;
+pub mod validation;
 
 pub use crate::config::AppConfig;
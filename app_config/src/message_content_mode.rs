@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+/// How the text of chat messages is stored.
+///
+/// Word counts, lengths, and emote usage are stored in every mode, so chat statistics work regardless of this setting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum MessageContentMode {
+  /// Message contents are stored as they were sent.
+  #[default]
+  Raw,
+  /// Message contents are replaced with an HMAC-SHA256 of them keyed with `tracker.messageHashKey`, so duplicate
+  /// messages can still be matched without the contents being recoverable by hashing guesses.
+  Hashed,
+  /// Message contents aren't stored.
+  Redacted,
+}
+
+/// A message content mode that isn't one of the known ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMessageContentMode(String);
+
+impl std::fmt::Display for UnknownMessageContentMode {
+  fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      formatter,
+      "Unknown message content mode `{}`. Expected `raw`, `hashed`, or `redacted`.",
+      self.0
+    )
+  }
+}
+
+impl std::error::Error for UnknownMessageContentMode {}
+
+impl TryFrom<&str> for MessageContentMode {
+  type Error = UnknownMessageContentMode;
+
+  fn try_from(mode_value: &str) -> Result<Self, Self::Error> {
+    match mode_value.to_lowercase().trim() {
+      "raw" => Ok(Self::Raw),
+      "hash" | "hashed" => Ok(Self::Hashed),
+      "redact" | "redacted" | "none" => Ok(Self::Redacted),
+      _ => Err(UnknownMessageContentMode(mode_value.to_string())),
+    }
+  }
+}
+
+impl TryFrom<String> for MessageContentMode {
+  type Error = UnknownMessageContentMode;
+
+  fn try_from(mode_value: String) -> Result<Self, Self::Error> {
+    Self::try_from(mode_value.as_str())
+  }
+}
+
+impl FromStr for MessageContentMode {
+  type Err = UnknownMessageContentMode;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::try_from(s)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn message_content_mode_from_str_expected_value() {
+    assert_eq!("raw".parse(), Ok(MessageContentMode::Raw));
+    assert_eq!(" Hashed".parse(), Ok(MessageContentMode::Hashed));
+    assert_eq!("none".parse(), Ok(MessageContentMode::Redacted));
+    assert!("encrypted".parse::<MessageContentMode>().is_err());
+  }
+
+  #[test]
+  fn unknown_message_content_modes_fail_to_deserialize() {
+    let mode: Result<MessageContentMode, _> = serde_json::from_str("\"hash\"");
+    let unknown_mode: Result<MessageContentMode, _> = serde_json::from_str("\"encrypted\"");
+
+    assert_eq!(mode.unwrap(), MessageContentMode::Hashed);
+    assert!(unknown_mode.is_err());
+  }
+}
@@ -0,0 +1,35 @@
+use crate::cron_schedule::CronSchedule;
+
+/// The settings for one of the nightly check jobs. Jobs without settings are enabled with the defaults.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightlyJobConfig {
+  /// Disabled jobs are skipped when running every job or on the schedule, but can still be run by name.
+  #[serde(default = "enabled_by_default")]
+  pub enabled: bool,
+  /// Replaces `nightlyChecks.schedule` for this job.
+  pub schedule: Option<CronSchedule>,
+  /// How many items are sent to Helix at once, from 1 to 100. Used by `update-changed-names`.
+  pub batch_size: Option<usize>,
+  /// How many days back to look. Used by `update-vod-data`.
+  pub max_age_days: Option<usize>,
+  /// Whether problems that are found get fixed, rather than only reported. Used by `check-data-integrity`.
+  #[serde(default)]
+  pub repair: bool,
+}
+
+impl Default for NightlyJobConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      schedule: None,
+      batch_size: None,
+      max_age_days: None,
+      repair: false,
+    }
+  }
+}
+
+fn enabled_by_default() -> bool {
+  true
+}
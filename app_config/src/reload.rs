@@ -0,0 +1,159 @@
+use crate::config::{AppConfig, RELOAD_CALLBACKS};
+use crate::log_level_wrapper::LoggingConfigLevel;
+use std::path::Path;
+use std::sync::Once;
+use std::time::{Duration, SystemTime};
+
+/// How often the config file is checked for changes.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub(crate) type ReloadCallback = Box<dyn Fn(&ReloadableSettings) + Send + Sync>;
+
+static WATCH_CONFIG_FILE: Once = Once::new();
+
+/// The settings that are safe to change without a restart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReloadableSettings {
+  pub log_level: Option<LoggingConfigLevel>,
+  pub channels: Vec<String>,
+}
+
+impl From<&AppConfig> for ReloadableSettings {
+  fn from(config: &AppConfig) -> Self {
+    Self {
+      log_level: config.logging.level,
+      channels: config.tracker.channels.clone(),
+    }
+  }
+}
+
+impl ReloadableSettings {
+  /// Names the settings that differ in the new settings, as they're named in the config file.
+  pub fn changed_settings(&self, new_settings: &Self) -> Vec<&'static str> {
+    [
+      ("logging.level", self.log_level != new_settings.log_level),
+      ("tracker.channels", self.channels != new_settings.channels),
+    ]
+    .into_iter()
+    .filter_map(|(setting, has_changed)| has_changed.then_some(setting))
+    .collect()
+  }
+}
+
+impl AppConfig {
+  /// Runs the callback with the new settings whenever the [`reloadable settings`](ReloadableSettings) change.
+  ///
+  /// Changes are only picked up once the config file is [`watched`](Self::watch_for_changes).
+  pub fn on_reload<F>(callback: F)
+  where
+    F: Fn(&ReloadableSettings) + Send + Sync + 'static,
+  {
+    RELOAD_CALLBACKS.lock().unwrap().push(Box::new(callback));
+  }
+
+  /// Starts checking the config file for changes in the background, applying the ones to the
+  /// [`reloadable settings`](ReloadableSettings). Does nothing if already called.
+  ///
+  /// A config with problems is ignored until they're fixed. Changes to any other setting need a restart.
+  pub fn watch_for_changes() {
+    WATCH_CONFIG_FILE.call_once(|| {
+      let config_path = Self::config_path();
+      let mut last_modified = get_last_modified(&config_path);
+
+      tracing::info!("Watching {config_path:?} for config changes.");
+
+      std::thread::spawn(move || loop {
+        std::thread::sleep(CONFIG_POLL_INTERVAL);
+
+        let modified = get_last_modified(&config_path);
+
+        if modified == last_modified {
+          continue;
+        }
+
+        last_modified = modified;
+
+        Self::reload(&config_path);
+      });
+    });
+  }
+
+  fn reload(config_path: &Path) {
+    let config = match Self::load_from_path(config_path) {
+      Ok(config) => config,
+      Err(error) => {
+        tracing::error!("Ignoring the changed config as it failed to load. Reason: {error}");
+
+        return;
+      }
+    };
+    let problems = config.validate(&[]);
+
+    if !problems.is_empty() {
+      tracing::error!("Ignoring the changed config as it has problems: {problems:#?}");
+
+      return;
+    }
+
+    let new_settings = ReloadableSettings::from(&config);
+    let mut settings = Self::reloadable_settings().write().unwrap();
+
+    let changed_settings = settings.changed_settings(&new_settings);
+
+    if changed_settings.is_empty() {
+      tracing::info!("The config changed, but none of the settings that can be reloaded did. Restart to apply it.");
+
+      return;
+    }
+
+    tracing::info!(
+      "Reloading {:?} from the config. {:?} -> {:?}",
+      changed_settings,
+      *settings,
+      new_settings
+    );
+
+    *settings = new_settings.clone();
+    drop(settings);
+
+    for callback in RELOAD_CALLBACKS.lock().unwrap().iter() {
+      callback(&new_settings);
+    }
+  }
+}
+
+fn get_last_modified(config_path: &Path) -> Option<SystemTime> {
+  std::fs::metadata(config_path)
+    .and_then(|metadata| metadata.modified())
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn only_changed_settings_are_named() {
+    let settings = ReloadableSettings {
+      log_level: Some(LoggingConfigLevel::Info),
+      channels: vec!["fallenshadow".into()],
+    };
+    let new_settings = ReloadableSettings {
+      channels: vec!["fallenshadow".into(), "shadowchama".into()],
+      ..settings.clone()
+    };
+
+    assert!(settings.changed_settings(&settings.clone()).is_empty());
+    assert_eq!(
+      settings.changed_settings(&new_settings),
+      vec!["tracker.channels"]
+    );
+    assert_eq!(
+      settings.changed_settings(&ReloadableSettings {
+        log_level: None,
+        ..new_settings
+      }),
+      vec!["logging.level", "tracker.channels"]
+    );
+  }
+}
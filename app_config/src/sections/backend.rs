@@ -0,0 +1,9 @@
+use crate::secret_string::Secret;
+use schematic::Config;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct BackendConfig {
+  /// Required for the admin API. The admin routes reject every request if this isn't set.
+  #[setting(env = "ADMIN_API_TOKEN")]
+  pub(crate) admin_api_token: Option<Secret>,
+}
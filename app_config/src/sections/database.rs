@@ -0,0 +1,15 @@
+use crate::secret_string::Secret;
+use schematic::Config;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct DatabaseConfig {
+  #[setting(default = "root", env = "DATABASE_USERNAME")]
+  pub(crate) username: String,
+  #[setting(default = "localhost:3306", env = "DATABASE_HOST_ADDRESS")]
+  pub(crate) host_address: String,
+  #[setting(default = "twitch_tracker_db")]
+  pub(crate) name: String,
+  /// We're not dealing with sensitive data here. So configuring a default is fine.
+  #[setting(default = "password", env = "DATABASE_PASSWORD")]
+  pub(crate) password: Option<Secret>,
+}
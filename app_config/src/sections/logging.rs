@@ -0,0 +1,16 @@
+use crate::log_level_wrapper::*;
+use crate::rolling_appender_rotation::*;
+use schematic::Config;
+use std::path::PathBuf;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct LoggingConfig {
+  /// Logging is disabled if not set. Can be changed without a restart.
+  pub(crate) level: Option<LoggingConfigLevel>,
+  /// Logs are written to stdout if not set.
+  pub(crate) dir: Option<PathBuf>,
+  #[setting(default = "")]
+  pub(crate) filename_prefix: String,
+  #[setting(default = "daily")]
+  pub(crate) roll_appender: RollingAppenderRotation,
+}
@@ -0,0 +1,17 @@
+//! The config is split into a section per service, so each only needs to look at its own settings.
+
+pub mod backend;
+pub mod database;
+pub mod logging;
+pub mod nightly_checks;
+pub mod reports;
+pub mod tracker;
+pub mod twitch;
+
+pub use backend::{BackendConfig, PartialBackendConfig};
+pub use database::{DatabaseConfig, PartialDatabaseConfig};
+pub use logging::{LoggingConfig, PartialLoggingConfig};
+pub use nightly_checks::{NightlyChecksConfig, PartialNightlyChecksConfig};
+pub use reports::{PartialReportConfig, ReportConfig};
+pub use tracker::{PartialTrackerConfig, TrackerConfig};
+pub use twitch::{PartialTwitchConfig, TwitchConfig};
@@ -0,0 +1,20 @@
+use crate::cron_schedule::CronSchedule;
+use crate::nightly_job_config::NightlyJobConfig;
+use schematic::Config;
+use std::collections::HashMap;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct NightlyChecksConfig {
+  /// Message contents older than this many days are cleared. Kept forever if not set.
+  pub(crate) message_contents_retention_days: Option<u32>,
+  /// Chatter join and part records older than this many days are deleted. Kept forever if not set.
+  pub(crate) chatter_presence_retention_days: Option<u32>,
+  /// Every user is verified with Helix over this many nights. Recently active users are verified every night on top
+  /// of that.
+  #[setting(default = 7, env = "NAME_CHECK_SWEEP_NIGHTS")]
+  pub(crate) name_check_sweep_nights: u32,
+  /// When the jobs run while the nightly checks are running on a schedule. Defaults to 23:00 UTC every day.
+  pub(crate) schedule: Option<CronSchedule>,
+  /// Settings for individual jobs, keyed by the job name.
+  pub(crate) jobs: HashMap<String, NightlyJobConfig>,
+}
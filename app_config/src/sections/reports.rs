@@ -0,0 +1,16 @@
+use crate::secret_string::Secret;
+use schematic::Config;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct ReportConfig {
+  /// Obtained from https://pastebin.com/doc_api#1. Required for the report generator.
+  #[setting(env = "PASTEBIN_API_KEY")]
+  pub(crate) pastebin_api_key: Option<Secret>,
+
+  /// Obtained from https://app.exchangerate-api.com
+  #[setting(env = "EXCHANGE_RATE_API_KEY")]
+  pub(crate) exchange_rate_api_key: Option<Secret>,
+  /// The ISO 4217 code of the currency that donations are converted to in reports.
+  #[setting(default = "GBP", env = "REPORT_CURRENCY")]
+  pub(crate) currency: String,
+}
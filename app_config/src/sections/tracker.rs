@@ -0,0 +1,26 @@
+use crate::donation_parser_config::DonationParserConfig;
+use crate::message_content_mode::MessageContentMode;
+use crate::secret_string::Secret;
+use schematic::Config;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct TrackerConfig {
+  /// Can be changed without a restart.
+  #[setting(merge = append_vec, validate = min_length(1), validate = max_length(100), env = "TRACKED_CHANNELS")]
+  pub(crate) channels: Vec<String>,
+
+  /// No longer used, as nothing queries the tracked channels on a timer. Still accepted so that existing configs load.
+  #[setting(default = 0)]
+  pub(crate) queries_per_minute: usize,
+
+  /// Whether chat message text is stored as is, hashed, or not at all.
+  #[setting(default = "raw", env = "MESSAGE_CONTENT_MODE")]
+  pub(crate) message_content_mode: MessageContentMode,
+
+  /// The key message contents are hashed with. Required for the hashed message content mode.
+  #[setting(env = "MESSAGE_HASH_KEY")]
+  pub(crate) message_hash_key: Option<Secret>,
+
+  /// Bots whose chat messages are tracked as donations, on top of the built in Streamlabs parser.
+  pub(crate) donation_parsers: Vec<DonationParserConfig>,
+}
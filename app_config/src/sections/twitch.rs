@@ -0,0 +1,24 @@
+use crate::secret_string::Secret;
+use schematic::Config;
+use std::path::PathBuf;
+
+#[derive(Debug, Config, serde::Serialize, serde::Deserialize)]
+pub struct TwitchConfig {
+  /// Required for the tracker.
+  pub(crate) nickname: Option<String>,
+  /// Required for the tracker and nightly checks.
+  #[setting(env = "TWITCH_ACCESS_TOKEN")]
+  pub(crate) access_token: Option<Secret>,
+  /// Required for the tracker and nightly checks.
+  #[setting(env = "TWITCH_CLIENT_ID")]
+  pub(crate) client_id: Option<Secret>,
+  /// Used to get new access tokens when the current one is rejected.
+  #[setting(env = "TWITCH_CLIENT_SECRET")]
+  pub(crate) client_secret: Option<Secret>,
+  /// Used to refresh the access token. Without one, expired tokens are replaced with app access tokens.
+  #[setting(env = "TWITCH_REFRESH_TOKEN")]
+  pub(crate) refresh_token: Option<Secret>,
+  /// Where refreshed tokens are written, so they outlive restarts. Tokens in this file take priority over the ones above.
+  #[setting(env = "TWITCH_TOKEN_STORE_PATH")]
+  pub(crate) token_store_path: Option<PathBuf>,
+}
@@ -0,0 +1,243 @@
+use crate::config::AppConfig;
+use crate::message_content_mode::MessageContentMode;
+use std::str::FromStr;
+
+/// The most items Helix takes in one request.
+const MAX_HELIX_BATCH_SIZE: usize = 100;
+
+/// The apps that can be checked for their required settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+  Tracker,
+  NightlyChecks,
+  ReportGenerator,
+  Backend,
+}
+
+impl Service {
+  pub const ALL: &[Service] = &[
+    Self::Tracker,
+    Self::NightlyChecks,
+    Self::ReportGenerator,
+    Self::Backend,
+  ];
+
+  /// The settings the service can't run without, as they're named in the config file.
+  fn required_settings(&self, config: &AppConfig) -> Vec<(&'static str, bool)> {
+    let twitch = &config.twitch;
+    let helix_credentials = [
+      ("twitch.accessToken", twitch.access_token.is_some()),
+      ("twitch.clientId", twitch.client_id.is_some()),
+    ];
+
+    match self {
+      Self::Tracker => [("twitch.nickname", twitch.nickname.is_some())]
+        .into_iter()
+        .chain(helix_credentials)
+        .collect(),
+      Self::NightlyChecks => helix_credentials.to_vec(),
+      Self::ReportGenerator => vec![(
+        "reports.pastebinApiKey",
+        config.reports.pastebin_api_key.is_some(),
+      )],
+      Self::Backend => vec![(
+        "backend.adminApiToken",
+        config.backend.admin_api_token.is_some(),
+      )],
+    }
+  }
+}
+
+impl FromStr for Service {
+  type Err = String;
+
+  fn from_str(service: &str) -> Result<Self, Self::Err> {
+    match service {
+      "tracker" => Ok(Self::Tracker),
+      "nightly-checks" => Ok(Self::NightlyChecks),
+      "report-generator" => Ok(Self::ReportGenerator),
+      "backend" => Ok(Self::Backend),
+      _ => Err(format!(
+        "Unknown service `{service}`. Expected one of tracker, nightly-checks, report-generator, or backend."
+      )),
+    }
+  }
+}
+
+impl AppConfig {
+  /// Returns every problem with the config, including settings missing for the given services.
+  pub fn validate(&self, services: &[Service]) -> Vec<String> {
+    let mut problems = vec![];
+
+    if self.tracker.message_content_mode == MessageContentMode::Hashed
+      && self.tracker.message_hash_key.is_none()
+    {
+      problems.push(
+        "`tracker.messageHashKey` is required when `tracker.messageContentMode` is `hashed`."
+          .to_string(),
+      );
+    }
+
+    for (index, donation_parser) in self.tracker.donation_parsers.iter().enumerate() {
+      if donation_parser.pattern.is_none() && donation_parser.template.is_none() {
+        problems.push(format!(
+          "`tracker.donationParsers[{index}]` for `{}` needs either a `pattern` or a `template`.",
+          donation_parser.bot_login
+        ));
+      }
+    }
+
+    let currency = &self.reports.currency;
+
+    if currency.len() != 3
+      || !currency
+        .chars()
+        .all(|character| character.is_ascii_uppercase())
+    {
+      problems.push(format!(
+        "`reports.currency` must be an ISO 4217 code such as GBP. Got `{currency}`."
+      ));
+    }
+
+    for (job_name, job_config) in &self.nightly_checks.jobs {
+      if let Some(batch_size) = job_config.batch_size {
+        if !(1..=MAX_HELIX_BATCH_SIZE).contains(&batch_size) {
+          problems.push(format!(
+            "`nightlyChecks.jobs.{job_name}.batchSize` must be from 1 to {MAX_HELIX_BATCH_SIZE}. Got {batch_size}."
+          ));
+        }
+      }
+    }
+
+    if self.nightly_checks.name_check_sweep_nights == 0 {
+      problems.push("`nightlyChecks.nameCheckSweepNights` must be at least 1.".to_string());
+    }
+
+    if let Some(logging_dir) = &self.logging.dir {
+      if logging_dir.exists() && !logging_dir.is_dir() {
+        problems.push(format!("`logging.dir` {logging_dir:?} isn't a directory."));
+      }
+    }
+
+    for service in services {
+      for (setting, is_set) in service.required_settings(self) {
+        if !is_set {
+          problems.push(format!(
+            "`{setting}` is required for the {service:?} service."
+          ));
+        }
+      }
+    }
+
+    problems
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::nightly_job_config::NightlyJobConfig;
+  use crate::secret_string::Secret;
+
+  fn valid_config() -> AppConfig {
+    let mut config = AppConfig::default();
+    config.tracker.channels = vec!["fallenshadow".into()];
+    config.reports.currency = "GBP".into();
+    config.nightly_checks.name_check_sweep_nights = 7;
+
+    config
+  }
+
+  #[test]
+  fn valid_config_has_no_problems() {
+    assert_eq!(valid_config().validate(&[]), Vec::<String>::new());
+  }
+
+  #[test]
+  fn currency_must_be_an_iso_code() {
+    for currency in ["gbp", "GB", "POUND"] {
+      let mut config = valid_config();
+      config.reports.currency = currency.into();
+
+      assert_eq!(
+        config.validate(&[]),
+        vec![format!(
+          "`reports.currency` must be an ISO 4217 code such as GBP. Got `{currency}`."
+        )]
+      );
+    }
+  }
+
+  #[test]
+  fn zero_sweep_nights_are_rejected() {
+    let mut config = valid_config();
+    config.nightly_checks.name_check_sweep_nights = 0;
+
+    assert_eq!(
+      config.validate(&[]),
+      vec!["`nightlyChecks.nameCheckSweepNights` must be at least 1."]
+    );
+  }
+
+  #[test]
+  fn batch_sizes_must_fit_in_a_helix_request() {
+    let mut config = valid_config();
+
+    for batch_size in [0, 1, 100, 101] {
+      config.nightly_checks.jobs.insert(
+        format!("job-{batch_size}"),
+        NightlyJobConfig {
+          batch_size: Some(batch_size),
+          ..Default::default()
+        },
+      );
+    }
+
+    let mut problems = config.validate(&[]);
+    problems.sort();
+
+    assert_eq!(
+      problems,
+      vec![
+        "`nightlyChecks.jobs.job-0.batchSize` must be from 1 to 100. Got 0.",
+        "`nightlyChecks.jobs.job-101.batchSize` must be from 1 to 100. Got 101.",
+      ]
+    );
+  }
+
+  #[test]
+  fn hashed_message_contents_need_a_key() {
+    let mut config = valid_config();
+    config.tracker.message_content_mode = MessageContentMode::Hashed;
+
+    assert_eq!(config.validate(&[]).len(), 1);
+
+    config.tracker.message_hash_key = Some(Secret::default());
+
+    assert!(config.validate(&[]).is_empty());
+  }
+
+  #[test]
+  fn missing_settings_are_only_reported_for_the_given_services() {
+    let mut config = valid_config();
+    config.twitch.access_token = Some(Secret::default());
+
+    assert_eq!(
+      config.validate(&[Service::NightlyChecks, Service::Backend]),
+      vec![
+        "`twitch.clientId` is required for the NightlyChecks service.",
+        "`backend.adminApiToken` is required for the Backend service.",
+      ]
+    );
+    assert!(config.validate(&[]).is_empty());
+  }
+
+  #[test]
+  fn services_are_parsed_from_their_names() {
+    assert_eq!("tracker".parse(), Ok(Service::Tracker));
+    assert_eq!("nightly-checks".parse(), Ok(Service::NightlyChecks));
+    assert_eq!("report-generator".parse(), Ok(Service::ReportGenerator));
+    assert_eq!("backend".parse(), Ok(Service::Backend));
+    assert!("nightly_checks".parse::<Service>().is_err());
+  }
+}
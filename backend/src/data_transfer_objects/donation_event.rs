@@ -10,6 +10,8 @@ pub struct DonationEventDto {
   pub id: i32,
   pub event_type: EventType,
   pub amount: f32,
+  /// The ISO 4217 code of the currency a Streamlabs donation was made in.
+  pub currency: Option<String>,
   pub timestamp: DateTimeUtc,
   pub donator: Option<twitch_user::Model>,
   pub donation_receiver: twitch_user::Model,
@@ -48,6 +50,7 @@ impl DonationEventDto {
       id: donation_event.id,
       event_type: donation_event.event_type,
       amount: donation_event.amount,
+      currency: donation_event.currency,
       timestamp: donation_event.timestamp,
       donator,
       donation_receiver,
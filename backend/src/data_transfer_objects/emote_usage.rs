@@ -0,0 +1,131 @@
+use crate::error::AppError;
+use entities::{emote, twitch_user};
+use entity::prelude::Decimal;
+use sea_orm::*;
+use std::collections::HashMap;
+
+#[derive(Debug, serde::Serialize)]
+pub struct EmoteUsageDto {
+  pub emote: emote::Model,
+  pub usage_count: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct EmoteUserUsageDto {
+  pub user: twitch_user::Model,
+  pub usage_count: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct EmoteUsageBucketDto {
+  /// The start of the bucket, formatted as `YYYY-MM-DD` or `YYYY-MM-DD hh:00:00` for hourly buckets.
+  pub bucket: String,
+  pub usage_count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct EmoteUsageAggregate {
+  pub emote_id: i32,
+  pub usage_count: Option<Decimal>,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct EmoteUserUsageAggregate {
+  pub twitch_user_id: i32,
+  pub usage_count: Option<Decimal>,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct EmoteUsageBucketAggregate {
+  pub bucket: String,
+  pub usage_count: Option<Decimal>,
+}
+
+impl EmoteUsageDto {
+  /// Pairs each aggregate with its emote, keeping the order of the aggregates.
+  pub async fn from_aggregates(
+    aggregates: Vec<EmoteUsageAggregate>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<Self>, AppError> {
+    let emotes: HashMap<i32, emote::Model> = emote::Entity::find()
+      .filter(emote::Column::Id.is_in(aggregates.iter().map(|aggregate| aggregate.emote_id)))
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|emote| (emote.id, emote))
+      .collect();
+
+    Ok(
+      aggregates
+        .into_iter()
+        .filter_map(|aggregate| {
+          let Some(emote) = emotes.get(&aggregate.emote_id).cloned() else {
+            tracing::error!("Failed to find emote with the ID {}", aggregate.emote_id);
+
+            return None;
+          };
+
+          Some(Self {
+            emote,
+            usage_count: usage_count_from_decimal(aggregate.usage_count),
+          })
+        })
+        .collect(),
+    )
+  }
+}
+
+impl EmoteUserUsageDto {
+  /// Pairs each aggregate with its user, keeping the order of the aggregates.
+  pub async fn from_aggregates(
+    aggregates: Vec<EmoteUserUsageAggregate>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<Self>, AppError> {
+    let users: HashMap<i32, twitch_user::Model> = twitch_user::Entity::find()
+      .filter(
+        twitch_user::Column::Id.is_in(aggregates.iter().map(|aggregate| aggregate.twitch_user_id)),
+      )
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|user| (user.id, user))
+      .collect();
+
+    Ok(
+      aggregates
+        .into_iter()
+        .filter_map(|aggregate| {
+          let Some(user) = users.get(&aggregate.twitch_user_id).cloned() else {
+            tracing::error!(
+              "Failed to find user with the ID {}",
+              aggregate.twitch_user_id
+            );
+
+            return None;
+          };
+
+          Some(Self {
+            user,
+            usage_count: usage_count_from_decimal(aggregate.usage_count),
+          })
+        })
+        .collect(),
+    )
+  }
+}
+
+impl From<EmoteUsageBucketAggregate> for EmoteUsageBucketDto {
+  fn from(aggregate: EmoteUsageBucketAggregate) -> Self {
+    Self {
+      bucket: aggregate.bucket,
+      usage_count: usage_count_from_decimal(aggregate.usage_count),
+    }
+  }
+}
+
+/// MySQL returns `SUM` over integer columns as a decimal.
+fn usage_count_from_decimal(usage_count: Option<Decimal>) -> i64 {
+  usage_count
+    .and_then(|usage_count| i64::try_from(usage_count).ok())
+    .unwrap_or_default()
+}
@@ -0,0 +1,20 @@
+use entity_extensions::user_erasure::ErasureSummary;
+use std::collections::BTreeMap;
+
+#[derive(Debug, serde::Serialize)]
+pub struct ErasureSummaryDto {
+  pub user_id: i32,
+  pub mode: String,
+  /// The amount of rows deleted or updated, keyed by table name.
+  pub affected_rows: BTreeMap<&'static str, u64>,
+}
+
+impl From<ErasureSummary> for ErasureSummaryDto {
+  fn from(summary: ErasureSummary) -> Self {
+    Self {
+      user_id: summary.user_id,
+      mode: summary.mode.to_string(),
+      affected_rows: summary.affected_rows,
+    }
+  }
+}
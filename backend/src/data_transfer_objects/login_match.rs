@@ -0,0 +1,26 @@
+use entities::twitch_user;
+use entity_extensions::twitch_user::LoginMatch;
+use sea_orm::prelude::DateTimeUtc;
+
+#[derive(Debug, serde::Serialize)]
+pub struct LoginMatchDto {
+  pub user: twitch_user::Model,
+  pub matched_login: String,
+  pub is_current: bool,
+  /// None if the user had the login when they were first seen.
+  pub used_from: Option<DateTimeUtc>,
+  /// None if the user still has the login.
+  pub used_until: Option<DateTimeUtc>,
+}
+
+impl From<LoginMatch> for LoginMatchDto {
+  fn from(login_match: LoginMatch) -> Self {
+    Self {
+      user: login_match.user,
+      matched_login: login_match.matched_login,
+      is_current: login_match.is_current,
+      used_from: login_match.used_from,
+      used_until: login_match.used_until,
+    }
+  }
+}
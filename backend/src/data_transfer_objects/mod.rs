@@ -1,8 +1,17 @@
 pub mod donation_event;
+pub mod emote_usage;
+pub mod erasure_summary;
 pub mod follow;
 pub mod gift_sub_recipient;
+pub mod login_match;
 pub mod raid;
 pub mod stream;
+pub mod stream_activity;
+pub mod stream_highlight;
 pub mod stream_message;
 pub mod subscription_event;
+pub mod twitch_user;
 pub mod twitch_user_name_change;
+pub mod unknown_user_match;
+pub mod user_profile;
+pub mod user_timeout;
@@ -1,7 +1,9 @@
 use crate::error::AppError;
 use entities::*;
-use entity::prelude::DateTimeUtc;
+use entity::prelude::{DateTimeUtc, Decimal};
+use sea_orm::sea_query::{Expr, Func, SimpleExpr};
 use sea_orm::*;
+use std::collections::HashMap;
 
 #[derive(Debug, serde::Serialize)]
 pub struct StreamDto {
@@ -27,6 +29,15 @@ pub struct StreamListItem {
   pub twitch_vod_id: Option<String>,
   pub title: Option<String>,
   pub muted_vod_segments: Vec<MutedVodSegmentResponse>,
+  pub peak_viewers: Option<i32>,
+  pub average_viewers: Option<f64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct StreamViewerStatistics {
+  stream_id: i32,
+  peak_viewers: Option<i32>,
+  average_viewers: Option<Decimal>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -43,6 +54,7 @@ impl StreamDto {
     streams: Vec<stream::Model>,
     database_connection: &DatabaseConnection,
   ) -> Result<StreamResponse, AppError> {
+    let mut viewer_statistics = Self::get_viewer_statistics(&streams, database_connection).await?;
     let streams_with_muted_segments =
       Self::get_muted_segments(streams, database_connection).await?;
 
@@ -58,6 +70,7 @@ impl StreamDto {
       }
 
       let muted_vod_segments: Vec<MutedVodSegmentResponse> = muted_vod_segments.into_iter().map(Into::into).collect();
+      let viewer_statistics = viewer_statistics.remove(&stream.id);
 
       Some(StreamListItem {
         id: stream.id,
@@ -66,7 +79,11 @@ impl StreamDto {
         end_timestamp: stream.end_timestamp,
         twitch_vod_id: stream.twitch_vod_id,
         title: stream.title,
-        muted_vod_segments
+        muted_vod_segments,
+        peak_viewers: viewer_statistics.as_ref().and_then(|statistics| statistics.peak_viewers),
+        average_viewers: viewer_statistics
+          .and_then(|statistics| statistics.average_viewers)
+          .and_then(|average_viewers| f64::try_from(average_viewers).ok()),
       })
     }).collect();
 
@@ -87,6 +104,40 @@ impl StreamDto {
     Ok(streams.into_iter().zip(muted_vod_segments).collect())
   }
 
+  /// Returns the peak and average viewer counts sampled for each stream, keyed by stream ID.
+  async fn get_viewer_statistics(
+    streams: &[stream::Model],
+    database_connection: &DatabaseConnection,
+  ) -> Result<HashMap<i32, StreamViewerStatistics>, AppError> {
+    let stream_ids: Vec<i32> = streams.iter().map(|stream| stream.id).collect();
+
+    let viewer_statistics = stream_viewer_sample::Entity::find()
+      .filter(stream_viewer_sample::Column::StreamId.is_in(stream_ids))
+      .select_only()
+      .column(stream_viewer_sample::Column::StreamId)
+      .column_as(
+        Expr::col(stream_viewer_sample::Column::ViewerCount).max(),
+        "peak_viewers",
+      )
+      .column_as(
+        SimpleExpr::from(Func::avg(Expr::col(
+          stream_viewer_sample::Column::ViewerCount,
+        ))),
+        "average_viewers",
+      )
+      .group_by(stream_viewer_sample::Column::StreamId)
+      .into_model::<StreamViewerStatistics>()
+      .all(database_connection)
+      .await?;
+
+    Ok(
+      viewer_statistics
+        .into_iter()
+        .map(|statistics| (statistics.stream_id, statistics))
+        .collect(),
+    )
+  }
+
   pub async fn from_stream(
     stream: stream::Model,
     database_connection: &DatabaseConnection,
@@ -0,0 +1,213 @@
+use crate::error::AppError;
+use chrono::DateTime;
+use entities::*;
+use entity::prelude::{DateTimeUtc, Decimal};
+use entity_extensions::time_buckets::unix_bucket_expression;
+use sea_orm::sea_query::{Alias, Expr, SimpleExpr};
+use sea_orm::*;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, serde::Serialize)]
+pub struct StreamActivityDto {
+  pub stream_id: i32,
+  /// In seconds.
+  pub bucket_size: i64,
+  pub buckets: Vec<ActivityBucketDto>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ActivityBucketDto {
+  pub bucket_start: DateTimeUtc,
+  pub message_count: i64,
+  pub unique_chatters: i64,
+  /// The ratio of messages in the bucket that only contained emotes.
+  pub emote_only_ratio: f64,
+  /// Only set if events were requested.
+  pub donation_count: Option<i64>,
+  pub subscription_count: Option<i64>,
+  pub raid_count: Option<i64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct MessageBucketAggregate {
+  bucket: i64,
+  message_count: i64,
+  unique_chatters: i64,
+  emote_only_messages: Option<Decimal>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct EventBucketAggregate {
+  bucket: i64,
+  event_count: i64,
+}
+
+impl StreamActivityDto {
+  /// Aggregates the stream's chat activity into buckets of `bucket_size` seconds.
+  ///
+  /// Buckets are aligned to the unix epoch rather than the start of the stream, and buckets without any activity are omitted.
+  pub async fn from_stream(
+    stream: &stream::Model,
+    bucket_size: i64,
+    include_events: bool,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Self, AppError> {
+    let message_buckets =
+      Self::get_message_buckets(stream, bucket_size, database_connection).await?;
+    let mut buckets: BTreeMap<i64, ActivityBucketDto> = message_buckets
+      .into_iter()
+      .map(|aggregate| {
+        let emote_only_messages = aggregate
+          .emote_only_messages
+          .and_then(|emote_only_messages| f64::try_from(emote_only_messages).ok())
+          .unwrap_or_default();
+        let mut bucket = ActivityBucketDto::empty(aggregate.bucket, include_events);
+        bucket.message_count = aggregate.message_count;
+        bucket.unique_chatters = aggregate.unique_chatters;
+
+        if aggregate.message_count > 0 {
+          bucket.emote_only_ratio = emote_only_messages / aggregate.message_count as f64;
+        }
+
+        (aggregate.bucket, bucket)
+      })
+      .collect();
+
+    if include_events {
+      let donation_counts = Self::get_event_counts(
+        donation_event::Entity::find().filter(donation_event::Column::StreamId.eq(stream.id)),
+        Expr::col((donation_event::Entity, donation_event::Column::Timestamp)).into(),
+        bucket_size,
+        database_connection,
+      )
+      .await?;
+      let subscription_counts = Self::get_event_counts(
+        subscription_event::Entity::find()
+          .filter(subscription_event::Column::StreamId.eq(stream.id)),
+        Expr::col((
+          subscription_event::Entity,
+          subscription_event::Column::Timestamp,
+        ))
+        .into(),
+        bucket_size,
+        database_connection,
+      )
+      .await?;
+      let raid_counts = Self::get_event_counts(
+        raid::Entity::find().filter(raid::Column::StreamId.eq(stream.id)),
+        Expr::col((raid::Entity, raid::Column::Timestamp)).into(),
+        bucket_size,
+        database_connection,
+      )
+      .await?;
+
+      for (bucket_start, donation_count) in donation_counts {
+        buckets
+          .entry(bucket_start)
+          .or_insert_with(|| ActivityBucketDto::empty(bucket_start, include_events))
+          .donation_count = Some(donation_count);
+      }
+
+      for (bucket_start, subscription_count) in subscription_counts {
+        buckets
+          .entry(bucket_start)
+          .or_insert_with(|| ActivityBucketDto::empty(bucket_start, include_events))
+          .subscription_count = Some(subscription_count);
+      }
+
+      for (bucket_start, raid_count) in raid_counts {
+        buckets
+          .entry(bucket_start)
+          .or_insert_with(|| ActivityBucketDto::empty(bucket_start, include_events))
+          .raid_count = Some(raid_count);
+      }
+    }
+
+    Ok(Self {
+      stream_id: stream.id,
+      bucket_size,
+      buckets: buckets.into_values().collect(),
+    })
+  }
+
+  async fn get_message_buckets(
+    stream: &stream::Model,
+    bucket_size: i64,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<MessageBucketAggregate>, AppError> {
+    stream_message::Entity::find()
+      .filter(stream_message::Column::StreamId.eq(stream.id))
+      .select_only()
+      .column_as(
+        unix_bucket_expression(
+          Expr::col((stream_message::Entity, stream_message::Column::Timestamp)).into(),
+          bucket_size,
+        ),
+        "bucket",
+      )
+      .column_as(
+        Expr::col(stream_message::Column::Id).count(),
+        "message_count",
+      )
+      .column_as(
+        Expr::cust_with_expr(
+          "COUNT(DISTINCT ?)",
+          Expr::col(stream_message::Column::TwitchUserId),
+        ),
+        "unique_chatters",
+      )
+      .column_as(
+        Expr::col(stream_message::Column::EmoteOnly).sum(),
+        "emote_only_messages",
+      )
+      .group_by(Expr::col(Alias::new("bucket")))
+      .order_by_asc(Expr::col(Alias::new("bucket")))
+      .into_model::<MessageBucketAggregate>()
+      .all(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  /// Counts the rows of the given query in each bucket, keyed by the bucket's unix timestamp.
+  async fn get_event_counts<E: EntityTrait>(
+    event_query: Select<E>,
+    timestamp_column: SimpleExpr,
+    bucket_size: i64,
+    database_connection: &DatabaseConnection,
+  ) -> Result<HashMap<i64, i64>, AppError> {
+    let event_buckets = event_query
+      .select_only()
+      .column_as(
+        unix_bucket_expression(timestamp_column, bucket_size),
+        "bucket",
+      )
+      .column_as(Expr::cust("COUNT(*)"), "event_count")
+      .group_by(Expr::col(Alias::new("bucket")))
+      .into_model::<EventBucketAggregate>()
+      .all(database_connection)
+      .await?;
+
+    Ok(
+      event_buckets
+        .into_iter()
+        .map(|aggregate| (aggregate.bucket, aggregate.event_count))
+        .collect(),
+    )
+  }
+}
+
+impl ActivityBucketDto {
+  fn empty(bucket: i64, include_events: bool) -> Self {
+    let event_count = include_events.then_some(0);
+
+    Self {
+      bucket_start: DateTime::from_timestamp(bucket, 0).unwrap_or_default(),
+      message_count: 0,
+      unique_chatters: 0,
+      emote_only_ratio: 0.0,
+      donation_count: event_count,
+      subscription_count: event_count,
+      raid_count: event_count,
+    }
+  }
+}
@@ -0,0 +1,29 @@
+use entity::prelude::DateTimeUtc;
+use entity_extensions::stream_highlights::StreamHighlight;
+use sea_orm::*;
+
+#[derive(Debug, serde::Serialize)]
+pub struct StreamHighlightDto {
+  pub timestamp: DateTimeUtc,
+  /// In seconds.
+  pub vod_offset: Option<i64>,
+  pub vod_url: Option<String>,
+  pub activity: i64,
+  pub baseline: f64,
+  pub z_score: f64,
+  pub is_muted: bool,
+}
+
+impl StreamHighlightDto {
+  pub fn from_highlight(highlight: StreamHighlight, twitch_vod_id: Option<&str>) -> Self {
+    Self {
+      timestamp: highlight.timestamp,
+      vod_offset: highlight.vod_offset,
+      vod_url: twitch_vod_id.and_then(|twitch_vod_id| highlight.vod_url(twitch_vod_id)),
+      activity: highlight.activity,
+      baseline: highlight.baseline,
+      z_score: highlight.z_score,
+      is_muted: highlight.is_muted,
+    }
+  }
+}
@@ -1,4 +1,5 @@
 use crate::error::AppError;
+use entities::sea_orm_active_enums::ContentMode;
 use entities::*;
 use entity_extensions::external_service::*;
 use sea_orm::{DatabaseConnection, LoaderTrait, prelude::DateTimeUtc};
@@ -8,8 +9,12 @@ pub struct StreamMessageDto {
   pub id: i32,
   pub is_first_message: bool,
   pub timestamp: DateTimeUtc,
+  /// Empty unless the contents were stored as they were sent.
   pub contents: String,
-  pub is_subscriber: bool,
+  /// Hashed and redacted messages have no text to show, only the mode they were stored in.
+  pub content_mode: ContentMode,
+  /// None if the message was imported from a log that doesn't record badges.
+  pub is_subscriber: Option<bool>,
   /// Contents index and emote data.
   pub emote_usage: Vec<StreamMessageEmote>,
 }
@@ -35,8 +40,15 @@ impl StreamMessageDto {
         .into_iter()
         .zip(emotes_used)
         .map(|(message, emotes)| {
-          let message_contents = message.contents.unwrap_or_default();
-          let mut emote_usage: Vec<StreamMessageEmote> = get_emote_usage(&message_contents, emotes);
+          let (message_contents, mut emote_usage) = match message.content_mode {
+            ContentMode::Raw => {
+              let message_contents = message.contents.unwrap_or_default();
+              let emote_usage = get_emote_usage(&message_contents, emotes);
+
+              (message_contents, emote_usage)
+            }
+            ContentMode::Hashed | ContentMode::Redacted => (String::new(), vec![]),
+          };
           emote_usage.sort_by(|lhs, rhs| lhs.contents_indices.cmp(&rhs.contents_indices));
 
           StreamMessageDto {
@@ -44,7 +56,10 @@ impl StreamMessageDto {
             is_first_message: message.is_first_message != 0,
             timestamp: message.timestamp,
             contents: message_contents,
-            is_subscriber: message.is_subscriber != 0,
+            content_mode: message.content_mode,
+            is_subscriber: message
+              .is_subscriber
+              .map(|is_subscriber| is_subscriber != 0),
             emote_usage,
           }
         })
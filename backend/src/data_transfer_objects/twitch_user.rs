@@ -0,0 +1,43 @@
+use crate::error::AppError;
+use entities::sea_orm_active_enums::AccountStatus;
+use entities::twitch_user;
+use entity_extensions::account_status_change::{current_status, get_latest_status_changes};
+use sea_orm::prelude::DateTimeUtc;
+use sea_orm::*;
+
+#[derive(Debug, serde::Serialize)]
+pub struct TwitchUserDto {
+  #[serde(flatten)]
+  pub user: twitch_user::Model,
+  pub account_status: AccountStatus,
+  /// When the latest status was recorded. None if no status was ever recorded for the user.
+  pub account_status_changed_at: Option<DateTimeUtc>,
+}
+
+impl TwitchUserDto {
+  pub async fn from_user_list(
+    users: Vec<twitch_user::Model>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<Self>, AppError> {
+    let user_ids: Vec<i32> = users.iter().map(|user| user.id).collect();
+    let latest_status_changes = get_latest_status_changes(&user_ids, database_connection).await?;
+
+    Ok(
+      users
+        .into_iter()
+        .map(|user| {
+          let latest_status_change = latest_status_changes.get(&user.id);
+
+          TwitchUserDto {
+            account_status: current_status(
+              latest_status_change.map(|status_change| &status_change.status),
+            ),
+            account_status_changed_at: latest_status_change
+              .map(|status_change| status_change.created_at),
+            user,
+          }
+        })
+        .collect(),
+    )
+  }
+}
@@ -0,0 +1,59 @@
+use crate::error::AppError;
+use entities::sea_orm_active_enums::MatchStatus;
+use entities::{twitch_user, unknown_user, unknown_user_match};
+use prelude::DateTimeUtc;
+use sea_orm::*;
+use std::collections::HashMap;
+
+#[derive(Debug, serde::Serialize)]
+pub struct UnknownUserMatchDto {
+  pub id: i32,
+  pub unknown_user: Option<unknown_user::Model>,
+  pub twitch_user: Option<twitch_user::Model>,
+  /// The Jaro-Winkler similarity of the names. None for matches made before scores were stored.
+  pub score: Option<f64>,
+  pub status: MatchStatus,
+  pub created_at: DateTimeUtc,
+  pub reviewed_at: Option<DateTimeUtc>,
+}
+
+impl UnknownUserMatchDto {
+  pub async fn from_match_list(
+    matches: Vec<unknown_user_match::Model>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<Self>, AppError> {
+    let unknown_users: HashMap<i32, unknown_user::Model> = unknown_user::Entity::find()
+      .filter(
+        unknown_user::Column::Id.is_in(matches.iter().map(|user_match| user_match.unknown_user_id)),
+      )
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|unknown_user| (unknown_user.id, unknown_user))
+      .collect();
+    let twitch_users: HashMap<i32, twitch_user::Model> = twitch_user::Entity::find()
+      .filter(
+        twitch_user::Column::Id.is_in(matches.iter().map(|user_match| user_match.twitch_user_id)),
+      )
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|twitch_user| (twitch_user.id, twitch_user))
+      .collect();
+
+    Ok(
+      matches
+        .into_iter()
+        .map(|user_match| Self {
+          id: user_match.id,
+          unknown_user: unknown_users.get(&user_match.unknown_user_id).cloned(),
+          twitch_user: twitch_users.get(&user_match.twitch_user_id).cloned(),
+          score: user_match.score,
+          status: user_match.status,
+          created_at: user_match.created_at,
+          reviewed_at: user_match.reviewed_at,
+        })
+        .collect(),
+    )
+  }
+}
@@ -0,0 +1,393 @@
+use crate::data_transfer_objects::twitch_user_name_change::TwitchUserNameChangeDto;
+use crate::error::AppError;
+use app_config::AppConfig;
+use entities::sea_orm_active_enums::{AccountStatus, EventType};
+use entities::*;
+use entity::prelude::{DateTimeUtc, Decimal};
+use entity_extensions::account_status_change::{current_status, get_status_history};
+use entity_extensions::exchange_rate::DonationCurrencyConverter;
+use sea_orm::sea_query::{Alias, Expr};
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserProfileDto {
+  pub user: twitch_user::Model,
+  pub first_seen: Option<DateTimeUtc>,
+  pub last_seen: Option<DateTimeUtc>,
+  pub channel_activity: Vec<ChannelActivity>,
+  pub total_bits: i64,
+  /// Direct donations converted to `reports.currency`.
+  pub total_donations: f64,
+  pub total_gifted_subs: i64,
+  pub subscriptions: Vec<ChannelSubscriptionStreak>,
+  pub timeouts: TimeoutTotals,
+  pub raids_led: RaidTotals,
+  pub name_history: Vec<TwitchUserNameChangeDto>,
+  pub account_status: AccountStatus,
+  /// Every status recorded for the user, oldest first. Shows when the account went missing or was renamed.
+  pub account_status_history: Vec<account_status_change::Model>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ChannelActivity {
+  pub channel: Option<twitch_user::Model>,
+  pub message_count: i64,
+  pub first_message: Option<DateTimeUtc>,
+  pub last_message: Option<DateTimeUtc>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ChannelSubscriptionStreak {
+  pub channel: Option<twitch_user::Model>,
+  /// The highest `months_subscribed` value seen for the channel.
+  pub longest_streak: i32,
+  pub last_subscribed: Option<DateTimeUtc>,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+pub struct TimeoutTotals {
+  pub temporary: i64,
+  pub permanent: i64,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+pub struct RaidTotals {
+  pub total_raids: i64,
+  pub total_viewers_raided: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ChannelMessageAggregate {
+  channel_id: i32,
+  message_count: i64,
+  first_message: Option<DateTimeUtc>,
+  last_message: Option<DateTimeUtc>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct DonationAggregate {
+  event_type: EventType,
+  total_amount: Option<f64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SubscriptionAggregate {
+  channel_id: i32,
+  longest_streak: Option<i32>,
+  last_subscribed: Option<DateTimeUtc>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TimeoutAggregate {
+  is_permanent: i8,
+  timeout_count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct RaidAggregate {
+  total_raids: i64,
+  total_viewers_raided: Option<Decimal>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct PresenceAggregate {
+  first_joined: Option<DateTimeUtc>,
+  last_joined: Option<DateTimeUtc>,
+}
+
+impl UserProfileDto {
+  pub async fn from_user(
+    user: twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Self, AppError> {
+    let message_aggregates = Self::get_message_aggregates(&user, database_connection).await?;
+    let subscription_aggregates =
+      Self::get_subscription_aggregates(&user, database_connection).await?;
+    let presence_aggregate = Self::get_presence_aggregate(&user, database_connection).await?;
+
+    let channel_ids: HashSet<i32> = message_aggregates
+      .iter()
+      .map(|aggregate| aggregate.channel_id)
+      .chain(
+        subscription_aggregates
+          .iter()
+          .map(|aggregate| aggregate.channel_id),
+      )
+      .collect();
+    let channels: HashMap<i32, twitch_user::Model> = twitch_user::Entity::find()
+      .filter(twitch_user::Column::Id.is_in(channel_ids))
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|channel| (channel.id, channel))
+      .collect();
+
+    let first_seen = message_aggregates
+      .iter()
+      .filter_map(|aggregate| aggregate.first_message)
+      .chain(
+        presence_aggregate
+          .as_ref()
+          .and_then(|presence| presence.first_joined),
+      )
+      .min();
+    let last_seen = message_aggregates
+      .iter()
+      .filter_map(|aggregate| aggregate.last_message)
+      .chain(
+        presence_aggregate
+          .as_ref()
+          .and_then(|presence| presence.last_joined),
+      )
+      .max();
+
+    let channel_activity = message_aggregates
+      .into_iter()
+      .map(|aggregate| ChannelActivity {
+        channel: channels.get(&aggregate.channel_id).cloned(),
+        message_count: aggregate.message_count,
+        first_message: aggregate.first_message,
+        last_message: aggregate.last_message,
+      })
+      .collect();
+    let subscriptions = subscription_aggregates
+      .into_iter()
+      .map(|aggregate| ChannelSubscriptionStreak {
+        channel: channels.get(&aggregate.channel_id).cloned(),
+        longest_streak: aggregate.longest_streak.unwrap_or_default(),
+        last_subscribed: aggregate.last_subscribed,
+      })
+      .collect();
+
+    let donation_totals = Self::get_donation_totals(&user, database_connection).await?;
+    let get_donation_total = |event_type: EventType| {
+      donation_totals
+        .iter()
+        .find_map(|(total_event_type, total)| (*total_event_type == event_type).then_some(*total))
+        .unwrap_or_default()
+    };
+
+    let account_status_history = get_status_history(user.id, database_connection).await?;
+
+    Ok(Self {
+      first_seen,
+      last_seen,
+      channel_activity,
+      total_bits: get_donation_total(EventType::Bits) as i64,
+      total_donations: get_donation_total(EventType::StreamlabsDonation),
+      total_gifted_subs: get_donation_total(EventType::GiftSubs) as i64,
+      subscriptions,
+      timeouts: Self::get_timeout_totals(&user, database_connection).await?,
+      raids_led: Self::get_raid_totals(&user, database_connection).await?,
+      name_history: Self::get_name_history(&user, database_connection).await?,
+      account_status: current_status(
+        account_status_history
+          .last()
+          .map(|status_change| &status_change.status),
+      ),
+      account_status_history,
+      user,
+    })
+  }
+
+  async fn get_message_aggregates(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<ChannelMessageAggregate>, AppError> {
+    stream_message::Entity::find()
+      .filter(stream_message::Column::TwitchUserId.eq(user.id))
+      .select_only()
+      .column(stream_message::Column::ChannelId)
+      .column_as(
+        Expr::col(stream_message::Column::Id).count(),
+        "message_count",
+      )
+      .column_as(
+        Expr::col(stream_message::Column::Timestamp).min(),
+        "first_message",
+      )
+      .column_as(
+        Expr::col(stream_message::Column::Timestamp).max(),
+        "last_message",
+      )
+      .group_by(stream_message::Column::ChannelId)
+      .order_by_desc(Expr::col(Alias::new("message_count")))
+      .into_model::<ChannelMessageAggregate>()
+      .all(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  async fn get_presence_aggregate(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<PresenceAggregate>, AppError> {
+    chatter_presence::Entity::find()
+      .filter(chatter_presence::Column::TwitchUserId.eq(user.id))
+      .select_only()
+      .column_as(
+        Expr::col(chatter_presence::Column::JoinedAt).min(),
+        "first_joined",
+      )
+      .column_as(
+        Expr::col(chatter_presence::Column::JoinedAt).max(),
+        "last_joined",
+      )
+      .into_model::<PresenceAggregate>()
+      .one(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  /// Returns the summed donation amount for each donation event type the user has sent.
+  ///
+  /// Direct donations can be in any currency, so they're converted to the report currency one by one before being
+  /// summed.
+  async fn get_donation_totals(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<(EventType, f64)>, AppError> {
+    let donation_aggregates = donation_event::Entity::find()
+      .filter(donation_event::Column::DonatorTwitchUserId.eq(user.id))
+      .filter(donation_event::Column::EventType.ne(EventType::StreamlabsDonation))
+      .select_only()
+      .column(donation_event::Column::EventType)
+      .column_as(
+        Expr::col(donation_event::Column::Amount).sum(),
+        "total_amount",
+      )
+      .group_by(donation_event::Column::EventType)
+      .into_model::<DonationAggregate>()
+      .all(database_connection)
+      .await?;
+    let direct_donations = donation_event::Entity::find()
+      .filter(donation_event::Column::DonatorTwitchUserId.eq(user.id))
+      .filter(donation_event::Column::EventType.eq(EventType::StreamlabsDonation))
+      .all(database_connection)
+      .await?;
+    let currency_converter = DonationCurrencyConverter::new(
+      &direct_donations,
+      AppConfig::report_currency(),
+      database_connection,
+    )
+    .await?;
+    let direct_donation_total = direct_donations
+      .iter()
+      .filter_map(|donation| currency_converter.convert_or_warn(donation))
+      .map(f64::from)
+      .sum();
+
+    Ok(
+      donation_aggregates
+        .into_iter()
+        .map(|aggregate| {
+          (
+            aggregate.event_type,
+            aggregate.total_amount.unwrap_or_default(),
+          )
+        })
+        .chain(std::iter::once((
+          EventType::StreamlabsDonation,
+          direct_donation_total,
+        )))
+        .collect(),
+    )
+  }
+
+  async fn get_subscription_aggregates(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<SubscriptionAggregate>, AppError> {
+    subscription_event::Entity::find()
+      .filter(subscription_event::Column::SubscriberTwitchUserId.eq(user.id))
+      .select_only()
+      .column(subscription_event::Column::ChannelId)
+      .column_as(
+        Expr::col(subscription_event::Column::MonthsSubscribed).max(),
+        "longest_streak",
+      )
+      .column_as(
+        Expr::col(subscription_event::Column::Timestamp).max(),
+        "last_subscribed",
+      )
+      .group_by(subscription_event::Column::ChannelId)
+      .into_model::<SubscriptionAggregate>()
+      .all(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  async fn get_timeout_totals(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<TimeoutTotals, AppError> {
+    let timeout_aggregates = user_timeout::Entity::find()
+      .filter(user_timeout::Column::TwitchUserId.eq(user.id))
+      .select_only()
+      .column(user_timeout::Column::IsPermanent)
+      .column_as(Expr::col(user_timeout::Column::Id).count(), "timeout_count")
+      .group_by(user_timeout::Column::IsPermanent)
+      .into_model::<TimeoutAggregate>()
+      .all(database_connection)
+      .await?;
+
+    Ok(
+      timeout_aggregates
+        .into_iter()
+        .fold(TimeoutTotals::default(), |mut totals, aggregate| {
+          if aggregate.is_permanent == 1 {
+            totals.permanent += aggregate.timeout_count;
+          } else {
+            totals.temporary += aggregate.timeout_count;
+          }
+
+          totals
+        }),
+    )
+  }
+
+  async fn get_raid_totals(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<RaidTotals, AppError> {
+    let raid_aggregate = raid::Entity::find()
+      .filter(raid::Column::RaiderTwitchUserId.eq(user.id))
+      .select_only()
+      .column_as(Expr::col(raid::Column::Id).count(), "total_raids")
+      .column_as(Expr::col(raid::Column::Size).sum(), "total_viewers_raided")
+      .into_model::<RaidAggregate>()
+      .one(database_connection)
+      .await?;
+
+    let Some(raid_aggregate) = raid_aggregate else {
+      return Ok(RaidTotals::default());
+    };
+
+    Ok(RaidTotals {
+      total_raids: raid_aggregate.total_raids,
+      total_viewers_raided: raid_aggregate
+        .total_viewers_raided
+        .and_then(|total| i64::try_from(total).ok())
+        .unwrap_or_default(),
+    })
+  }
+
+  async fn get_name_history(
+    user: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<TwitchUserNameChangeDto>, AppError> {
+    let name_changes = twitch_user_name_change::Entity::find()
+      .filter(twitch_user_name_change::Column::TwitchUserId.eq(user.id))
+      .order_by_asc(twitch_user_name_change::Column::CreatedAt)
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|name_change| (name_change, Some(user.clone())))
+      .collect();
+
+    Ok(TwitchUserNameChangeDto::from_name_changes_and_users(
+      name_changes,
+    ))
+  }
+}
@@ -0,0 +1,56 @@
+use crate::error::AppError;
+use entities::{stream, twitch_user, user_timeout};
+use sea_orm::{prelude::DateTimeUtc, *};
+use std::collections::HashMap;
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserTimeoutDto {
+  pub id: i32,
+  pub user: Option<twitch_user::Model>,
+  pub channel: Option<twitch_user::Model>,
+  /// In seconds. None if the timeout was a ban.
+  pub duration: Option<i32>,
+  pub is_permanent: bool,
+  pub timestamp: DateTimeUtc,
+  pub stream_title: Option<String>,
+}
+
+impl UserTimeoutDto {
+  /// Converts the list of timeouts with their related users, channels, and stream titles.
+  pub async fn from_timeout_list(
+    timeouts: Vec<user_timeout::Model>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<Self>, AppError> {
+    let related_streams = timeouts
+      .load_one(stream::Entity, database_connection)
+      .await?;
+    let users = twitch_user::Entity::find()
+      .filter(
+        twitch_user::Column::Id.is_in(
+          timeouts
+            .iter()
+            .flat_map(|timeout| [timeout.twitch_user_id, timeout.channel_id]),
+        ),
+      )
+      .all(database_connection)
+      .await?;
+    let users: HashMap<i32, twitch_user::Model> =
+      users.into_iter().map(|user| (user.id, user)).collect();
+
+    Ok(
+      timeouts
+        .into_iter()
+        .zip(related_streams)
+        .map(|(timeout, stream)| UserTimeoutDto {
+          id: timeout.id,
+          user: users.get(&timeout.twitch_user_id).cloned(),
+          channel: users.get(&timeout.channel_id).cloned(),
+          duration: timeout.duration,
+          is_permanent: timeout.is_permanent == 1,
+          timestamp: timeout.timestamp,
+          stream_title: stream.and_then(|stream| stream.title),
+        })
+        .collect(),
+    )
+  }
+}
@@ -27,6 +27,13 @@ pub enum AppError {
   #[error("Could not find user. Login: {}", login)]
   CouldNotFindUserByLoginName { login: String },
 
+  #[error(
+    "More than one user has had the login {}. User IDs: {:?}",
+    login,
+    user_ids
+  )]
+  AmbiguousLogin { login: String, user_ids: Vec<i32> },
+
   #[error("Could not find user. Interal ID: {}", internal_id)]
   CouldNotFindUserByInternalID { internal_id: i32 },
 
@@ -35,14 +42,35 @@ pub enum AppError {
     identifier: ChannelIdentifier<String>,
   },
 
+  #[error("Could not find an emote with the name {}", name)]
+  CouldNotFindEmoteByName { name: String },
+
   #[error("Failed to find a stream with the ID {}", stream_id)]
   FailedToFindStreamByID { stream_id: i32 },
 
   #[error("Failed to find a donation event with the ID {}", donation_event_id)]
   FailedToFindDonationEventByID { donation_event_id: i32 },
 
+  #[error("Invalid value `{}` for query parameter `{}`", value, parameter)]
+  InvalidQueryParameter {
+    parameter: &'static str,
+    value: String,
+  },
+
   #[error("Failed to parse response {}", response)]
   FailedToParseResponse { response: String },
+
+  #[error("The admin API is disabled since no admin token is configured.")]
+  AdminApiDisabled,
+
+  #[error("Missing or incorrect admin token.")]
+  Unauthorized,
+
+  #[error("User {} is a tracked channel and can't be erased.", user_id)]
+  CannotEraseChannel { user_id: i32 },
+
+  #[error("Failed to find an unknown user match with the ID {}", match_id)]
+  FailedToFindUnknownUserMatchByID { match_id: i32 },
 }
 
 impl axum::response::IntoResponse for AppError {
@@ -64,13 +92,20 @@ impl From<AppError> for StatusCode {
       AppError::ReqwestError(_) => StatusCode::INTERNAL_SERVER_ERROR,
       AppError::SerdeError(_) => StatusCode::INTERNAL_SERVER_ERROR,
       AppError::NoQueryParameterFound => StatusCode::BAD_REQUEST,
+      AppError::InvalidQueryParameter { .. } => StatusCode::BAD_REQUEST,
       AppError::CouldNotFindUserByTwitchId { .. } => StatusCode::NOT_FOUND,
       AppError::CouldNotFindUserByLoginName { .. } => StatusCode::NOT_FOUND,
+      AppError::AmbiguousLogin { .. } => StatusCode::CONFLICT,
       AppError::CouldNotFindUserByInternalID { .. } => StatusCode::NOT_FOUND,
       AppError::CouldNotFindUserByIdentifier { .. } => StatusCode::NOT_FOUND,
+      AppError::CouldNotFindEmoteByName { .. } => StatusCode::NOT_FOUND,
       AppError::FailedToFindStreamByID { .. } => StatusCode::NOT_FOUND,
       AppError::FailedToFindDonationEventByID { .. } => StatusCode::NOT_FOUND,
       AppError::FailedToParseResponse { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+      AppError::AdminApiDisabled => StatusCode::FORBIDDEN,
+      AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+      AppError::CannotEraseChannel { .. } => StatusCode::CONFLICT,
+      AppError::FailedToFindUnknownUserMatchByID { .. } => StatusCode::NOT_FOUND,
 
       AppError::ChronoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
@@ -1,6 +1,6 @@
 use app_config::{AppConfig, log_level_wrapper::LoggingConfigLevel};
 use std::path::PathBuf;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::{EnvFilter, reload};
 
 const SEA_ORM_LOG_LEVEL: LoggingConfigLevel = LoggingConfigLevel::Warn;
 
@@ -11,15 +11,8 @@ pub fn setup_logging_config() -> Result<(), Box<dyn std::error::Error>> {
     return Ok(());
   };
 
-  let filter_string = format!(
-    "{},sea_orm={seaorm_level},sea_orm_migration={seaorm_level},sqlx={seaorm_level}",
-    log_level,
-    seaorm_level = SEA_ORM_LOG_LEVEL
-  );
-  let env_filter = EnvFilter::new(filter_string);
-
   let subscriber_builder = tracing_subscriber::fmt()
-    .with_env_filter(env_filter)
+    .with_env_filter(create_env_filter(Some(log_level)))
     .with_ansi(false);
 
   if let Some(logging_dir) = AppConfig::logging_dir() {
@@ -28,14 +21,43 @@ pub fn setup_logging_config() -> Result<(), Box<dyn std::error::Error>> {
     let filename_prefix = PathBuf::from(AppConfig::logging_filename_prefix());
     let logging_file = AppConfig::logging_file_roll_appender().clone();
 
-    subscriber_builder
+    let subscriber_builder = subscriber_builder
       .with_writer(logging_file.to_file_appender(logging_dir, &filename_prefix)?)
-      .init();
+      .with_filter_reloading();
+
+    reload_filter_on_config_change(subscriber_builder.reload_handle());
+    subscriber_builder.init();
   } else {
     println!("Logging to stdout.");
 
+    let subscriber_builder = subscriber_builder.with_filter_reloading();
+
+    reload_filter_on_config_change(subscriber_builder.reload_handle());
     subscriber_builder.init();
   }
 
   Ok(())
 }
+
+fn create_env_filter(log_level: Option<LoggingConfigLevel>) -> EnvFilter {
+  let Some(log_level) = log_level else {
+    return EnvFilter::new("off");
+  };
+
+  EnvFilter::new(format!(
+    "{},sea_orm={seaorm_level},sea_orm_migration={seaorm_level},sqlx={seaorm_level}",
+    log_level,
+    seaorm_level = SEA_ORM_LOG_LEVEL
+  ))
+}
+
+/// Applies the log level from the config whenever it's reloaded.
+fn reload_filter_on_config_change<S: 'static>(reload_handle: reload::Handle<EnvFilter, S>) {
+  AppConfig::on_reload(move |reloadable_settings| {
+    let env_filter = create_env_filter(reloadable_settings.log_level);
+
+    if let Err(error) = reload_handle.reload(env_filter) {
+      tracing::error!("Failed to apply the reloaded log level. Reason: {error}");
+    }
+  });
+}
@@ -1,3 +1,4 @@
+use app_config::AppConfig;
 use axum::Router;
 use backend::app::InterfaceConfig;
 use backend::routes::route_builder::RouteBuilder;
@@ -9,6 +10,12 @@ const LISTENING_ADDRESS: &str = "0.0.0.0:8080";
 #[tokio::main]
 async fn main() {
   backend::logging::setup_logging_config().unwrap();
+  AppConfig::watch_for_changes();
+
+  // The backend can still serve stored data without a valid token, so this isn't fatal.
+  if let Err(error) = helix_client::start_token_lifecycle(&AppConfig::channels()).await {
+    tracing::error!("Failed to validate the Twitch access token. Reason: {error}");
+  }
 
   let interface_config = InterfaceConfig::new().await.unwrap();
 
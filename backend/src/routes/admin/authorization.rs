@@ -0,0 +1,31 @@
+use crate::error::AppError;
+use app_config::AppConfig;
+use app_config::secret_string::Secret;
+use axum::http::HeaderMap;
+use axum::http::header::AUTHORIZATION;
+use subtle::ConstantTimeEq;
+
+/// Checks the request for a bearer token matching the configured admin API token.
+///
+/// The tokens are compared in constant time so the response time doesn't reveal how much of a guess was right.
+pub fn authorize_admin_request(headers: &HeaderMap) -> Result<(), AppError> {
+  let Some(admin_api_token) = AppConfig::admin_api_token() else {
+    return Err(AppError::AdminApiDisabled);
+  };
+  let admin_api_token = Secret::read_secret_string(admin_api_token.read_value());
+
+  let given_token = headers
+    .get(AUTHORIZATION)
+    .and_then(|header_value| header_value.to_str().ok())
+    .and_then(|header_value| header_value.strip_prefix("Bearer "));
+
+  match given_token {
+    Some(given_token)
+      if !admin_api_token.is_empty()
+        && bool::from(given_token.as_bytes().ct_eq(admin_api_token.as_bytes())) =>
+    {
+      Ok(())
+    }
+    _ => Err(AppError::Unauthorized),
+  }
+}
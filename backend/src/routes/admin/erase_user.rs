@@ -0,0 +1,52 @@
+use crate::data_transfer_objects::erasure_summary::ErasureSummaryDto;
+use crate::routes::admin::authorization::authorize_admin_request;
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
+use entity_extensions::errors::EntityExtensionError;
+use entity_extensions::user_erasure::{ErasureMode, erase_user};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct EraseUserQuery {
+  /// Either `anonymize` or `delete`. Defaults to `anonymize`.
+  mode: Option<String>,
+}
+
+/// Deletes or anonymizes everything stored about the user with the given internal ID.
+#[axum::debug_handler]
+pub async fn erase_user_data(
+  headers: HeaderMap,
+  Query(query_payload): Query<EraseUserQuery>,
+  State(interface_config): State<InterfaceConfig>,
+  Path(user_id): Path<i32>,
+) -> Result<axum::Json<ErasureSummaryDto>, AppError> {
+  authorize_admin_request(&headers)?;
+
+  let mode = match &query_payload.mode {
+    Some(mode) => mode
+      .parse::<ErasureMode>()
+      .map_err(|_| AppError::InvalidQueryParameter {
+        parameter: "mode",
+        value: mode.to_owned(),
+      })?,
+    None => ErasureMode::Anonymize,
+  };
+
+  tracing::info!("Got a request to {mode} user {user_id}.");
+
+  let database_connection = interface_config.database_connection();
+  let summary = match erase_user(user_id, mode, database_connection).await {
+    Ok(summary) => summary,
+    Err(EntityExtensionError::FailedToGetValue { .. }) => {
+      return Err(AppError::CouldNotFindUserByInternalID {
+        internal_id: user_id,
+      });
+    }
+    Err(EntityExtensionError::CannotEraseChannel(_)) => {
+      return Err(AppError::CannotEraseChannel { user_id });
+    }
+    Err(error) => return Err(error.into()),
+  };
+
+  Ok(axum::Json(ErasureSummaryDto::from(summary)))
+}
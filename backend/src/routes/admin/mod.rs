@@ -0,0 +1,3 @@
+pub mod authorization;
+pub mod erase_user;
+pub mod unknown_user_matches;
@@ -0,0 +1,98 @@
+use crate::data_transfer_objects::unknown_user_match::UnknownUserMatchDto;
+use crate::routes::admin::authorization::authorize_admin_request;
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
+use entities::sea_orm_active_enums::MatchStatus;
+use entities::unknown_user_match;
+use entity_extensions::errors::EntityExtensionError;
+use entity_extensions::prelude::*;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct UnknownUserMatchQuery {
+  /// Either `pending`, `confirmed`, or `rejected`. Every match is returned if not set.
+  status: Option<String>,
+}
+
+/// Lists the candidate matches between unknown donator names and known users.
+#[axum::debug_handler]
+pub async fn get_unknown_user_matches(
+  headers: HeaderMap,
+  Query(query_payload): Query<UnknownUserMatchQuery>,
+  State(interface_config): State<InterfaceConfig>,
+) -> Result<axum::Json<Vec<UnknownUserMatchDto>>, AppError> {
+  authorize_admin_request(&headers)?;
+
+  let status = match query_payload.status.as_deref() {
+    None => None,
+    Some("pending") => Some(MatchStatus::Pending),
+    Some("confirmed") => Some(MatchStatus::Confirmed),
+    Some("rejected") => Some(MatchStatus::Rejected),
+    Some(status) => {
+      return Err(AppError::InvalidQueryParameter {
+        parameter: "status",
+        value: status.to_owned(),
+      });
+    }
+  };
+  let database_connection = interface_config.database_connection();
+  let matches = unknown_user_match::Model::get_by_status(status, database_connection).await?;
+
+  Ok(axum::Json(
+    UnknownUserMatchDto::from_match_list(matches, database_connection).await?,
+  ))
+}
+
+/// Confirms the match, attributing the unknown user's donations to the matched user.
+#[axum::debug_handler]
+pub async fn confirm_unknown_user_match(
+  headers: HeaderMap,
+  State(interface_config): State<InterfaceConfig>,
+  Path(match_id): Path<i32>,
+) -> Result<axum::Json<UnknownUserMatchDto>, AppError> {
+  authorize_admin_request(&headers)?;
+
+  tracing::info!("Got a request to confirm unknown user match {match_id}.");
+
+  let database_connection = interface_config.database_connection();
+  let result = unknown_user_match::Model::confirm(match_id, database_connection).await;
+
+  review_response(result, match_id, database_connection).await
+}
+
+/// Rejects the match, reverting its donations if it was confirmed.
+#[axum::debug_handler]
+pub async fn reject_unknown_user_match(
+  headers: HeaderMap,
+  State(interface_config): State<InterfaceConfig>,
+  Path(match_id): Path<i32>,
+) -> Result<axum::Json<UnknownUserMatchDto>, AppError> {
+  authorize_admin_request(&headers)?;
+
+  tracing::info!("Got a request to reject unknown user match {match_id}.");
+
+  let database_connection = interface_config.database_connection();
+  let result = unknown_user_match::Model::reject(match_id, database_connection).await;
+
+  review_response(result, match_id, database_connection).await
+}
+
+async fn review_response(
+  result: Result<unknown_user_match::Model, EntityExtensionError>,
+  match_id: i32,
+  database_connection: &sea_orm::DatabaseConnection,
+) -> Result<axum::Json<UnknownUserMatchDto>, AppError> {
+  let user_match = match result {
+    Ok(user_match) => user_match,
+    Err(EntityExtensionError::FailedToGetValue { .. }) => {
+      return Err(AppError::FailedToFindUnknownUserMatchByID { match_id });
+    }
+    Err(error) => return Err(error.into()),
+  };
+
+  UnknownUserMatchDto::from_match_list(vec![user_match], database_connection)
+    .await?
+    .pop()
+    .map(axum::Json)
+    .ok_or(AppError::FailedToFindUnknownUserMatchByID { match_id })
+}
@@ -70,14 +70,7 @@ async fn get_user(
   query_payload: &DonationEventQuery,
   database_connection: &DatabaseConnection,
 ) -> Result<Option<twitch_user::Model>, AppError> {
-  let Some(user_query) = query_payload.get_maybe_user_query() else {
-    return Ok(None);
-  };
-
-  user_query
-    .one(database_connection)
-    .await
-    .map_err(Into::into)
+  query_payload.find_user(database_connection).await
 }
 
 async fn get_donation_query(
@@ -1,6 +1,7 @@
 use crate::{app::InterfaceConfig, error::AppError};
 use axum::extract::State;
 use entities::{donation_event, sea_orm_active_enums::EventType, subscription_event};
+use entity_extensions::exchange_rate::DonationCurrencyConverter;
 use sea_orm::*;
 use sqlx::types::chrono::{TimeZone, Utc};
 
@@ -9,6 +10,8 @@ const POINTS_PER_TIER_1_SUB: f64 = 5.0;
 const POINTS_PER_TIER_2_SUB: f64 = 10.0;
 const POINTS_PER_TIER_3_SUB: f64 = 25.0;
 const POINTS_PER_DOLLAR: f64 = 1.0;
+/// The currency that direct donations are converted to before being counted.
+const DIRECT_DONATION_CURRENCY: &str = "USD";
 
 #[derive(Debug, Default, serde::Serialize)]
 pub struct SubathonResponse {
@@ -40,6 +43,7 @@ pub async fn get_subathon_data(
   let mut all_donations = donation_event::Entity::find()
     .filter(donation_event::Column::Timestamp.gte(subathon_start_result))
     .filter(donation_event::Column::DonationReceiverTwitchUserId.eq(1))
+    .filter(donation_event::Column::EventType.ne(EventType::StreamlabsDonation))
     .select_only()
     .column(donation_event::Column::EventType)
     .column(donation_event::Column::SubscriptionTier)
@@ -49,6 +53,27 @@ pub async fn get_subathon_data(
     .into_model::<DonationSum>()
     .all(database_connection)
     .await?;
+  let direct_donations = donation_event::Entity::find()
+    .filter(donation_event::Column::Timestamp.gte(subathon_start_result))
+    .filter(donation_event::Column::DonationReceiverTwitchUserId.eq(1))
+    .filter(donation_event::Column::EventType.eq(EventType::StreamlabsDonation))
+    .all(database_connection)
+    .await?;
+  let currency_converter = DonationCurrencyConverter::new(
+    &direct_donations,
+    DIRECT_DONATION_CURRENCY,
+    database_connection,
+  )
+  .await?;
+  all_donations.push(DonationSum {
+    event_type: EventType::StreamlabsDonation,
+    sum_amount: direct_donations
+      .iter()
+      .filter_map(|donation| currency_converter.convert_or_warn(donation))
+      .map(f64::from)
+      .sum(),
+    subscription_tier: None,
+  });
   let subscriptions = subscription_event::Entity::find()
     .filter(subscription_event::Column::Timestamp.gte(subathon_start_result))
     .filter(subscription_event::Column::ChannelId.eq(1))
@@ -0,0 +1,4 @@
+pub mod top_emotes;
+pub mod top_users;
+pub mod usage_filters;
+pub mod usage_over_time;
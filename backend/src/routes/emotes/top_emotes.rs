@@ -0,0 +1,73 @@
+use crate::data_transfer_objects::emote_usage::{EmoteUsageAggregate, EmoteUsageDto};
+use crate::response_models::paginated_parameters::PaginationParameters;
+use crate::response_models::paginatied_response::{PaginatedResponse, Pagination};
+use crate::routes::emotes::usage_filters::EmoteUsageFilters;
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Query, State};
+use entities::*;
+use sea_orm::sea_query::{Alias, Expr};
+use sea_orm::*;
+
+const MAX_PAGE_SIZE: u64 = 100;
+const MIN_PAGE_SIZE: u64 = 1;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TopEmotesQuery {
+  #[serde(flatten)]
+  filters: EmoteUsageFilters,
+
+  #[serde(flatten)]
+  pagination_parameters: PaginationParameters,
+}
+
+#[axum::debug_handler]
+pub async fn get_top_emotes(
+  Query(query_payload): Query<TopEmotesQuery>,
+  State(interface_config): State<InterfaceConfig>,
+) -> Result<axum::Json<PaginatedResponse<Vec<EmoteUsageDto>>>, AppError> {
+  tracing::info!("Got a top emotes request: {query_payload:?}");
+
+  let database_connection = interface_config.database_connection();
+  let pagination = query_payload
+    .pagination_parameters
+    .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
+  let message_condition = query_payload
+    .filters
+    .get_message_condition(database_connection)
+    .await?;
+
+  let top_emotes_query = emote_usage::Entity::find()
+    .join(
+      JoinType::InnerJoin,
+      emote_usage::Relation::StreamMessage.def(),
+    )
+    .filter(message_condition)
+    .select_only()
+    .column(emote_usage::Column::EmoteId)
+    .column_as(
+      Expr::col((emote_usage::Entity, emote_usage::Column::UsageCount)).sum(),
+      "usage_count",
+    )
+    .group_by(emote_usage::Column::EmoteId)
+    .order_by_desc(Expr::col(Alias::new("usage_count")))
+    .into_model::<EmoteUsageAggregate>();
+  let paginated_top_emotes = top_emotes_query.paginate(database_connection, pagination.page_size);
+
+  let top_emotes = paginated_top_emotes.fetch_page(pagination.page).await?;
+  let ItemsAndPagesNumber {
+    number_of_items,
+    number_of_pages,
+  } = paginated_top_emotes.num_items_and_pages().await?;
+
+  let emote_usage_dtos = EmoteUsageDto::from_aggregates(top_emotes, database_connection).await?;
+
+  Ok(axum::Json(PaginatedResponse {
+    data: emote_usage_dtos,
+    pagination: Pagination {
+      total_items: number_of_items,
+      total_pages: number_of_pages,
+      page: pagination.page,
+      page_size: pagination.page_size,
+    },
+  }))
+}
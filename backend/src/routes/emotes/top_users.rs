@@ -0,0 +1,78 @@
+use crate::data_transfer_objects::emote_usage::{EmoteUserUsageAggregate, EmoteUserUsageDto};
+use crate::response_models::paginated_parameters::PaginationParameters;
+use crate::response_models::paginatied_response::{PaginatedResponse, Pagination};
+use crate::routes::emotes::usage_filters::{EmoteUsageFilters, get_emote_ids_by_name};
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use entities::*;
+use sea_orm::sea_query::{Alias, Expr};
+use sea_orm::*;
+
+const MAX_PAGE_SIZE: u64 = 100;
+const MIN_PAGE_SIZE: u64 = 1;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TopEmoteUsersQuery {
+  #[serde(flatten)]
+  filters: EmoteUsageFilters,
+
+  #[serde(flatten)]
+  pagination_parameters: PaginationParameters,
+}
+
+/// Ranks the users who've used the given emote the most.
+#[axum::debug_handler]
+pub async fn get_top_emote_users(
+  Query(query_payload): Query<TopEmoteUsersQuery>,
+  State(interface_config): State<InterfaceConfig>,
+  Path(emote_name): Path<String>,
+) -> Result<axum::Json<PaginatedResponse<Vec<EmoteUserUsageDto>>>, AppError> {
+  tracing::info!("Got a top emote users request for {emote_name:?}: {query_payload:?}");
+
+  let database_connection = interface_config.database_connection();
+  let pagination = query_payload
+    .pagination_parameters
+    .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
+  let emote_ids = get_emote_ids_by_name(&emote_name, database_connection).await?;
+  let message_condition = query_payload
+    .filters
+    .get_message_condition(database_connection)
+    .await?;
+
+  let top_users_query = emote_usage::Entity::find()
+    .join(
+      JoinType::InnerJoin,
+      emote_usage::Relation::StreamMessage.def(),
+    )
+    .filter(emote_usage::Column::EmoteId.is_in(emote_ids))
+    .filter(message_condition)
+    .select_only()
+    .column(stream_message::Column::TwitchUserId)
+    .column_as(
+      Expr::col((emote_usage::Entity, emote_usage::Column::UsageCount)).sum(),
+      "usage_count",
+    )
+    .group_by(stream_message::Column::TwitchUserId)
+    .order_by_desc(Expr::col(Alias::new("usage_count")))
+    .into_model::<EmoteUserUsageAggregate>();
+  let paginated_top_users = top_users_query.paginate(database_connection, pagination.page_size);
+
+  let top_users = paginated_top_users.fetch_page(pagination.page).await?;
+  let ItemsAndPagesNumber {
+    number_of_items,
+    number_of_pages,
+  } = paginated_top_users.num_items_and_pages().await?;
+
+  let emote_user_usage_dtos =
+    EmoteUserUsageDto::from_aggregates(top_users, database_connection).await?;
+
+  Ok(axum::Json(PaginatedResponse {
+    data: emote_user_usage_dtos,
+    pagination: Pagination {
+      total_items: number_of_items,
+      total_pages: number_of_pages,
+      page: pagination.page,
+      page_size: pagination.page_size,
+    },
+  }))
+}
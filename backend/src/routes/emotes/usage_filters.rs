@@ -0,0 +1,117 @@
+use crate::error::AppError;
+use crate::routes::helpers::get_users::GetUsers;
+use entities::*;
+use entity::prelude::DateTimeUtc;
+use sea_orm::*;
+
+/// The filters shared by the emote usage routes.
+///
+/// Every filter applies to the stream messages the emotes were used in.
+#[derive(Debug, serde::Deserialize)]
+pub struct EmoteUsageFilters {
+  #[serde(flatten)]
+  channel: ChannelInfo,
+
+  #[serde(flatten)]
+  chatter: ChatterInfo,
+
+  /// The internal ID of the stream the emotes were used in.
+  stream_id: Option<String>,
+  start_date: Option<DateTimeUtc>,
+  end_date: Option<DateTimeUtc>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ChannelInfo {
+  channel_login: Option<String>,
+  channel_id: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ChatterInfo {
+  maybe_login: Option<String>,
+  user_id: Option<String>,
+}
+
+impl EmoteUsageFilters {
+  /// Builds the condition for the stream messages to aggregate emote usage over.
+  pub async fn get_message_condition(
+    &self,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Condition, AppError> {
+    let mut condition = Condition::all();
+
+    if let Some(channel) = self.channel.get_maybe_user(database_connection).await? {
+      condition = condition.add(stream_message::Column::ChannelId.eq(channel.id));
+    }
+
+    if let Some(chatter) = self.chatter.get_maybe_user(database_connection).await? {
+      condition = condition.add(stream_message::Column::TwitchUserId.eq(chatter.id));
+    }
+
+    if let Some(stream_id) = &self.stream_id {
+      let Ok(stream_id) = stream_id.parse::<i32>() else {
+        return Err(AppError::InvalidQueryParameter {
+          parameter: "stream_id",
+          value: stream_id.to_owned(),
+        });
+      };
+
+      condition = condition.add(stream_message::Column::StreamId.eq(stream_id));
+    }
+
+    if let Some(start_date) = self.start_date {
+      condition = condition.add(stream_message::Column::Timestamp.gte(start_date));
+    }
+
+    if let Some(end_date) = self.end_date {
+      condition = condition.add(stream_message::Column::Timestamp.lte(end_date));
+    }
+
+    Ok(condition)
+  }
+}
+
+/// Returns the IDs of every emote with the given name.
+///
+/// Emote names aren't unique across services, so usage is combined for all of them.
+pub async fn get_emote_ids_by_name(
+  emote_name: &str,
+  database_connection: &DatabaseConnection,
+) -> Result<Vec<i32>, AppError> {
+  let emote_ids: Vec<i32> = emote::Entity::find()
+    .filter(emote::Column::Name.eq(emote_name))
+    .select_only()
+    .column(emote::Column::Id)
+    .into_tuple()
+    .all(database_connection)
+    .await?;
+
+  if emote_ids.is_empty() {
+    return Err(AppError::CouldNotFindEmoteByName {
+      name: emote_name.to_owned(),
+    });
+  }
+
+  Ok(emote_ids)
+}
+
+impl GetUsers for ChannelInfo {
+  fn get_login(&self) -> Option<&str> {
+    self.channel_login.as_deref()
+  }
+
+  fn get_twitch_id(&self) -> Option<&str> {
+    self.channel_id.as_deref()
+  }
+}
+
+impl GetUsers for ChatterInfo {
+  fn get_login(&self) -> Option<&str> {
+    self.maybe_login.as_deref()
+  }
+
+  fn get_twitch_id(&self) -> Option<&str> {
+    self.user_id.as_deref()
+  }
+}
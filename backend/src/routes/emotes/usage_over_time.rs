@@ -0,0 +1,86 @@
+use crate::data_transfer_objects::emote_usage::{EmoteUsageBucketAggregate, EmoteUsageBucketDto};
+use crate::routes::emotes::usage_filters::{EmoteUsageFilters, get_emote_ids_by_name};
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use entities::*;
+use sea_orm::sea_query::{Alias, Expr, SimpleExpr};
+use sea_orm::*;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct EmoteUsageOverTimeQuery {
+  bucket_size: Option<BucketSize>,
+
+  #[serde(flatten)]
+  filters: EmoteUsageFilters,
+}
+
+#[derive(Debug, Default, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketSize {
+  Hour,
+  #[default]
+  Day,
+  Week,
+  Month,
+}
+
+impl BucketSize {
+  /// The expression truncating a message timestamp down to the start of its bucket.
+  fn bucket_expression(&self) -> SimpleExpr {
+    let timestamp =
+      || Expr::col((stream_message::Entity, stream_message::Column::Timestamp)).into();
+
+    match self {
+      BucketSize::Hour => Expr::cust_with_expr("DATE_FORMAT(?, '%Y-%m-%d %H:00:00')", timestamp()),
+      BucketSize::Day => Expr::cust_with_expr("DATE_FORMAT(?, '%Y-%m-%d')", timestamp()),
+      BucketSize::Week => Expr::cust_with_exprs(
+        "DATE_FORMAT(DATE_SUB(?, INTERVAL WEEKDAY(?) DAY), '%Y-%m-%d')",
+        [timestamp(), timestamp()],
+      ),
+      BucketSize::Month => Expr::cust_with_expr("DATE_FORMAT(?, '%Y-%m-01')", timestamp()),
+    }
+  }
+}
+
+/// Returns how often the given emote was used in each time bucket, oldest first.
+///
+/// Buckets without any usage are omitted.
+#[axum::debug_handler]
+pub async fn get_emote_usage_over_time(
+  Query(query_payload): Query<EmoteUsageOverTimeQuery>,
+  State(interface_config): State<InterfaceConfig>,
+  Path(emote_name): Path<String>,
+) -> Result<axum::Json<Vec<EmoteUsageBucketDto>>, AppError> {
+  tracing::info!("Got an emote usage over time request for {emote_name:?}: {query_payload:?}");
+
+  let database_connection = interface_config.database_connection();
+  let bucket_size = query_payload.bucket_size.unwrap_or_default();
+  let emote_ids = get_emote_ids_by_name(&emote_name, database_connection).await?;
+  let message_condition = query_payload
+    .filters
+    .get_message_condition(database_connection)
+    .await?;
+
+  let usage_buckets = emote_usage::Entity::find()
+    .join(
+      JoinType::InnerJoin,
+      emote_usage::Relation::StreamMessage.def(),
+    )
+    .filter(emote_usage::Column::EmoteId.is_in(emote_ids))
+    .filter(message_condition)
+    .select_only()
+    .column_as(bucket_size.bucket_expression(), "bucket")
+    .column_as(
+      Expr::col((emote_usage::Entity, emote_usage::Column::UsageCount)).sum(),
+      "usage_count",
+    )
+    .group_by(Expr::col(Alias::new("bucket")))
+    .order_by_asc(Expr::col(Alias::new("bucket")))
+    .into_model::<EmoteUsageBucketAggregate>()
+    .all(database_connection)
+    .await?;
+
+  Ok(axum::Json(
+    usage_buckets.into_iter().map(Into::into).collect(),
+  ))
+}
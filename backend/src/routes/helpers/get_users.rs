@@ -1,8 +1,10 @@
 use entities::twitch_user;
+use entity_extensions::prelude::*;
 use sea_orm::*;
 
 use crate::error::AppError;
 
+#[allow(async_fn_in_trait)]
 pub trait GetUsers {
   fn get_login(&self) -> Option<&str> {
     None
@@ -54,6 +56,96 @@ pub trait GetUsers {
     None
   }
 
+  /// Returns the user matching the query. None if there was no query parameter or no user was found.
+  ///
+  /// Logins are resolved in order of the user currently holding the exact login, the user who held it before, and
+  /// then the first user with a login containing it. Errors if more than one user held the login before.
+  async fn find_user(
+    &self,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<twitch_user::Model>, AppError> {
+    let Some(user_login) = self.get_login() else {
+      let Some(user_query) = self.get_maybe_user_query() else {
+        return Ok(None);
+      };
+
+      return user_query
+        .one(database_connection)
+        .await
+        .map_err(Into::into);
+    };
+
+    let current_holder = twitch_user::Entity::find()
+      .filter(twitch_user::Column::LoginName.eq(user_login))
+      .one(database_connection)
+      .await?;
+
+    if current_holder.is_some() {
+      return Ok(current_holder);
+    }
+
+    let mut previous_holders: Vec<twitch_user::Model> = vec![];
+
+    for login_match in
+      twitch_user::Model::get_by_login_history(user_login, database_connection).await?
+    {
+      if !previous_holders
+        .iter()
+        .any(|user| user.id == login_match.user.id)
+      {
+        previous_holders.push(login_match.user);
+      }
+    }
+
+    if previous_holders.len() > 1 {
+      return Err(AppError::AmbiguousLogin {
+        login: user_login.to_string(),
+        user_ids: previous_holders.iter().map(|user| user.id).collect(),
+      });
+    }
+
+    if let Some(previous_holder) = previous_holders.pop() {
+      return Ok(Some(previous_holder));
+    }
+
+    twitch_user::Entity::find()
+      .filter(twitch_user::Column::LoginName.contains(user_login))
+      .one(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  /// Returns the user matching the query. See [`find_user`](GetUsers::find_user).
+  ///
+  /// Errors if there was no query parameter or no user was found.
+  async fn get_user(
+    &self,
+    database_connection: &DatabaseConnection,
+  ) -> Result<twitch_user::Model, AppError> {
+    self
+      .find_user(database_connection)
+      .await?
+      .ok_or_else(|| self.get_missing_user_error())
+  }
+
+  /// Returns the user matching the query, or None if there was no query parameter. See [`find_user`](GetUsers::find_user).
+  ///
+  /// Errors if no user was found.
+  async fn get_maybe_user(
+    &self,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<twitch_user::Model>, AppError> {
+    if self.get_maybe_user_query().is_none() {
+      return Ok(None);
+    }
+
+    self
+      .find_user(database_connection)
+      .await?
+      .map(Some)
+      .ok_or_else(|| self.get_missing_user_error())
+  }
+
   fn get_missing_user_error(&self) -> AppError {
     if let Some(user_login) = self.get_login() {
       return AppError::CouldNotFindUserByLoginName {
@@ -1,4 +1,7 @@
+pub mod admin;
 pub mod donations;
+pub mod emotes;
 pub mod helpers;
 pub mod route_builder;
+pub mod streams;
 pub mod users;
@@ -1,10 +1,13 @@
 use crate::app::InterfaceConfig;
-use axum::routing::get;
+use axum::routing::{delete, get, post};
 
 pub trait RouteBuilder {
   fn apply_all_routes(self) -> Self;
   fn apply_user_routes(self) -> Self;
   fn apply_donation_routes(self) -> Self;
+  fn apply_emote_routes(self) -> Self;
+  fn apply_stream_routes(self) -> Self;
+  fn apply_admin_routes(self) -> Self;
 }
 
 impl RouteBuilder for axum::Router<InterfaceConfig> {
@@ -12,15 +15,26 @@ impl RouteBuilder for axum::Router<InterfaceConfig> {
     self //
       .apply_user_routes()
       .apply_donation_routes()
+      .apply_emote_routes()
+      .apply_stream_routes()
+      .apply_admin_routes()
   }
 
   fn apply_user_routes(self) -> Self {
     self
       .route("/users", get(crate::routes::users::get_users::get_users))
+      .route(
+        "/users/login_history",
+        get(crate::routes::users::login_history::get_login_history),
+      )
       .route(
         "/users/name_changes",
         get(crate::routes::users::name_changes::get_name_changes),
       )
+      .route(
+        "/users/{id}/profile",
+        get(crate::routes::users::profile::get_user_profile),
+      )
       .route(
         "/users/following",
         get(crate::routes::users::following::get_following),
@@ -34,6 +48,14 @@ impl RouteBuilder for axum::Router<InterfaceConfig> {
         get(crate::routes::users::streams::get_streams),
       )
       .route("/users/raids", get(crate::routes::users::raids::get_raids))
+      .route(
+        "/{channel}/timeouts",
+        get(crate::routes::users::timeouts::get_timeouts),
+      )
+      .route(
+        "/users/timeouts",
+        get(crate::routes::users::timeouts::get_timeouts),
+      )
   }
 
   fn apply_donation_routes(self) -> Self {
@@ -59,4 +81,52 @@ impl RouteBuilder for axum::Router<InterfaceConfig> {
     //   get(crate::routes::donations::subathon_data::get_subathon_data),
     // )
   }
+
+  fn apply_emote_routes(self) -> Self {
+    self
+      .route(
+        "/emotes",
+        get(crate::routes::emotes::top_emotes::get_top_emotes),
+      )
+      .route(
+        "/emotes/{emote}/usage",
+        get(crate::routes::emotes::usage_over_time::get_emote_usage_over_time),
+      )
+      .route(
+        "/emotes/{emote}/users",
+        get(crate::routes::emotes::top_users::get_top_emote_users),
+      )
+  }
+
+  fn apply_stream_routes(self) -> Self {
+    self
+      .route(
+        "/{channel}/streams/{stream_id}/activity",
+        get(crate::routes::streams::activity::get_stream_activity),
+      )
+      .route(
+        "/{channel}/streams/{stream_id}/highlights",
+        get(crate::routes::streams::highlights::get_stream_highlights),
+      )
+  }
+
+  fn apply_admin_routes(self) -> Self {
+    self
+      .route(
+        "/admin/users/{id}",
+        delete(crate::routes::admin::erase_user::erase_user_data),
+      )
+      .route(
+        "/admin/unknown_user_matches",
+        get(crate::routes::admin::unknown_user_matches::get_unknown_user_matches),
+      )
+      .route(
+        "/admin/unknown_user_matches/{id}/confirm",
+        post(crate::routes::admin::unknown_user_matches::confirm_unknown_user_match),
+      )
+      .route(
+        "/admin/unknown_user_matches/{id}/reject",
+        post(crate::routes::admin::unknown_user_matches::reject_unknown_user_match),
+      )
+  }
 }
@@ -0,0 +1,75 @@
+use crate::data_transfer_objects::stream_activity::StreamActivityDto;
+use crate::routes::helpers::get_channel::get_channel;
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use entities::*;
+use sea_orm::*;
+
+const DEFAULT_BUCKET_SIZE: i64 = 60;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct StreamActivityQuery {
+  /// The size of each bucket such as `30s`, `5m`, or `1h`. Plain numbers are treated as seconds.
+  bucket: Option<String>,
+
+  /// Whether to include donation, subscription, and raid counts in each bucket.
+  include_events: Option<bool>,
+}
+
+#[axum::debug_handler]
+pub async fn get_stream_activity(
+  Query(query_payload): Query<StreamActivityQuery>,
+  State(interface_config): State<InterfaceConfig>,
+  Path((channel_login, stream_id)): Path<(String, i32)>,
+) -> Result<axum::Json<StreamActivityDto>, AppError> {
+  tracing::info!(
+    "Got a stream activity request for stream {stream_id} in {channel_login:?}: {query_payload:?}"
+  );
+
+  let database_connection = interface_config.database_connection();
+  let bucket_size = match &query_payload.bucket {
+    Some(bucket) => parse_bucket_size(bucket)?,
+    None => DEFAULT_BUCKET_SIZE,
+  };
+
+  let channel = get_channel(channel_login, database_connection).await?;
+  let Some(stream) = stream::Entity::find_by_id(stream_id)
+    .filter(stream::Column::TwitchUserId.eq(channel.id))
+    .one(database_connection)
+    .await?
+  else {
+    return Err(AppError::FailedToFindStreamByID { stream_id });
+  };
+
+  let stream_activity = StreamActivityDto::from_stream(
+    &stream,
+    bucket_size,
+    query_payload.include_events.unwrap_or(false),
+    database_connection,
+  )
+  .await?;
+
+  Ok(axum::Json(stream_activity))
+}
+
+/// Parses a bucket size such as `30s`, `5m`, or `1h` into seconds.
+pub fn parse_bucket_size(bucket: &str) -> Result<i64, AppError> {
+  let invalid_bucket = || AppError::InvalidQueryParameter {
+    parameter: "bucket",
+    value: bucket.to_owned(),
+  };
+
+  let (amount, multiplier) = match bucket.char_indices().last() {
+    Some((index, 's')) => (&bucket[..index], 1),
+    Some((index, 'm')) => (&bucket[..index], 60),
+    Some((index, 'h')) => (&bucket[..index], 60 * 60),
+    _ => (bucket, 1),
+  };
+  let amount: i64 = amount.parse().map_err(|_| invalid_bucket())?;
+
+  if amount <= 0 {
+    return Err(invalid_bucket());
+  }
+
+  amount.checked_mul(multiplier).ok_or_else(invalid_bucket)
+}
@@ -0,0 +1,72 @@
+use crate::data_transfer_objects::stream_highlight::StreamHighlightDto;
+use crate::routes::emotes::usage_filters::get_emote_ids_by_name;
+use crate::routes::helpers::get_channel::get_channel;
+use crate::routes::streams::activity::parse_bucket_size;
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use entities::*;
+use entity_extensions::prelude::StreamExtensions;
+use entity_extensions::stream_highlights::HighlightOptions;
+use sea_orm::*;
+
+const MAX_HIGHLIGHTS: usize = 100;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct StreamHighlightQuery {
+  /// Looks for spikes in the usage of this emote rather than in all messages.
+  emote: Option<String>,
+  /// The size of each bucket such as `30s` or `1m`.
+  bucket: Option<String>,
+  /// How many standard deviations above the baseline a moment must be.
+  threshold: Option<f64>,
+  limit: Option<usize>,
+}
+
+#[axum::debug_handler]
+pub async fn get_stream_highlights(
+  Query(query_payload): Query<StreamHighlightQuery>,
+  State(interface_config): State<InterfaceConfig>,
+  Path((channel_login, stream_id)): Path<(String, i32)>,
+) -> Result<axum::Json<Vec<StreamHighlightDto>>, AppError> {
+  tracing::info!(
+    "Got a stream highlight request for stream {stream_id} in {channel_login:?}: {query_payload:?}"
+  );
+
+  let database_connection = interface_config.database_connection();
+  let mut highlight_options = HighlightOptions::default();
+
+  if let Some(bucket) = &query_payload.bucket {
+    highlight_options.bucket_size = parse_bucket_size(bucket)?;
+  }
+
+  if let Some(threshold) = query_payload.threshold {
+    highlight_options.z_score_threshold = threshold;
+  }
+
+  if let Some(limit) = query_payload.limit {
+    highlight_options.limit = limit.min(MAX_HIGHLIGHTS);
+  }
+
+  if let Some(emote_name) = &query_payload.emote {
+    highlight_options.emote_ids =
+      Some(get_emote_ids_by_name(emote_name, database_connection).await?);
+  }
+
+  let channel = get_channel(channel_login, database_connection).await?;
+  let Some(stream) = stream::Entity::find_by_id(stream_id)
+    .filter(stream::Column::TwitchUserId.eq(channel.id))
+    .one(database_connection)
+    .await?
+  else {
+    return Err(AppError::FailedToFindStreamByID { stream_id });
+  };
+
+  let highlights = stream
+    .get_highlights(&highlight_options, database_connection)
+    .await?
+    .into_iter()
+    .map(|highlight| StreamHighlightDto::from_highlight(highlight, stream.twitch_vod_id.as_deref()))
+    .collect();
+
+  Ok(axum::Json(highlights))
+}
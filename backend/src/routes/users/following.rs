@@ -24,8 +24,7 @@ pub async fn get_following(
   tracing::info!("Got a following request: {query_payload:?}");
 
   let user = query_payload
-    .get_user_query()?
-    .one(interface_config.database_connection())
+    .find_user(interface_config.database_connection())
     .await?;
   let user_login = if let Some(user) = &user {
     &user.login_name
@@ -1,9 +1,11 @@
+use crate::data_transfer_objects::twitch_user::TwitchUserDto;
 use crate::response_models::paginated_parameters::PaginationParameters;
 use crate::response_models::paginatied_response::{PaginatedResponse, Pagination};
 use crate::routes::helpers::get_users::GetUsers;
 use crate::{app::InterfaceConfig, error::*};
 use axum::extract::{Query, State};
 use entities::twitch_user;
+use entity_extensions::name_index;
 use sea_orm::*;
 
 const MAX_PAGE_SIZE: u64 = 100;
@@ -23,7 +25,7 @@ pub struct UserQuery {
 pub async fn get_users(
   Query(query_payload): Query<UserQuery>,
   State(interface_config): State<InterfaceConfig>,
-) -> Result<axum::Json<PaginatedResponse<Vec<twitch_user::Model>>>, AppError> {
+) -> Result<axum::Json<PaginatedResponse<Vec<TwitchUserDto>>>, AppError> {
   tracing::info!("Got a user request: {query_payload:?}");
 
   let database_connection = interface_config.database_connection();
@@ -31,14 +33,43 @@ pub async fn get_users(
     .pagination_parameters
     .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
 
-  let user_query = query_payload.get_user_query()?;
-  let paginated_get_users = user_query.paginate(database_connection, pagination.page_size);
+  // Partial logins are looked up in the name index, which also matches previous names. The matched IDs are paged
+  // through here so that only a page of them is ever sent to the database.
+  let (users, number_of_items, number_of_pages) = match &query_payload.maybe_login {
+    Some(maybe_login) => {
+      let user_ids = name_index::find_users_containing(maybe_login, database_connection).await?;
+      let number_of_items = user_ids.len() as u64;
+      let page_user_ids = user_ids
+        .chunks(pagination.page_size as usize)
+        .nth(pagination.page as usize)
+        .map(<[i32]>::to_vec)
+        .unwrap_or_default();
+      let users = twitch_user::Entity::find()
+        .filter(twitch_user::Column::Id.is_in(page_user_ids))
+        .order_by_asc(twitch_user::Column::Id)
+        .all(database_connection)
+        .await?;
 
-  let users = paginated_get_users.fetch_page(pagination.page).await?;
-  let ItemsAndPagesNumber {
-    number_of_items,
-    number_of_pages,
-  } = paginated_get_users.num_items_and_pages().await?;
+      (
+        users,
+        number_of_items,
+        number_of_items.div_ceil(pagination.page_size),
+      )
+    }
+    None => {
+      let paginated_get_users = query_payload
+        .get_user_query()?
+        .paginate(database_connection, pagination.page_size);
+      let users = paginated_get_users.fetch_page(pagination.page).await?;
+      let ItemsAndPagesNumber {
+        number_of_items,
+        number_of_pages,
+      } = paginated_get_users.num_items_and_pages().await?;
+
+      (users, number_of_items, number_of_pages)
+    }
+  };
+  let users = TwitchUserDto::from_user_list(users, database_connection).await?;
 
   Ok(axum::Json(PaginatedResponse {
     data: users,
@@ -0,0 +1,33 @@
+use crate::{app::InterfaceConfig, data_transfer_objects::login_match::LoginMatchDto, error::*};
+use axum::extract::{Query, State};
+use entities::twitch_user;
+use entity_extensions::prelude::*;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct LoginHistoryQuery {
+  login: String,
+}
+
+/// Returns every user who has had the login, current holder first, along with when they had it.
+#[axum::debug_handler]
+pub async fn get_login_history(
+  Query(query_payload): Query<LoginHistoryQuery>,
+  State(interface_config): State<InterfaceConfig>,
+) -> Result<axum::Json<Vec<LoginMatchDto>>, AppError> {
+  tracing::info!("Got a login history request: {query_payload:?}");
+
+  let database_connection = interface_config.database_connection();
+
+  let login_matches =
+    twitch_user::Model::get_by_login_history(&query_payload.login, database_connection).await?;
+
+  if login_matches.is_empty() {
+    return Err(AppError::CouldNotFindUserByLoginName {
+      login: query_payload.login,
+    });
+  }
+
+  Ok(axum::Json(
+    login_matches.into_iter().map(LoginMatchDto::from).collect(),
+  ))
+}
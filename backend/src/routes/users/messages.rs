@@ -5,6 +5,7 @@ use crate::response_models::{paginated_parameters::*, paginatied_response::*};
 use crate::routes::helpers::get_channel::get_channel;
 use crate::routes::helpers::get_users::GetUsers;
 use axum::extract::{Path, Query, State};
+use entities::sea_orm_active_enums::ContentMode;
 use entities::*;
 use sea_orm::*;
 
@@ -43,13 +44,7 @@ pub async fn get_messages(
     .pagination_parameters
     .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
 
-  let Some(user) = query_payload
-    .get_user_query()?
-    .one(database_connection)
-    .await?
-  else {
-    return Err(query_payload.get_missing_user_error());
-  };
+  let user = query_payload.get_user(database_connection).await?;
   let channel = get_channel(channel_name, database_connection).await?;
 
   let user_messages_query = get_user_messages_query(&query_payload.message_search, &user, &channel);
@@ -90,8 +85,11 @@ fn get_user_messages_query(
     .filter(stream_message::Column::ChannelId.eq(channel.id))
     .order_by(stream_message::Column::Timestamp, Order::Desc);
 
+  // Hashed contents aren't the message text, so only messages stored as they were sent can be searched.
   if let Some(message_search) = message_search {
-    message_query = message_query.filter(stream_message::Column::Contents.contains(message_search));
+    message_query = message_query
+      .filter(stream_message::Column::ContentMode.eq(ContentMode::Raw))
+      .filter(stream_message::Column::Contents.contains(message_search));
   }
 
   message_query
@@ -1,6 +1,9 @@
 pub mod following;
 pub mod get_users;
+pub mod login_history;
 pub mod messages;
 pub mod name_changes;
+pub mod profile;
 pub mod raids;
 pub mod streams;
+pub mod timeouts;
@@ -0,0 +1,28 @@
+use crate::app::InterfaceConfig;
+use crate::data_transfer_objects::user_profile::UserProfileDto;
+use crate::error::*;
+use axum::extract::{Path, State};
+use entities::twitch_user;
+use sea_orm::*;
+
+#[axum::debug_handler]
+pub async fn get_user_profile(
+  State(interface_config): State<InterfaceConfig>,
+  Path(twitch_id): Path<String>,
+) -> Result<axum::Json<UserProfileDto>, AppError> {
+  tracing::info!("Got a user profile request for: {twitch_id:?}");
+
+  let database_connection = interface_config.database_connection();
+
+  let Some(user) = twitch_user::Entity::find()
+    .filter(twitch_user::Column::TwitchId.eq(&twitch_id))
+    .one(database_connection)
+    .await?
+  else {
+    return Err(AppError::CouldNotFindUserByTwitchId { user_id: twitch_id });
+  };
+
+  let user_profile = UserProfileDto::from_user(user, database_connection).await?;
+
+  Ok(axum::Json(user_profile))
+}
@@ -49,13 +49,7 @@ pub async fn get_raids(
     .pagination_parameters
     .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
 
-  let Some(user) = query_payload
-    .get_user_query()?
-    .one(database_connection)
-    .await?
-  else {
-    return Err(query_payload.get_missing_user_error());
-  };
+  let user = query_payload.get_user(database_connection).await?;
   let raid_query = get_raids_query(&user, &query_payload, database_connection).await?;
   let paginate_raids = raid_query.paginate(database_connection, pagination.page_size);
 
@@ -89,13 +83,12 @@ async fn get_raids_query(
 ) -> Result<Select<raid::Entity>, AppError> {
   let mut raid_query = raid::Entity::find().filter(raid::Column::TwitchUserId.eq(user.id));
 
-  let maybe_raider = query_payload.raider.get_maybe_user_query();
-
-  if let Some(raider_query) = maybe_raider {
-    let Some(raider) = raider_query.one(database_connection).await? else {
-      return Err(query_payload.raider.get_missing_user_error());
-    };
+  let maybe_raider = query_payload
+    .raider
+    .get_maybe_user(database_connection)
+    .await?;
 
+  if let Some(raider) = maybe_raider {
     raid_query = raid_query.filter(raid::Column::RaiderTwitchUserId.eq(raider.id));
   }
 
@@ -31,10 +31,7 @@ pub async fn get_streams(
     .pagination_parameters
     .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
 
-  let user_query = query_payload.get_user_query()?;
-  let Some(user) = user_query.one(database_connection).await? else {
-    return Err(query_payload.get_missing_user_error());
-  };
+  let user = query_payload.get_user(database_connection).await?;
   let stream_query = stream::Entity::find().filter(stream::Column::TwitchUserId.eq(user.id));
   let paginated_streams = stream_query.paginate(database_connection, pagination.page_size);
 
@@ -47,7 +44,7 @@ pub async fn get_streams(
   let mut stream_response =
     StreamDto::response_from_stream_list(&user, fetched_paginated_streams, database_connection)
       .await?;
-  stream_response.streams.sort_by(|lhs, rhs| rhs.id.cmp(&lhs.id));
+  stream_response.streams.sort_by_key(|stream| std::cmp::Reverse(stream.id));
 
   Ok(axum::Json(PaginatedResponse {
     data: stream_response,
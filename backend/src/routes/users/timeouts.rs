@@ -0,0 +1,132 @@
+use crate::data_transfer_objects::user_timeout::UserTimeoutDto;
+use crate::response_models::paginated_parameters::PaginationParameters;
+use crate::response_models::paginatied_response::{PaginatedResponse, Pagination};
+use crate::routes::helpers::get_channel::get_channel;
+use crate::routes::helpers::get_users::GetUsers;
+use crate::{app::InterfaceConfig, error::*};
+use axum::extract::{Path, Query, State};
+use entities::*;
+use entity::prelude::DateTimeUtc;
+use sea_orm::*;
+
+const MAX_PAGE_SIZE: u64 = 100;
+const MIN_PAGE_SIZE: u64 = 1;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TimeoutQuery {
+  maybe_login: Option<String>,
+  user_id: Option<String>,
+
+  /// The internal ID of the stream the timeouts happened in.
+  stream_id: Option<String>,
+  timeout_type: Option<TimeoutType>,
+  start_date: Option<DateTimeUtc>,
+  end_date: Option<DateTimeUtc>,
+
+  #[serde(flatten)]
+  pagination_parameters: PaginationParameters,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeoutType {
+  Permanent,
+  Temporary,
+}
+
+#[axum::debug_handler]
+pub async fn get_timeouts(
+  Query(query_payload): Query<TimeoutQuery>,
+  State(interface_config): State<InterfaceConfig>,
+  channel_login: Option<Path<String>>,
+) -> Result<axum::Json<PaginatedResponse<Vec<UserTimeoutDto>>>, AppError> {
+  tracing::info!("Got a timeout request for {channel_login:?}: {query_payload:?}");
+
+  let database_connection = interface_config.database_connection();
+  let pagination = query_payload
+    .pagination_parameters
+    .clamped_page_size(MIN_PAGE_SIZE, MAX_PAGE_SIZE);
+
+  let timeout_query = get_timeout_query(&query_payload, channel_login, database_connection).await?;
+  let paginated_timeouts = timeout_query.paginate(database_connection, pagination.page_size);
+
+  let timeouts = paginated_timeouts.fetch_page(pagination.page).await?;
+  let ItemsAndPagesNumber {
+    number_of_items,
+    number_of_pages,
+  } = paginated_timeouts.num_items_and_pages().await?;
+
+  let timeout_dtos = UserTimeoutDto::from_timeout_list(timeouts, database_connection).await?;
+
+  Ok(axum::Json(PaginatedResponse {
+    data: timeout_dtos,
+    pagination: Pagination {
+      total_items: number_of_items,
+      total_pages: number_of_pages,
+      page: pagination.page,
+      page_size: pagination.page_size,
+    },
+  }))
+}
+
+/// Builds the timeout query from the given filters.
+///
+/// A user or channel is required so the query isn't run across every timeout in the database.
+async fn get_timeout_query(
+  query_payload: &TimeoutQuery,
+  channel_login: Option<Path<String>>,
+  database_connection: &DatabaseConnection,
+) -> Result<Select<user_timeout::Entity>, AppError> {
+  let mut timeout_query = user_timeout::Entity::find();
+
+  if let Some(user) = query_payload.get_maybe_user(database_connection).await? {
+    timeout_query = timeout_query.filter(user_timeout::Column::TwitchUserId.eq(user.id));
+  } else if channel_login.is_none() {
+    return Err(AppError::NoQueryParameterFound);
+  }
+
+  if let Some(Path(channel_login)) = channel_login {
+    let channel = get_channel(channel_login, database_connection).await?;
+
+    timeout_query = timeout_query.filter(user_timeout::Column::ChannelId.eq(channel.id));
+  }
+
+  if let Some(stream_id) = &query_payload.stream_id {
+    let Ok(stream_id) = stream_id.parse::<i32>() else {
+      return Err(AppError::InvalidQueryParameter {
+        parameter: "stream_id",
+        value: stream_id.to_owned(),
+      });
+    };
+
+    timeout_query = timeout_query.filter(user_timeout::Column::StreamId.eq(stream_id));
+  }
+
+  if let Some(timeout_type) = query_payload.timeout_type {
+    let is_permanent = matches!(timeout_type, TimeoutType::Permanent);
+
+    timeout_query = timeout_query.filter(user_timeout::Column::IsPermanent.eq(is_permanent));
+  }
+
+  if let Some(start_date) = query_payload.start_date {
+    timeout_query = timeout_query.filter(user_timeout::Column::Timestamp.gte(start_date));
+  }
+
+  if let Some(end_date) = query_payload.end_date {
+    timeout_query = timeout_query.filter(user_timeout::Column::Timestamp.lte(end_date));
+  }
+
+  timeout_query = timeout_query.order_by(user_timeout::Column::Timestamp, Order::Desc);
+
+  Ok(timeout_query)
+}
+
+impl GetUsers for TimeoutQuery {
+  fn get_login(&self) -> Option<&str> {
+    self.maybe_login.as_deref()
+  }
+
+  fn get_twitch_id(&self) -> Option<&str> {
+    self.user_id.as_deref()
+  }
+}
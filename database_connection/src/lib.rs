@@ -65,6 +65,7 @@ async fn run_migration(database: &DatabaseConnection) -> anyhow::Result<()> {
 
   // Ensure that all expected tables exist before attempting to finish the migration.
   let check_tables = [
+    entities::chatter_presence::Entity.table_name(),
     entities::donation_event::Entity.table_name(),
     entities::emote::Entity.table_name(),
     entities::emote_usage::Entity.table_name(),
@@ -73,6 +74,7 @@ async fn run_migration(database: &DatabaseConnection) -> anyhow::Result<()> {
     entities::raid::Entity.table_name(),
     entities::stream::Entity.table_name(),
     entities::stream_message::Entity.table_name(),
+    entities::stream_viewer_sample::Entity.table_name(),
     entities::subscription_event::Entity.table_name(),
     entities::twitch_user::Entity.table_name(),
     entities::twitch_user_name_change::Entity.table_name(),
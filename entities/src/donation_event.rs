@@ -21,6 +21,10 @@ pub struct Model {
   pub origin_id: Option<String>,
   #[sea_orm(unique)]
   pub source_id: Option<String>,
+  /// The ISO 4217 code of the currency a Streamlabs donation was made in.
+  pub currency: Option<String>,
+  /// The login name of the chat bot that announced a Streamlabs donation.
+  pub donation_bot_login: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
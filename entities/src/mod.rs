@@ -2,19 +2,26 @@
 
 pub mod prelude;
 
+pub mod account_status_change;
+pub mod backfill_progress;
+pub mod chatter_presence;
 pub mod donation_event;
 pub mod emote;
 pub mod emote_usage;
+pub mod exchange_rate;
 pub mod gift_sub_recipient;
+pub mod job_run;
 pub mod muted_vod_segment;
 pub mod raid;
 pub mod sea_orm_active_enums;
 pub mod stream;
 pub mod stream_message;
 pub mod stream_name;
+pub mod stream_viewer_sample;
 pub mod subscription_event;
 pub mod twitch_user;
 pub mod twitch_user_name_change;
 pub mod twitch_user_unknown_user_association;
 pub mod unknown_user;
+pub mod unknown_user_match;
 pub mod user_timeout;
@@ -1,17 +1,24 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.7
 
+pub use super::account_status_change::Entity as AccountStatusChange;
+pub use super::backfill_progress::Entity as BackfillProgress;
+pub use super::chatter_presence::Entity as ChatterPresence;
 pub use super::donation_event::Entity as DonationEvent;
 pub use super::emote::Entity as Emote;
 pub use super::emote_usage::Entity as EmoteUsage;
+pub use super::exchange_rate::Entity as ExchangeRate;
 pub use super::gift_sub_recipient::Entity as GiftSubRecipient;
+pub use super::job_run::Entity as JobRun;
 pub use super::muted_vod_segment::Entity as MutedVodSegment;
 pub use super::raid::Entity as Raid;
 pub use super::stream::Entity as Stream;
 pub use super::stream_message::Entity as StreamMessage;
 pub use super::stream_name::Entity as StreamName;
+pub use super::stream_viewer_sample::Entity as StreamViewerSample;
 pub use super::subscription_event::Entity as SubscriptionEvent;
 pub use super::twitch_user::Entity as TwitchUser;
 pub use super::twitch_user_name_change::Entity as TwitchUserNameChange;
 pub use super::twitch_user_unknown_user_association::Entity as TwitchUserUnknownUserAssociation;
 pub use super::unknown_user::Entity as UnknownUser;
+pub use super::unknown_user_match::Entity as UnknownUserMatch;
 pub use super::user_timeout::Entity as UserTimeout;
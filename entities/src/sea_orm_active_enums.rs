@@ -3,6 +3,28 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "account_status")]
+pub enum AccountStatus {
+  #[sea_orm(string_value = "active")]
+  Active,
+  #[sea_orm(string_value = "suspended")]
+  Suspended,
+  #[sea_orm(string_value = "deleted")]
+  Deleted,
+  #[sea_orm(string_value = "renamed")]
+  Renamed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "content_mode")]
+pub enum ContentMode {
+  #[sea_orm(string_value = "raw")]
+  Raw,
+  #[sea_orm(string_value = "hashed")]
+  Hashed,
+  #[sea_orm(string_value = "redacted")]
+  Redacted,
+}
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "event_type")]
 pub enum EventType {
@@ -25,3 +47,13 @@ pub enum ExternalService {
   #[sea_orm(string_value = "franker_face_z")]
   FrankerFaceZ,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "match_status")]
+pub enum MatchStatus {
+  #[sea_orm(string_value = "pending")]
+  Pending,
+  #[sea_orm(string_value = "confirmed")]
+  Confirmed,
+  #[sea_orm(string_value = "rejected")]
+  Rejected,
+}
@@ -20,6 +20,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+  #[sea_orm(has_many = "super::chatter_presence::Entity")]
+  ChatterPresence,
   #[sea_orm(has_many = "super::donation_event::Entity")]
   DonationEvent,
   #[sea_orm(has_many = "super::muted_vod_segment::Entity")]
@@ -30,6 +32,8 @@ pub enum Relation {
   StreamMessage,
   #[sea_orm(has_many = "super::stream_name::Entity")]
   StreamName,
+  #[sea_orm(has_many = "super::stream_viewer_sample::Entity")]
+  StreamViewerSample,
   #[sea_orm(has_many = "super::subscription_event::Entity")]
   SubscriptionEvent,
   #[sea_orm(
@@ -44,6 +48,12 @@ pub enum Relation {
   UserTimeout,
 }
 
+impl Related<super::chatter_presence::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::ChatterPresence.def()
+  }
+}
+
 impl Related<super::donation_event::Entity> for Entity {
   fn to() -> RelationDef {
     Relation::DonationEvent.def()
@@ -74,6 +84,12 @@ impl Related<super::stream_name::Entity> for Entity {
   }
 }
 
+impl Related<super::stream_viewer_sample::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::StreamViewerSample.def()
+  }
+}
+
 impl Related<super::subscription_event::Entity> for Entity {
   fn to() -> RelationDef {
     Relation::SubscriptionEvent.def()
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.7
 
+use super::sea_orm_active_enums::ContentMode;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -16,9 +17,12 @@ pub struct Model {
   pub twitch_user_id: i32,
   pub channel_id: i32,
   pub stream_id: Option<i32>,
-  pub is_subscriber: i8,
+  pub is_subscriber: Option<i8>,
   #[sea_orm(unique)]
   pub origin_id: Option<String>,
+  pub word_count: Option<i32>,
+  pub content_length: Option<i32>,
+  pub content_mode: ContentMode,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
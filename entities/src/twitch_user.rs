@@ -12,10 +12,13 @@ pub struct Model {
   pub twitch_id: i32,
   pub display_name: String,
   pub login_name: String,
+  pub last_verified_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+  #[sea_orm(has_many = "super::account_status_change::Entity")]
+  AccountStatusChange,
   #[sea_orm(has_many = "super::gift_sub_recipient::Entity")]
   GiftSubRecipient,
   #[sea_orm(has_many = "super::stream::Entity")]
@@ -26,6 +29,12 @@ pub enum Relation {
   TwitchUserUnknownUserAssociation,
 }
 
+impl Related<super::account_status_change::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::AccountStatusChange.def()
+  }
+}
+
 impl Related<super::gift_sub_recipient::Entity> for Entity {
   fn to() -> RelationDef {
     Relation::GiftSubRecipient.def()
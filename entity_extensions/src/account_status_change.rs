@@ -0,0 +1,156 @@
+use crate::errors::EntityExtensionError;
+use chrono::Utc;
+use entities::account_status_change;
+use entities::sea_orm_active_enums::AccountStatus;
+use sea_orm::*;
+use std::collections::HashMap;
+
+/// Returns the most recently recorded status change of each user. Users without a recorded status are left out.
+pub async fn get_latest_status_changes(
+  user_ids: &[i32],
+  database_connection: &DatabaseConnection,
+) -> Result<HashMap<i32, account_status_change::Model>, EntityExtensionError> {
+  if user_ids.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  let status_changes = get_status_history_query(user_ids)
+    .all(database_connection)
+    .await?;
+
+  Ok(
+    status_changes
+      .into_iter()
+      .map(|status_change| (status_change.twitch_user_id, status_change))
+      .collect(),
+  )
+}
+
+/// Returns every status recorded for the user, oldest first.
+pub async fn get_status_history(
+  user_id: i32,
+  database_connection: &DatabaseConnection,
+) -> Result<Vec<account_status_change::Model>, EntityExtensionError> {
+  get_status_history_query(&[user_id])
+    .all(database_connection)
+    .await
+    .map_err(Into::into)
+}
+
+/// Returns the state of the account from its latest recorded status.
+///
+/// A rename leaves the account active, and users without a recorded status are assumed to be active.
+pub fn current_status(latest_status: Option<&AccountStatus>) -> AccountStatus {
+  match latest_status {
+    None | Some(AccountStatus::Renamed) => AccountStatus::Active,
+    Some(status) => status.clone(),
+  }
+}
+
+/// Records each user's status if it differs from the one last recorded for them, returning the changes.
+///
+/// Renames are events rather than states, so use [`record_rename`] for those.
+pub async fn record_statuses(
+  statuses: &[(i32, AccountStatus)],
+  database_connection: &DatabaseConnection,
+) -> Result<Vec<(i32, AccountStatus)>, EntityExtensionError> {
+  let user_ids: Vec<i32> = statuses.iter().map(|(user_id, _)| *user_id).collect();
+  let latest_statuses: HashMap<i32, AccountStatus> =
+    get_latest_status_changes(&user_ids, database_connection)
+      .await?
+      .into_iter()
+      .map(|(user_id, status_change)| (user_id, status_change.status))
+      .collect();
+  let changed_statuses = get_changed_statuses(&latest_statuses, statuses);
+
+  insert_statuses(&changed_statuses, database_connection).await?;
+
+  Ok(changed_statuses)
+}
+
+/// Records that the user changed their name.
+pub async fn record_rename(
+  user_id: i32,
+  database_connection: &DatabaseConnection,
+) -> Result<(), EntityExtensionError> {
+  insert_statuses(&[(user_id, AccountStatus::Renamed)], database_connection).await
+}
+
+async fn insert_statuses(
+  statuses: &[(i32, AccountStatus)],
+  database_connection: &DatabaseConnection,
+) -> Result<(), EntityExtensionError> {
+  if statuses.is_empty() {
+    return Ok(());
+  }
+
+  let status_changes =
+    statuses
+      .iter()
+      .map(|(user_id, status)| account_status_change::ActiveModel {
+        twitch_user_id: Set(*user_id),
+        status: Set(status.clone()),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+      });
+
+  account_status_change::Entity::insert_many(status_changes)
+    .exec(database_connection)
+    .await?;
+
+  Ok(())
+}
+
+fn get_status_history_query(user_ids: &[i32]) -> Select<account_status_change::Entity> {
+  account_status_change::Entity::find()
+    .filter(account_status_change::Column::TwitchUserId.is_in(user_ids.iter().copied()))
+    .order_by_asc(account_status_change::Column::CreatedAt)
+    .order_by_asc(account_status_change::Column::Id)
+}
+
+fn get_changed_statuses(
+  latest_statuses: &HashMap<i32, AccountStatus>,
+  statuses: &[(i32, AccountStatus)],
+) -> Vec<(i32, AccountStatus)> {
+  statuses
+    .iter()
+    .filter(|(user_id, status)| {
+      let current_status = current_status(latest_statuses.get(user_id));
+
+      // A deleted account stays deleted, even once its old login is free again and it looks suspended.
+      let is_still_deleted =
+        current_status == AccountStatus::Deleted && *status == AccountStatus::Suspended;
+
+      current_status != *status && !is_still_deleted
+    })
+    .cloned()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn only_statuses_that_changed_are_recorded() {
+    let latest_statuses = HashMap::from([
+      (1, AccountStatus::Suspended),
+      (2, AccountStatus::Active),
+      (5, AccountStatus::Renamed),
+      (6, AccountStatus::Deleted),
+    ]);
+    let statuses = vec![
+      (1, AccountStatus::Active),
+      (2, AccountStatus::Active),
+      (3, AccountStatus::Active),
+      (4, AccountStatus::Suspended),
+      (5, AccountStatus::Active),
+      (6, AccountStatus::Suspended),
+    ];
+
+    assert_eq!(
+      get_changed_statuses(&latest_statuses, &statuses),
+      vec![(1, AccountStatus::Active), (4, AccountStatus::Suspended)]
+    );
+  }
+}
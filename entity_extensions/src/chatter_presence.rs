@@ -0,0 +1,325 @@
+use crate::errors::EntityExtensionError;
+use chrono::{DateTime, Utc};
+use entities::{chatter_presence, stream, stream_message, stream_viewer_sample};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::*;
+
+/// Closes every presence interval that's still open at the last activity recorded for its stream or channel.
+///
+/// Used on startup, as it's unknown when chatters left while the tracker wasn't running. Closing them at startup
+/// would count them as present for the whole time the tracker was down. Intervals opened after the last recorded
+/// activity are closed when they were opened.
+pub async fn close_open_presences(
+  database_connection: &DatabaseConnection,
+) -> Result<u64, EntityExtensionError> {
+  let open_presence_groups: Vec<(i32, Option<i32>)> = chatter_presence::Entity::find()
+    .select_only()
+    .column(chatter_presence::Column::ChannelId)
+    .column(chatter_presence::Column::StreamId)
+    .filter(chatter_presence::Column::PartedAt.is_null())
+    .distinct()
+    .into_tuple()
+    .all(database_connection)
+    .await?;
+  let mut closed_presences = 0;
+
+  for (channel_id, stream_id) in open_presence_groups {
+    let last_activity = get_last_activity(channel_id, stream_id, database_connection).await?;
+    let open_in_group = Condition::all()
+      .add(chatter_presence::Column::ChannelId.eq(channel_id))
+      .add(match stream_id {
+        Some(stream_id) => chatter_presence::Column::StreamId.eq(stream_id),
+        None => chatter_presence::Column::StreamId.is_null(),
+      })
+      .add(chatter_presence::Column::PartedAt.is_null());
+
+    if let Some(last_activity) = last_activity {
+      closed_presences += chatter_presence::Entity::update_many()
+        .col_expr(
+          chatter_presence::Column::PartedAt,
+          Expr::value(last_activity),
+        )
+        .filter(open_in_group.clone())
+        .filter(chatter_presence::Column::JoinedAt.lte(last_activity))
+        .exec(database_connection)
+        .await?
+        .rows_affected;
+    }
+
+    closed_presences += chatter_presence::Entity::update_many()
+      .col_expr(
+        chatter_presence::Column::PartedAt,
+        Expr::col(chatter_presence::Column::JoinedAt).into(),
+      )
+      .filter(open_in_group)
+      .exec(database_connection)
+      .await?
+      .rows_affected;
+  }
+
+  Ok(closed_presences)
+}
+
+/// Returns when activity was last recorded for the stream, or for the channel if there's no stream.
+///
+/// A stream's end is used if it has one, otherwise its last message or viewer count sample.
+async fn get_last_activity(
+  channel_id: i32,
+  stream_id: Option<i32>,
+  database_connection: &DatabaseConnection,
+) -> Result<Option<DateTime<Utc>>, DbErr> {
+  let Some(stream_id) = stream_id else {
+    return get_last_message_timestamp(
+      stream_message::Column::ChannelId.eq(channel_id),
+      database_connection,
+    )
+    .await;
+  };
+  let stream_end: Option<Option<DateTime<Utc>>> = stream::Entity::find_by_id(stream_id)
+    .select_only()
+    .column(stream::Column::EndTimestamp)
+    .into_tuple()
+    .one(database_connection)
+    .await?;
+
+  if let Some(Some(stream_end)) = stream_end {
+    return Ok(Some(stream_end));
+  }
+
+  let last_message = get_last_message_timestamp(
+    stream_message::Column::StreamId.eq(stream_id),
+    database_connection,
+  )
+  .await?;
+  let last_viewer_sample: Option<Option<DateTime<Utc>>> = stream_viewer_sample::Entity::find()
+    .select_only()
+    .column_as(
+      stream_viewer_sample::Column::Timestamp.max(),
+      "last_timestamp",
+    )
+    .filter(stream_viewer_sample::Column::StreamId.eq(stream_id))
+    .into_tuple()
+    .one(database_connection)
+    .await?;
+
+  Ok(last_message.max(last_viewer_sample.flatten()))
+}
+
+async fn get_last_message_timestamp(
+  condition: SimpleExpr,
+  database_connection: &DatabaseConnection,
+) -> Result<Option<DateTime<Utc>>, DbErr> {
+  let last_message: Option<Option<DateTime<Utc>>> = stream_message::Entity::find()
+    .select_only()
+    .column_as(stream_message::Column::Timestamp.max(), "last_timestamp")
+    .filter(condition)
+    .into_tuple()
+    .one(database_connection)
+    .await?;
+
+  Ok(last_message.flatten())
+}
+
+/// Moves the chatters present in the stream's channel over to the stream once it starts.
+///
+/// Their open intervals are closed at the stream's start, and new ones are opened for the stream from that point.
+pub async fn carry_over_to_stream(
+  stream: &stream::Model,
+  database_connection: &DatabaseConnection,
+) -> Result<(), EntityExtensionError> {
+  let stream_start = stream.start_timestamp.unwrap_or_else(Utc::now);
+  let open_presences = chatter_presence::Entity::find()
+    .filter(chatter_presence::Column::ChannelId.eq(stream.twitch_user_id))
+    .filter(chatter_presence::Column::PartedAt.is_null())
+    .filter(
+      Condition::any()
+        .add(chatter_presence::Column::StreamId.is_null())
+        .add(chatter_presence::Column::StreamId.ne(stream.id)),
+    )
+    .all(database_connection)
+    .await?;
+
+  if open_presences.is_empty() {
+    return Ok(());
+  }
+
+  let presence_ids: Vec<i32> = open_presences.iter().map(|presence| presence.id).collect();
+
+  chatter_presence::Entity::update_many()
+    .col_expr(
+      chatter_presence::Column::PartedAt,
+      Expr::value(stream_start),
+    )
+    .filter(chatter_presence::Column::Id.is_in(presence_ids))
+    .exec(database_connection)
+    .await?;
+
+  let stream_presences = open_presences
+    .into_iter()
+    .map(|presence| chatter_presence::ActiveModel {
+      twitch_user_id: Set(presence.twitch_user_id),
+      channel_id: Set(presence.channel_id),
+      stream_id: Set(Some(stream.id)),
+      joined_at: Set(stream_start),
+      parted_at: Set(None),
+      ..Default::default()
+    });
+
+  chatter_presence::Entity::insert_many(stream_presences)
+    .exec(database_connection)
+    .await?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::BTreeMap;
+
+  #[tokio::test]
+  async fn carry_over_to_stream_reopens_presences_for_the_stream() {
+    let stream_start = Utc::now();
+    let stream = stream::Model {
+      id: 2,
+      twitch_stream_id: 2,
+      start_timestamp: Some(stream_start),
+      end_timestamp: None,
+      twitch_user_id: 1,
+      twitch_vod_id: None,
+      title: None,
+    };
+    let open_presence = chatter_presence::Model {
+      id: 5,
+      twitch_user_id: 3,
+      channel_id: 1,
+      stream_id: None,
+      joined_at: stream_start - chrono::Duration::minutes(10),
+      parted_at: None,
+    };
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![open_presence]])
+      .append_exec_results([
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 1,
+        },
+        MockExecResult {
+          last_insert_id: 6,
+          rows_affected: 1,
+        },
+      ])
+      .into_connection();
+
+    carry_over_to_stream(&stream, &mock_database).await.unwrap();
+
+    let transaction_log = mock_database.into_transaction_log();
+    let statements: Vec<String> = transaction_log
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.sql.clone())
+      .collect();
+
+    assert_eq!(statements.len(), 3);
+    assert!(statements[1].starts_with("UPDATE `chatter_presence` SET `parted_at`"));
+    assert!(statements[2].starts_with("INSERT INTO `chatter_presence`"));
+  }
+
+  #[tokio::test]
+  async fn carry_over_to_stream_does_nothing_without_open_presences() {
+    let stream = stream::Model {
+      id: 2,
+      twitch_stream_id: 2,
+      start_timestamp: Some(Utc::now()),
+      end_timestamp: None,
+      twitch_user_id: 1,
+      twitch_vod_id: None,
+      title: None,
+    };
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results::<chatter_presence::Model, _, _>([vec![]])
+      .into_connection();
+
+    carry_over_to_stream(&stream, &mock_database).await.unwrap();
+
+    assert_eq!(mock_database.into_transaction_log().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn close_open_presences_closes_them_at_the_last_recorded_activity() {
+    let stream_end = Utc::now() - chrono::Duration::hours(8);
+    let last_channel_message = Utc::now() - chrono::Duration::hours(2);
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![
+        BTreeMap::from([
+          ("channel_id", Value::Int(Some(1))),
+          ("stream_id", Value::Int(Some(2))),
+        ]),
+        BTreeMap::from([
+          ("channel_id", Value::Int(Some(1))),
+          ("stream_id", Value::Int(None)),
+        ]),
+      ]])
+      .append_query_results([vec![BTreeMap::from([(
+        "end_timestamp",
+        Value::ChronoDateTimeUtc(Some(Box::new(stream_end))),
+      )])]])
+      .append_query_results([vec![BTreeMap::from([(
+        "last_timestamp",
+        Value::ChronoDateTimeUtc(Some(Box::new(last_channel_message))),
+      )])]])
+      .append_exec_results([
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 3,
+        },
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 1,
+        },
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 2,
+        },
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 0,
+        },
+      ])
+      .into_connection();
+
+    let closed_presences = close_open_presences(&mock_database).await.unwrap();
+
+    let statements: Vec<String> = mock_database
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect();
+    let updates: Vec<&String> = statements
+      .iter()
+      .filter(|statement| statement.starts_with("UPDATE"))
+      .collect();
+    let format_timestamp =
+      |timestamp: DateTime<Utc>| timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    assert_eq!(closed_presences, 6);
+    assert_eq!(updates.len(), 4);
+    assert!(updates[0].contains(&format!(
+      "SET `parted_at` = '{}",
+      format_timestamp(stream_end)
+    )));
+    assert!(updates[0].contains("`chatter_presence`.`stream_id` = 2"));
+    assert!(updates[1].contains("SET `parted_at` = `joined_at`"));
+    assert!(updates[2].contains(&format!(
+      "SET `parted_at` = '{}",
+      format_timestamp(last_channel_message)
+    )));
+    assert!(updates[2].contains("`chatter_presence`.`stream_id` IS NULL"));
+    assert!(
+      !statements
+        .iter()
+        .any(|statement| statement.contains("`stream_viewer_sample`"))
+    );
+  }
+}
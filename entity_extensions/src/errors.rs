@@ -3,6 +3,9 @@ pub enum EntityExtensionError {
   #[error("{}", .0)]
   ReqwestError(#[from] reqwest::Error),
 
+  #[error("{}", .0)]
+  HelixError(#[from] helix_client::HelixError),
+
   #[error("{}", .0)]
   SeaOrmDbError(#[from] sea_orm::error::DbErr),
 
@@ -45,4 +48,10 @@ pub enum EntityExtensionError {
 
   #[error("Received a failed response from {}. Code: {}", location, code)]
   FailedResponse { location: &'static str, code: u16 },
+
+  #[error("User {} is a tracked channel and can't be erased.", .0)]
+  CannotEraseChannel(i32),
+
+  #[error("Missing API key for https://app.exchangerate-api.com/")]
+  MissingExchangeRateApiKey,
 }
@@ -0,0 +1,392 @@
+use crate::errors::EntityExtensionError;
+use app_config::{AppConfig, secret_string::Secret};
+use chrono::NaiveDate;
+use entities::{donation_event, exchange_rate};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// The currency that every stored rate is relative to.
+pub const EXCHANGE_RATE_BASE_CURRENCY: &str = "USD";
+const EXCHANGERATE_URL: &str = "https://v6.exchangerate-api.com/v6/{API_KEY}/latest/{FROM}";
+
+/// Retrieves today's rates for `base_currency` from https://app.exchangerate-api.com/, keyed by currency code.
+pub async fn fetch_latest_exchange_rates(
+  base_currency: &str,
+  reqwest_client: &reqwest::Client,
+) -> Result<HashMap<String, f64>, EntityExtensionError> {
+  let Some(api_key) = AppConfig::exchange_rate_api_key() else {
+    return Err(EntityExtensionError::MissingExchangeRateApiKey);
+  };
+  let request_url = EXCHANGERATE_URL
+    .replace(
+      "{API_KEY}",
+      Secret::read_secret_string(api_key.read_value()),
+    )
+    .replace("{FROM}", base_currency);
+  let response = reqwest_client.get(request_url).send().await?;
+
+  if !response.status().is_success() {
+    return Err(EntityExtensionError::FailedResponse {
+      location: "exchange rate fetch",
+      code: response.status().as_u16(),
+    });
+  }
+
+  let response_body = response.text().await?;
+  let Value::Object(data) = serde_json::from_str(&response_body)? else {
+    return Err(EntityExtensionError::UnknownResponseBody {
+      location: "exchange rate response body",
+      response: response_body,
+    });
+  };
+  let Some(Value::Object(conversion_rates)) = data.get("conversion_rates") else {
+    return Err(EntityExtensionError::UnknownResponseBody {
+      location: "conversion_rates",
+      response: response_body,
+    });
+  };
+
+  Ok(
+    conversion_rates
+      .iter()
+      .filter_map(|(currency, rate)| Some((currency.to_uppercase(), rate.as_f64()?)))
+      .collect(),
+  )
+}
+
+/// Stores the rates for the given date, replacing any that were already stored for it.
+pub async fn store_exchange_rates(
+  date: NaiveDate,
+  base_currency: &str,
+  rates: &HashMap<String, f64>,
+  database_connection: &DatabaseConnection,
+) -> Result<(), EntityExtensionError> {
+  if rates.is_empty() {
+    return Ok(());
+  }
+
+  let active_models = rates
+    .iter()
+    .map(|(target_currency, rate)| exchange_rate::ActiveModel {
+      date: Set(date),
+      base_currency: Set(base_currency.to_uppercase()),
+      target_currency: Set(target_currency.to_uppercase()),
+      rate: Set(*rate),
+      ..Default::default()
+    });
+
+  exchange_rate::Entity::insert_many(active_models)
+    .on_conflict(
+      OnConflict::columns([
+        exchange_rate::Column::Date,
+        exchange_rate::Column::BaseCurrency,
+        exchange_rate::Column::TargetCurrency,
+      ])
+      .update_column(exchange_rate::Column::Rate)
+      .to_owned(),
+    )
+    .exec(database_connection)
+    .await?;
+
+  Ok(())
+}
+
+/// Stored daily exchange rates relative to [`EXCHANGE_RATE_BASE_CURRENCY`], used to convert donations at the rate
+/// of the day they were made.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExchangeRates {
+  rates_by_date: BTreeMap<NaiveDate, HashMap<String, f64>>,
+}
+
+impl ExchangeRates {
+  pub fn new(rates: Vec<exchange_rate::Model>) -> Self {
+    let rates_by_date = rates
+      .into_iter()
+      .filter(|rate| {
+        rate
+          .base_currency
+          .eq_ignore_ascii_case(EXCHANGE_RATE_BASE_CURRENCY)
+      })
+      .fold(
+        BTreeMap::new(),
+        |mut rates_by_date: BTreeMap<NaiveDate, HashMap<String, f64>>, rate| {
+          rates_by_date
+            .entry(rate.date)
+            .or_default()
+            .insert(rate.target_currency.to_uppercase(), rate.rate);
+
+          rates_by_date
+        },
+      );
+
+    Self { rates_by_date }
+  }
+
+  /// Loads every rate needed to convert donations made between the two dates.
+  ///
+  /// This includes the last day of rates before `start_date`, and if there's nothing at or before `end_date`, the
+  /// first day of rates after it.
+  pub async fn load(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Self, EntityExtensionError> {
+    let last_date_before_start = exchange_rate::Entity::find()
+      .filter(exchange_rate::Column::Date.lte(start_date))
+      .order_by_desc(exchange_rate::Column::Date)
+      .one(database_connection)
+      .await?
+      .map(|rate| rate.date);
+    let mut rates = exchange_rate::Entity::find()
+      .filter(exchange_rate::Column::Date.gte(last_date_before_start.unwrap_or(start_date)))
+      .filter(exchange_rate::Column::Date.lte(end_date))
+      .all(database_connection)
+      .await?;
+
+    if rates.is_empty() {
+      let first_date_after_end = exchange_rate::Entity::find()
+        .filter(exchange_rate::Column::Date.gt(end_date))
+        .order_by_asc(exchange_rate::Column::Date)
+        .one(database_connection)
+        .await?
+        .map(|rate| rate.date);
+
+      if let Some(first_date_after_end) = first_date_after_end {
+        rates = exchange_rate::Entity::find()
+          .filter(exchange_rate::Column::Date.eq(first_date_after_end))
+          .all(database_connection)
+          .await?;
+      }
+    }
+
+    Ok(Self::new(rates))
+  }
+
+  /// Converts `amount` from one currency to another at the rate of `date`.
+  ///
+  /// Uses the latest rates on or before the date, falling back to the earliest rates after it for donations made
+  /// before rates were being stored. Returns None if either currency has no rate.
+  pub fn convert(&self, amount: f64, from: &str, to: &str, date: NaiveDate) -> Option<f64> {
+    if from.eq_ignore_ascii_case(to) {
+      return Some(amount);
+    }
+
+    let from_rate = self.rate(from, date)?;
+    let to_rate = self.rate(to, date)?;
+
+    Some(amount / from_rate * to_rate)
+  }
+
+  /// Returns how much of `currency` one unit of the base currency was worth on the date.
+  fn rate(&self, currency: &str, date: NaiveDate) -> Option<f64> {
+    if currency.eq_ignore_ascii_case(EXCHANGE_RATE_BASE_CURRENCY) {
+      return Some(1.0);
+    }
+
+    let currency = currency.to_uppercase();
+
+    self
+      .rates_by_date
+      .range(..=date)
+      .rev()
+      .chain(self.rates_by_date.range(date..))
+      .find_map(|(_, rates)| rates.get(&currency).copied())
+  }
+}
+
+/// Converts donations into a target currency at the stored exchange rate of the day they were made.
+pub struct DonationCurrencyConverter {
+  exchange_rates: ExchangeRates,
+  target_currency: &'static str,
+}
+
+impl DonationCurrencyConverter {
+  /// Loads the exchange rates needed to convert the given donations into `target_currency`.
+  ///
+  /// Nothing is queried if every donation is already in the target currency.
+  pub async fn new(
+    donations: &[donation_event::Model],
+    target_currency: &'static str,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Self, EntityExtensionError> {
+    let donation_dates = donations
+      .iter()
+      .filter(|donation| {
+        donation
+          .currency
+          .as_ref()
+          .is_some_and(|currency| !currency.eq_ignore_ascii_case(target_currency))
+      })
+      .map(|donation| donation.timestamp.date_naive());
+    let (start_date, end_date) = donation_dates.fold((None, None), |(start, end), date| {
+      (
+        Some(start.map_or(date, |start: NaiveDate| start.min(date))),
+        Some(end.map_or(date, |end: NaiveDate| end.max(date))),
+      )
+    });
+
+    let exchange_rates = match (start_date, end_date) {
+      (Some(start_date), Some(end_date)) => {
+        ExchangeRates::load(start_date, end_date, database_connection).await?
+      }
+      _ => ExchangeRates::default(),
+    };
+
+    Ok(Self::with_rates(exchange_rates, target_currency))
+  }
+
+  pub fn with_rates(exchange_rates: ExchangeRates, target_currency: &'static str) -> Self {
+    Self {
+      exchange_rates,
+      target_currency,
+    }
+  }
+
+  /// Returns the donation amount in the target currency.
+  ///
+  /// Donations without a currency are assumed to already be in it.
+  pub fn convert(&self, donation: &donation_event::Model) -> Option<f32> {
+    let Some(currency) = &donation.currency else {
+      return Some(donation.amount);
+    };
+
+    self
+      .exchange_rates
+      .convert(
+        donation.amount as f64,
+        currency,
+        self.target_currency,
+        donation.timestamp.date_naive(),
+      )
+      .map(|amount| amount as f32)
+  }
+
+  /// Returns the donation amount in the target currency, logging a warning if it couldn't be converted.
+  pub fn convert_or_warn(&self, donation: &donation_event::Model) -> Option<f32> {
+    let converted_amount = self.convert(donation);
+
+    if converted_amount.is_none() {
+      tracing::warn!(
+        "Skipping donation {} of {} {:?}. No exchange rate to {} was stored for it.",
+        donation.id,
+        donation.amount,
+        donation.currency,
+        self.target_currency
+      );
+    }
+
+    converted_amount
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{TimeZone, Utc};
+  use entities::sea_orm_active_enums::EventType;
+
+  fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+  }
+
+  fn rate(id: i32, day: u32, target_currency: &str, rate: f64) -> exchange_rate::Model {
+    exchange_rate::Model {
+      id,
+      date: date(day),
+      base_currency: EXCHANGE_RATE_BASE_CURRENCY.to_string(),
+      target_currency: target_currency.to_string(),
+      rate,
+    }
+  }
+
+  #[test]
+  fn convert_uses_the_rate_of_the_donation_date() {
+    let exchange_rates = ExchangeRates::new(vec![
+      rate(1, 10, "GBP", 0.5),
+      rate(2, 20, "GBP", 0.8),
+      rate(3, 10, "EUR", 1.0),
+      rate(4, 20, "EUR", 0.9),
+    ]);
+
+    assert_eq!(
+      exchange_rates.convert(10.0, "USD", "GBP", date(15)),
+      Some(5.0)
+    );
+    assert_eq!(
+      exchange_rates.convert(10.0, "USD", "GBP", date(20)),
+      Some(8.0)
+    );
+    assert_eq!(
+      exchange_rates.convert(10.0, "eur", "gbp", date(12)),
+      Some(5.0)
+    );
+    assert_eq!(
+      exchange_rates.convert(9.0, "EUR", "USD", date(25)),
+      Some(10.0)
+    );
+  }
+
+  #[test]
+  fn convert_falls_back_to_the_earliest_rate_for_older_donations() {
+    let exchange_rates = ExchangeRates::new(vec![rate(1, 10, "GBP", 0.5)]);
+
+    assert_eq!(
+      exchange_rates.convert(10.0, "USD", "GBP", date(1)),
+      Some(5.0)
+    );
+  }
+
+  #[test]
+  fn convert_returns_none_for_unknown_currencies() {
+    let exchange_rates = ExchangeRates::new(vec![rate(1, 10, "GBP", 0.5)]);
+
+    assert_eq!(exchange_rates.convert(10.0, "JPY", "GBP", date(10)), None);
+    assert_eq!(
+      exchange_rates.convert(10.0, "JPY", "JPY", date(10)),
+      Some(10.0)
+    );
+  }
+
+  fn donation(amount: f32, currency: Option<&str>, day: u32) -> donation_event::Model {
+    donation_event::Model {
+      id: 1,
+      event_type: EventType::StreamlabsDonation,
+      amount,
+      timestamp: Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap(),
+      donator_twitch_user_id: None,
+      donation_receiver_twitch_user_id: 1,
+      stream_id: None,
+      subscription_tier: None,
+      unknown_user_id: None,
+      origin_id: None,
+      source_id: None,
+      currency: currency.map(str::to_string),
+      donation_bot_login: None,
+    }
+  }
+
+  #[test]
+  fn donation_converter_uses_the_rate_of_the_donation_day() {
+    let converter = DonationCurrencyConverter::with_rates(
+      ExchangeRates::new(vec![rate(1, 1, "GBP", 0.5), rate(2, 10, "GBP", 0.75)]),
+      "GBP",
+    );
+
+    assert_eq!(
+      converter.convert(&donation(10.0, Some("USD"), 5)),
+      Some(5.0)
+    );
+    assert_eq!(
+      converter.convert(&donation(10.0, Some("USD"), 12)),
+      Some(7.5)
+    );
+    assert_eq!(
+      converter.convert(&donation(10.0, Some("GBP"), 12)),
+      Some(10.0)
+    );
+    assert_eq!(converter.convert(&donation(10.0, None, 12)), Some(10.0));
+    assert_eq!(converter.convert(&donation(10.0, Some("JPY"), 12)), None);
+  }
+}
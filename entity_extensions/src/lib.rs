@@ -3,12 +3,23 @@
 
 pub mod prelude;
 
+pub mod account_status_change;
+pub mod chatter_presence;
 pub mod donation_event;
 pub mod emote;
 pub mod errors;
+pub mod exchange_rate;
 pub mod external_service;
+#[cfg(test)]
+mod mock_helix;
+pub mod name_index;
+pub mod retention;
 pub mod stream;
+pub mod stream_highlights;
 pub mod stream_message;
+pub mod time_buckets;
 pub mod twitch_user;
 pub mod twitch_user_unknown_user_association;
 pub mod unknown_user;
+pub mod unknown_user_match;
+pub mod user_erasure;
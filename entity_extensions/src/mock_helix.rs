@@ -0,0 +1,80 @@
+//! The mock Helix shared by every test in the crate, as the shared client can only be set once per test binary.
+
+use axum::Router;
+use axum::extract::RawQuery;
+use axum::routing::get;
+use serde_json::{Value, json};
+
+/// Makes the mock the shared Helix client.
+///
+/// - `streams` has `fallenshadow` live and `shadowchama` offline.
+/// - `users` knows `fallenshadow` with the Twitch ID 300 and `shadowchama` with the Twitch ID 400.
+pub(crate) fn use_mock_helix() {
+  helix_client::mock::use_mock_helix(|| {
+    Router::new()
+      .route(
+        "/streams",
+        get(|RawQuery(query): RawQuery| async move {
+          let requested_logins = query_values(query, "user_login");
+          let streams = [
+            json!({
+              "id": "40952121085",
+              "user_login": "fallenshadow",
+              "type": "live",
+              "started_at": "2025-05-08T00:02:29Z",
+              "viewer_count": 1234,
+            }),
+            json!({
+              "id": "40952121086",
+              "user_login": "shadowchama",
+              "type": "",
+              "started_at": "2025-05-08T00:02:29Z",
+              "viewer_count": 0,
+            }),
+          ];
+          let data: Vec<&Value> = streams
+            .iter()
+            .filter(|stream| {
+              requested_logins
+                .iter()
+                .any(|login| stream["user_login"] == **login)
+            })
+            .collect();
+
+          axum::Json(json!({ "data": data }))
+        }),
+      )
+      .route(
+        "/users",
+        get(|RawQuery(query): RawQuery| async move {
+          let requested_ids = query_values(query.clone(), "id");
+          let requested_logins = query_values(query, "login");
+          let users = [
+            json!({ "id": "300", "login": "fallenshadow", "display_name": "FallenShadow" }),
+            json!({ "id": "400", "login": "shadowchama", "display_name": "ShadowChama" }),
+          ];
+          let data: Vec<&Value> = users
+            .iter()
+            .filter(|user| {
+              requested_ids.iter().any(|id| user["id"] == **id)
+                || requested_logins
+                  .iter()
+                  .any(|login| user["login"] == login.to_lowercase())
+            })
+            .collect();
+
+          axum::Json(json!({ "data": data }))
+        }),
+      )
+  });
+}
+
+/// Returns every value of the query parameter.
+fn query_values(query: Option<String>, key: &str) -> Vec<String> {
+  let query = query.unwrap_or_default();
+
+  url::form_urlencoded::parse(query.as_bytes())
+    .filter(|(query_key, _)| query_key == key)
+    .map(|(_, value)| value.into_owned())
+    .collect()
+}
@@ -0,0 +1,501 @@
+use crate::errors::EntityExtensionError;
+use entities::{twitch_user, twitch_user_name_change};
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use strsim::jaro_winkler;
+
+/// The amount of rows read per query when loading the index.
+const LOAD_CHUNK_SIZE: u64 = 10_000;
+/// The amount of users sharing the most trigrams with a name that are scored against it.
+const MAX_SCORED_USERS: usize = 250;
+/// How long the index is kept before being rebuilt from the database.
+///
+/// Between rebuilds only new rows are added, so this is how long other processes keep matching the names of erased
+/// users.
+const REBUILD_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+static NAME_INDEX: LazyLock<RwLock<NameIndex>> = LazyLock::new(Default::default);
+
+tokio::task_local! {
+  static INJECTED_NAME_INDEX: NameIndex;
+}
+
+/// A trigram index over every login and display name a user has had, including the ones from their
+/// [`name changes`](twitch_user_name_change).
+///
+/// Names are lowercased and padded before being split, so short names and shared prefixes still produce trigrams.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+  names_by_user: HashMap<i32, HashSet<String>>,
+  users_by_trigram: HashMap<[char; 3], HashSet<i32>>,
+  last_user_id: i32,
+  last_name_change_id: i32,
+  built_at: Option<Instant>,
+}
+
+impl NameIndex {
+  pub fn insert_name(&mut self, user_id: i32, name: &str) {
+    let name = name.trim().to_lowercase();
+
+    if name.is_empty() {
+      return;
+    }
+
+    for trigram in padded_trigrams(&name) {
+      self
+        .users_by_trigram
+        .entry(trigram)
+        .or_default()
+        .insert(user_id);
+    }
+
+    self.names_by_user.entry(user_id).or_default().insert(name);
+  }
+
+  pub fn insert_user(&mut self, user: &twitch_user::Model) {
+    self.insert_name(user.id, &user.login_name);
+    self.insert_name(user.id, &user.display_name);
+    self.last_user_id = self.last_user_id.max(user.id);
+  }
+
+  pub fn insert_name_change(&mut self, name_change: &twitch_user_name_change::Model) {
+    [
+      &name_change.previous_login_name,
+      &name_change.previous_display_name,
+      &name_change.new_login_name,
+      &name_change.new_display_name,
+    ]
+    .into_iter()
+    .flatten()
+    .for_each(|name| self.insert_name(name_change.twitch_user_id, name));
+
+    self.last_name_change_id = self.last_name_change_id.max(name_change.id);
+  }
+
+  pub fn insert_rows(
+    &mut self,
+    users: &[twitch_user::Model],
+    name_changes: &[twitch_user_name_change::Model],
+  ) {
+    users.iter().for_each(|user| self.insert_user(user));
+    name_changes
+      .iter()
+      .for_each(|name_change| self.insert_name_change(name_change));
+  }
+
+  /// Removes every name of the user from the index.
+  pub fn remove_user(&mut self, user_id: i32) {
+    let Some(names) = self.names_by_user.remove(&user_id) else {
+      return;
+    };
+
+    for trigram in names.iter().flat_map(|name| padded_trigrams(name)) {
+      if let Some(user_ids) = self.users_by_trigram.get_mut(&trigram) {
+        user_ids.remove(&user_id);
+
+        if user_ids.is_empty() {
+          self.users_by_trigram.remove(&trigram);
+        }
+      }
+    }
+  }
+
+  /// Returns the IDs of the users with a name scoring at least `threshold` against the given one, along with their
+  /// best Jaro-Winkler score, closest first.
+  ///
+  /// Only the users sharing the most trigrams with the name are scored.
+  pub fn similar_users(&self, name: &str, threshold: f64, limit: usize) -> Vec<(i32, f64)> {
+    let name = name.trim().to_lowercase();
+    let mut shared_trigram_counts: HashMap<i32, usize> = HashMap::new();
+
+    for trigram in padded_trigrams(&name) {
+      for user_id in self.users_by_trigram.get(&trigram).into_iter().flatten() {
+        *shared_trigram_counts.entry(*user_id).or_default() += 1;
+      }
+    }
+
+    let mut most_shared: Vec<(i32, usize)> = shared_trigram_counts.into_iter().collect();
+    most_shared.sort_by(|(user_id, count), (other_user_id, other_count)| {
+      other_count.cmp(count).then(user_id.cmp(other_user_id))
+    });
+    most_shared.truncate(MAX_SCORED_USERS);
+
+    let mut similar_users: Vec<(i32, f64)> = most_shared
+      .into_iter()
+      .filter_map(|(user_id, _)| {
+        let score = self
+          .names_by_user
+          .get(&user_id)?
+          .iter()
+          .map(|user_name| jaro_winkler(user_name, &name))
+          .fold(0.0, f64::max);
+
+        (score >= threshold).then_some((user_id, score))
+      })
+      .collect();
+
+    similar_users.sort_by(|(user_id, score), (other_user_id, other_score)| {
+      other_score
+        .total_cmp(score)
+        .then(user_id.cmp(other_user_id))
+    });
+    similar_users.truncate(limit);
+
+    similar_users
+  }
+
+  /// Returns the IDs of every user with a name containing the fragment, ignoring case.
+  pub fn users_containing(&self, fragment: &str) -> Vec<i32> {
+    let fragment = fragment.trim().to_lowercase();
+    let fragment_trigrams = trigrams(&fragment);
+
+    let Some(mut user_sets) = fragment_trigrams
+      .iter()
+      .map(|trigram| self.users_by_trigram.get(trigram))
+      .collect::<Option<Vec<_>>>()
+    else {
+      return vec![];
+    };
+    user_sets.sort_by_key(|user_ids| user_ids.len());
+
+    // Fragments too short for a trigram have to be checked against every name.
+    let possible_users: Vec<i32> = match user_sets.split_first() {
+      Some((smallest_set, other_sets)) => smallest_set
+        .iter()
+        .filter(|user_id| other_sets.iter().all(|user_ids| user_ids.contains(user_id)))
+        .copied()
+        .collect(),
+      None => self.names_by_user.keys().copied().collect(),
+    };
+
+    let mut user_ids: Vec<i32> = possible_users
+      .into_iter()
+      .filter(|user_id| {
+        self
+          .names_by_user
+          .get(user_id)
+          .is_some_and(|names| names.iter().any(|name| name.contains(&fragment)))
+      })
+      .collect();
+    user_ids.sort_unstable();
+
+    user_ids
+  }
+}
+
+/// Returns the users with a name similar to the given one. See [`NameIndex::similar_users`].
+pub async fn find_similar_users(
+  name: &str,
+  threshold: f64,
+  limit: usize,
+  database_connection: &DatabaseConnection,
+) -> Result<Vec<(i32, f64)>, EntityExtensionError> {
+  search_updated_index(database_connection, |name_index| {
+    name_index.similar_users(name, threshold, limit)
+  })
+  .await
+}
+
+/// Returns the users with a current or previous name containing the fragment. See [`NameIndex::users_containing`].
+pub async fn find_users_containing(
+  fragment: &str,
+  database_connection: &DatabaseConnection,
+) -> Result<Vec<i32>, EntityExtensionError> {
+  search_updated_index(database_connection, |name_index| {
+    name_index.users_containing(fragment)
+  })
+  .await
+}
+
+/// Removes the user from this process' index.
+///
+/// Other processes stop matching the user's names on their next [`rebuild`](REBUILD_INTERVAL).
+pub fn forget_user(user_id: i32) {
+  NAME_INDEX
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .remove_user(user_id);
+}
+
+/// Runs the future with every search made in it using the given index instead of this process' one.
+///
+/// Nothing is loaded into an injected index.
+pub async fn with_index<F: Future>(name_index: NameIndex, future: F) -> F::Output {
+  INJECTED_NAME_INDEX.scope(name_index, future).await
+}
+
+/// Runs the search against the [`injected index`](with_index) if there is one, otherwise against this process'
+/// index after bringing it up to date.
+async fn search_updated_index<T>(
+  database_connection: &DatabaseConnection,
+  search: impl FnOnce(&NameIndex) -> T,
+) -> Result<T, EntityExtensionError> {
+  if INJECTED_NAME_INDEX.try_with(|_| ()).is_ok() {
+    return Ok(INJECTED_NAME_INDEX.with(search));
+  }
+
+  update_index(database_connection).await?;
+
+  Ok(search(
+    &NAME_INDEX.read().unwrap_or_else(PoisonError::into_inner),
+  ))
+}
+
+/// Rebuilds this process' index if it's older than [`REBUILD_INTERVAL`], otherwise adds any users and name changes
+/// inserted since it was last updated.
+///
+/// Users are only ever added, and renames are stored as name changes, so catching up on changes made by other
+/// processes only takes a query per table. Erased users can only be dropped by a rebuild.
+async fn update_index(
+  database_connection: &DatabaseConnection,
+) -> Result<(), EntityExtensionError> {
+  let (last_user_id, last_name_change_id, needs_rebuild) = {
+    let current_index = NAME_INDEX.read().unwrap_or_else(PoisonError::into_inner);
+    let needs_rebuild = current_index
+      .built_at
+      .is_none_or(|built_at| built_at.elapsed() >= REBUILD_INTERVAL);
+
+    if needs_rebuild {
+      (0, 0, true)
+    } else {
+      (
+        current_index.last_user_id,
+        current_index.last_name_change_id,
+        false,
+      )
+    }
+  };
+  let (users, name_changes) =
+    load_rows_after(last_user_id, last_name_change_id, database_connection).await?;
+
+  if needs_rebuild {
+    tracing::debug!(
+      "Rebuilding the name index from {} users and {} name changes.",
+      users.len(),
+      name_changes.len()
+    );
+
+    let mut rebuilt_index = NameIndex {
+      built_at: Some(Instant::now()),
+      ..Default::default()
+    };
+    rebuilt_index.insert_rows(&users, &name_changes);
+
+    *NAME_INDEX.write().unwrap_or_else(PoisonError::into_inner) = rebuilt_index;
+
+    return Ok(());
+  }
+
+  if users.is_empty() && name_changes.is_empty() {
+    return Ok(());
+  }
+
+  tracing::debug!(
+    "Adding {} users and {} name changes to the name index.",
+    users.len(),
+    name_changes.len()
+  );
+
+  NAME_INDEX
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .insert_rows(&users, &name_changes);
+
+  Ok(())
+}
+
+/// Reads every user and name change with an ID past the given ones.
+async fn load_rows_after(
+  mut last_user_id: i32,
+  mut last_name_change_id: i32,
+  database_connection: &DatabaseConnection,
+) -> Result<(Vec<twitch_user::Model>, Vec<twitch_user_name_change::Model>), EntityExtensionError> {
+  let mut users = vec![];
+
+  loop {
+    let user_chunk = twitch_user::Entity::find()
+      .filter(twitch_user::Column::Id.gt(last_user_id))
+      .order_by_asc(twitch_user::Column::Id)
+      .limit(LOAD_CHUNK_SIZE)
+      .all(database_connection)
+      .await?;
+
+    let Some(last_user) = user_chunk.last() else {
+      break;
+    };
+    last_user_id = last_user.id;
+
+    users.extend(user_chunk);
+  }
+
+  let mut name_changes = vec![];
+
+  loop {
+    let name_change_chunk = twitch_user_name_change::Entity::find()
+      .filter(twitch_user_name_change::Column::Id.gt(last_name_change_id))
+      .order_by_asc(twitch_user_name_change::Column::Id)
+      .limit(LOAD_CHUNK_SIZE)
+      .all(database_connection)
+      .await?;
+
+    let Some(last_name_change) = name_change_chunk.last() else {
+      break;
+    };
+    last_name_change_id = last_name_change.id;
+
+    name_changes.extend(name_change_chunk);
+  }
+
+  Ok((users, name_changes))
+}
+
+fn trigrams(name: &str) -> Vec<[char; 3]> {
+  let characters: Vec<char> = name.chars().collect();
+
+  characters
+    .windows(3)
+    .map(|window| [window[0], window[1], window[2]])
+    .collect()
+}
+
+fn padded_trigrams(name: &str) -> Vec<[char; 3]> {
+  trigrams(&format!("  {name} "))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+
+  fn user(id: i32, login_name: &str, display_name: &str) -> twitch_user::Model {
+    twitch_user::Model {
+      id,
+      twitch_id: id,
+      login_name: login_name.into(),
+      display_name: display_name.into(),
+      last_verified_at: None,
+    }
+  }
+
+  fn name_index(users: &[twitch_user::Model]) -> NameIndex {
+    let mut name_index = NameIndex::default();
+    users.iter().for_each(|user| name_index.insert_user(user));
+
+    name_index
+  }
+
+  #[test]
+  fn similar_users_orders_close_names_first() {
+    let name_index = name_index(&[
+      user(1, "completely_different", "Completely_Different"),
+      user(2, "anonymoose", "AnonyMoose"),
+      user(3, "anon_y_moose", "anon_y_moose"),
+    ]);
+
+    let similar_users = name_index.similar_users("anon_y_moose", 0.85, 5);
+
+    assert_eq!(
+      similar_users.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+      vec![3, 2]
+    );
+    assert_eq!(similar_users[0].1, 1.0);
+    assert!(similar_users[1].1 < 0.97);
+  }
+
+  #[test]
+  fn similar_users_uses_display_names_and_ignores_case() {
+    let name_index = name_index(&[user(1, "some_login", "Moose")]);
+
+    assert_eq!(name_index.similar_users("MOOSE", 0.85, 5), vec![(1, 1.0)]);
+  }
+
+  #[test]
+  fn name_changes_keep_previous_names_searchable() {
+    let mut name_index = name_index(&[user(1, "new_name", "New_Name")]);
+    name_index.insert_name_change(&twitch_user_name_change::Model {
+      id: 1,
+      twitch_user_id: 1,
+      previous_login_name: Some("old_name".into()),
+      previous_display_name: Some("Old_Name".into()),
+      new_login_name: Some("new_name".into()),
+      new_display_name: Some("New_Name".into()),
+      created_at: Utc::now(),
+    });
+
+    assert_eq!(name_index.users_containing("old"), vec![1]);
+    assert_eq!(name_index.similar_users("old_nam", 0.85, 5)[0].0, 1);
+  }
+
+  #[test]
+  fn users_containing_matches_fragments_anywhere_in_names() {
+    let name_index = name_index(&[
+      user(1, "fallenshadow", "FallenShadow"),
+      user(2, "shadowchama", "ShadowChama"),
+      user(3, "moose", "Moose"),
+    ]);
+
+    assert_eq!(name_index.users_containing("SHADOW"), vec![1, 2]);
+    assert_eq!(name_index.users_containing("ow"), vec![1, 2]);
+    assert_eq!(name_index.users_containing("lens"), vec![1]);
+    assert!(name_index.users_containing("shadowmoose").is_empty());
+  }
+
+  #[test]
+  fn removed_users_are_no_longer_found() {
+    let mut name_index = name_index(&[user(1, "fallenshadow", "FallenShadow")]);
+    name_index.remove_user(1);
+
+    assert!(name_index.users_containing("shadow").is_empty());
+    assert!(name_index.similar_users("fallenshadow", 0.85, 5).is_empty());
+    assert!(name_index.users_by_trigram.is_empty());
+  }
+
+  #[tokio::test]
+  async fn stale_indexes_are_rebuilt_without_erased_users() {
+    let loading_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([
+        vec![user(1, "fallenshadow", "FallenShadow")],
+        Vec::<twitch_user::Model>::new(),
+      ])
+      .append_query_results([Vec::<twitch_user_name_change::Model>::new()])
+      .into_connection();
+
+    assert_eq!(
+      find_users_containing("shadow", &loading_database)
+        .await
+        .unwrap(),
+      vec![1]
+    );
+
+    NAME_INDEX
+      .write()
+      .unwrap_or_else(PoisonError::into_inner)
+      .built_at = Instant::now().checked_sub(REBUILD_INTERVAL);
+    let rebuilding_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([Vec::<twitch_user::Model>::new()])
+      .append_query_results([Vec::<twitch_user_name_change::Model>::new()])
+      .into_connection();
+
+    assert!(
+      find_users_containing("shadow", &rebuilding_database)
+        .await
+        .unwrap()
+        .is_empty()
+    );
+  }
+
+  #[tokio::test]
+  async fn injected_indexes_are_searched_without_loading() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql).into_connection();
+    let name_index = name_index(&[user(1, "fallenshadow", "FallenShadow")]);
+
+    let user_ids = with_index(name_index, find_users_containing("shadow", &mock_database))
+      .await
+      .unwrap();
+
+    assert_eq!(user_ids, vec![1]);
+    assert!(mock_database.into_transaction_log().is_empty());
+  }
+}
@@ -3,3 +3,4 @@ pub use crate::stream::StreamExtensions;
 pub use crate::twitch_user::TwitchUserExtensions;
 pub use crate::twitch_user_unknown_user_association::TwitchUserUnkownUserAssociationExtensions;
 pub use crate::unknown_user::UnknownUserExtensions;
+pub use crate::unknown_user_match::UnknownUserMatchExtensions;
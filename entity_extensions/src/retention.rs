@@ -0,0 +1,83 @@
+use crate::errors::EntityExtensionError;
+use app_config::AppConfig;
+use chrono::{DateTime, Duration, Utc};
+use entities::sea_orm_active_enums::ContentMode;
+use entities::{chatter_presence, stream_message};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+/// How long data is kept before it's purged. A value of None keeps the data forever.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+  /// Message contents older than this are cleared.
+  ///
+  /// The messages themselves are kept so that message counts and emote usage stay intact.
+  pub message_contents_days: Option<u32>,
+  /// Join and part records older than this are deleted.
+  pub chatter_presence_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionSummary {
+  pub cleared_message_contents: u64,
+  pub deleted_chatter_presences: u64,
+}
+
+impl RetentionPolicy {
+  pub fn from_config() -> Self {
+    Self {
+      message_contents_days: AppConfig::message_contents_retention_days(),
+      chatter_presence_days: AppConfig::chatter_presence_retention_days(),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.message_contents_days.is_none() && self.chatter_presence_days.is_none()
+  }
+
+  /// Purges everything older than the policy allows, relative to `now`.
+  pub async fn apply(
+    &self,
+    now: DateTime<Utc>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<RetentionSummary, EntityExtensionError> {
+    let transaction = database_connection.begin().await?;
+    let mut summary = RetentionSummary::default();
+
+    if let Some(cutoff) = retention_cutoff(now, self.message_contents_days) {
+      let result = stream_message::Entity::update_many()
+        .col_expr(
+          stream_message::Column::Contents,
+          Expr::value(Option::<String>::None),
+        )
+        .col_expr(
+          stream_message::Column::ContentMode,
+          Expr::value(ContentMode::Redacted),
+        )
+        .filter(stream_message::Column::Timestamp.lt(cutoff))
+        .filter(stream_message::Column::Contents.is_not_null())
+        .exec(&transaction)
+        .await?;
+
+      summary.cleared_message_contents = result.rows_affected;
+    }
+
+    if let Some(cutoff) = retention_cutoff(now, self.chatter_presence_days) {
+      let result = chatter_presence::Entity::delete_many()
+        .filter(chatter_presence::Column::JoinedAt.lt(cutoff))
+        .exec(&transaction)
+        .await?;
+
+      summary.deleted_chatter_presences = result.rows_affected;
+    }
+
+    transaction.commit().await?;
+
+    Ok(summary)
+  }
+}
+
+/// Returns the timestamp that anything older than is purged.
+fn retention_cutoff(now: DateTime<Utc>, retention_days: Option<u32>) -> Option<DateTime<Utc>> {
+  retention_days.map(|days| now - Duration::days(days as i64))
+}
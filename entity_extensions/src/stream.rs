@@ -1,15 +1,17 @@
 use crate::errors::EntityExtensionError;
-use app_config::AppConfig;
-use app_config::secret_string::Secret;
+use crate::stream_highlights::{
+  HighlightOptions, StreamHighlight, detect_activity_spikes, is_offset_muted,
+};
+use crate::time_buckets::unix_bucket_expression;
 use chrono::{DateTime, Utc};
-use entities::{muted_vod_segment, stream, twitch_user};
-use reqwest::RequestBuilder;
-use sea_orm::{sea_query::OnConflict, *};
-use serde_json::Value;
+use entities::{emote_usage, muted_vod_segment, stream, stream_message, twitch_user};
+use helix_client::{HelixRequest, get_helix_client};
+use sea_orm::sea_query::{Alias, Expr, OnConflict};
+use sea_orm::*;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
-use url::Url;
 
-const HELIX_STREAM_QUERY_URL: &str = "https://api.twitch.tv/helix/streams";
+const HELIX_STREAM_QUERY_PATH: &str = "streams";
 
 pub trait StreamExtensions {
   fn is_live(&self) -> bool;
@@ -26,6 +28,12 @@ pub trait StreamExtensions {
   async fn get_active_livestreams<'a, I>(
     channels: I,
   ) -> Result<HashMap<String, (DateTime<Utc>, String)>, EntityExtensionError>
+  where
+    I: IntoIterator<Item = &'a twitch_user::Model>;
+  /// Returns a map of login_name: (stream_twitch_id, viewer_count)
+  async fn get_live_viewer_counts<'a, I>(
+    channels: I,
+  ) -> Result<HashMap<String, (String, i32)>, EntityExtensionError>
   where
     I: IntoIterator<Item = &'a twitch_user::Model>;
   async fn insert_muted_segments<I, M>(
@@ -36,6 +44,21 @@ pub trait StreamExtensions {
   where
     I: IntoIterator<Item = M>,
     M: Into<muted_vod_segment::ActiveModel>;
+  /// Returns the moments in the stream where chat activity spiked, ranked by how far above the rolling baseline they were.
+  async fn get_highlights(
+    &self,
+    options: &HighlightOptions,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<StreamHighlight>, DbErr>;
+  /// Returns the [`highlights`](StreamExtensions::get_highlights) of each stream keyed by stream ID, querying the
+  /// activity of every stream at once.
+  ///
+  /// Streams without any highlights are left out.
+  async fn get_highlights_for_streams(
+    streams: &[stream::Model],
+    options: &HighlightOptions,
+    database_connection: &DatabaseConnection,
+  ) -> Result<HashMap<i32, Vec<StreamHighlight>>, DbErr>;
 }
 
 impl StreamExtensions for stream::Model {
@@ -74,47 +97,10 @@ impl StreamExtensions for stream::Model {
   where
     I: IntoIterator<Item = &'a twitch_user::Model>,
   {
-    let request = build_get_streams_request(channels).await?;
-    let response = request.send().await?;
-
-    let status = response.status();
-
-    if !status.is_success() {
-      return Err(EntityExtensionError::FailedResponse {
-        location: "get active livestreams",
-        code: status.as_u16(),
-      });
-    }
-
-    let response_body = response.text().await?;
-    let Value::Object(response_value): Value = serde_json::from_str(&response_body)? else {
-      return Err(EntityExtensionError::UnknownResponseBody {
-        location: "get active livestreams update live status",
-        response: response_body,
-      });
-    };
-    let Some(Value::Array(live_streams)) = response_value.get("data") else {
-      return Err(EntityExtensionError::UnknownResponseBody {
-        location: "get active livestreams update live status live stream list",
-        response: response_body,
-      });
-    };
-
+    let live_streams = query_live_streams(channels).await?;
     let mut live_channels: HashMap<String, (DateTime<Utc>, String)> = HashMap::new();
 
     for live_stream in live_streams {
-      let Value::Object(live_stream) = live_stream else {
-        continue;
-      };
-
-      let Some(Value::String(live_status)) = live_stream.get("type") else {
-        continue;
-      };
-
-      if live_status != "live" {
-        continue;
-      }
-
       let Some(Value::String(streamer_login_name)) = live_stream.get("user_login") else {
         continue;
       };
@@ -149,6 +135,44 @@ impl StreamExtensions for stream::Model {
     Ok(live_channels)
   }
 
+  /// Returns a map of login_name: (stream_twitch_id, viewer_count)
+  async fn get_live_viewer_counts<'a, I>(
+    channels: I,
+  ) -> Result<HashMap<String, (String, i32)>, EntityExtensionError>
+  where
+    I: IntoIterator<Item = &'a twitch_user::Model>,
+  {
+    let live_streams = query_live_streams(channels).await?;
+    let mut viewer_counts: HashMap<String, (String, i32)> = HashMap::new();
+
+    for live_stream in live_streams {
+      let Some(Value::String(streamer_login_name)) = live_stream.get("user_login") else {
+        continue;
+      };
+      let Some(Value::String(stream_id)) = live_stream.get("id") else {
+        tracing::error!(
+          "Failed to get livestream ID for channel `{:?}`",
+          streamer_login_name
+        );
+        continue;
+      };
+      let Some(viewer_count) = live_stream.get("viewer_count").and_then(Value::as_i64) else {
+        tracing::error!(
+          "Failed to get the viewer count for channel `{:?}`",
+          streamer_login_name
+        );
+        continue;
+      };
+
+      viewer_counts.insert(
+        streamer_login_name.to_owned(),
+        (stream_id.to_owned(), viewer_count as i32),
+      );
+    }
+
+    Ok(viewer_counts)
+  }
+
   async fn insert_muted_segments<I, M>(
     &self,
     database_connection: &DatabaseConnection,
@@ -191,39 +215,279 @@ impl StreamExtensions for stream::Model {
 
     Ok(())
   }
+
+  async fn get_highlights(
+    &self,
+    options: &HighlightOptions,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<StreamHighlight>, DbErr> {
+    let mut highlights =
+      Self::get_highlights_for_streams(std::slice::from_ref(self), options, database_connection)
+        .await?;
+
+    Ok(highlights.remove(&self.id).unwrap_or_default())
+  }
+
+  async fn get_highlights_for_streams(
+    streams: &[stream::Model],
+    options: &HighlightOptions,
+    database_connection: &DatabaseConnection,
+  ) -> Result<HashMap<i32, Vec<StreamHighlight>>, DbErr> {
+    if streams.is_empty() {
+      return Ok(HashMap::new());
+    }
+
+    let stream_ids: Vec<i32> = streams.iter().map(|stream| stream.id).collect();
+    let stream_id_column = Expr::col((stream_message::Entity, stream_message::Column::StreamId));
+    let bucket_expression = unix_bucket_expression(
+      Expr::col((stream_message::Entity, stream_message::Column::Timestamp)).into(),
+      options.bucket_size,
+    );
+
+    let bucket_activity: Vec<(i32, i64, i64)> = if let Some(emote_ids) = &options.emote_ids {
+      emote_usage::Entity::find()
+        .join(
+          JoinType::InnerJoin,
+          emote_usage::Relation::StreamMessage.def(),
+        )
+        .filter(stream_message::Column::StreamId.is_in(stream_ids.clone()))
+        .filter(emote_usage::Column::EmoteId.is_in(emote_ids.clone()))
+        .select_only()
+        .column_as(stream_id_column.clone(), "stream_id")
+        .column_as(bucket_expression, "bucket")
+        .column_as(
+          Expr::cust_with_expr(
+            "CAST(SUM(?) AS SIGNED)",
+            Expr::col((emote_usage::Entity, emote_usage::Column::UsageCount)),
+          ),
+          "activity",
+        )
+        .group_by(stream_id_column)
+        .group_by(Expr::col(Alias::new("bucket")))
+        .order_by_asc(Expr::col(Alias::new("bucket")))
+        .into_tuple()
+        .all(database_connection)
+        .await?
+    } else {
+      stream_message::Entity::find()
+        .filter(stream_message::Column::StreamId.is_in(stream_ids.clone()))
+        .select_only()
+        .column_as(stream_id_column.clone(), "stream_id")
+        .column_as(bucket_expression, "bucket")
+        .column_as(Expr::col(stream_message::Column::Id).count(), "activity")
+        .group_by(stream_id_column)
+        .group_by(Expr::col(Alias::new("bucket")))
+        .order_by_asc(Expr::col(Alias::new("bucket")))
+        .into_tuple()
+        .all(database_connection)
+        .await?
+    };
+    let muted_vod_segments = muted_vod_segment::Entity::find()
+      .filter(muted_vod_segment::Column::StreamId.is_in(stream_ids))
+      .all(database_connection)
+      .await?;
+
+    let mut stream_bucket_activity: HashMap<i32, Vec<(i64, i64)>> = HashMap::new();
+    let mut stream_muted_vod_segments: HashMap<i32, Vec<muted_vod_segment::Model>> = HashMap::new();
+
+    for (stream_id, bucket, activity) in bucket_activity {
+      stream_bucket_activity
+        .entry(stream_id)
+        .or_default()
+        .push((bucket, activity));
+    }
+
+    for muted_vod_segment in muted_vod_segments {
+      stream_muted_vod_segments
+        .entry(muted_vod_segment.stream_id)
+        .or_default()
+        .push(muted_vod_segment);
+    }
+
+    Ok(
+      streams
+        .iter()
+        .filter_map(|stream| {
+          let bucket_activity = stream_bucket_activity.get(&stream.id)?;
+          let muted_vod_segments = stream_muted_vod_segments
+            .get(&stream.id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+          let highlights =
+            highlights_from_activity(stream, bucket_activity, muted_vod_segments, options);
+
+          (!highlights.is_empty()).then_some((stream.id, highlights))
+        })
+        .collect(),
+    )
+  }
 }
 
-/// Takes the list of channels and builds the request for querying streams.
-async fn build_get_streams_request<'a, I>(
+/// Turns the spikes in the stream's bucketed activity into highlights, keeping the top `options.limit`.
+fn highlights_from_activity(
+  stream: &stream::Model,
+  bucket_activity: &[(i64, i64)],
+  muted_vod_segments: &[muted_vod_segment::Model],
+  options: &HighlightOptions,
+) -> Vec<StreamHighlight> {
+  let spikes = detect_activity_spikes(
+    bucket_activity,
+    options.bucket_size,
+    options.baseline_window,
+    options.z_score_threshold,
+  );
+
+  spikes
+    .into_iter()
+    .take(options.limit)
+    .filter_map(|spike| {
+      let timestamp = DateTime::from_timestamp(spike.bucket_start, 0)?;
+      let vod_offset = stream
+        .start_timestamp
+        .map(|start_timestamp| (timestamp - start_timestamp).num_seconds().max(0));
+      let is_muted =
+        vod_offset.is_some_and(|vod_offset| is_offset_muted(vod_offset, muted_vod_segments));
+
+      Some(StreamHighlight {
+        timestamp,
+        vod_offset,
+        activity: spike.activity,
+        baseline: spike.baseline,
+        z_score: spike.z_score,
+        is_muted,
+      })
+    })
+    .collect()
+}
+
+/// Queries Helix for the streams of the given channels, returning only the ones that are currently live.
+async fn query_live_streams<'a, I>(
   channels: I,
-) -> Result<RequestBuilder, EntityExtensionError>
+) -> Result<Vec<Map<String, Value>>, EntityExtensionError>
 where
   I: IntoIterator<Item = &'a twitch_user::Model>,
 {
-  let mut query_url = Url::parse(HELIX_STREAM_QUERY_URL)?;
-  let reqwest_client = reqwest::Client::new();
+  let request = build_get_streams_request(channels);
+  let response = request.send().await?;
 
-  query_url.query_pairs_mut().append_pair("first", "100");
+  let status = response.status();
 
-  for channel_data in channels {
-    query_url
-      .query_pairs_mut()
-      .append_pair("user_login", &channel_data.login_name);
+  if !status.is_success() {
+    return Err(EntityExtensionError::FailedResponse {
+      location: "get active livestreams",
+      code: status.as_u16(),
+    });
   }
 
+  let response_body = response.text().await?;
+  let Value::Object(mut response_value): Value = serde_json::from_str(&response_body)? else {
+    return Err(EntityExtensionError::UnknownResponseBody {
+      location: "get active livestreams update live status",
+      response: response_body,
+    });
+  };
+  let Some(Value::Array(live_streams)) = response_value.remove("data") else {
+    return Err(EntityExtensionError::UnknownResponseBody {
+      location: "get active livestreams update live status live stream list",
+      response: response_body,
+    });
+  };
+
   Ok(
-    reqwest_client
-      .get(query_url)
-      .header(
-        "Authorization",
-        format!(
-          "Bearer {}",
-          Secret::read_secret_string(AppConfig::access_token().read_value())
-        ),
-      )
-      .header(
-        "Client-Id",
-        Secret::read_secret_string(AppConfig::client_id().read_value()),
-      ),
+    live_streams
+      .into_iter()
+      .filter_map(|live_stream| {
+        let Value::Object(live_stream) = live_stream else {
+          return None;
+        };
+
+        let is_live = live_stream.get("type").and_then(Value::as_str) == Some("live");
+
+        is_live.then_some(live_stream)
+      })
+      .collect(),
   )
 }
+
+/// Takes the list of channels and builds the request for querying streams.
+fn build_get_streams_request<'a, I>(channels: I) -> HelixRequest<'static>
+where
+  I: IntoIterator<Item = &'a twitch_user::Model>,
+{
+  let mut request = get_helix_client()
+    .get(HELIX_STREAM_QUERY_PATH)
+    .query("first", "100");
+
+  for channel_data in channels {
+    request = request.query("user_login", &channel_data.login_name);
+  }
+
+  request
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::mock_helix::use_mock_helix;
+
+  fn channel(id: i32, login_name: &str) -> twitch_user::Model {
+    twitch_user::Model {
+      id,
+      twitch_id: id,
+      login_name: login_name.into(),
+      display_name: login_name.into(),
+      last_verified_at: None,
+    }
+  }
+
+  #[tokio::test]
+  async fn get_active_livestreams_only_returns_live_channels() {
+    use_mock_helix();
+    let channels = [channel(1, "fallenshadow"), channel(2, "shadowchama")];
+
+    let live_streams = stream::Model::get_active_livestreams(&channels)
+      .await
+      .unwrap();
+
+    assert_eq!(
+      live_streams,
+      HashMap::from([(
+        "fallenshadow".to_string(),
+        (
+          "2025-05-08T00:02:29Z".parse::<DateTime<Utc>>().unwrap(),
+          "40952121085".to_string()
+        )
+      )])
+    );
+  }
+
+  #[tokio::test]
+  async fn get_live_viewer_counts_returns_the_count_of_each_live_stream() {
+    use_mock_helix();
+    let channels = [channel(1, "fallenshadow"), channel(2, "shadowchama")];
+
+    let viewer_counts = stream::Model::get_live_viewer_counts(&channels)
+      .await
+      .unwrap();
+
+    assert_eq!(
+      viewer_counts,
+      HashMap::from([(
+        "fallenshadow".to_string(),
+        ("40952121085".to_string(), 1234)
+      )])
+    );
+  }
+
+  #[tokio::test]
+  async fn get_live_viewer_counts_is_empty_for_offline_channels() {
+    use_mock_helix();
+    let channels = [channel(2, "shadowchama")];
+
+    let viewer_counts = stream::Model::get_live_viewer_counts(&channels)
+      .await
+      .unwrap();
+
+    assert!(viewer_counts.is_empty());
+  }
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, Utc};
+use entities::muted_vod_segment;
+
+/// The settings used when looking for spikes in chat activity.
+#[derive(Debug, Clone)]
+pub struct HighlightOptions {
+  /// The size of each bucket of chat activity, in seconds.
+  pub bucket_size: i64,
+  /// How many of the preceding buckets are used as the baseline for a bucket.
+  pub baseline_window: usize,
+  /// The amount of standard deviations above the baseline a bucket must be to count as a highlight.
+  pub z_score_threshold: f64,
+  /// Only counts usages of these emotes rather than all messages if set.
+  pub emote_ids: Option<Vec<i32>>,
+  /// The maximum amount of highlights returned.
+  pub limit: usize,
+}
+
+impl Default for HighlightOptions {
+  fn default() -> Self {
+    Self {
+      bucket_size: 30,
+      baseline_window: 20,
+      z_score_threshold: 3.0,
+      emote_ids: None,
+      limit: 10,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamHighlight {
+  pub timestamp: DateTime<Utc>,
+  /// Seconds into the VOD. None if the stream has no start timestamp.
+  pub vod_offset: Option<i64>,
+  /// The amount of messages or emote usages in the bucket.
+  pub activity: i64,
+  /// The average activity of the buckets preceding this one.
+  pub baseline: f64,
+  pub z_score: f64,
+  /// Whether the highlight falls within a muted segment of the VOD.
+  pub is_muted: bool,
+}
+
+impl StreamHighlight {
+  /// Returns the link to this moment in the given VOD.
+  pub fn vod_url(&self, twitch_vod_id: &str) -> Option<String> {
+    let vod_offset = self.vod_offset?;
+    let hours = vod_offset / 3600;
+    let minutes = (vod_offset % 3600) / 60;
+    let seconds = vod_offset % 60;
+
+    Some(format!(
+      "https://www.twitch.tv/videos/{twitch_vod_id}?t={hours}h{minutes}m{seconds}s"
+    ))
+  }
+
+  /// Formats the VOD offset as `hh:mm:ss`.
+  pub fn formatted_vod_offset(&self) -> Option<String> {
+    let vod_offset = self.vod_offset?;
+
+    Some(format!(
+      "{:02}:{:02}:{:02}",
+      vod_offset / 3600,
+      (vod_offset % 3600) / 60,
+      vod_offset % 60
+    ))
+  }
+}
+
+/// A bucket whose activity spiked above its rolling baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActivitySpike {
+  /// The unix timestamp of the start of the bucket.
+  pub bucket_start: i64,
+  pub activity: i64,
+  pub baseline: f64,
+  pub z_score: f64,
+}
+
+/// The smallest standard deviation used when scoring a bucket.
+///
+/// Prevents a near constant baseline, such as an empty chat, from turning a handful of messages into a huge z-score.
+const MINIMUM_STANDARD_DEVIATION: f64 = 1.0;
+
+/// The minimum amount of preceding buckets required before a bucket can be scored.
+const MINIMUM_BASELINE_BUCKETS: usize = 3;
+
+/// Finds the buckets whose activity is at least `z_score_threshold` standard deviations above the mean of the
+/// `baseline_window` buckets preceding them.
+///
+/// `bucket_activity` is a list of `(bucket_start, activity)` sorted by bucket start. Buckets missing from the list
+/// are treated as having no activity. Consecutive spiking buckets are merged into the one with the highest z-score,
+/// and the result is sorted by z-score descending.
+pub fn detect_activity_spikes(
+  bucket_activity: &[(i64, i64)],
+  bucket_size: i64,
+  baseline_window: usize,
+  z_score_threshold: f64,
+) -> Vec<ActivitySpike> {
+  let (Some((first_bucket, _)), Some((last_bucket, _))) =
+    (bucket_activity.first(), bucket_activity.last())
+  else {
+    return vec![];
+  };
+
+  if bucket_size <= 0 || baseline_window == 0 {
+    return vec![];
+  }
+
+  let bucket_count = ((last_bucket - first_bucket) / bucket_size + 1) as usize;
+  let mut activity = vec![0; bucket_count];
+
+  for (bucket_start, bucket_activity) in bucket_activity {
+    let index = ((bucket_start - first_bucket) / bucket_size) as usize;
+    activity[index] += bucket_activity;
+  }
+
+  let mut spikes: Vec<ActivitySpike> = vec![];
+  let mut previous_spike_index: Option<usize> = None;
+
+  for index in MINIMUM_BASELINE_BUCKETS..activity.len() {
+    let baseline = &activity[index.saturating_sub(baseline_window)..index];
+    let mean = baseline.iter().sum::<i64>() as f64 / baseline.len() as f64;
+    let variance = baseline
+      .iter()
+      .map(|value| (*value as f64 - mean).powi(2))
+      .sum::<f64>()
+      / baseline.len() as f64;
+    let standard_deviation = variance.sqrt().max(MINIMUM_STANDARD_DEVIATION);
+    let z_score = (activity[index] as f64 - mean) / standard_deviation;
+
+    if z_score < z_score_threshold {
+      continue;
+    }
+
+    let spike = ActivitySpike {
+      bucket_start: first_bucket + index as i64 * bucket_size,
+      activity: activity[index],
+      baseline: mean,
+      z_score,
+    };
+
+    match (previous_spike_index, spikes.last_mut()) {
+      (Some(previous_index), Some(previous_spike)) if previous_index + 1 == index => {
+        if spike.z_score > previous_spike.z_score {
+          *previous_spike = spike;
+        }
+      }
+      _ => spikes.push(spike),
+    }
+
+    previous_spike_index = Some(index);
+  }
+
+  spikes.sort_by(|lhs, rhs| rhs.z_score.total_cmp(&lhs.z_score));
+
+  spikes
+}
+
+/// Returns true if the VOD offset falls within any of the muted segments.
+pub fn is_offset_muted(vod_offset: i64, muted_vod_segments: &[muted_vod_segment::Model]) -> bool {
+  muted_vod_segments.iter().any(|muted_vod_segment| {
+    let segment_start = muted_vod_segment.offset as i64;
+    let segment_end = segment_start + muted_vod_segment.duration as i64;
+
+    (segment_start..segment_end).contains(&vod_offset)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn detect_activity_spikes_finds_spike_above_baseline() {
+    let bucket_activity: Vec<(i64, i64)> = (0..10)
+      .map(|index| (index * 30, if index == 7 { 40 } else { 5 }))
+      .collect();
+
+    let spikes = detect_activity_spikes(&bucket_activity, 30, 5, 3.0);
+
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].bucket_start, 210);
+    assert_eq!(spikes[0].activity, 40);
+    assert_eq!(spikes[0].baseline, 5.0);
+  }
+
+  #[test]
+  fn detect_activity_spikes_merges_consecutive_buckets() {
+    let bucket_activity = vec![
+      (0, 2),
+      (30, 2),
+      (60, 2),
+      (90, 2),
+      (120, 30),
+      (150, 50),
+      (180, 2),
+    ];
+
+    let spikes = detect_activity_spikes(&bucket_activity, 30, 4, 3.0);
+
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].bucket_start, 120);
+  }
+
+  #[test]
+  fn detect_activity_spikes_fills_missing_buckets() {
+    // The gap between 0 and 150 should count as silence rather than being skipped.
+    let bucket_activity = vec![(0, 1), (150, 20)];
+
+    let spikes = detect_activity_spikes(&bucket_activity, 30, 5, 3.0);
+
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].bucket_start, 150);
+    assert_eq!(spikes[0].baseline, 0.2);
+  }
+
+  #[test]
+  fn detect_activity_spikes_ranks_by_z_score() {
+    let mut bucket_activity: Vec<(i64, i64)> = (0..20).map(|index| (index, 3)).collect();
+    bucket_activity[6].1 = 20;
+    bucket_activity[15].1 = 50;
+
+    let spikes = detect_activity_spikes(&bucket_activity, 1, 5, 3.0);
+
+    assert_eq!(spikes.len(), 2);
+    assert_eq!(spikes[0].bucket_start, 15);
+    assert_eq!(spikes[1].bucket_start, 6);
+  }
+
+  #[test]
+  fn vod_url_expected_value() {
+    let highlight = StreamHighlight {
+      timestamp: Utc::now(),
+      vod_offset: Some(3725),
+      activity: 10,
+      baseline: 1.0,
+      z_score: 9.0,
+      is_muted: false,
+    };
+
+    assert_eq!(
+      highlight.vod_url("123"),
+      Some("https://www.twitch.tv/videos/123?t=1h2m5s".to_string())
+    );
+    assert_eq!(
+      highlight.formatted_vod_offset(),
+      Some("01:02:05".to_string())
+    );
+  }
+
+  #[test]
+  fn is_offset_muted_expected_value() {
+    let muted_vod_segments = vec![muted_vod_segment::Model {
+      stream_id: 1,
+      offset: 100,
+      duration: 60,
+    }];
+
+    assert!(is_offset_muted(100, &muted_vod_segments));
+    assert!(is_offset_muted(159, &muted_vod_segments));
+    assert!(!is_offset_muted(160, &muted_vod_segments));
+    assert!(!is_offset_muted(99, &muted_vod_segments));
+  }
+}
@@ -33,3 +33,18 @@ impl StreamMessageExtensions for stream_message::Model {
     Ok(())
   }
 }
+
+/// The amount of whitespace separated words in the message contents.
+pub fn count_words(contents: &str) -> i32 {
+  contents.split_whitespace().count() as i32
+}
+
+/// Returns the stored word count of the message, or counts it from the contents for messages stored before word
+/// counts were.
+///
+/// Returns None if the message has neither.
+pub fn message_word_count(message: &stream_message::Model) -> Option<i32> {
+  message
+    .word_count
+    .or_else(|| Some(count_words(message.contents.as_ref()?)))
+}
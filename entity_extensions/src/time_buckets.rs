@@ -0,0 +1,39 @@
+use sea_orm::sea_query::{Expr, SimpleExpr};
+
+/// Truncates the timestamp down to the unix timestamp of the start of its bucket, with buckets aligned to the unix
+/// epoch.
+///
+/// Timestamps are read back in the session's time zone, so they're converted to UTC before being compared to the
+/// epoch. This keeps the buckets the same whatever the session or server time zone is.
+pub fn unix_bucket_expression(timestamp: SimpleExpr, bucket_size: i64) -> SimpleExpr {
+  Expr::cust_with_exprs(
+    "CAST(FLOOR(TIMESTAMPDIFF(SECOND, '1970-01-01 00:00:00', CONVERT_TZ(?, @@session.time_zone, '+00:00')) / ?) * ? AS SIGNED)",
+    [
+      timestamp,
+      Expr::val(bucket_size).into(),
+      Expr::val(bucket_size).into(),
+    ],
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use entities::stream_message;
+  use sea_orm::sea_query::{MysqlQueryBuilder, Query};
+
+  #[test]
+  fn buckets_are_calculated_in_utc() {
+    let query = Query::select()
+      .expr(unix_bucket_expression(
+        Expr::col((stream_message::Entity, stream_message::Column::Timestamp)).into(),
+        30,
+      ))
+      .to_string(MysqlQueryBuilder);
+
+    assert_eq!(
+      query,
+      "SELECT CAST(FLOOR(TIMESTAMPDIFF(SECOND, '1970-01-01 00:00:00', CONVERT_TZ(`stream_message`.`timestamp`, @@session.time_zone, '+00:00')) / 30) * 30 AS SIGNED)"
+    );
+  }
+}
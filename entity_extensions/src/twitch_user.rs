@@ -1,17 +1,20 @@
+use crate::account_status_change;
 use crate::errors::EntityExtensionError;
+use crate::name_index;
 use crate::prelude::*;
-use app_config::AppConfig;
-use app_config::secret_string::Secret;
-use entities::{
-  twitch_user, twitch_user_name_change, twitch_user_unknown_user_association, unknown_user,
-};
+use entities::sea_orm_active_enums::AccountStatus;
+use entities::{twitch_user, twitch_user_name_change, unknown_user, unknown_user_match};
+use helix_client::get_helix_client;
+use sea_orm::prelude::DateTimeUtc;
 use sea_orm::*;
 use serde_json::Value;
-use strsim::jaro_winkler;
-use url::Url;
+use std::collections::{BTreeMap, HashSet};
 
-const HELIX_USER_QUERY_URL: &str = "https://api.twitch.tv/helix/users";
-const JARO_NAME_SIMILARITY_THRESHOLD: f64 = 0.85;
+const HELIX_USER_QUERY_PATH: &str = "users";
+/// How many users Helix takes per request.
+const HELIX_BATCH_SIZE: usize = 100;
+/// The most users returned for an incomplete name.
+const MAX_INCOMPLETE_NAME_MATCHES: usize = 100;
 
 #[derive(Debug, Clone)]
 pub enum ChannelIdentifier<S: AsRef<str>> {
@@ -19,6 +22,20 @@ pub enum ChannelIdentifier<S: AsRef<str>> {
   TwitchID(S),
 }
 
+/// A user who had a login at some point, and the period they had it for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginMatch {
+  pub user: twitch_user::Model,
+  /// The login as it was stored.
+  pub matched_login: String,
+  /// Whether the user still has the login.
+  pub is_current: bool,
+  /// When the user changed to the login. None if they had it when they were first seen.
+  pub used_from: Option<DateTimeUtc>,
+  /// When the user changed away from the login. None if they still have it.
+  pub used_until: Option<DateTimeUtc>,
+}
+
 impl<'a> From<ChannelIdentifier<&'a str>> for &'a str {
   fn from(value: ChannelIdentifier<&'a str>) -> Self {
     match value {
@@ -37,20 +54,49 @@ pub trait TwitchUserExtensions {
     identifier: ChannelIdentifier<S>,
     database_connection: &DatabaseConnection,
   ) -> Result<Vec<twitch_user::Model>, EntityExtensionError>;
+  /// Returns every user who has had the login, using their [`name changes`](twitch_user_name_change) for previous
+  /// holders.
+  ///
+  /// The current holder comes first, followed by previous holders with the most recent first. A user appears once
+  /// for each period they had the login.
+  async fn get_by_login_history(
+    login: &str,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<LoginMatch>, EntityExtensionError>;
   async fn get_or_set_by_name(
     login_name: &str,
     database_connection: &DatabaseConnection,
   ) -> Result<twitch_user::Model, EntityExtensionError>;
+  /// Retrieves every user with one of the logins, querying Helix for the ones missing from the database in batches
+  /// rather than one request per login.
+  ///
+  /// Logins Helix doesn't know are left out.
+  async fn get_or_set_by_names(
+    login_names: &[&str],
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<twitch_user::Model>, EntityExtensionError>;
   async fn get_or_set_by_twitch_id(
     twitch_id: &str,
     database_connection: &DatabaseConnection,
   ) -> Result<twitch_user::Model, EntityExtensionError>;
   /// Queries Helix for every user passed in.
+  ///
+  /// Known users Helix returns nothing for have their [`account status`](crate::account_status_change) checked
+  /// through [`query_helix_for_users`](TwitchUserExtensions::query_helix_for_users).
   async fn query_helix_for_channels_from_list<S: AsRef<str>>(
     channels: &[ChannelIdentifier<S>],
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<twitch_user::ActiveModel>, EntityExtensionError>;
+  /// Queries Helix for every user passed in by their Twitch ID, recording the
+  /// [`account status`](crate::account_status_change) of each.
+  ///
+  /// Users Helix returns nothing for are suspended, or deleted if their login now belongs to someone else.
+  async fn query_helix_for_users(
+    users: &[twitch_user::Model],
+    database_connection: &DatabaseConnection,
   ) -> Result<Vec<twitch_user::ActiveModel>, EntityExtensionError>;
 
-  /// Takes a login name that might be within the database, and guesses the user using a levenshtein distance.
+  /// Takes a login name that might be within the database, and guesses the user using a Jaro-Winkler distance.
   ///
   /// Use this if [`get_or_set_by_name`](TwitchUserExtensions::get_or_set_by_name) fails on a name you expect to exist.
   async fn guess_name(
@@ -62,19 +108,31 @@ pub trait TwitchUserExtensions {
 impl TwitchUserExtensions for twitch_user::Model {
   /// Retrieves the user based on the given identifier.
   ///
-  /// Returns an error if the user was not found.
+  /// Logins no user currently has fall back to the most recent user who had it.
   async fn get_by_identifier<S: AsRef<str>>(
     identifier: ChannelIdentifier<S>,
     database_connection: &DatabaseConnection,
   ) -> Result<Option<twitch_user::Model>, EntityExtensionError> {
     match identifier {
       ChannelIdentifier::Login(user_login) => {
-        // -
-        twitch_user::Entity::find()
-          .filter(twitch_user::Column::LoginName.like(user_login.as_ref()))
+        let current_user = twitch_user::Entity::find()
+          .filter(twitch_user::Column::LoginName.eq(user_login.as_ref()))
           .one(database_connection)
-          .await
-          .map_err(Into::into)
+          .await?;
+
+        if current_user.is_some() {
+          return Ok(current_user);
+        }
+
+        let login_matches =
+          Self::get_by_login_history(user_login.as_ref(), database_connection).await?;
+
+        Ok(
+          login_matches
+            .into_iter()
+            .next()
+            .map(|login_match| login_match.user),
+        )
       }
       ChannelIdentifier::TwitchID(twitch_id) => {
         // -
@@ -90,6 +148,9 @@ impl TwitchUserExtensions for twitch_user::Model {
   /// Returns the list of users based on an incomplete login.
   /// For example, if you pass in a login of "fall", then the list of every user with "fall" in their name is returned.
   ///
+  /// Logins are looked up in the [`name index`](crate::name_index), so previous names are matched too. At most
+  /// [`MAX_INCOMPLETE_NAME_MATCHES`] users are returned, lowest ID first.
+  ///
   /// An error is returned if there are no matches.
   async fn get_list_by_incomplete_name<S: AsRef<str> + std::fmt::Debug>(
     identifier: ChannelIdentifier<S>,
@@ -97,9 +158,22 @@ impl TwitchUserExtensions for twitch_user::Model {
   ) -> Result<Vec<twitch_user::Model>, EntityExtensionError> {
     let user_list_result = match &identifier {
       ChannelIdentifier::Login(approximate_login) => {
-        // -
+        let mut user_ids =
+          name_index::find_users_containing(approximate_login.as_ref(), database_connection)
+            .await?;
+        user_ids.truncate(MAX_INCOMPLETE_NAME_MATCHES);
+
+        if user_ids.is_empty() {
+          return Err(EntityExtensionError::FailedToGetValue {
+            value_name: "user",
+            location: "get_list_by_incomplete_name",
+            additional_data: format!("{:?}", identifier),
+          });
+        }
+
         twitch_user::Entity::find()
-          .filter(twitch_user::Column::LoginName.contains(approximate_login.as_ref()))
+          .filter(twitch_user::Column::Id.is_in(user_ids))
+          .order_by_asc(twitch_user::Column::Id)
       }
       ChannelIdentifier::TwitchID(twitch_id) => {
         // -
@@ -123,6 +197,63 @@ impl TwitchUserExtensions for twitch_user::Model {
     user_list_result
   }
 
+  async fn get_by_login_history(
+    login: &str,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<LoginMatch>, EntityExtensionError> {
+    let current_users = twitch_user::Entity::find()
+      .filter(twitch_user::Column::LoginName.eq(login))
+      .all(database_connection)
+      .await?;
+    let login_changes = twitch_user_name_change::Entity::find()
+      .filter(
+        Condition::any()
+          .add(twitch_user_name_change::Column::PreviousLoginName.eq(login))
+          .add(twitch_user_name_change::Column::NewLoginName.eq(login)),
+      )
+      .find_also_related(twitch_user::Entity)
+      .order_by_asc(twitch_user_name_change::Column::CreatedAt)
+      .all(database_connection)
+      .await?;
+
+    let mut users_and_changes: BTreeMap<
+      i32,
+      (twitch_user::Model, Vec<twitch_user_name_change::Model>),
+    > = current_users
+      .into_iter()
+      .map(|user| (user.id, (user, vec![])))
+      .collect();
+
+    for (name_change, user) in login_changes {
+      let Some(user) = user else {
+        continue;
+      };
+
+      users_and_changes
+        .entry(user.id)
+        .or_insert_with(|| (user, vec![]))
+        .1
+        .push(name_change);
+    }
+
+    let mut login_matches: Vec<LoginMatch> = users_and_changes
+      .into_values()
+      .flat_map(|(user, name_changes)| login_periods(login, &user, &name_changes))
+      .collect();
+
+    // Sorted oldest first, then reversed so the current holder leads.
+    login_matches.sort_by_key(|login_match| {
+      (
+        login_match.is_current,
+        login_match.used_until,
+        login_match.used_from,
+      )
+    });
+    login_matches.reverse();
+
+    Ok(login_matches)
+  }
+
   /// Retrieves the user model from the database if it exists.
   /// Otherwise creates the user entry for the database and returns the resulting model.                 
   ///
@@ -143,8 +274,11 @@ impl TwitchUserExtensions for twitch_user::Model {
       return Ok(user_model);
     }
 
-    let helix_channel =
-      Self::query_helix_for_channels_from_list(&[ChannelIdentifier::Login(user_name)]).await?;
+    let helix_channel = Self::query_helix_for_channels_from_list(
+      &[ChannelIdentifier::Login(user_name)],
+      database_connection,
+    )
+    .await?;
     let Some(helix_channel) = helix_channel.first().cloned() else {
       return Err(EntityExtensionError::FailedToQuery {
         value_name: "helix user data",
@@ -177,6 +311,55 @@ impl TwitchUserExtensions for twitch_user::Model {
     }
   }
 
+  async fn get_or_set_by_names(
+    login_names: &[&str],
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<twitch_user::Model>, EntityExtensionError> {
+    if login_names.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let mut users = twitch_user::Entity::find()
+      .filter(twitch_user::Column::LoginName.is_in(login_names.iter().copied()))
+      .all(database_connection)
+      .await?;
+    let known_logins: HashSet<String> = users
+      .iter()
+      .map(|user| user.login_name.to_lowercase())
+      .collect();
+    let missing_logins: Vec<ChannelIdentifier<&str>> = login_names
+      .iter()
+      .filter(|login_name| !known_logins.contains(&login_name.to_lowercase()))
+      .map(|login_name| ChannelIdentifier::Login(*login_name))
+      .collect();
+
+    for missing_login_batch in missing_logins.chunks(HELIX_BATCH_SIZE) {
+      let helix_channels =
+        Self::query_helix_for_channels_from_list(missing_login_batch, database_connection).await?;
+
+      for helix_channel in helix_channels {
+        let ActiveValue::Set(twitch_id) = helix_channel.twitch_id else {
+          continue;
+        };
+        let maybe_model = twitch_user::Entity::find()
+          .filter(twitch_user::Column::TwitchId.eq(twitch_id))
+          .one(database_connection)
+          .await?;
+
+        let user = match maybe_model {
+          Some(existing_model) => {
+            check_for_name_change(existing_model, helix_channel, database_connection).await?
+          }
+          None => attempt_insert(helix_channel, database_connection).await?,
+        };
+
+        users.push(user);
+      }
+    }
+
+    Ok(users)
+  }
+
   /// Retrieves the user model from the database if it exists.
   /// Otherwise creates the user entry for the database and returns the resulting model.
   ///
@@ -190,16 +373,15 @@ impl TwitchUserExtensions for twitch_user::Model {
       .one(database_connection)
       .await?;
 
-    // if cfg!(test) || cfg!(feature = "__test_hook") {
-    //   return Ok(user_model.unwrap());
-    // }
-
     if let Some(user_model) = user_model {
       return Ok(user_model);
     }
 
-    let helix_channel =
-      Self::query_helix_for_channels_from_list(&[ChannelIdentifier::TwitchID(twitch_id)]).await?;
+    let helix_channel = Self::query_helix_for_channels_from_list(
+      &[ChannelIdentifier::TwitchID(twitch_id)],
+      database_connection,
+    )
+    .await?;
     let Some(helix_channel) = helix_channel.first().cloned() else {
       return Err(EntityExtensionError::FailedToQuery {
         value_name: "helix user data",
@@ -213,104 +395,99 @@ impl TwitchUserExtensions for twitch_user::Model {
 
   async fn query_helix_for_channels_from_list<S: AsRef<str>>(
     channels: &[ChannelIdentifier<S>],
+    database_connection: &DatabaseConnection,
   ) -> Result<Vec<twitch_user::ActiveModel>, EntityExtensionError> {
-    if channels.is_empty() || cfg!(feature = "__test_hook") || cfg!(test) {
-      return Ok(vec![]);
-    }
-
-    let mut query_url = Url::parse(HELIX_USER_QUERY_URL)?;
-    let reqwest_client = reqwest::Client::new();
-
-    {
-      let mut query_pairs = query_url.query_pairs_mut();
-
-      for channel_name in channels {
-        match channel_name {
-          ChannelIdentifier::Login(channel_name) => {
-            query_pairs.append_pair("login", channel_name.as_ref());
+    let helix_channels = request_helix_channels(channels).await?;
+    let returned_twitch_ids: HashSet<i32> = helix_channels
+      .iter()
+      .filter_map(|channel| channel.twitch_id.try_as_ref().copied())
+      .collect();
+    let returned_logins: HashSet<String> = helix_channels
+      .iter()
+      .filter_map(|channel| Some(channel.login_name.try_as_ref()?.to_lowercase()))
+      .collect();
+
+    let mut missed_twitch_ids = vec![];
+    let mut missed_logins = vec![];
+
+    for channel in channels {
+      match channel {
+        ChannelIdentifier::TwitchID(twitch_id) => {
+          if let Ok(twitch_id) = twitch_id.as_ref().parse::<i32>()
+            && !returned_twitch_ids.contains(&twitch_id)
+          {
+            missed_twitch_ids.push(twitch_id);
           }
-          ChannelIdentifier::TwitchID(twitch_id) => {
-            query_pairs.append_pair("id", twitch_id.as_ref());
+        }
+        ChannelIdentifier::Login(login) => {
+          if !returned_logins.contains(&login.as_ref().to_lowercase()) {
+            missed_logins.push(login.as_ref().to_owned());
           }
         }
       }
     }
 
-    let request = reqwest_client
-      .get(query_url)
-      .header(
-        "Authorization",
-        format!(
-          "Bearer {}",
-          Secret::read_secret_string(AppConfig::access_token().read_value())
-        ),
-      )
-      .header(
-        "Client-Id",
-        Secret::read_secret_string(AppConfig::client_id().read_value()),
-      );
-
-    let response = request.send().await?;
-    let response_body = response.text().await?;
-
-    let Value::Object(response_value) = serde_json::from_str::<Value>(&response_body)? else {
-      return Err(EntityExtensionError::UnknownResponseBody {
-        location: "query channel list",
-        response: response_body.to_owned(),
-      });
-    };
-    let Some(Value::Array(channel_list)) = response_value.get("data") else {
-      return Err(EntityExtensionError::UnknownResponseBody {
-        location: "query channel list internal list",
-        response: response_body.to_owned(),
-      });
-    };
-
-    let mut user_list = vec![];
+    if missed_twitch_ids.is_empty() && missed_logins.is_empty() {
+      return Ok(helix_channels);
+    }
 
-    for channel in channel_list {
-      let Value::Object(channel) = channel else {
-        continue;
-      };
+    let missed_users = twitch_user::Entity::find()
+      .filter(
+        Condition::any()
+          .add(twitch_user::Column::TwitchId.is_in(missed_twitch_ids))
+          .add(twitch_user::Column::LoginName.is_in(missed_logins)),
+      )
+      .all(database_connection)
+      .await?;
 
-      let Some(Value::String(login_name)) = channel.get("login") else {
-        tracing::error!("Unkown response: {:?}", channel);
-        tracing::error!(
-          "Received an unknown response body structure when querying. Body location: query channel list internal list.",
-        );
-        continue;
-      };
-      let Some(Value::String(display_name)) = channel.get("display_name") else {
-        continue;
-      };
-      let Some(Value::String(user_id)) = channel.get("id") else {
-        continue;
-      };
-      let Ok(user_id) = user_id.parse::<i32>() else {
-        return Err(EntityExtensionError::FailedToParseValue {
-          value_name: "twitch user id",
-          location: "query helix for channels from list",
-          value: user_id.to_string(),
-        });
-      };
+    if !missed_users.is_empty() {
+      Self::query_helix_for_users(&missed_users, database_connection).await?;
+    }
 
-      let user = twitch_user::ActiveModel {
-        twitch_id: ActiveValue::Set(user_id),
-        login_name: ActiveValue::Set(login_name.to_owned()),
-        display_name: ActiveValue::Set(display_name.to_owned()),
-        ..Default::default()
-      };
+    Ok(helix_channels)
+  }
 
-      user_list.push(user);
+  async fn query_helix_for_users(
+    users: &[twitch_user::Model],
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<twitch_user::ActiveModel>, EntityExtensionError> {
+    let twitch_ids: Vec<ChannelIdentifier<String>> = users
+      .iter()
+      .map(|user| ChannelIdentifier::TwitchID(user.twitch_id.to_string()))
+      .collect();
+    let helix_channels = request_helix_channels(&twitch_ids).await?;
+
+    let returned_twitch_ids: HashSet<i32> = helix_channels
+      .iter()
+      .filter_map(|channel| channel.twitch_id.try_as_ref().copied())
+      .collect();
+    let missing_logins: Vec<ChannelIdentifier<&str>> = users
+      .iter()
+      .filter(|user| !returned_twitch_ids.contains(&user.twitch_id))
+      .map(|user| ChannelIdentifier::Login(user.login_name.as_str()))
+      .collect();
+    // The ID lookup missed these users, so anyone Helix returns for their login is a different account.
+    let reused_logins: HashSet<String> = request_helix_channels(&missing_logins)
+      .await?
+      .iter()
+      .filter_map(|channel| Some(channel.login_name.try_as_ref()?.to_lowercase()))
+      .collect();
+
+    let statuses = get_account_statuses(users, &returned_twitch_ids, &reused_logins);
+
+    for (user_id, status) in
+      account_status_change::record_statuses(&statuses, database_connection).await?
+    {
+      tracing::info!("User {user_id} is now {status:?}.");
     }
 
-    Ok(user_list)
+    Ok(helix_channels)
   }
 
-  /// Takes a guessed name and compares it against all login and display names in the database.
+  /// Takes a guessed name and compares it against every current and previous login and display name.
   ///
-  /// If the name matches close enough to one in the database, the model for it is returned.
-  /// Otherwise None is returned.
+  /// Close names are stored as [`candidate matches`](crate::unknown_user_match) to be reviewed. The user is only
+  /// returned if the name was already confirmed as theirs, or was close enough to be confirmed automatically.
   async fn guess_name(
     guess_name: &str,
     database_connection: &DatabaseConnection,
@@ -325,23 +502,7 @@ impl TwitchUserExtensions for twitch_user::Model {
       return Ok(Some(associated_user));
     }
 
-    let all_users = twitch_user::Entity::find().all(database_connection).await?;
-
-    let maybe_user_match = all_users.into_iter().find(|user| {
-      jaro_winkler(&user.login_name, guess_name) >= JARO_NAME_SIMILARITY_THRESHOLD
-        || jaro_winkler(&user.display_name, guess_name) >= JARO_NAME_SIMILARITY_THRESHOLD
-    });
-
-    if let Some(matched_user) = &maybe_user_match {
-      let _ = twitch_user_unknown_user_association::Model::get_or_set_connection(
-        &unknown_user,
-        matched_user,
-        database_connection,
-      )
-      .await?;
-    }
-
-    Ok(maybe_user_match)
+    unknown_user_match::Model::find_candidates(&unknown_user, database_connection).await
   }
 }
 
@@ -380,7 +541,8 @@ async fn check_for_name_change(
     ..existing_twitch_user.into_active_model()
   };
 
-  name_change.insert(database_connection).await?;
+  let name_change = name_change.insert(database_connection).await?;
+  account_status_change::record_rename(name_change.twitch_user_id, database_connection).await?;
 
   updated_twitch_user
     .update(database_connection)
@@ -388,6 +550,104 @@ async fn check_for_name_change(
     .map_err(Into::into)
 }
 
+/// Sends a single Helix request for every user passed in.
+async fn request_helix_channels<S: AsRef<str>>(
+  channels: &[ChannelIdentifier<S>],
+) -> Result<Vec<twitch_user::ActiveModel>, EntityExtensionError> {
+  if channels.is_empty() {
+    return Ok(vec![]);
+  }
+
+  let mut request = get_helix_client().get(HELIX_USER_QUERY_PATH);
+
+  for channel_name in channels {
+    request = match channel_name {
+      ChannelIdentifier::Login(channel_name) => request.query("login", channel_name.as_ref()),
+      ChannelIdentifier::TwitchID(twitch_id) => request.query("id", twitch_id.as_ref()),
+    };
+  }
+
+  let response = request.send().await?;
+  let response_body = response.text().await?;
+
+  let Value::Object(response_value) = serde_json::from_str::<Value>(&response_body)? else {
+    return Err(EntityExtensionError::UnknownResponseBody {
+      location: "query channel list",
+      response: response_body.to_owned(),
+    });
+  };
+  let Some(Value::Array(channel_list)) = response_value.get("data") else {
+    return Err(EntityExtensionError::UnknownResponseBody {
+      location: "query channel list internal list",
+      response: response_body.to_owned(),
+    });
+  };
+
+  let mut user_list = vec![];
+
+  for channel in channel_list {
+    let Value::Object(channel) = channel else {
+      continue;
+    };
+
+    let Some(Value::String(login_name)) = channel.get("login") else {
+      tracing::error!("Unkown response: {:?}", channel);
+      tracing::error!(
+        "Received an unknown response body structure when querying. Body location: query channel list internal list.",
+      );
+      continue;
+    };
+    let Some(Value::String(display_name)) = channel.get("display_name") else {
+      continue;
+    };
+    let Some(Value::String(user_id)) = channel.get("id") else {
+      continue;
+    };
+    let Ok(user_id) = user_id.parse::<i32>() else {
+      return Err(EntityExtensionError::FailedToParseValue {
+        value_name: "twitch user id",
+        location: "request helix channels",
+        value: user_id.to_string(),
+      });
+    };
+
+    let user = twitch_user::ActiveModel {
+      twitch_id: ActiveValue::Set(user_id),
+      login_name: ActiveValue::Set(login_name.to_owned()),
+      display_name: ActiveValue::Set(display_name.to_owned()),
+      ..Default::default()
+    };
+
+    user_list.push(user);
+  }
+
+  Ok(user_list)
+}
+
+/// Works out each user's account status from a Helix lookup by their Twitch ID.
+///
+/// Users missing from the lookup are deleted if their login now belongs to someone else, and suspended otherwise.
+fn get_account_statuses(
+  users: &[twitch_user::Model],
+  returned_twitch_ids: &HashSet<i32>,
+  reused_logins: &HashSet<String>,
+) -> Vec<(i32, AccountStatus)> {
+  users
+    .iter()
+    .map(|user| {
+      let status = if returned_twitch_ids.contains(&user.twitch_id) {
+        AccountStatus::Active
+      } else if reused_logins.contains(&user.login_name.to_lowercase()) {
+        AccountStatus::Deleted
+      } else {
+        AccountStatus::Suspended
+      };
+
+      (user.id, status)
+    })
+    .collect()
+}
+
 /// Attempts to insert the user into the database.
 ///
 /// If there is a unique constraint violation, attempts to get the user again and returns the value.
@@ -427,6 +687,53 @@ async fn attempt_insert(
   result.map_err(Into::into)
 }
 
+/// Splits the user's time with the login into periods using their name changes, ordered oldest first.
+fn login_periods(
+  login: &str,
+  user: &twitch_user::Model,
+  name_changes: &[twitch_user_name_change::Model],
+) -> Vec<LoginMatch> {
+  let is_login = |name: &Option<String>| {
+    name
+      .as_ref()
+      .is_some_and(|name| name.eq_ignore_ascii_case(login))
+  };
+  let login_match = |matched_login: &str, used_from, used_until: Option<DateTimeUtc>| LoginMatch {
+    user: user.clone(),
+    matched_login: matched_login.to_owned(),
+    is_current: used_until.is_none(),
+    used_from,
+    used_until,
+  };
+
+  let mut login_periods = vec![];
+  let mut used_from = None;
+
+  for name_change in name_changes {
+    if let Some(previous_login) = name_change
+      .previous_login_name
+      .as_ref()
+      .filter(|_| is_login(&name_change.previous_login_name))
+    {
+      login_periods.push(login_match(
+        previous_login,
+        used_from.take(),
+        Some(name_change.created_at),
+      ));
+    }
+
+    if is_login(&name_change.new_login_name) {
+      used_from = Some(name_change.created_at);
+    }
+  }
+
+  if user.login_name.eq_ignore_ascii_case(login) {
+    login_periods.push(login_match(&user.login_name, used_from, None));
+  }
+
+  login_periods
+}
+
 impl ChannelIdentifier<&str> {
   pub fn to_owned(&self) -> ChannelIdentifier<String> {
     match self {
@@ -439,6 +746,7 @@ impl ChannelIdentifier<&str> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::mock_helix::use_mock_helix;
 
   #[tokio::test]
   async fn get_by_identifier_method_works_with_string_twitch_ids() {
@@ -447,6 +755,7 @@ mod tests {
       twitch_id: 578762718,
       login_name: "fallenshadow".into(),
       display_name: "fallenshadow".into(),
+      last_verified_at: None,
     };
     let mock_database = MockDatabase::new(DatabaseBackend::MySql)
       .append_query_results([vec![user_model.clone()]])
@@ -468,6 +777,7 @@ mod tests {
       twitch_id: 578762718,
       login_name: "fallenshadow".into(),
       display_name: "fallenshadow".into(),
+      last_verified_at: None,
     };
     let mock_database = MockDatabase::new(DatabaseBackend::MySql)
       .append_query_results([vec![user_model.clone()]])
@@ -482,6 +792,105 @@ mod tests {
     assert_eq!(result, Some(user_model));
   }
 
+  fn name_change(
+    id: i32,
+    twitch_user_id: i32,
+    previous_login_name: &str,
+    new_login_name: &str,
+    day: u32,
+  ) -> twitch_user_name_change::Model {
+    twitch_user_name_change::Model {
+      id,
+      twitch_user_id,
+      previous_login_name: Some(previous_login_name.into()),
+      previous_display_name: Some(previous_login_name.into()),
+      new_login_name: Some(new_login_name.into()),
+      new_display_name: Some(new_login_name.into()),
+      created_at: chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2025, 3, day, 0, 0, 0).unwrap(),
+    }
+  }
+
+  #[tokio::test]
+  async fn get_by_login_history_returns_the_current_holder_before_previous_holders() {
+    let previous_holder = twitch_user::Model {
+      id: 1,
+      twitch_id: 100,
+      login_name: "moose_renamed".into(),
+      display_name: "Moose_Renamed".into(),
+      last_verified_at: None,
+    };
+    let current_holder = twitch_user::Model {
+      id: 2,
+      twitch_id: 200,
+      login_name: "moose".into(),
+      display_name: "Moose".into(),
+      last_verified_at: None,
+    };
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![current_holder.clone()]])
+      .append_query_results([vec![
+        (
+          name_change(1, 1, "moose", "moose_renamed", 5),
+          Some(previous_holder.clone()),
+        ),
+        (
+          name_change(2, 2, "not_moose", "moose", 10),
+          Some(current_holder.clone()),
+        ),
+      ]])
+      .into_connection();
+
+    let login_matches = twitch_user::Model::get_by_login_history("moose", &mock_database)
+      .await
+      .unwrap();
+
+    assert_eq!(
+      login_matches
+        .iter()
+        .map(|login_match| (login_match.user.id, login_match.is_current))
+        .collect::<Vec<_>>(),
+      vec![(2, true), (1, false)]
+    );
+    assert_eq!(
+      login_matches[0].used_from,
+      Some(name_change(2, 2, "", "", 10).created_at)
+    );
+    assert_eq!(login_matches[1].used_from, None);
+    assert_eq!(
+      login_matches[1].used_until,
+      Some(name_change(1, 1, "", "", 5).created_at)
+    );
+  }
+
+  #[test]
+  fn login_periods_splits_repeated_uses_of_a_login() {
+    let user = twitch_user::Model {
+      id: 1,
+      twitch_id: 100,
+      login_name: "Moose".into(),
+      display_name: "Moose".into(),
+      last_verified_at: None,
+    };
+    let name_changes = vec![
+      name_change(1, 1, "moose", "goose", 5),
+      name_change(2, 1, "goose", "moose", 10),
+    ];
+
+    let periods = login_periods("MOOSE", &user, &name_changes);
+
+    assert_eq!(periods.len(), 2);
+    assert_eq!(
+      (periods[0].used_from, periods[0].used_until),
+      (None, Some(name_changes[0].created_at))
+    );
+    assert_eq!(
+      (periods[1].used_from, periods[1].used_until),
+      (Some(name_changes[1].created_at), None)
+    );
+    assert!(periods[1].is_current);
+    assert_eq!(periods[1].matched_login, "Moose");
+  }
+
   #[tokio::test]
   async fn get_list_by_incomplete_name_works_for_list() {
     let user_models = vec![
@@ -490,38 +899,62 @@ mod tests {
         twitch_id: 578762718,
         login_name: "fallenshadow".into(),
         display_name: "fallenshadow".into(),
+        last_verified_at: None,
       },
       twitch_user::Model {
         id: 2,
         twitch_id: 795025340,
         login_name: "shadowchama".into(),
         display_name: "shadowchama".into(),
+        last_verified_at: None,
       },
     ];
+    let mut name_index = name_index::NameIndex::default();
+    name_index.insert_rows(&user_models, &[]);
+    name_index.insert_rows(
+      &[twitch_user::Model {
+        id: 3,
+        twitch_id: 128831052,
+        login_name: "moose".into(),
+        display_name: "Moose".into(),
+        last_verified_at: None,
+      }],
+      &[],
+    );
     let mock_database = MockDatabase::new(DatabaseBackend::MySql)
       .append_query_results([user_models.clone()])
       .into_connection();
 
     let incomplete_login = ChannelIdentifier::Login("shadow");
 
-    let result = twitch_user::Model::get_list_by_incomplete_name(incomplete_login, &mock_database)
-      .await
-      .unwrap();
+    let result = name_index::with_index(
+      name_index,
+      twitch_user::Model::get_list_by_incomplete_name(incomplete_login, &mock_database),
+    )
+    .await
+    .unwrap();
+    let statements: Vec<String> = mock_database
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect();
 
     assert_eq!(result, user_models);
+    assert!(statements[0].contains("WHERE `twitch_user`.`id` IN (1, 2)"));
   }
 
   #[tokio::test]
   async fn get_list_by_incomplete_name_returns_empty_list_with_no_results() {
-    let user_models: Vec<twitch_user::Model> = vec![];
-    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
-      .append_query_results([user_models])
-      .into_connection();
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql).into_connection();
 
     let incomplete_login = ChannelIdentifier::Login("svrfkljnsrvbujklnsdtbujkln;dtbujnkl;dbtjnkl");
 
-    let result =
-      twitch_user::Model::get_list_by_incomplete_name(incomplete_login, &mock_database).await;
+    let result = name_index::with_index(
+      name_index::NameIndex::default(),
+      twitch_user::Model::get_list_by_incomplete_name(incomplete_login, &mock_database),
+    )
+    .await;
 
     assert!(
       matches!(result, Err(EntityExtensionError::FailedToGetValue { .. })),
@@ -529,4 +962,148 @@ mod tests {
       result
     );
   }
+
+  fn status_user(id: i32, twitch_id: i32, login_name: &str) -> twitch_user::Model {
+    twitch_user::Model {
+      id,
+      twitch_id,
+      login_name: login_name.into(),
+      display_name: login_name.into(),
+      last_verified_at: None,
+    }
+  }
+
+  #[test]
+  fn get_account_statuses_marks_missing_users_with_reused_logins_as_deleted() {
+    let users = vec![
+      status_user(1, 100, "moose"),
+      status_user(2, 200, "FallenShadow"),
+      status_user(3, 300, "shadowchama"),
+    ];
+    let returned_twitch_ids = HashSet::from([100]);
+    let reused_logins = HashSet::from(["fallenshadow".to_string()]);
+
+    assert_eq!(
+      get_account_statuses(&users, &returned_twitch_ids, &reused_logins),
+      vec![
+        (1, AccountStatus::Active),
+        (2, AccountStatus::Deleted),
+        (3, AccountStatus::Suspended),
+      ]
+    );
+  }
+
+  #[test]
+  fn get_account_statuses_keeps_returned_users_active_when_their_login_was_reused() {
+    let users = vec![status_user(1, 100, "moose")];
+    let returned_twitch_ids = HashSet::from([100]);
+    let reused_logins = HashSet::from(["moose".to_string()]);
+
+    assert_eq!(
+      get_account_statuses(&users, &returned_twitch_ids, &reused_logins),
+      vec![(1, AccountStatus::Active)]
+    );
+  }
+
+  fn logged_statements(database_connection: DatabaseConnection) -> Vec<String> {
+    database_connection
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn query_helix_for_channels_from_list_marks_missing_known_users_as_suspended() {
+    use_mock_helix();
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![status_user(1, 100, "moose")]])
+      .append_query_results([Vec::<entities::account_status_change::Model>::new()])
+      .append_exec_results([MockExecResult {
+        last_insert_id: 1,
+        rows_affected: 1,
+      }])
+      .into_connection();
+
+    let helix_channels = twitch_user::Model::query_helix_for_channels_from_list(
+      &[ChannelIdentifier::Login("moose")],
+      &mock_database,
+    )
+    .await
+    .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert!(helix_channels.is_empty());
+    assert!(statements[0].contains("`twitch_user`.`login_name` IN ('moose')"));
+    assert!(statements[2].starts_with("INSERT INTO `account_status_change`"));
+    assert!(statements[2].contains("'suspended'"));
+  }
+
+  #[tokio::test]
+  async fn query_helix_for_channels_from_list_marks_missing_users_with_reused_logins_as_deleted() {
+    use_mock_helix();
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![status_user(2, 200, "fallenshadow")]])
+      .append_query_results([Vec::<entities::account_status_change::Model>::new()])
+      .append_exec_results([MockExecResult {
+        last_insert_id: 1,
+        rows_affected: 1,
+      }])
+      .into_connection();
+
+    let helix_channels = twitch_user::Model::query_helix_for_channels_from_list(
+      &[ChannelIdentifier::TwitchID("200")],
+      &mock_database,
+    )
+    .await
+    .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert!(helix_channels.is_empty());
+    assert!(statements[0].contains("`twitch_user`.`twitch_id` IN (200)"));
+    assert!(statements[2].starts_with("INSERT INTO `account_status_change`"));
+    assert!(statements[2].contains("'deleted'"));
+  }
+
+  #[tokio::test]
+  async fn query_helix_for_channels_from_list_returns_the_users_helix_knows() {
+    use_mock_helix();
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql).into_connection();
+
+    let helix_channels = twitch_user::Model::query_helix_for_channels_from_list(
+      &[
+        ChannelIdentifier::Login("ShadowChama"),
+        ChannelIdentifier::TwitchID("300"),
+      ],
+      &mock_database,
+    )
+    .await
+    .unwrap();
+    let twitch_ids: Vec<i32> = helix_channels
+      .iter()
+      .map(|channel| *channel.twitch_id.as_ref())
+      .collect();
+
+    assert_eq!(twitch_ids, vec![300, 400]);
+    assert!(mock_database.into_transaction_log().is_empty());
+  }
+
+  #[tokio::test]
+  async fn query_helix_for_channels_from_list_skips_unknown_misses() {
+    use_mock_helix();
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([Vec::<twitch_user::Model>::new()])
+      .into_connection();
+
+    let helix_channels = twitch_user::Model::query_helix_for_channels_from_list(
+      &[ChannelIdentifier::TwitchID("100")],
+      &mock_database,
+    )
+    .await
+    .unwrap();
+
+    assert!(helix_channels.is_empty());
+    assert_eq!(mock_database.into_transaction_log().len(), 1);
+  }
 }
@@ -0,0 +1,439 @@
+use crate::errors::EntityExtensionError;
+use crate::name_index;
+use chrono::Utc;
+use entities::sea_orm_active_enums::MatchStatus;
+use entities::{
+  donation_event, twitch_user, twitch_user_unknown_user_association, unknown_user,
+  unknown_user_match,
+};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+/// Names scoring at least this against a user are stored as a candidate match for review.
+pub const MATCH_CANDIDATE_THRESHOLD: f64 = 0.85;
+/// Candidates scoring at least this are confirmed without a review, as long as no other candidate ties them.
+pub const AUTO_CONFIRM_THRESHOLD: f64 = 0.97;
+const MAX_MATCH_CANDIDATES: usize = 5;
+
+pub trait UnknownUserMatchExtensions {
+  /// Scores the unknown user's name against the [`name index`](crate::name_index), storing the closest users as candidates.
+  ///
+  /// Returns the matched user if a candidate was close enough to be confirmed automatically.
+  /// Nothing is done if the unknown user already has candidates.
+  async fn find_candidates(
+    unknown_user: &unknown_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<twitch_user::Model>, EntityExtensionError>;
+  async fn get_by_status(
+    status: Option<MatchStatus>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<unknown_user_match::Model>, EntityExtensionError>;
+  /// Associates the unknown user with the matched user, attributing the unknown user's donations to them.
+  ///
+  /// Any other confirmed match for the unknown user is reverted first.
+  async fn confirm(
+    match_id: i32,
+    database_connection: &DatabaseConnection,
+  ) -> Result<unknown_user_match::Model, EntityExtensionError>;
+  /// Rejects the match, reverting the association and donations if it was confirmed.
+  async fn reject(
+    match_id: i32,
+    database_connection: &DatabaseConnection,
+  ) -> Result<unknown_user_match::Model, EntityExtensionError>;
+}
+
+impl UnknownUserMatchExtensions for unknown_user_match::Model {
+  async fn find_candidates(
+    unknown_user: &unknown_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<twitch_user::Model>, EntityExtensionError> {
+    let has_candidates = unknown_user_match::Entity::find()
+      .filter(unknown_user_match::Column::UnknownUserId.eq(unknown_user.id))
+      .one(database_connection)
+      .await?
+      .is_some();
+
+    if has_candidates {
+      return Ok(None);
+    }
+
+    let candidates = name_index::find_similar_users(
+      &unknown_user.name,
+      MATCH_CANDIDATE_THRESHOLD,
+      MAX_MATCH_CANDIDATES,
+      database_connection,
+    )
+    .await?;
+
+    let Some((best_user_id, best_score)) = candidates.first().copied() else {
+      return Ok(None);
+    };
+    let is_unique_best = candidates
+      .get(1)
+      .is_none_or(|(_, second_score)| *second_score < best_score);
+
+    tracing::info!(
+      "Found {} candidate matches for unknown user `{}`.",
+      candidates.len(),
+      unknown_user.name
+    );
+
+    let candidate_models =
+      candidates
+        .iter()
+        .map(|(twitch_user_id, score)| unknown_user_match::ActiveModel {
+          unknown_user_id: Set(unknown_user.id),
+          twitch_user_id: Set(*twitch_user_id),
+          score: Set(Some(*score)),
+          status: Set(MatchStatus::Pending),
+          created_at: Set(Utc::now()),
+          ..Default::default()
+        });
+
+    unknown_user_match::Entity::insert_many(candidate_models)
+      .exec(database_connection)
+      .await?;
+
+    if best_score < AUTO_CONFIRM_THRESHOLD || !is_unique_best {
+      return Ok(None);
+    }
+
+    let Some(best_match) = unknown_user_match::Entity::find()
+      .filter(unknown_user_match::Column::UnknownUserId.eq(unknown_user.id))
+      .filter(unknown_user_match::Column::TwitchUserId.eq(best_user_id))
+      .one(database_connection)
+      .await?
+    else {
+      return Ok(None);
+    };
+
+    Self::confirm(best_match.id, database_connection).await?;
+
+    twitch_user::Entity::find_by_id(best_user_id)
+      .one(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  async fn get_by_status(
+    status: Option<MatchStatus>,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Vec<unknown_user_match::Model>, EntityExtensionError> {
+    let mut query = unknown_user_match::Entity::find();
+
+    if let Some(status) = status {
+      query = query.filter(unknown_user_match::Column::Status.eq(status));
+    }
+
+    query
+      .order_by_asc(unknown_user_match::Column::UnknownUserId)
+      .order_by_desc(unknown_user_match::Column::Score)
+      .all(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+
+  async fn confirm(
+    match_id: i32,
+    database_connection: &DatabaseConnection,
+  ) -> Result<unknown_user_match::Model, EntityExtensionError> {
+    let transaction = database_connection.begin().await?;
+    let user_match = get_match(match_id, &transaction).await?;
+
+    if user_match.status == MatchStatus::Confirmed {
+      return Ok(user_match);
+    }
+
+    let other_confirmed_matches = unknown_user_match::Entity::find()
+      .filter(unknown_user_match::Column::UnknownUserId.eq(user_match.unknown_user_id))
+      .filter(unknown_user_match::Column::Status.eq(MatchStatus::Confirmed))
+      .all(&transaction)
+      .await?;
+
+    for other_match in other_confirmed_matches {
+      revert_association(&other_match, &transaction).await?;
+      set_status(other_match, MatchStatus::Rejected, &transaction).await?;
+    }
+
+    let has_association = twitch_user_unknown_user_association::Entity::find_by_id((
+      user_match.unknown_user_id,
+      user_match.twitch_user_id,
+    ))
+    .one(&transaction)
+    .await?
+    .is_some();
+
+    if !has_association {
+      twitch_user_unknown_user_association::ActiveModel {
+        unknown_user_id: Set(user_match.unknown_user_id),
+        twitch_user_id: Set(user_match.twitch_user_id),
+        created_at: Set(Utc::now()),
+      }
+      .insert(&transaction)
+      .await?;
+    }
+
+    donation_event::Entity::update_many()
+      .col_expr(
+        donation_event::Column::DonatorTwitchUserId,
+        Expr::value(user_match.twitch_user_id),
+      )
+      .filter(donation_event::Column::UnknownUserId.eq(user_match.unknown_user_id))
+      .filter(donation_event::Column::DonatorTwitchUserId.is_null())
+      .exec(&transaction)
+      .await?;
+
+    let user_match = set_status(user_match, MatchStatus::Confirmed, &transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(user_match)
+  }
+
+  async fn reject(
+    match_id: i32,
+    database_connection: &DatabaseConnection,
+  ) -> Result<unknown_user_match::Model, EntityExtensionError> {
+    let transaction = database_connection.begin().await?;
+    let user_match = get_match(match_id, &transaction).await?;
+
+    if user_match.status == MatchStatus::Confirmed {
+      revert_association(&user_match, &transaction).await?;
+    }
+
+    let user_match = set_status(user_match, MatchStatus::Rejected, &transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(user_match)
+  }
+}
+
+async fn get_match(
+  match_id: i32,
+  transaction: &DatabaseTransaction,
+) -> Result<unknown_user_match::Model, EntityExtensionError> {
+  unknown_user_match::Entity::find_by_id(match_id)
+    .one(transaction)
+    .await?
+    .ok_or(EntityExtensionError::FailedToGetValue {
+      value_name: "unknown user match",
+      location: "unknown user match review",
+      additional_data: format!("No match with the ID {match_id}"),
+    })
+}
+
+/// Removes the association for the match and unattributes the donations it was given.
+async fn revert_association(
+  user_match: &unknown_user_match::Model,
+  transaction: &DatabaseTransaction,
+) -> Result<(), EntityExtensionError> {
+  twitch_user_unknown_user_association::Entity::delete_by_id((
+    user_match.unknown_user_id,
+    user_match.twitch_user_id,
+  ))
+  .exec(transaction)
+  .await?;
+
+  donation_event::Entity::update_many()
+    .col_expr(
+      donation_event::Column::DonatorTwitchUserId,
+      Expr::value(Option::<i32>::None),
+    )
+    .filter(donation_event::Column::UnknownUserId.eq(user_match.unknown_user_id))
+    .filter(donation_event::Column::DonatorTwitchUserId.eq(user_match.twitch_user_id))
+    .exec(transaction)
+    .await?;
+
+  Ok(())
+}
+
+async fn set_status(
+  user_match: unknown_user_match::Model,
+  status: MatchStatus,
+  transaction: &DatabaseTransaction,
+) -> Result<unknown_user_match::Model, EntityExtensionError> {
+  let mut active_model = user_match.into_active_model();
+  active_model.status = Set(status);
+  active_model.reviewed_at = Set(Some(Utc::now()));
+
+  active_model.update(transaction).await.map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  const UNKNOWN_USER_ID: i32 = 7;
+
+  #[tokio::test]
+  async fn confirm_associates_the_user_and_attributes_the_unknown_users_donations() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![user_match(5, 2, MatchStatus::Pending)]])
+      .append_query_results([Vec::<unknown_user_match::Model>::new()])
+      .append_query_results([
+        Vec::<twitch_user_unknown_user_association::Model>::new(),
+        vec![association(2)],
+      ])
+      .append_query_results([vec![user_match(5, 2, MatchStatus::Confirmed)]])
+      .append_exec_results(exec_results(&[1, 3, 1]))
+      .into_connection();
+
+    let user_match = unknown_user_match::Model::confirm(5, &mock_database)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert_eq!(user_match.status, MatchStatus::Confirmed);
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("INSERT INTO `twitch_user_unknown_user_association`")
+    }));
+    assert!(statements.contains(&"UPDATE `donation_event` SET `donator_twitch_user_id` = 2 WHERE `donation_event`.`unknown_user_id` = 7 AND `donation_event`.`donator_twitch_user_id` IS NULL".to_string()));
+    assert!(
+      !statements
+        .iter()
+        .any(|statement| statement.starts_with("DELETE"))
+    );
+  }
+
+  #[tokio::test]
+  async fn confirm_reverts_the_previously_confirmed_match() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![user_match(5, 2, MatchStatus::Pending)]])
+      .append_query_results([
+        vec![user_match(6, 3, MatchStatus::Confirmed)],
+        vec![user_match(6, 3, MatchStatus::Rejected)],
+      ])
+      .append_query_results([
+        Vec::<twitch_user_unknown_user_association::Model>::new(),
+        vec![association(2)],
+      ])
+      .append_query_results([vec![user_match(5, 2, MatchStatus::Confirmed)]])
+      .append_exec_results(exec_results(&[1, 3, 1, 1, 3, 1]))
+      .into_connection();
+
+    unknown_user_match::Model::confirm(5, &mock_database)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+    let revert_position = statements
+      .iter()
+      .position(|statement| statement == "UPDATE `donation_event` SET `donator_twitch_user_id` = NULL WHERE `donation_event`.`unknown_user_id` = 7 AND `donation_event`.`donator_twitch_user_id` = 3")
+      .unwrap();
+    let attribute_position = statements
+      .iter()
+      .position(|statement| statement == "UPDATE `donation_event` SET `donator_twitch_user_id` = 2 WHERE `donation_event`.`unknown_user_id` = 7 AND `donation_event`.`donator_twitch_user_id` IS NULL")
+      .unwrap();
+
+    assert!(revert_position < attribute_position);
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("DELETE FROM `twitch_user_unknown_user_association`")
+        && statement.contains("`twitch_user_id` = 3")
+    }));
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("UPDATE `unknown_user_match` SET `status` = 'rejected'")
+        && statement.ends_with("WHERE `unknown_user_match`.`id` = 6")
+    }));
+  }
+
+  #[tokio::test]
+  async fn reject_reverts_a_confirmed_match() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([
+        vec![user_match(5, 2, MatchStatus::Confirmed)],
+        vec![user_match(5, 2, MatchStatus::Rejected)],
+      ])
+      .append_exec_results(exec_results(&[1, 3, 1]))
+      .into_connection();
+
+    let user_match = unknown_user_match::Model::reject(5, &mock_database)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert_eq!(user_match.status, MatchStatus::Rejected);
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("DELETE FROM `twitch_user_unknown_user_association`")
+        && statement.contains("`unknown_user_id` = 7")
+        && statement.contains("`twitch_user_id` = 2")
+    }));
+    assert!(statements.contains(&"UPDATE `donation_event` SET `donator_twitch_user_id` = NULL WHERE `donation_event`.`unknown_user_id` = 7 AND `donation_event`.`donator_twitch_user_id` = 2".to_string()));
+  }
+
+  #[tokio::test]
+  async fn reject_leaves_donations_alone_for_a_pending_match() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([
+        vec![user_match(5, 2, MatchStatus::Pending)],
+        vec![user_match(5, 2, MatchStatus::Rejected)],
+      ])
+      .append_exec_results(exec_results(&[1]))
+      .into_connection();
+
+    let user_match = unknown_user_match::Model::reject(5, &mock_database)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert_eq!(user_match.status, MatchStatus::Rejected);
+    assert!(
+      !statements
+        .iter()
+        .any(|statement| statement.contains("`donation_event`") || statement.starts_with("DELETE"))
+    );
+  }
+
+  #[tokio::test]
+  async fn reviewing_a_missing_match_fails() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([Vec::<unknown_user_match::Model>::new()])
+      .into_connection();
+
+    let result = unknown_user_match::Model::reject(5, &mock_database).await;
+
+    assert!(matches!(
+      result,
+      Err(EntityExtensionError::FailedToGetValue { .. })
+    ));
+  }
+
+  fn user_match(id: i32, twitch_user_id: i32, status: MatchStatus) -> unknown_user_match::Model {
+    unknown_user_match::Model {
+      id,
+      unknown_user_id: UNKNOWN_USER_ID,
+      twitch_user_id,
+      score: Some(0.9),
+      status,
+      created_at: Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap(),
+      reviewed_at: None,
+    }
+  }
+
+  fn association(twitch_user_id: i32) -> twitch_user_unknown_user_association::Model {
+    twitch_user_unknown_user_association::Model {
+      unknown_user_id: UNKNOWN_USER_ID,
+      twitch_user_id,
+      created_at: Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap(),
+    }
+  }
+
+  fn exec_results(rows_affected: &[u64]) -> Vec<MockExecResult> {
+    rows_affected
+      .iter()
+      .map(|rows_affected| MockExecResult {
+        last_insert_id: 0,
+        rows_affected: *rows_affected,
+      })
+      .collect()
+  }
+
+  fn logged_statements(mock_database: DatabaseConnection) -> Vec<String> {
+    mock_database
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+}
@@ -0,0 +1,520 @@
+use crate::errors::EntityExtensionError;
+use crate::name_index;
+use entities::sea_orm_active_enums::ContentMode;
+use entities::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+const ANONYMIZED_NAME_PREFIX: &str = "erased_user_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErasureMode {
+  /// Removes every row belonging to the user along with the user itself.
+  ///
+  /// Rows belonging to other users that reference the user, such as a gift sub they received, have the reference removed.
+  Delete,
+  /// Keeps the user's rows so aggregates stay intact, but removes their names, message contents, and name history.
+  Anonymize,
+}
+
+impl FromStr for ErasureMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_lowercase().as_str() {
+      "delete" => Ok(Self::Delete),
+      "anonymize" => Ok(Self::Anonymize),
+      _ => Err(format!(
+        "Unknown erasure mode `{s}`. Expected `delete` or `anonymize`."
+      )),
+    }
+  }
+}
+
+impl fmt::Display for ErasureMode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Delete => write!(f, "delete"),
+      Self::Anonymize => write!(f, "anonymize"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErasureSummary {
+  pub user_id: i32,
+  pub mode: ErasureMode,
+  /// The amount of rows deleted or updated, keyed by table name.
+  pub affected_rows: BTreeMap<&'static str, u64>,
+}
+
+impl ErasureSummary {
+  fn record(&mut self, table_name: &'static str, rows_affected: u64) {
+    if rows_affected > 0 {
+      *self.affected_rows.entry(table_name).or_default() += rows_affected;
+    }
+  }
+}
+
+/// Removes or anonymizes everything stored about a user in a single transaction.
+///
+/// The foreign keys referencing `twitch_user` don't share the same delete rules, so each table is handled explicitly
+/// rather than relying on cascades. Tracked channels can't be erased, since that would remove the data of every user
+/// who chatted in them.
+pub async fn erase_user(
+  user_id: i32,
+  mode: ErasureMode,
+  database_connection: &DatabaseConnection,
+) -> Result<ErasureSummary, EntityExtensionError> {
+  let transaction = database_connection.begin().await?;
+
+  let Some(user) = twitch_user::Entity::find_by_id(user_id)
+    .one(&transaction)
+    .await?
+  else {
+    return Err(EntityExtensionError::FailedToGetValue {
+      value_name: "twitch user",
+      location: "erase user",
+      additional_data: format!("No user with the ID {user_id}"),
+    });
+  };
+
+  if is_tracked_channel(user.id, &transaction).await? {
+    return Err(EntityExtensionError::CannotEraseChannel(user.id));
+  }
+
+  let mut summary = ErasureSummary {
+    user_id: user.id,
+    mode,
+    affected_rows: BTreeMap::new(),
+  };
+
+  erase_unknown_user_associations(&user, mode, &mut summary, &transaction).await?;
+
+  let result = twitch_user_name_change::Entity::delete_many()
+    .filter(twitch_user_name_change::Column::TwitchUserId.eq(user.id))
+    .exec(&transaction)
+    .await?;
+  summary.record("twitch_user_name_change", result.rows_affected);
+
+  let result = account_status_change::Entity::delete_many()
+    .filter(account_status_change::Column::TwitchUserId.eq(user.id))
+    .exec(&transaction)
+    .await?;
+  summary.record("account_status_change", result.rows_affected);
+
+  match mode {
+    ErasureMode::Delete => delete_user_rows(&user, &mut summary, &transaction).await?,
+    ErasureMode::Anonymize => anonymize_user_rows(&user, &mut summary, &transaction).await?,
+  }
+
+  transaction.commit().await?;
+  name_index::forget_user(user.id);
+
+  Ok(summary)
+}
+
+/// The name an anonymized user is given in place of their login and display names.
+pub fn anonymized_name(user_id: i32) -> String {
+  format!("{ANONYMIZED_NAME_PREFIX}{user_id}")
+}
+
+async fn is_tracked_channel<C: ConnectionTrait>(
+  user_id: i32,
+  database_connection: &C,
+) -> Result<bool, EntityExtensionError> {
+  let stream_count = stream::Entity::find()
+    .filter(stream::Column::TwitchUserId.eq(user_id))
+    .count(database_connection)
+    .await?;
+
+  if stream_count > 0 {
+    return Ok(true);
+  }
+
+  let channel_message_count = stream_message::Entity::find()
+    .filter(stream_message::Column::ChannelId.eq(user_id))
+    .count(database_connection)
+    .await?;
+
+  Ok(channel_message_count > 0)
+}
+
+/// Removes the user's unknown user associations and candidate matches, along with any unknown users only associated with them.
+///
+/// Donations from those unknown users are attributed to the user when anonymizing, and left without a donator when
+/// deleting.
+async fn erase_unknown_user_associations<C: ConnectionTrait>(
+  user: &twitch_user::Model,
+  mode: ErasureMode,
+  summary: &mut ErasureSummary,
+  database_connection: &C,
+) -> Result<(), EntityExtensionError> {
+  let associated_unknown_user_ids: Vec<i32> = twitch_user_unknown_user_association::Entity::find()
+    .select_only()
+    .column(twitch_user_unknown_user_association::Column::UnknownUserId)
+    .filter(twitch_user_unknown_user_association::Column::TwitchUserId.eq(user.id))
+    .into_tuple()
+    .all(database_connection)
+    .await?;
+
+  let result = unknown_user_match::Entity::delete_many()
+    .filter(unknown_user_match::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("unknown_user_match", result.rows_affected);
+
+  if associated_unknown_user_ids.is_empty() {
+    return Ok(());
+  }
+
+  let result = twitch_user_unknown_user_association::Entity::delete_many()
+    .filter(twitch_user_unknown_user_association::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("twitch_user_unknown_user_association", result.rows_affected);
+
+  let shared_unknown_user_ids: HashSet<i32> = twitch_user_unknown_user_association::Entity::find()
+    .select_only()
+    .column(twitch_user_unknown_user_association::Column::UnknownUserId)
+    .filter(
+      twitch_user_unknown_user_association::Column::UnknownUserId
+        .is_in(associated_unknown_user_ids.clone()),
+    )
+    .into_tuple()
+    .all(database_connection)
+    .await?
+    .into_iter()
+    .collect();
+  let exclusive_unknown_user_ids: Vec<i32> = associated_unknown_user_ids
+    .into_iter()
+    .filter(|unknown_user_id| !shared_unknown_user_ids.contains(unknown_user_id))
+    .collect();
+
+  if exclusive_unknown_user_ids.is_empty() {
+    return Ok(());
+  }
+
+  let mut donation_update = donation_event::Entity::update_many()
+    .col_expr(
+      donation_event::Column::UnknownUserId,
+      Expr::value(Option::<i32>::None),
+    )
+    .filter(donation_event::Column::UnknownUserId.is_in(exclusive_unknown_user_ids.clone()));
+
+  if mode == ErasureMode::Anonymize {
+    donation_update = donation_update.col_expr(
+      donation_event::Column::DonatorTwitchUserId,
+      Expr::value(user.id),
+    );
+  }
+
+  let result = donation_update.exec(database_connection).await?;
+  summary.record("donation_event", result.rows_affected);
+
+  let result = unknown_user::Entity::delete_many()
+    .filter(unknown_user::Column::Id.is_in(exclusive_unknown_user_ids))
+    .exec(database_connection)
+    .await?;
+  summary.record("unknown_user", result.rows_affected);
+
+  Ok(())
+}
+
+async fn delete_user_rows<C: ConnectionTrait>(
+  user: &twitch_user::Model,
+  summary: &mut ErasureSummary,
+  database_connection: &C,
+) -> Result<(), EntityExtensionError> {
+  let user_message_ids = stream_message::Entity::find()
+    .select_only()
+    .column(stream_message::Column::Id)
+    .filter(stream_message::Column::TwitchUserId.eq(user.id))
+    .into_query();
+
+  // Emote usage doesn't cascade on message deletion, so it has to go first.
+  let result = emote_usage::Entity::delete_many()
+    .filter(emote_usage::Column::StreamMessageId.in_subquery(user_message_ids))
+    .exec(database_connection)
+    .await?;
+  summary.record("emote_usage", result.rows_affected);
+
+  let result = stream_message::Entity::delete_many()
+    .filter(stream_message::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("stream_message", result.rows_affected);
+
+  let result = user_timeout::Entity::delete_many()
+    .filter(user_timeout::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("user_timeout", result.rows_affected);
+
+  let result = chatter_presence::Entity::delete_many()
+    .filter(chatter_presence::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("chatter_presence", result.rows_affected);
+
+  let result = backfill_progress::Entity::delete_many()
+    .filter(backfill_progress::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("backfill_progress", result.rows_affected);
+
+  // Events in other users' streams are kept for the channel's totals, only losing who they came from.
+  let result = gift_sub_recipient::Entity::update_many()
+    .col_expr(
+      gift_sub_recipient::Column::TwitchUserId,
+      Expr::value(Option::<i32>::None),
+    )
+    .filter(gift_sub_recipient::Column::TwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("gift_sub_recipient", result.rows_affected);
+
+  let result = subscription_event::Entity::update_many()
+    .col_expr(
+      subscription_event::Column::SubscriberTwitchUserId,
+      Expr::value(Option::<i32>::None),
+    )
+    .filter(subscription_event::Column::SubscriberTwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("subscription_event", result.rows_affected);
+
+  let result = donation_event::Entity::update_many()
+    .col_expr(
+      donation_event::Column::DonatorTwitchUserId,
+      Expr::value(Option::<i32>::None),
+    )
+    .filter(donation_event::Column::DonatorTwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("donation_event", result.rows_affected);
+
+  let result = raid::Entity::update_many()
+    .col_expr(
+      raid::Column::RaiderTwitchUserId,
+      Expr::value(Option::<i32>::None),
+    )
+    .filter(raid::Column::RaiderTwitchUserId.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("raid", result.rows_affected);
+
+  let result = twitch_user::Entity::delete_by_id(user.id)
+    .exec(database_connection)
+    .await?;
+  summary.record("twitch_user", result.rows_affected);
+
+  Ok(())
+}
+
+async fn anonymize_user_rows<C: ConnectionTrait>(
+  user: &twitch_user::Model,
+  summary: &mut ErasureSummary,
+  database_connection: &C,
+) -> Result<(), EntityExtensionError> {
+  let result = stream_message::Entity::update_many()
+    .col_expr(
+      stream_message::Column::Contents,
+      Expr::value(Option::<String>::None),
+    )
+    .col_expr(
+      stream_message::Column::ContentMode,
+      Expr::value(ContentMode::Redacted),
+    )
+    .filter(stream_message::Column::TwitchUserId.eq(user.id))
+    .filter(stream_message::Column::Contents.is_not_null())
+    .exec(database_connection)
+    .await?;
+  summary.record("stream_message", result.rows_affected);
+
+  let anonymized_name = anonymized_name(user.id);
+  // Twitch IDs are always positive, so this can't collide with a real user who is tracked later on.
+  let result = twitch_user::Entity::update_many()
+    .col_expr(twitch_user::Column::TwitchId, Expr::value(-user.id))
+    .col_expr(
+      twitch_user::Column::LoginName,
+      Expr::value(anonymized_name.clone()),
+    )
+    .col_expr(
+      twitch_user::Column::DisplayName,
+      Expr::value(anonymized_name),
+    )
+    .filter(twitch_user::Column::Id.eq(user.id))
+    .exec(database_connection)
+    .await?;
+  summary.record("twitch_user", result.rows_affected);
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn erasure_mode_from_str_expected_value() {
+    assert_eq!("delete".parse(), Ok(ErasureMode::Delete));
+    assert_eq!(" Anonymize".parse(), Ok(ErasureMode::Anonymize));
+    assert!("remove".parse::<ErasureMode>().is_err());
+  }
+
+  #[tokio::test]
+  async fn erase_user_refuses_tracked_channels() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![twitch_user::Model {
+        id: 1,
+        twitch_id: 578762718,
+        display_name: "fallenshadow".into(),
+        login_name: "fallenshadow".into(),
+        last_verified_at: None,
+      }]])
+      .append_query_results([vec![count_query_result(3)]])
+      .into_connection();
+
+    let result = erase_user(1, ErasureMode::Delete, &mock_database).await;
+
+    assert!(matches!(
+      result,
+      Err(EntityExtensionError::CannotEraseChannel(1))
+    ));
+  }
+
+  #[tokio::test]
+  async fn delete_removes_the_users_rows_and_detaches_their_unknown_user_donations() {
+    let mock_database = erasable_user_database(&[7])
+      .append_query_results([Vec::<BTreeMap<String, Value>>::new()])
+      .append_exec_results(exec_results(&[
+        1, 1, 2, 1, 1, 0, 4, 5, 1, 0, 0, 1, 0, 2, 0, 1,
+      ]))
+      .into_connection();
+
+    let summary = erase_user(2, ErasureMode::Delete, &mock_database)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert_eq!(
+      summary.affected_rows,
+      BTreeMap::from([
+        ("donation_event", 4),
+        ("emote_usage", 4),
+        ("gift_sub_recipient", 1),
+        ("stream_message", 5),
+        ("twitch_user", 1),
+        ("twitch_user_name_change", 1),
+        ("twitch_user_unknown_user_association", 1),
+        ("unknown_user", 1),
+        ("unknown_user_match", 1),
+        ("user_timeout", 1),
+      ])
+    );
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("UPDATE `donation_event` SET `unknown_user_id` = NULL WHERE")
+    }));
+    assert!(
+      !statements
+        .iter()
+        .any(|statement| statement.contains("SET `donator_twitch_user_id` = 2"))
+    );
+    assert!(
+      statements.contains(&"DELETE FROM `twitch_user` WHERE `twitch_user`.`id` = 2".to_string())
+    );
+  }
+
+  #[tokio::test]
+  async fn anonymize_keeps_the_users_rows_without_their_names_or_message_contents() {
+    let mock_database = erasable_user_database(&[7])
+      .append_query_results([Vec::<BTreeMap<String, Value>>::new()])
+      .append_exec_results(exec_results(&[0, 1, 3, 1, 2, 0, 12, 1]))
+      .into_connection();
+
+    let summary = erase_user(2, ErasureMode::Anonymize, &mock_database)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert_eq!(
+      summary.affected_rows,
+      BTreeMap::from([
+        ("donation_event", 3),
+        ("stream_message", 12),
+        ("twitch_user", 1),
+        ("twitch_user_name_change", 2),
+        ("twitch_user_unknown_user_association", 1),
+        ("unknown_user", 1),
+      ])
+    );
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with(
+        "UPDATE `donation_event` SET `unknown_user_id` = NULL, `donator_twitch_user_id` = 2",
+      )
+    }));
+    assert!(statements.iter().any(|statement| {
+      statement
+        .starts_with("UPDATE `stream_message` SET `contents` = NULL, `content_mode` = 'redacted'")
+    }));
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("UPDATE `twitch_user` SET `twitch_id` = -2, `login_name` = 'erased_user_2', `display_name` = 'erased_user_2'")
+    }));
+    assert!(
+      !statements
+        .iter()
+        .any(|statement| statement.starts_with("DELETE FROM `stream_message`"))
+    );
+  }
+
+  /// A database holding a user who isn't a channel, associated with the given unknown users.
+  fn erasable_user_database(unknown_user_ids: &[i32]) -> MockDatabase {
+    let unknown_user_rows: Vec<BTreeMap<String, Value>> = unknown_user_ids
+      .iter()
+      .map(|unknown_user_id| {
+        BTreeMap::from([(
+          "unknown_user_id".to_string(),
+          Value::Int(Some(*unknown_user_id)),
+        )])
+      })
+      .collect();
+
+    MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![twitch_user::Model {
+        id: 2,
+        twitch_id: 128831052,
+        display_name: "Moose".into(),
+        login_name: "moose".into(),
+        last_verified_at: None,
+      }]])
+      .append_query_results([vec![count_query_result(0)], vec![count_query_result(0)]])
+      .append_query_results([unknown_user_rows])
+  }
+
+  fn exec_results(rows_affected: &[u64]) -> Vec<MockExecResult> {
+    rows_affected
+      .iter()
+      .map(|rows_affected| MockExecResult {
+        last_insert_id: 0,
+        rows_affected: *rows_affected,
+      })
+      .collect()
+  }
+
+  fn logged_statements(mock_database: DatabaseConnection) -> Vec<String> {
+    mock_database
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  fn count_query_result(count: i32) -> BTreeMap<String, Value> {
+    BTreeMap::from([("num_items".to_string(), Value::Int(Some(count)))])
+  }
+}
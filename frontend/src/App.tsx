@@ -14,6 +14,7 @@ import { MessageResults } from './components/UserMessageResults';
 import { StreamsResults } from './components/StreamsResults';
 import { DonationsResults } from './components/DonationsResults'
 import { RaidsResults } from './components/RaidsResults';
+import { TimeoutsResults } from './components/TimeoutsResults';
 
 const CATEGORY_COMPONENTS = {
   [CategoryState.Users]: UserResults,
@@ -24,6 +25,7 @@ const CATEGORY_COMPONENTS = {
   [CategoryState.Streams]: StreamsResults,
   [CategoryState.Raids]: RaidsResults,
   [CategoryState.Donations]: DonationsResults,
+  [CategoryState.Timeouts]: TimeoutsResults,
 } as const;
 
 export default function App() {
@@ -43,12 +43,14 @@ const QueryForm: React.FC<QueryFormProps> = ({ onSubmitQuery }) => {
     CategoryState.Subscriptions,
     CategoryState.Messages,
     CategoryState.Raids,
-    CategoryState.Donations
+    CategoryState.Donations,
+    CategoryState.Timeouts,
   ].includes(formData.category);
   const userIsOptional = [
     CategoryState.Subscriptions,
     CategoryState.Raids,
     CategoryState.Donations,
+    CategoryState.Timeouts,
   ].includes(formData.category);
 
   return (
@@ -0,0 +1,219 @@
+import { useEffect, useState } from 'react';
+import { buildFetchUrl } from '../services/FetchUrl';
+import { formatDate } from '../services/FormatDate';
+import { ActivityBucket, StreamActivity } from '../types/StreamActivity';
+
+export interface StreamActivityChartProps {
+  channel: string;
+  stream_id: number;
+}
+
+const CHART_WIDTH = 800;
+const CHART_HEIGHT = 300;
+const CHART_PADDING = 40;
+
+interface ChartSeries {
+  name: string;
+  color: string;
+  // Returns a value between 0 and 1 for the point's height.
+  value: (bucket: ActivityBucket) => number;
+}
+
+export default function StreamActivityChartButton({
+  channel,
+  stream_id
+}: StreamActivityChartProps) {
+  const [isOpen, setIsOpen] = useState(false);
+  const [activity, setActivity] = useState<StreamActivity | null>(null);
+  const [error, setError] = useState<any | null>(null);
+
+  useEffect(() => {
+    if (!isOpen || activity) {
+      return;
+    }
+
+    const fetchActivity = async () => {
+      try {
+        const requestUrl = buildFetchUrl({
+          route: `/streams/${stream_id}/activity`,
+          dataName: "bucket",
+          data: "60s",
+          pagination: null,
+          channel,
+          additional: "include_events=true",
+        });
+        const response = await fetch(requestUrl);
+
+        if (!response.ok) {
+          throw new Error(`HTTP error! ${await response.text()}`);
+        }
+
+        setActivity(await response.json());
+        setError(null);
+      } catch (err: any) {
+        setError(err);
+      }
+    };
+
+    fetchActivity();
+  }, [isOpen, activity, channel, stream_id]);
+
+  useEffect(() => {
+    const handleEscape = (e: KeyboardEvent) => {
+      if (e.key === 'Escape') {
+        setIsOpen(false);
+      }
+    };
+
+    if (isOpen) {
+      document.addEventListener('keydown', handleEscape);
+    }
+
+    return () => {
+      document.removeEventListener('keydown', handleEscape);
+    };
+  }, [isOpen]);
+
+  return (
+    <>
+      <button
+        onClick={() => setIsOpen(true)}
+        className="text-purple-400 hover:underline"
+      >
+        Activity
+      </button>
+
+      {isOpen && (
+        <>
+          <div
+            className="fixed inset-0 bg-black opacity-50"
+            onClick={() => setIsOpen(false)}
+          />
+          <div className="fixed inset-0 flex items-center justify-center p-4 pointer-events-none">
+            <div className="rounded-xl shadow-2xl max-w-4xl w-full bg-gray-900 border border-gray-800 overflow-hidden pointer-events-auto max-h-[80vh] flex flex-col">
+              <div className="p-6 flex-shrink-0">
+                <div className="flex justify-between items-center">
+                  <h2 className="text-xl font-semibold text-gray-300">Chat Activity</h2>
+                  <button
+                    onClick={() => setIsOpen(false)}
+                    className="text-gray-500 hover:text-gray-300"
+                  >
+                    ✕
+                  </button>
+                </div>
+              </div>
+
+              <div className="overflow-y-auto flex-1 px-6 pb-6">
+                {error && (
+                  <p className="text-red-400">Error: {error.message || "Failed to fetch stream activity."}</p>
+                )}
+                {!error && !activity && (
+                  <p className="text-gray-400">Loading stream activity...</p>
+                )}
+                {activity && <ActivityChart activity={activity} />}
+              </div>
+            </div>
+          </div>
+        </>
+      )}
+    </>
+  );
+}
+
+function ActivityChart({ activity }: { activity: StreamActivity }) {
+  const buckets = activity.buckets;
+
+  if (buckets.length === 0) {
+    return <p className="text-gray-400">No chat activity found for this stream.</p>;
+  }
+
+  const perMinute = 60 / activity.bucket_size;
+  const peakMessages = Math.max(...buckets.map((bucket) => bucket.message_count)) || 1;
+  const series: ChartSeries[] = [
+    {
+      name: `Messages per minute (peak ${(peakMessages * perMinute).toFixed(0)})`,
+      color: '#a855f7',
+      value: (bucket) => bucket.message_count / peakMessages,
+    },
+    {
+      name: 'Unique chatters per minute',
+      color: '#ec4899',
+      value: (bucket) => bucket.unique_chatters / peakMessages,
+    },
+    {
+      name: 'Emote only ratio',
+      color: '#22c55e',
+      value: (bucket) => bucket.emote_only_ratio,
+    },
+  ];
+
+  const plotWidth = CHART_WIDTH - CHART_PADDING * 2;
+  const plotHeight = CHART_HEIGHT - CHART_PADDING * 2;
+  const xPosition = (index: number) =>
+    CHART_PADDING + (buckets.length === 1 ? plotWidth / 2 : (index / (buckets.length - 1)) * plotWidth);
+  const yPosition = (value: number) => CHART_PADDING + (1 - value) * plotHeight;
+
+  return (
+    <div>
+      <svg viewBox={`0 0 ${CHART_WIDTH} ${CHART_HEIGHT}`} className="w-full">
+        <line
+          x1={CHART_PADDING}
+          y1={CHART_HEIGHT - CHART_PADDING}
+          x2={CHART_WIDTH - CHART_PADDING}
+          y2={CHART_HEIGHT - CHART_PADDING}
+          stroke="#4b5563"
+        />
+        <line
+          x1={CHART_PADDING}
+          y1={CHART_PADDING}
+          x2={CHART_PADDING}
+          y2={CHART_HEIGHT - CHART_PADDING}
+          stroke="#4b5563"
+        />
+        {series.map((line) => (
+          <polyline
+            key={line.name}
+            fill="none"
+            stroke={line.color}
+            strokeWidth={2}
+            points={buckets
+              .map((bucket, index) => `${xPosition(index)},${yPosition(line.value(bucket))}`)
+              .join(' ')}
+          />
+        ))}
+        {buckets.map((bucket, index) => {
+          const eventCount = (bucket.donation_count ?? 0) + (bucket.subscription_count ?? 0) + (bucket.raid_count ?? 0);
+
+          return (
+            <circle
+              key={bucket.bucket_start}
+              cx={xPosition(index)}
+              cy={yPosition(bucket.message_count / peakMessages)}
+              r={eventCount > 0 ? 4 : 2}
+              fill={eventCount > 0 ? '#facc15' : 'transparent'}
+            >
+              <title>
+                {`${formatDate(bucket.bucket_start)}\nMessages: ${bucket.message_count}\nChatters: ${bucket.unique_chatters}\nEmote only: ${(bucket.emote_only_ratio * 100).toFixed(0)}%`}
+              </title>
+            </circle>
+          );
+        })}
+        <text x={CHART_PADDING} y={CHART_HEIGHT - CHART_PADDING / 4} fill="#9ca3af" fontSize="12">
+          {formatDate(buckets[0].bucket_start)}
+        </text>
+        <text x={CHART_WIDTH - CHART_PADDING} y={CHART_HEIGHT - CHART_PADDING / 4} fill="#9ca3af" fontSize="12" textAnchor="end">
+          {formatDate(buckets[buckets.length - 1].bucket_start)}
+        </text>
+      </svg>
+
+      <div className="flex flex-wrap gap-4 mt-4">
+        {series.map((line) => (
+          <div key={line.name} className="flex items-center gap-2 text-sm text-gray-300">
+            <span className="inline-block w-3 h-3 rounded-full" style={{ backgroundColor: line.color }} />
+            {line.name}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
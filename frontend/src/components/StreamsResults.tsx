@@ -6,6 +6,7 @@ import { Stream, Streams } from "../types/Streams";
 import { Column, ResponsiveDataDisplay } from "./ResponsiveDataDisplay";
 import { formatDate } from '../services/FormatDate';
 import MutedVodTable from "./MutedVodTableButton";
+import StreamActivityChartButton from "./StreamActivityChart";
 
 export interface StreamsResultsProps {
   queryResults: QueryFormData;
@@ -54,6 +55,20 @@ export function StreamsResults(props: StreamsResultsProps) {
         </span>
       )
     },
+    { header_name: 'Peak Viewers', header_value_key: 'peak_viewers' },
+    {
+      header_name: 'Avg Viewers',
+      render: (item) => {
+        if (item.average_viewers === null) {
+          return null;
+        }
+        return (
+          <span className="text-sm text-gray-300">
+            {item.average_viewers.toFixed(0)}
+          </span>
+        );
+      }
+    },
     {
       header_name: 'Is Muted',
       render: (item) => {
@@ -71,6 +86,15 @@ export function StreamsResults(props: StreamsResultsProps) {
         );
       }
     },
+    {
+      header_name: 'Activity',
+      render: (item) => {
+        if (!response_data?.data.user) {
+          return null;
+        }
+        return <StreamActivityChartButton channel={response_data.data.user.login_name} stream_id={item.id} />;
+      }
+    },
     {
       header_name: 'Title',
       render: (item) => {
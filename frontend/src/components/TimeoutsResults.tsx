@@ -0,0 +1,101 @@
+import { useGetData } from "../services/DataRequest";
+import { buildFetchUrl } from "../services/FetchUrl";
+import { formatDate } from "../services/FormatDate";
+import { Pagination } from "../types/Pagination";
+import { QueryFormData } from "../types/QueryFormData";
+import { Timeout } from "../types/Timeouts";
+import { Column, ResponsiveDataDisplay } from "./ResponsiveDataDisplay";
+
+export interface TimeoutsResultsProps {
+  queryResults: QueryFormData;
+  pagination: Pagination | null;
+  updatePagination: (paginationResponse: Pagination | null) => void;
+  setIsLoading: (isLoading: boolean) => void;
+}
+
+export function TimeoutsResults(props: TimeoutsResultsProps) {
+  if (!props.queryResults.userSearchQuery && !props.queryResults.channelSearchQuery) {
+    return;
+  }
+
+  const userIdentifier = props.queryResults.userSearchQuery;
+  const requestType = Number(userIdentifier) ? "user_id" : "maybe_login";
+  const route = props.queryResults.channelSearchQuery ? "/timeouts" : "/users/timeouts";
+
+  const requestUrl = buildFetchUrl({
+    route,
+    dataName: requestType,
+    data: userIdentifier,
+    pagination: props.pagination,
+    channel: props.queryResults.channelSearchQuery,
+  });
+
+  const { response_data, error } = useGetData<Timeout[]>({
+    requestUrl,
+    updatePagination: props.updatePagination,
+    setIsLoading: props.setIsLoading,
+  });
+
+  const timeoutColumns: Column<Timeout>[] = [
+    { header_name: 'Id', header_value_key: 'id' },
+    {
+      header_name: 'Timestamp',
+      render: (item) => (
+        <span className="text-sm text-gray-300">
+          {formatDate(item.timestamp)}
+        </span>
+      )
+    },
+    {
+      header_name: 'User Name',
+      render: (item) => item.user?.login_name
+    },
+    {
+      header_name: 'Channel Name',
+      render: (item) => item.channel?.login_name
+    },
+    {
+      header_name: 'Duration',
+      render: (item) => {
+        if (item.is_permanent) {
+          return <span style={{ color: 'red' }}>Permanent</span>;
+        }
+        return item.duration !== null ? `${item.duration}s` : null;
+      }
+    },
+    {
+      header_name: 'Stream Title',
+      render: (item) => {
+        if (!item.stream_title) {
+          return null;
+        }
+        return (
+          <div className="max-w-xs truncate" title={item.stream_title}>
+            {item.stream_title}
+          </div>
+        );
+      }
+    },
+  ];
+
+  if (error) {
+    return (
+      <div className="bg-red-900/20 border border-red-800 rounded-lg p-6 text-center">
+        <p className="text-red-400">Error: {error.message || "Failed to fetch timeouts."}</p>
+      </div>
+    );
+  }
+
+  return (
+    <>
+      {response_data?.data && (
+        <ResponsiveDataDisplay
+          data={response_data.data}
+          columns={timeoutColumns}
+          rowKey="id"
+          emptyMessage="No timeouts found."
+        />
+      )}
+    </>
+  );
+}
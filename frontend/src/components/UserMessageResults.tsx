@@ -238,7 +238,9 @@ const MessageRow: React.FC<{ message: UserMessage; user: User }> = ({ message, u
 
       {/* Message content with emotes */}
       <div className="text-gray-100 flex-1 min-w-0">
-        {renderMessageWithEmotes(message.contents, message.emote_usage)}
+        {message.content_mode === "Raw"
+          ? renderMessageWithEmotes(message.contents, message.emote_usage)
+          : <span className="text-gray-500 italic">({message.content_mode.toLowerCase()} message)</span>}
       </div>
     </div>
   );
@@ -7,4 +7,5 @@ export enum CategoryState {
   Streams = "Streams",
   Raids = "Raids",
   Donations = "Donations",
+  Timeouts = "Timeouts",
 }
@@ -0,0 +1,16 @@
+export interface StreamActivity {
+  stream_id: number;
+  // In seconds
+  bucket_size: number;
+  buckets: ActivityBucket[];
+}
+
+export interface ActivityBucket {
+  bucket_start: string;
+  message_count: number;
+  unique_chatters: number;
+  emote_only_ratio: number;
+  donation_count: number | null;
+  subscription_count: number | null;
+  raid_count: number | null;
+}
@@ -13,6 +13,8 @@ export interface Stream {
   twitch_vod_id: string | null;
   title: string | null;
   muted_vod_segments: MutedVodSegment[];
+  peak_viewers: number | null;
+  average_viewers: number | null;
 }
 
 export interface MutedVodSegment {
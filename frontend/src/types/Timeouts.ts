@@ -0,0 +1,14 @@
+import { Response } from "./Response";
+import { User } from "./users";
+
+export interface Timeout {
+  id: number;
+  user: User | null;
+  channel: User | null;
+  duration: number | null;
+  is_permanent: boolean;
+  timestamp: string;
+  stream_title: string | null;
+}
+
+export interface TimeoutResponse extends Response<Timeout[]> {}
@@ -12,7 +12,8 @@ export interface UserMessage {
   is_first_message: boolean,
   timestamp: string,
   contents: string,
-  is_subscriber: boolean,
+  content_mode: "Raw" | "Hashed" | "Redacted",
+  is_subscriber: boolean | null,
   emote_usage: Emote[],
 }
 
@@ -0,0 +1,634 @@
+use crate::credentials::{HelixCredentials, TokenResponse};
+use crate::errors::HelixError;
+use crate::rate_limit::RateLimit;
+use crate::token_lifecycle::TokenValidation;
+use crate::token_store::lock_token_store;
+use reqwest::{Method, Response, StatusCode};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+pub const HELIX_API_URL: &str = "https://api.twitch.tv/helix/";
+pub const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+pub const VALIDATE_URL: &str = "https://id.twitch.tv/oauth2/validate";
+/// Access tokens expiring within this long are refreshed before they're used.
+pub const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+/// How long a request can take before it's abandoned, so that a stalled connection can't hang the caller.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How failed requests are retried. Only connection errors, server errors, and rate limited requests are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+  /// How many times a request is sent before giving up.
+  pub max_attempts: u32,
+  /// The wait after the first failure, doubled after each one that follows.
+  pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      base_delay: Duration::from_secs(1),
+    }
+  }
+}
+
+impl RetryPolicy {
+  fn backoff(&self, failed_attempts: u32) -> Duration {
+    self.base_delay * 2_u32.pow(failed_attempts.saturating_sub(1))
+  }
+}
+
+/// Sends requests to Helix, keeping to the rate limit and refreshing the access token when it's rejected.
+pub struct HelixClient {
+  reqwest_client: reqwest::Client,
+  api_url: Url,
+  token_url: Url,
+  validate_url: Url,
+  retry_policy: RetryPolicy,
+  client_id: String,
+  credentials: tokio::sync::Mutex<HelixCredentials>,
+  rate_limit: Mutex<RateLimit>,
+}
+
+impl HelixClient {
+  pub fn new(credentials: HelixCredentials) -> Self {
+    Self {
+      reqwest_client: reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap(),
+      api_url: Url::parse(HELIX_API_URL).unwrap(),
+      token_url: Url::parse(TOKEN_URL).unwrap(),
+      validate_url: Url::parse(VALIDATE_URL).unwrap(),
+      retry_policy: RetryPolicy::default(),
+      client_id: credentials.client_id.clone(),
+      credentials: tokio::sync::Mutex::new(credentials),
+      rate_limit: Mutex::new(RateLimit::default()),
+    }
+  }
+
+  /// The URL request paths are joined onto. Must end with a `/`.
+  pub fn with_api_url(mut self, api_url: Url) -> Self {
+    self.api_url = api_url;
+
+    self
+  }
+
+  pub fn with_token_url(mut self, token_url: Url) -> Self {
+    self.token_url = token_url;
+
+    self
+  }
+
+  pub fn with_validate_url(mut self, validate_url: Url) -> Self {
+    self.validate_url = validate_url;
+
+    self
+  }
+
+  pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+    self.retry_policy = retry_policy;
+
+    self
+  }
+
+  /// Takes a path relative to the API URL, such as `users`.
+  pub fn get(&self, path: &str) -> HelixRequest<'_> {
+    HelixRequest::new(self, Method::GET, path)
+  }
+
+  /// Takes a path relative to the API URL, such as `eventsub/subscriptions`.
+  pub fn post(&self, path: &str) -> HelixRequest<'_> {
+    HelixRequest::new(self, Method::POST, path)
+  }
+
+  /// The rate limit as of the last response.
+  pub fn rate_limit(&self) -> RateLimit {
+    *self.rate_limit.lock().unwrap()
+  }
+
+  /// Returns the current access token, requesting one if there isn't one yet.
+  ///
+  /// Tokens about to expire are refreshed first if possible. If that fails, the current token is still returned.
+  pub async fn access_token(&self) -> Result<String, HelixError> {
+    let mut credentials = self.credentials.lock().await;
+
+    let Some(access_token) = credentials.access_token.clone() else {
+      return self.request_new_token(&mut credentials).await;
+    };
+
+    if credentials.client_secret.is_none() || !credentials.expires_within(TOKEN_REFRESH_MARGIN) {
+      return Ok(access_token);
+    }
+
+    tracing::info!("The Twitch access token is about to expire. Refreshing it.");
+
+    match self.request_new_token(&mut credentials).await {
+      Ok(access_token) => Ok(access_token),
+      Err(error) => {
+        tracing::error!(
+          "Failed to refresh the Twitch access token before it expired. Reason: {error}"
+        );
+
+        Ok(access_token)
+      }
+    }
+  }
+
+  /// Time until the access token should be refreshed. None if its expiry isn't known.
+  pub async fn time_until_refresh(&self) -> Option<Duration> {
+    let expires_at = self.credentials.lock().await.expires_at?;
+
+    Some(expires_at.saturating_duration_since(std::time::Instant::now() + TOKEN_REFRESH_MARGIN))
+  }
+
+  /// Checks the access token with Twitch, refreshing it if it was rejected, and notes when it expires.
+  ///
+  /// https://dev.twitch.tv/docs/authentication/validate-tokens/
+  pub async fn validate_token(&self) -> Result<TokenValidation, HelixError> {
+    let mut access_token = self.access_token().await?;
+    let mut has_refreshed_token = false;
+
+    loop {
+      let response = self
+        .reqwest_client
+        .get(self.validate_url.clone())
+        .header("Authorization", format!("OAuth {access_token}"))
+        .send()
+        .await?;
+      let status = response.status();
+
+      if status == StatusCode::UNAUTHORIZED && !has_refreshed_token {
+        has_refreshed_token = true;
+        access_token = self.refresh_access_token(&access_token).await?;
+
+        continue;
+      }
+
+      if !status.is_success() {
+        return Err(HelixError::InvalidAccessToken {
+          code: status.as_u16(),
+          response: response.text().await?,
+        });
+      }
+
+      let token_validation: TokenValidation = serde_json::from_str(&response.text().await?)?;
+      let mut credentials = self.credentials.lock().await;
+
+      if credentials.access_token.as_ref() == Some(&access_token) {
+        credentials.set_expires_in(token_validation.expires_in);
+      }
+
+      return Ok(token_validation);
+    }
+  }
+
+  /// Gets a new access token, unless the rejected one was already replaced by another request.
+  async fn refresh_access_token(&self, rejected_token: &str) -> Result<String, HelixError> {
+    let mut credentials = self.credentials.lock().await;
+
+    if let Some(access_token) = &credentials.access_token
+      && access_token != rejected_token
+    {
+      return Ok(access_token.clone());
+    }
+
+    tracing::info!("The Twitch access token was rejected. Requesting a new one.");
+
+    self.request_new_token(&mut credentials).await
+  }
+
+  /// Refreshes the access token while holding the token store lock, unless another process sharing the store
+  /// already refreshed it.
+  async fn request_new_token(
+    &self,
+    credentials: &mut HelixCredentials,
+  ) -> Result<String, HelixError> {
+    let _token_store_lock = match &credentials.token_store_path {
+      Some(token_store_path) => Some(lock_token_store(token_store_path).await?),
+      None => None,
+    };
+
+    if credentials.reload_stored_tokens()
+      && let Some(access_token) = &credentials.access_token
+    {
+      tracing::info!("Using the Twitch access token refreshed by another process.");
+
+      return Ok(access_token.clone());
+    }
+
+    let form = match credentials.token_request_form() {
+      Ok(form) => form,
+      Err(error) => {
+        tracing::error!("The Twitch access token can't be renewed. Reason: {error}");
+
+        return Err(error);
+      }
+    };
+
+    let response = self
+      .reqwest_client
+      .post(self.token_url.clone())
+      .form(&form)
+      .send()
+      .await?;
+    let status = response.status();
+
+    if !status.is_success() {
+      return Err(HelixError::FailedToRefreshToken {
+        code: status.as_u16(),
+        response: response.text().await?,
+      });
+    }
+
+    let token_response: TokenResponse = serde_json::from_str(&response.text().await?)?;
+    let access_token = token_response.access_token.clone();
+
+    credentials.update_tokens(token_response);
+
+    Ok(access_token)
+  }
+
+  /// Waits until the rate limit allows another request.
+  async fn wait_for_rate_limit(&self) {
+    loop {
+      let wait_time = self.rate_limit.lock().unwrap().reserve(SystemTime::now());
+      let Some(wait_time) = wait_time else {
+        return;
+      };
+
+      tracing::info!(
+        "Helix rate limit reached. Waiting {:?} for it to reset.",
+        wait_time
+      );
+
+      tokio::time::sleep(wait_time).await;
+    }
+  }
+
+  fn update_rate_limit(&self, response: &Response) {
+    if let Some(rate_limit) = RateLimit::from_headers(response.headers()) {
+      *self.rate_limit.lock().unwrap() = rate_limit;
+    }
+  }
+}
+
+/// A request to Helix, built up before sending so that it can be resent.
+pub struct HelixRequest<'a> {
+  helix_client: &'a HelixClient,
+  method: Method,
+  path: String,
+  query: Vec<(String, String)>,
+  body: Option<Value>,
+}
+
+impl<'a> HelixRequest<'a> {
+  fn new(helix_client: &'a HelixClient, method: Method, path: &str) -> Self {
+    Self {
+      helix_client,
+      method,
+      path: path.to_owned(),
+      query: vec![],
+      body: None,
+    }
+  }
+
+  /// Appends a query pair. Keys can be repeated, such as for querying multiple users.
+  pub fn query(mut self, key: &str, value: impl Into<String>) -> Self {
+    self.query.push((key.to_owned(), value.into()));
+
+    self
+  }
+
+  pub fn json(mut self, body: Value) -> Self {
+    self.body = Some(body);
+
+    self
+  }
+
+  /// Sends the request, retrying as configured in the client's [`RetryPolicy`].
+  ///
+  /// The final response is returned whatever its status, so callers still need to check it.
+  pub async fn send(self) -> Result<Response, HelixError> {
+    let helix_client = self.helix_client;
+    let retry_policy = helix_client.retry_policy;
+    let mut url = helix_client.api_url.join(&self.path)?;
+
+    if !self.query.is_empty() {
+      url.query_pairs_mut().extend_pairs(&self.query);
+    }
+
+    let mut access_token = helix_client.access_token().await?;
+    let mut has_refreshed_token = false;
+    let mut attempts = 0;
+
+    loop {
+      helix_client.wait_for_rate_limit().await;
+
+      let result = self.build(url.clone(), &access_token).send().await;
+      let is_retryable = match &result {
+        Ok(response) => {
+          helix_client.update_rate_limit(response);
+
+          let status = response.status();
+
+          if status == StatusCode::UNAUTHORIZED && !has_refreshed_token {
+            has_refreshed_token = true;
+            access_token = helix_client.refresh_access_token(&access_token).await?;
+
+            continue;
+          }
+
+          status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        }
+        Err(error) => error.is_connect() || error.is_timeout(),
+      };
+
+      attempts += 1;
+
+      if !is_retryable || attempts >= retry_policy.max_attempts {
+        return result.map_err(Into::into);
+      }
+
+      let backoff = retry_policy.backoff(attempts);
+
+      tracing::warn!(
+        "Helix request to `{}` failed. Retrying in {:?}. Result: {:?}",
+        self.path,
+        backoff,
+        result
+      );
+
+      tokio::time::sleep(backoff).await;
+    }
+  }
+
+  fn build(&self, url: Url, access_token: &str) -> reqwest::RequestBuilder {
+    let helix_client = self.helix_client;
+    let request = helix_client
+      .reqwest_client
+      .request(self.method.clone(), url)
+      .bearer_auth(access_token)
+      .header("Client-Id", &helix_client.client_id);
+
+    match &self.body {
+      Some(body) => request.json(body),
+      None => request,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum::Form;
+  use axum::Router;
+  use axum::extract::State;
+  use axum::http::HeaderMap;
+  use axum::routing::{get, post};
+  use std::collections::HashMap;
+  use std::sync::Arc;
+  use std::sync::atomic::{AtomicU32, Ordering};
+
+  #[derive(Clone, Default)]
+  struct MockHelix {
+    user_requests: Arc<AtomicU32>,
+    token_requests: Arc<AtomicU32>,
+  }
+
+  /// Starts a mock of Helix and the token endpoint, returning a client pointing at it.
+  async fn start_mock_helix(router: Router) -> HelixClient {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    let credentials = HelixCredentials {
+      client_id: "client_id".into(),
+      client_secret: Some("client_secret".into()),
+      access_token: Some("expired_token".into()),
+      refresh_token: Some("refresh_token".into()),
+      ..Default::default()
+    };
+
+    HelixClient::new(credentials)
+      .with_api_url(Url::parse(&format!("http://{address}/helix/")).unwrap())
+      .with_token_url(Url::parse(&format!("http://{address}/oauth2/token")).unwrap())
+      .with_validate_url(Url::parse(&format!("http://{address}/oauth2/validate")).unwrap())
+      .with_retry_policy(RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(1),
+      })
+  }
+
+  #[tokio::test]
+  async fn token_refreshed_by_another_process_is_reused() {
+    let mock_helix = MockHelix::default();
+    let router = Router::new()
+      .route(
+        "/helix/users",
+        get(|headers: HeaderMap| async move {
+          if headers["Authorization"] == "Bearer new_token" {
+            StatusCode::OK
+          } else {
+            StatusCode::UNAUTHORIZED
+          }
+        }),
+      )
+      .route(
+        "/oauth2/token",
+        post(|State(mock_helix): State<MockHelix>| async move {
+          mock_helix.token_requests.fetch_add(1, Ordering::SeqCst);
+
+          r#"{"access_token": "new_token", "refresh_token": "new_refresh_token"}"#
+        }),
+      )
+      .with_state(mock_helix.clone());
+    let token_store_path = std::env::temp_dir()
+      .join(format!("helix_client_shared_{}", std::process::id()))
+      .join("twitch_tokens.json");
+    let first_helix_client = start_mock_helix(router.clone()).await;
+    let second_helix_client = start_mock_helix(router).await;
+
+    for helix_client in [&first_helix_client, &second_helix_client] {
+      helix_client.credentials.lock().await.token_store_path = Some(token_store_path.clone());
+    }
+
+    let first_response = first_helix_client.get("users").send().await.unwrap();
+    let second_response = second_helix_client.get("users").send().await.unwrap();
+
+    assert_eq!(first_response.status(), StatusCode::OK);
+    assert_eq!(second_response.status(), StatusCode::OK);
+    assert_eq!(mock_helix.token_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(
+      second_helix_client
+        .credentials
+        .lock()
+        .await
+        .refresh_token
+        .as_deref(),
+      Some("new_refresh_token")
+    );
+
+    std::fs::remove_dir_all(token_store_path.parent().unwrap()).unwrap();
+  }
+
+  #[tokio::test]
+  async fn rejected_token_is_refreshed() {
+    let mock_helix = MockHelix::default();
+    let router = Router::new()
+      .route(
+        "/helix/users",
+        get(|headers: HeaderMap| async move {
+          if headers["Authorization"] == "Bearer new_token" {
+            StatusCode::OK
+          } else {
+            StatusCode::UNAUTHORIZED
+          }
+        }),
+      )
+      .route(
+        "/oauth2/token",
+        post(
+          |State(mock_helix): State<MockHelix>, Form(form): Form<HashMap<String, String>>| async move {
+            mock_helix.token_requests.fetch_add(1, Ordering::SeqCst);
+
+            assert_eq!(form["grant_type"], "refresh_token");
+            assert_eq!(form["refresh_token"], "refresh_token");
+
+            r#"{"access_token": "new_token", "refresh_token": "new_refresh_token"}"#
+          },
+        ),
+      )
+      .with_state(mock_helix.clone());
+    let helix_client = start_mock_helix(router).await;
+
+    let response = helix_client.get("users").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(mock_helix.token_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(helix_client.access_token().await.unwrap(), "new_token");
+    assert_eq!(
+      helix_client
+        .credentials
+        .lock()
+        .await
+        .refresh_token
+        .as_deref(),
+      Some("new_refresh_token")
+    );
+  }
+
+  #[tokio::test]
+  async fn validation_records_when_the_token_expires() {
+    let router = Router::new().route(
+      "/oauth2/validate",
+      get(|headers: HeaderMap| async move {
+        assert_eq!(headers["Authorization"], "OAuth expired_token");
+
+        r#"{"client_id": "client_id", "login": "fallenshadow", "user_id": "1", "scopes": ["bits:read"], "expires_in": 3600}"#
+      }),
+    );
+    let helix_client = start_mock_helix(router).await;
+
+    let token_validation = helix_client.validate_token().await.unwrap();
+    let time_until_refresh = helix_client.time_until_refresh().await.unwrap();
+
+    assert_eq!(token_validation.login.as_deref(), Some("fallenshadow"));
+    assert_eq!(token_validation.scopes, vec!["bits:read"]);
+    assert!(time_until_refresh > Duration::from_secs(3000));
+    assert!(time_until_refresh <= Duration::from_secs(3600) - TOKEN_REFRESH_MARGIN);
+  }
+
+  #[tokio::test]
+  async fn expiring_token_is_refreshed_before_use() {
+    let mock_helix = MockHelix::default();
+    let router = Router::new()
+      .route(
+        "/oauth2/token",
+        post(|State(mock_helix): State<MockHelix>| async move {
+          mock_helix.token_requests.fetch_add(1, Ordering::SeqCst);
+
+          r#"{"access_token": "new_token", "expires_in": 14400}"#
+        }),
+      )
+      .with_state(mock_helix.clone());
+    let helix_client = start_mock_helix(router).await;
+
+    helix_client.credentials.lock().await.expires_at = Some(std::time::Instant::now());
+
+    assert_eq!(helix_client.access_token().await.unwrap(), "new_token");
+    assert_eq!(helix_client.access_token().await.unwrap(), "new_token");
+    assert_eq!(mock_helix.token_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(
+      helix_client
+        .credentials
+        .lock()
+        .await
+        .refresh_token
+        .as_deref(),
+      Some("refresh_token")
+    );
+  }
+
+  #[tokio::test]
+  async fn server_errors_are_retried_until_the_attempts_run_out() {
+    let mock_helix = MockHelix::default();
+    let router = Router::new()
+      .route(
+        "/helix/users",
+        get(|State(mock_helix): State<MockHelix>| async move {
+          let request_number = mock_helix.user_requests.fetch_add(1, Ordering::SeqCst);
+
+          if request_number < 2 {
+            StatusCode::SERVICE_UNAVAILABLE
+          } else {
+            StatusCode::OK
+          }
+        }),
+      )
+      .route("/helix/streams", get(|| async { StatusCode::BAD_GATEWAY }))
+      .with_state(mock_helix.clone());
+    let helix_client = start_mock_helix(router).await;
+
+    let response = helix_client.get("users").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(mock_helix.user_requests.load(Ordering::SeqCst), 3);
+
+    let response = helix_client.get("streams").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+  }
+
+  #[tokio::test]
+  async fn rate_limit_is_tracked_from_responses() {
+    let router = Router::new().route(
+      "/helix/users",
+      get(|| async {
+        (
+          [
+            ("Ratelimit-Limit", "800"),
+            ("Ratelimit-Remaining", "750"),
+            ("Ratelimit-Reset", "1700000000"),
+          ],
+          StatusCode::OK,
+        )
+      }),
+    );
+    let helix_client = start_mock_helix(router).await;
+
+    helix_client
+      .get("users")
+      .query("login", "fallenshadow")
+      .send()
+      .await
+      .unwrap();
+
+    assert_eq!(helix_client.rate_limit().limit, Some(800));
+    assert_eq!(helix_client.rate_limit().remaining, Some(750));
+  }
+}
@@ -0,0 +1,197 @@
+use crate::errors::HelixError;
+use crate::token_store::{StoredTokens, load_tokens, save_tokens};
+use app_config::AppConfig;
+use app_config::secret_string::Secret;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// The values used to authenticate with Helix.
+#[derive(Debug, Clone, Default)]
+pub struct HelixCredentials {
+  pub client_id: String,
+  /// Required to get new access tokens.
+  pub client_secret: Option<String>,
+  /// The user access token sent with each request.
+  pub access_token: Option<String>,
+  /// Used to renew a user access token once it expires. Without one, an app access token is requested instead.
+  pub refresh_token: Option<String>,
+  /// When the access token expires, if known. Set when a token is refreshed or validated.
+  pub expires_at: Option<Instant>,
+  /// Where refreshed tokens are persisted. Nothing is persisted if not set.
+  pub token_store_path: Option<PathBuf>,
+}
+
+/// The body of a successful response from the token endpoint.
+///
+/// https://dev.twitch.tv/docs/authentication/refresh-tokens/
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct TokenResponse {
+  pub access_token: String,
+  pub refresh_token: Option<String>,
+  /// In seconds.
+  pub expires_in: Option<u64>,
+}
+
+impl HelixCredentials {
+  /// Reads the credentials from the config, preferring any tokens persisted by an earlier refresh.
+  ///
+  /// Errors if the client ID is missing, or there's no access token in either the config or the token store.
+  pub fn from_app_config() -> Result<Self, HelixError> {
+    let read_secret = |secret: &Secret| Secret::read_secret_string(secret.read_value()).to_owned();
+    let token_store_path = AppConfig::token_store_path();
+    let Some(client_id) = AppConfig::client_id() else {
+      return Err(HelixError::MissingConfigValue("twitch.clientId"));
+    };
+    let mut credentials = Self {
+      client_id: read_secret(client_id),
+      client_secret: AppConfig::client_secret().map(read_secret),
+      access_token: AppConfig::access_token().map(read_secret),
+      refresh_token: AppConfig::refresh_token().map(read_secret),
+      expires_at: None,
+      token_store_path: None,
+    };
+
+    if let Some(stored_tokens) = load_tokens(&token_store_path) {
+      tracing::info!("Using the Twitch tokens stored at {token_store_path:?}.");
+
+      credentials.access_token = Some(stored_tokens.access_token);
+      credentials.refresh_token = stored_tokens.refresh_token.or(credentials.refresh_token);
+    }
+
+    if credentials.access_token.is_none() {
+      return Err(HelixError::MissingConfigValue("twitch.accessToken"));
+    }
+
+    credentials.token_store_path = Some(token_store_path);
+
+    Ok(credentials)
+  }
+
+  /// Takes the tokens in the token store if another process refreshed them since they were last read.
+  ///
+  /// Returns true if the stored tokens replaced the current ones.
+  pub(crate) fn reload_stored_tokens(&mut self) -> bool {
+    let Some(stored_tokens) = self.token_store_path.as_deref().and_then(load_tokens) else {
+      return false;
+    };
+
+    if self.access_token.as_ref() == Some(&stored_tokens.access_token) {
+      return false;
+    }
+
+    self.access_token = Some(stored_tokens.access_token);
+    self.refresh_token = stored_tokens.refresh_token.or(self.refresh_token.take());
+    // The other process knows when the token expires, but it isn't stored. The next validation sets it again.
+    self.expires_at = None;
+
+    true
+  }
+
+  /// Returns true if the access token expires within the given margin.
+  pub fn expires_within(&self, margin: Duration) -> bool {
+    self
+      .expires_at
+      .is_some_and(|expires_at| expires_at <= Instant::now() + margin)
+  }
+
+  /// Sets when the access token expires from a lifetime in seconds. Tokens with a lifetime of 0 don't expire.
+  pub(crate) fn set_expires_in(&mut self, expires_in: u64) {
+    self.expires_at = (expires_in > 0).then(|| Instant::now() + Duration::from_secs(expires_in));
+  }
+
+  /// Returns the form for getting a new access token.
+  ///
+  /// Renews the user access token with the refresh token if there is one, otherwise requests an app access token
+  /// with the client credentials grant.
+  pub(crate) fn token_request_form(&self) -> Result<Vec<(&'static str, &str)>, HelixError> {
+    let Some(client_secret) = self.client_secret.as_deref() else {
+      return Err(HelixError::MissingClientSecret);
+    };
+    let mut form = vec![
+      ("client_id", self.client_id.as_str()),
+      ("client_secret", client_secret),
+    ];
+
+    match self.refresh_token.as_deref() {
+      Some(refresh_token) => form.extend([
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+      ]),
+      None => form.push(("grant_type", "client_credentials")),
+    }
+
+    Ok(form)
+  }
+
+  /// Takes the tokens from a successful refresh, persisting them if there's a token store.
+  pub(crate) fn update_tokens(&mut self, token_response: TokenResponse) {
+    self.access_token = Some(token_response.access_token.clone());
+    self.expires_at = None;
+
+    if let Some(expires_in) = token_response.expires_in {
+      self.set_expires_in(expires_in);
+    }
+
+    if token_response.refresh_token.is_some() {
+      self.refresh_token = token_response.refresh_token;
+    }
+
+    let Some(token_store_path) = &self.token_store_path else {
+      return;
+    };
+    let stored_tokens = StoredTokens {
+      access_token: token_response.access_token,
+      refresh_token: self.refresh_token.clone(),
+    };
+
+    if let Err(error) = save_tokens(token_store_path, &stored_tokens) {
+      tracing::error!(
+        "Failed to store the refreshed Twitch tokens at {token_store_path:?}. Reason: {error}"
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn token_request_form_uses_the_refresh_token() {
+    let credentials = HelixCredentials {
+      client_id: "client_id".into(),
+      client_secret: Some("client_secret".into()),
+      refresh_token: Some("refresh_token".into()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      credentials.token_request_form().unwrap(),
+      vec![
+        ("client_id", "client_id"),
+        ("client_secret", "client_secret"),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", "refresh_token"),
+      ]
+    );
+  }
+
+  #[test]
+  fn token_request_form_uses_the_client_credentials_without_a_refresh_token() {
+    let credentials = HelixCredentials {
+      client_id: "client_id".into(),
+      client_secret: Some("client_secret".into()),
+      access_token: Some("access_token".into()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      credentials.token_request_form().unwrap(),
+      vec![
+        ("client_id", "client_id"),
+        ("client_secret", "client_secret"),
+        ("grant_type", "client_credentials"),
+      ]
+    );
+  }
+}
@@ -0,0 +1,34 @@
+#[derive(Debug, thiserror::Error)]
+pub enum HelixError {
+  #[error("{}", .0)]
+  ReqwestError(#[from] reqwest::Error),
+
+  #[error("{}", .0)]
+  SerdeError(#[from] serde_json::Error),
+
+  #[error("{}", .0)]
+  UrlParseError(#[from] url::ParseError),
+
+  #[error("{}", .0)]
+  IoError(#[from] std::io::Error),
+
+  #[error("`{}` isn't set in the config.", .0)]
+  MissingConfigValue(&'static str),
+
+  #[error("A client secret is required to get a new Twitch access token.")]
+  MissingClientSecret,
+
+  #[error(
+    "Failed to refresh the Twitch access token. Code: {}. Response: {}",
+    code,
+    response
+  )]
+  FailedToRefreshToken { code: u16, response: String },
+
+  #[error(
+    "The Twitch access token is invalid and couldn't be refreshed. Code: {}. Response: {}",
+    code,
+    response
+  )]
+  InvalidAccessToken { code: u16, response: String },
+}
@@ -0,0 +1,46 @@
+pub mod client;
+pub mod credentials;
+pub mod errors;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod rate_limit;
+pub mod token_lifecycle;
+pub mod token_store;
+
+pub use client::{HelixClient, HelixRequest, RetryPolicy};
+pub use credentials::HelixCredentials;
+pub use errors::HelixError;
+pub use token_lifecycle::start_token_lifecycle;
+
+use std::sync::OnceLock;
+
+static HELIX_CLIENT: OnceLock<HelixClient> = OnceLock::new();
+
+/// Returns the client shared by every Helix call in the process, built from the [`app config`](app_config::AppConfig)
+/// on first use.
+///
+/// Panics if the Twitch credentials are missing from the config. [`start_token_lifecycle`] checks them first.
+pub fn get_helix_client() -> &'static HelixClient {
+  match try_get_helix_client() {
+    Ok(helix_client) => helix_client,
+    Err(error) => panic!("Failed to create the Helix client. Reason: {error}"),
+  }
+}
+
+/// Returns the shared client, or why it couldn't be built from the config.
+pub fn try_get_helix_client() -> Result<&'static HelixClient, HelixError> {
+  if let Some(helix_client) = HELIX_CLIENT.get() {
+    return Ok(helix_client);
+  }
+
+  let credentials = HelixCredentials::from_app_config()?;
+
+  Ok(HELIX_CLIENT.get_or_init(|| HelixClient::new(credentials)))
+}
+
+/// Replaces the shared client, such as with one pointing at a local mock.
+///
+/// Must be called before the first call to [`get_helix_client`]. Returns false if the shared client was already set.
+pub fn set_helix_client(helix_client: HelixClient) -> bool {
+  HELIX_CLIENT.set(helix_client).is_ok()
+}
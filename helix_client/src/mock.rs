@@ -0,0 +1,58 @@
+//! A local stand-in for Helix, for testing code that goes through the [`shared client`](crate::get_helix_client).
+
+use crate::{HelixClient, HelixCredentials, RetryPolicy, set_helix_client};
+use axum::Router;
+use std::sync::OnceLock;
+use std::time::Duration;
+use url::Url;
+
+static MOCK_HELIX: OnceLock<()> = OnceLock::new();
+
+/// Serves the router on a local port, returning a client pointing at it. Request paths are served under `/helix/`.
+///
+/// The server runs on its own thread and runtime, so it outlives the runtime of the test that started it.
+pub fn start_mock_helix(router: Router) -> HelixClient {
+  let (address_sender, address_receiver) = std::sync::mpsc::channel();
+
+  std::thread::spawn(move || {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_all()
+      .build()
+      .unwrap();
+
+    runtime.block_on(async move {
+      let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+      address_sender.send(listener.local_addr().unwrap()).unwrap();
+      axum::serve(listener, Router::new().nest("/helix", router))
+        .await
+        .unwrap();
+    });
+  });
+
+  let address = address_receiver.recv().unwrap();
+  let credentials = HelixCredentials {
+    client_id: "client_id".into(),
+    access_token: Some("access_token".into()),
+    ..Default::default()
+  };
+
+  HelixClient::new(credentials)
+    .with_api_url(Url::parse(&format!("http://{address}/helix/")).unwrap())
+    .with_retry_policy(RetryPolicy {
+      max_attempts: 1,
+      base_delay: Duration::from_millis(1),
+    })
+}
+
+/// Makes a mock serving the router the shared client for the rest of the process.
+///
+/// Every test in a binary shares the client, so they all have to use the same router. Only the first call builds it.
+pub fn use_mock_helix(router: impl FnOnce() -> Router) {
+  MOCK_HELIX.get_or_init(|| {
+    assert!(
+      set_helix_client(start_mock_helix(router())),
+      "The shared Helix client was used before the mock was set."
+    );
+  });
+}
@@ -0,0 +1,99 @@
+use reqwest::header::HeaderMap;
+use std::time::{Duration, SystemTime};
+
+const LIMIT_HEADER: &str = "Ratelimit-Limit";
+const REMAINING_HEADER: &str = "Ratelimit-Remaining";
+const RESET_HEADER: &str = "Ratelimit-Reset";
+
+/// The state of the Helix token bucket, as reported by the `Ratelimit-*` headers of the last response.
+///
+/// https://dev.twitch.tv/docs/api/guide/#twitch-rate-limits
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+  pub limit: Option<u32>,
+  pub remaining: Option<u32>,
+  /// When the bucket is refilled.
+  pub reset_at: Option<SystemTime>,
+}
+
+impl RateLimit {
+  /// Returns None if the response had no rate limit headers.
+  pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+    let read_header =
+      |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+    let remaining = read_header(REMAINING_HEADER)?;
+
+    Some(Self {
+      limit: read_header(LIMIT_HEADER).map(|limit| limit as u32),
+      remaining: Some(remaining as u32),
+      reset_at: read_header(RESET_HEADER)
+        .map(|reset_at| SystemTime::UNIX_EPOCH + Duration::from_secs(reset_at)),
+    })
+  }
+
+  /// Takes a token for a request. If the bucket is empty, the time until it's refilled is returned instead.
+  pub fn reserve(&mut self, now: SystemTime) -> Option<Duration> {
+    if self.reset_at.is_some_and(|reset_at| reset_at <= now) {
+      self.remaining = self.limit;
+      self.reset_at = None;
+    }
+
+    match self.remaining {
+      Some(0) => Some(
+        self
+          .reset_at
+          .and_then(|reset_at| reset_at.duration_since(now).ok())
+          .unwrap_or_default(),
+      ),
+      Some(remaining) => {
+        self.remaining = Some(remaining - 1);
+
+        None
+      }
+      None => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use reqwest::header::HeaderValue;
+
+  fn rate_limit_headers(remaining: u32, reset_at: u64) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    headers.insert(LIMIT_HEADER, HeaderValue::from(800));
+    headers.insert(REMAINING_HEADER, HeaderValue::from(remaining));
+    headers.insert(RESET_HEADER, HeaderValue::from(reset_at));
+
+    headers
+  }
+
+  #[test]
+  fn rate_limit_is_read_from_headers() {
+    assert_eq!(
+      RateLimit::from_headers(&rate_limit_headers(799, 1_700_000_000)),
+      Some(RateLimit {
+        limit: Some(800),
+        remaining: Some(799),
+        reset_at: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+      })
+    );
+    assert_eq!(RateLimit::from_headers(&HeaderMap::new()), None);
+  }
+
+  #[test]
+  fn empty_bucket_waits_until_reset() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut rate_limit = RateLimit::from_headers(&rate_limit_headers(1, 1_700_000_030)).unwrap();
+
+    assert_eq!(rate_limit.reserve(now), None);
+    assert_eq!(rate_limit.reserve(now), Some(Duration::from_secs(30)));
+
+    let after_reset = now + Duration::from_secs(30);
+
+    assert_eq!(rate_limit.reserve(after_reset), None);
+    assert_eq!(rate_limit.remaining, Some(799));
+  }
+}
@@ -0,0 +1,136 @@
+use crate::client::HelixClient;
+use crate::errors::HelixError;
+use crate::try_get_helix_client;
+use std::time::Duration;
+
+/// Scopes needed to track bits and subscriptions in channels the token belongs to.
+pub const OWNED_CHANNEL_SCOPES: &[&str] = &["bits:read", "channel:read:subscriptions"];
+/// Twitch requires tokens to be validated at least once an hour.
+const VALIDATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Keeps an expired token that can't be refreshed from being validated in a tight loop.
+const MINIMUM_VALIDATION_WAIT: Duration = Duration::from_secs(60);
+
+/// The body of a successful response from the validation endpoint.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TokenValidation {
+  pub client_id: String,
+  /// Missing for app access tokens.
+  pub login: Option<String>,
+  pub user_id: Option<String>,
+  #[serde(default)]
+  pub scopes: Vec<String>,
+  /// In seconds. 0 if the token doesn't expire.
+  pub expires_in: u64,
+}
+
+impl TokenValidation {
+  /// Returns the [`owned channel scopes`](OWNED_CHANNEL_SCOPES) the token is missing, if it belongs to one of the
+  /// tracked channels.
+  pub fn missing_owned_channel_scopes<S: AsRef<str>>(
+    &self,
+    tracked_channels: &[S],
+  ) -> Vec<&'static str> {
+    let Some(login) = &self.login else {
+      return vec![];
+    };
+    let owns_tracked_channel = tracked_channels.iter().any(|channel| {
+      channel
+        .as_ref()
+        .trim_start_matches('#')
+        .eq_ignore_ascii_case(login)
+    });
+
+    if !owns_tracked_channel {
+      return vec![];
+    }
+
+    OWNED_CHANNEL_SCOPES
+      .iter()
+      .filter(|scope| !self.scopes.iter().any(|token_scope| token_scope == *scope))
+      .copied()
+      .collect()
+  }
+}
+
+/// Validates the shared client's access token, warning about missing scopes, then keeps it valid in the background.
+///
+/// The token is revalidated every hour, and refreshed ahead of when it expires. Errors without panicking if the
+/// credentials are missing from the config.
+pub async fn start_token_lifecycle<S: AsRef<str>>(
+  tracked_channels: &[S],
+) -> Result<TokenValidation, HelixError> {
+  let helix_client = try_get_helix_client()?;
+  let token_validation = helix_client.validate_token().await?;
+
+  tracing::info!(
+    "Validated the Twitch access token for {:?}. Expires in {} seconds.",
+    token_validation.login,
+    token_validation.expires_in
+  );
+
+  let missing_scopes = token_validation.missing_owned_channel_scopes(tracked_channels);
+
+  if !missing_scopes.is_empty() {
+    tracing::warn!(
+      "The Twitch access token belongs to a tracked channel but is missing the scopes {:?}. Bits and subscriptions in that channel won't be tracked.",
+      missing_scopes
+    );
+  }
+
+  tokio::spawn(keep_token_valid(helix_client));
+
+  Ok(token_validation)
+}
+
+async fn keep_token_valid(helix_client: &'static HelixClient) {
+  loop {
+    let wait_time = helix_client
+      .time_until_refresh()
+      .await
+      .map_or(VALIDATION_INTERVAL, |time_until_refresh| {
+        time_until_refresh.clamp(MINIMUM_VALIDATION_WAIT, VALIDATION_INTERVAL)
+      });
+
+    tokio::time::sleep(wait_time).await;
+
+    if let Err(error) = helix_client.validate_token().await {
+      tracing::error!("Failed to validate the Twitch access token. Reason: {error}");
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn validation_for(login: &str, scopes: &[&str]) -> TokenValidation {
+    TokenValidation {
+      client_id: "client_id".into(),
+      login: Some(login.into()),
+      user_id: Some("1".into()),
+      scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+      expires_in: 3600,
+    }
+  }
+
+  #[test]
+  fn missing_scopes_are_only_reported_for_owned_channels() {
+    let tracked_channels = ["#FallenShadow", "shadowchama"];
+
+    assert_eq!(
+      validation_for("fallenshadow", &["bits:read"])
+        .missing_owned_channel_scopes(&tracked_channels),
+      vec!["channel:read:subscriptions"]
+    );
+    assert!(
+      validation_for("fallenshadow", OWNED_CHANNEL_SCOPES)
+        .missing_owned_channel_scopes(&tracked_channels)
+        .is_empty()
+    );
+    assert!(
+      validation_for("someone_else", &[])
+        .missing_owned_channel_scopes(&tracked_channels)
+        .is_empty()
+    );
+  }
+}
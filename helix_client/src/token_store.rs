@@ -0,0 +1,126 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+/// The tokens written to disk after a refresh, so that a restart doesn't fall back to the expired ones in the config.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StoredTokens {
+  pub access_token: String,
+  pub refresh_token: Option<String>,
+}
+
+/// Returns None if nothing has been stored yet, or the file can't be read.
+pub fn load_tokens(path: &Path) -> Option<StoredTokens> {
+  let contents = match std::fs::read_to_string(path) {
+    Ok(contents) => contents,
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => return None,
+    Err(error) => {
+      tracing::error!("Failed to read the stored Twitch tokens at {path:?}. Reason: {error}");
+
+      return None;
+    }
+  };
+
+  match serde_json::from_str(&contents) {
+    Ok(stored_tokens) => Some(stored_tokens),
+    Err(error) => {
+      tracing::error!("Failed to parse the stored Twitch tokens at {path:?}. Reason: {error}");
+
+      None
+    }
+  }
+}
+
+/// Locks the token store against the other processes sharing it until the returned file is dropped.
+///
+/// Held while refreshing, so that two processes never redeem the same refresh token.
+pub async fn lock_token_store(path: &Path) -> std::io::Result<File> {
+  let mut lock_path = path.as_os_str().to_owned();
+  lock_path.push(".lock");
+  let lock_path = PathBuf::from(lock_path);
+
+  tokio::task::spawn_blocking(move || {
+    if let Some(parent) = lock_path.parent()
+      && !parent.as_os_str().is_empty()
+    {
+      std::fs::create_dir_all(parent)?;
+    }
+
+    let lock_file = OpenOptions::new()
+      .write(true)
+      .create(true)
+      .truncate(false)
+      .open(&lock_path)?;
+    lock_file.lock()?;
+
+    Ok(lock_file)
+  })
+  .await?
+}
+
+/// Writes the tokens to a temporary file readable only by the owner, then moves it over the stored tokens so that
+/// a failed write never leaves them half written.
+pub fn save_tokens(path: &Path, stored_tokens: &StoredTokens) -> std::io::Result<()> {
+  if let Some(parent) = path.parent()
+    && !parent.as_os_str().is_empty()
+  {
+    std::fs::create_dir_all(parent)?;
+  }
+
+  let mut temporary_path = path.as_os_str().to_owned();
+  temporary_path.push(".tmp");
+  let temporary_path = PathBuf::from(temporary_path);
+
+  // A leftover file could have been created with looser permissions, which opening it wouldn't change.
+  if let Err(error) = std::fs::remove_file(&temporary_path)
+    && error.kind() != std::io::ErrorKind::NotFound
+  {
+    return Err(error);
+  }
+
+  let mut open_options = OpenOptions::new();
+  open_options.write(true).create_new(true);
+  #[cfg(unix)]
+  open_options.mode(0o600);
+
+  let mut file = open_options.open(&temporary_path)?;
+  file.write_all(serde_json::to_string_pretty(stored_tokens)?.as_bytes())?;
+  file.sync_all()?;
+
+  std::fs::rename(&temporary_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stored_tokens_are_loaded_back() {
+    let path = std::env::temp_dir()
+      .join(format!("helix_client_{}", std::process::id()))
+      .join("twitch_tokens.json");
+    let stored_tokens = StoredTokens {
+      access_token: "access_token".into(),
+      refresh_token: Some("refresh_token".into()),
+    };
+
+    assert_eq!(load_tokens(&path), None);
+
+    save_tokens(&path, &stored_tokens).unwrap();
+
+    assert_eq!(load_tokens(&path), Some(stored_tokens));
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+
+      let permissions = std::fs::metadata(&path).unwrap().permissions();
+
+      assert_eq!(permissions.mode() & 0o777, 0o600);
+    }
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+  }
+}
@@ -28,6 +28,18 @@ mod m20250713_194533_add_message_source_id_to_sharedchat_tables;
 mod m20250721_001104_update_emote_table_for_third_party_emote_storage;
 mod m20250721_001110_convert_stream_message_emote_columns_to_many_to_many_tables;
 mod m20251109_005842_add_additional_stream_table_data;
+mod m20251201_183012_create_chatter_presence_table;
+mod m20251203_201544_create_stream_viewer_sample_table;
+mod m20261019_154210_create_backfill_progress_table;
+mod m20261019_181544_add_content_metrics_to_stream_message;
+mod m20261019_193027_add_currency_and_exchange_rate_table;
+mod m20261019_210412_create_unknown_user_match_table;
+mod m20261020_084516_add_name_check_tracking;
+mod m20261020_142730_create_job_run_table;
+mod m20261021_093012_add_channel_user_timestamp_index_to_stream_message;
+mod m20261021_093348_make_stream_message_is_subscriber_nullable;
+mod m20261021_141905_add_donation_bot_login_to_donation_event;
+mod m20261021_170418_add_content_mode_to_stream_message;
 
 pub struct Migrator;
 
@@ -63,6 +75,18 @@ impl MigratorTrait for Migrator {
             Box::new(m20250721_001104_update_emote_table_for_third_party_emote_storage::Migration),
             Box::new(m20250721_001110_convert_stream_message_emote_columns_to_many_to_many_tables::Migration),
             Box::new(m20251109_005842_add_additional_stream_table_data::Migration),
+            Box::new(m20251201_183012_create_chatter_presence_table::Migration),
+            Box::new(m20251203_201544_create_stream_viewer_sample_table::Migration),
+            Box::new(m20261019_154210_create_backfill_progress_table::Migration),
+            Box::new(m20261019_181544_add_content_metrics_to_stream_message::Migration),
+            Box::new(m20261019_193027_add_currency_and_exchange_rate_table::Migration),
+            Box::new(m20261019_210412_create_unknown_user_match_table::Migration),
+            Box::new(m20261020_084516_add_name_check_tracking::Migration),
+            Box::new(m20261020_142730_create_job_run_table::Migration),
+            Box::new(m20261021_093012_add_channel_user_timestamp_index_to_stream_message::Migration),
+            Box::new(m20261021_093348_make_stream_message_is_subscriber_nullable::Migration),
+            Box::new(m20261021_141905_add_donation_bot_login_to_donation_event::Migration),
+            Box::new(m20261021_170418_add_content_mode_to_stream_message::Migration),
         ]
   }
 }
@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .create_table(
+        Table::create()
+          .table(ChatterPresence::Table)
+          .if_not_exists()
+          .col(
+            ColumnDef::new(ChatterPresence::Id)
+              .integer()
+              .not_null()
+              .primary_key()
+              .auto_increment(),
+          )
+          .col(
+            ColumnDef::new(ChatterPresence::TwitchUserId)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(ChatterPresence::ChannelId)
+              .integer()
+              .not_null(),
+          )
+          .col(ColumnDef::new(ChatterPresence::StreamId).integer().null())
+          .col(
+            ColumnDef::new(ChatterPresence::JoinedAt)
+              .timestamp()
+              .not_null(),
+          )
+          .col(ColumnDef::new(ChatterPresence::PartedAt).timestamp().null())
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-chatter_presence-twitch_user_id")
+              .from(ChatterPresence::Table, ChatterPresence::TwitchUserId)
+              .to(TwitchUser::Table, TwitchUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-chatter_presence-channel_id")
+              .from(ChatterPresence::Table, ChatterPresence::ChannelId)
+              .to(TwitchUser::Table, TwitchUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-chatter_presence-stream_id")
+              .from(ChatterPresence::Table, ChatterPresence::StreamId)
+              .to(Stream::Table, Stream::Id)
+              .on_delete(ForeignKeyAction::SetNull),
+          )
+          .to_owned(),
+      )
+      .await?;
+
+    manager
+      .create_index(
+        Index::create()
+          .name("idx-chatter_presence-user-channel-parted_at")
+          .table(ChatterPresence::Table)
+          .col(ChatterPresence::TwitchUserId)
+          .col(ChatterPresence::ChannelId)
+          .col(ChatterPresence::PartedAt)
+          .to_owned(),
+      )
+      .await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_table(Table::drop().table(ChatterPresence::Table).to_owned())
+      .await
+  }
+}
+
+#[derive(Iden)]
+enum ChatterPresence {
+  Table,
+  Id,
+  TwitchUserId,
+  ChannelId,
+  StreamId,
+  JoinedAt,
+  PartedAt,
+}
+
+#[derive(Iden)]
+enum TwitchUser {
+  Table,
+  Id,
+  _TwitchId,
+  _DisplayName,
+  _LoginName,
+}
+
+#[derive(Iden)]
+enum Stream {
+  Table,
+  Id,
+  _TwitchUserId,
+  _TwitchStreamId,
+  _StartTimestamp,
+  _EndTimestamp,
+}
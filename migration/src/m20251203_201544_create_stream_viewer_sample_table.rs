@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .create_table(
+        Table::create()
+          .table(StreamViewerSample::Table)
+          .if_not_exists()
+          .col(
+            ColumnDef::new(StreamViewerSample::Id)
+              .integer()
+              .not_null()
+              .primary_key()
+              .auto_increment(),
+          )
+          .col(
+            ColumnDef::new(StreamViewerSample::StreamId)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(StreamViewerSample::Timestamp)
+              .timestamp()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(StreamViewerSample::ViewerCount)
+              .integer()
+              .not_null(),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-stream_viewer_sample-stream_id")
+              .from(StreamViewerSample::Table, StreamViewerSample::StreamId)
+              .to(Stream::Table, Stream::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .to_owned(),
+      )
+      .await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_table(Table::drop().table(StreamViewerSample::Table).to_owned())
+      .await
+  }
+}
+
+#[derive(Iden)]
+enum StreamViewerSample {
+  Table,
+  Id,
+  StreamId,
+  Timestamp,
+  ViewerCount,
+}
+
+#[derive(Iden)]
+enum Stream {
+  Table,
+  Id,
+  _TwitchUserId,
+  _TwitchStreamId,
+  _StartTimestamp,
+  _EndTimestamp,
+}
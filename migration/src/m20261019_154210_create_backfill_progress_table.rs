@@ -0,0 +1,115 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .create_table(
+        Table::create()
+          .table(BackfillProgress::Table)
+          .if_not_exists()
+          .col(
+            ColumnDef::new(BackfillProgress::Id)
+              .integer()
+              .not_null()
+              .primary_key()
+              .auto_increment(),
+          )
+          .col(
+            ColumnDef::new(BackfillProgress::Provider)
+              .string()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(BackfillProgress::ChannelId)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(BackfillProgress::TwitchUserId)
+              .integer()
+              .not_null(),
+          )
+          .col(ColumnDef::new(BackfillProgress::Year).integer().not_null())
+          .col(ColumnDef::new(BackfillProgress::Month).integer().not_null())
+          .col(
+            ColumnDef::new(BackfillProgress::MessageCount)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(BackfillProgress::FailedMessageCount)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(BackfillProgress::CompletedAt)
+              .timestamp()
+              .not_null(),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-backfill_progress-channel_id")
+              .from(BackfillProgress::Table, BackfillProgress::ChannelId)
+              .to(TwitchUser::Table, TwitchUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-backfill_progress-twitch_user_id")
+              .from(BackfillProgress::Table, BackfillProgress::TwitchUserId)
+              .to(TwitchUser::Table, TwitchUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .to_owned(),
+      )
+      .await?;
+
+    manager
+      .create_index(
+        Index::create()
+          .name("idx-backfill_progress-provider-channel-user-month")
+          .table(BackfillProgress::Table)
+          .col(BackfillProgress::Provider)
+          .col(BackfillProgress::ChannelId)
+          .col(BackfillProgress::TwitchUserId)
+          .col(BackfillProgress::Year)
+          .col(BackfillProgress::Month)
+          .unique()
+          .to_owned(),
+      )
+      .await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_table(Table::drop().table(BackfillProgress::Table).to_owned())
+      .await
+  }
+}
+
+#[derive(Iden)]
+enum BackfillProgress {
+  Table,
+  Id,
+  Provider,
+  ChannelId,
+  TwitchUserId,
+  Year,
+  Month,
+  MessageCount,
+  FailedMessageCount,
+  CompletedAt,
+}
+
+#[derive(Iden)]
+enum TwitchUser {
+  Table,
+  Id,
+  _TwitchId,
+  _DisplayName,
+  _LoginName,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let add_content_metric_columns = Table::alter()
+      .table(StreamMessage::Table)
+      .add_column(integer(StreamMessage::WordCount).null())
+      .add_column(integer(StreamMessage::ContentLength).null())
+      .to_owned();
+
+    // Twitch collapses repeated whitespace in messages, so counting the spaces gives the amount of words.
+    let fill_content_metrics = format!(
+      "UPDATE `{table}` SET `{content_length}` = CHAR_LENGTH(`{contents}`), `{word_count}` = CASE WHEN TRIM(`{contents}`) = '' THEN 0 ELSE CHAR_LENGTH(TRIM(`{contents}`)) - CHAR_LENGTH(REPLACE(TRIM(`{contents}`), ' ', '')) + 1 END WHERE `{contents}` IS NOT NULL",
+      table = StreamMessage::Table.to_string(),
+      content_length = StreamMessage::ContentLength.to_string(),
+      word_count = StreamMessage::WordCount.to_string(),
+      contents = StreamMessage::Contents.to_string(),
+    );
+
+    manager.alter_table(add_content_metric_columns).await?;
+
+    manager
+      .get_connection()
+      .execute_unprepared(&fill_content_metrics)
+      .await?;
+
+    Ok(())
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let remove_content_metric_columns = Table::alter()
+      .table(StreamMessage::Table)
+      .drop_column(StreamMessage::WordCount)
+      .drop_column(StreamMessage::ContentLength)
+      .to_owned();
+
+    manager.alter_table(remove_content_metric_columns).await?;
+
+    Ok(())
+  }
+}
+
+#[derive(Iden)]
+enum StreamMessage {
+  Table,
+  Contents,
+  WordCount,
+  ContentLength,
+}
@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let add_currency_column = Table::alter()
+      .table(DonationEvent::Table)
+      .add_column(ColumnDef::new(DonationEvent::Currency).string_len(3).null())
+      .to_owned();
+
+    // Every Streamlabs donation before this point was parsed from a `£` amount.
+    let set_existing_donation_currency = Query::update()
+      .table(DonationEvent::Table)
+      .value(DonationEvent::Currency, "GBP")
+      .and_where(Expr::col(DonationEvent::EventType).eq("streamlabs_donation"))
+      .to_owned();
+
+    let create_exchange_rate_table = Table::create()
+      .table(ExchangeRate::Table)
+      .if_not_exists()
+      .col(
+        ColumnDef::new(ExchangeRate::Id)
+          .integer()
+          .not_null()
+          .primary_key()
+          .auto_increment(),
+      )
+      .col(ColumnDef::new(ExchangeRate::Date).date().not_null())
+      .col(
+        ColumnDef::new(ExchangeRate::BaseCurrency)
+          .string_len(3)
+          .not_null(),
+      )
+      .col(
+        ColumnDef::new(ExchangeRate::TargetCurrency)
+          .string_len(3)
+          .not_null(),
+      )
+      .col(ColumnDef::new(ExchangeRate::Rate).double().not_null())
+      .to_owned();
+
+    let create_exchange_rate_index = Index::create()
+      .name("idx-exchange_rate-date-base-target")
+      .table(ExchangeRate::Table)
+      .col(ExchangeRate::Date)
+      .col(ExchangeRate::BaseCurrency)
+      .col(ExchangeRate::TargetCurrency)
+      .unique()
+      .to_owned();
+
+    manager.alter_table(add_currency_column).await?;
+    manager.exec_stmt(set_existing_donation_currency).await?;
+    manager.create_table(create_exchange_rate_table).await?;
+    manager.create_index(create_exchange_rate_index).await?;
+
+    Ok(())
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let drop_exchange_rate_table = Table::drop().table(ExchangeRate::Table).to_owned();
+    let remove_currency_column = Table::alter()
+      .table(DonationEvent::Table)
+      .drop_column(DonationEvent::Currency)
+      .to_owned();
+
+    manager.drop_table(drop_exchange_rate_table).await?;
+    manager.alter_table(remove_currency_column).await?;
+
+    Ok(())
+  }
+}
+
+#[derive(Iden)]
+enum DonationEvent {
+  Table,
+  EventType,
+  Currency,
+}
+
+#[derive(Iden)]
+enum ExchangeRate {
+  Table,
+  Id,
+  Date,
+  BaseCurrency,
+  TargetCurrency,
+  Rate,
+}
@@ -0,0 +1,216 @@
+use sea_orm::{DeriveActiveEnum, DeriveDisplay, EnumIter};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .create_table(
+        Table::create()
+          .table(UnknownUserMatch::Table)
+          .if_not_exists()
+          .col(
+            ColumnDef::new(UnknownUserMatch::Id)
+              .integer()
+              .not_null()
+              .primary_key()
+              .auto_increment(),
+          )
+          .col(
+            ColumnDef::new(UnknownUserMatch::UnknownUserId)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(UnknownUserMatch::TwitchUserId)
+              .integer()
+              .not_null(),
+          )
+          .col(ColumnDef::new(UnknownUserMatch::Score).double().null())
+          .col(
+            ColumnDef::new(UnknownUserMatch::Status)
+              .enumeration(
+                UnknownUserMatch::Status,
+                [
+                  MatchStatus::Pending,
+                  MatchStatus::Confirmed,
+                  MatchStatus::Rejected,
+                ],
+              )
+              .default(MatchStatus::Pending)
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(UnknownUserMatch::CreatedAt)
+              .timestamp()
+              .not_null()
+              .default(Expr::current_timestamp()),
+          )
+          .col(
+            ColumnDef::new(UnknownUserMatch::ReviewedAt)
+              .timestamp()
+              .null(),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-unknown_user_match-unknown_user_id")
+              .from(UnknownUserMatch::Table, UnknownUserMatch::UnknownUserId)
+              .to(UnknownUser::Table, UnknownUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-unknown_user_match-twitch_user_id")
+              .from(UnknownUserMatch::Table, UnknownUserMatch::TwitchUserId)
+              .to(TwitchUser::Table, TwitchUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .to_owned(),
+      )
+      .await?;
+
+    manager
+      .create_index(
+        Index::create()
+          .name("idx-unknown_user_match-unknown_user-twitch_user")
+          .table(UnknownUserMatch::Table)
+          .col(UnknownUserMatch::UnknownUserId)
+          .col(UnknownUserMatch::TwitchUserId)
+          .unique()
+          .to_owned(),
+      )
+      .await?;
+
+    // Donations attributed through an association used to be stored without their unknown user, which left
+    // nothing for a rejected match to revert. The bot's announcement is stored as a message at the same time as the
+    // donation, so its leading name shows which of the user's donations came through the unknown user.
+    let link_associated_donations = format!(
+      "UPDATE `{donation_event}` JOIN `{association}` ON `{association}`.`{association_twitch_user_id}` = `{donation_event}`.`{donator_id}` JOIN `{unknown_user}` ON `{unknown_user}`.`{unknown_user_id}` = `{association}`.`{association_unknown_user_id}` JOIN `{stream_message}` ON `{stream_message}`.`{channel_id}` = `{donation_event}`.`{receiver_id}` AND `{stream_message}`.`{message_timestamp}` = `{donation_event}`.`{donation_timestamp}` AND LEFT(`{stream_message}`.`{contents}`, CHAR_LENGTH(`{unknown_user}`.`{unknown_user_name}`) + 13) = CONCAT(`{unknown_user}`.`{unknown_user_name}`, ' just tipped ') SET `{donation_event}`.`{donation_unknown_user_id}` = `{association}`.`{association_unknown_user_id}` WHERE `{donation_event}`.`{event_type}` = 'streamlabs_donation' AND `{donation_event}`.`{donation_unknown_user_id}` IS NULL",
+      donation_event = DonationEvent::Table.to_string(),
+      donator_id = DonationEvent::DonatorTwitchUserId.to_string(),
+      receiver_id = DonationEvent::DonationReceiverTwitchUserId.to_string(),
+      donation_timestamp = DonationEvent::Timestamp.to_string(),
+      donation_unknown_user_id = DonationEvent::UnknownUserId.to_string(),
+      event_type = DonationEvent::EventType.to_string(),
+      association = TwitchUserUnknownUserAssociation::Table.to_string(),
+      association_twitch_user_id = TwitchUserUnknownUserAssociation::TwitchUserId.to_string(),
+      association_unknown_user_id = TwitchUserUnknownUserAssociation::UnknownUserId.to_string(),
+      unknown_user = UnknownUser::Table.to_string(),
+      unknown_user_id = UnknownUser::Id.to_string(),
+      unknown_user_name = UnknownUser::Name.to_string(),
+      stream_message = StreamMessage::Table.to_string(),
+      channel_id = StreamMessage::ChannelId.to_string(),
+      message_timestamp = StreamMessage::Timestamp.to_string(),
+      contents = StreamMessage::Contents.to_string(),
+    );
+
+    manager
+      .get_connection()
+      .execute_unprepared(&link_associated_donations)
+      .await?;
+
+    // Associations made before matches were reviewed were accepted automatically.
+    let existing_associations = Query::insert()
+      .into_table(UnknownUserMatch::Table)
+      .columns([
+        UnknownUserMatch::UnknownUserId,
+        UnknownUserMatch::TwitchUserId,
+        UnknownUserMatch::Status,
+        UnknownUserMatch::CreatedAt,
+        UnknownUserMatch::ReviewedAt,
+      ])
+      .select_from(
+        Query::select()
+          .columns([
+            TwitchUserUnknownUserAssociation::UnknownUserId,
+            TwitchUserUnknownUserAssociation::TwitchUserId,
+          ])
+          .expr(Expr::val(MatchStatus::Confirmed))
+          .columns([
+            TwitchUserUnknownUserAssociation::CreatedAt,
+            TwitchUserUnknownUserAssociation::CreatedAt,
+          ])
+          .from(TwitchUserUnknownUserAssociation::Table)
+          .to_owned(),
+      )
+      .map_err(|error| DbErr::Migration(error.to_string()))?
+      .to_owned();
+
+    manager.exec_stmt(existing_associations).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_table(Table::drop().table(UnknownUserMatch::Table).to_owned())
+      .await
+  }
+}
+
+#[derive(Iden)]
+enum UnknownUserMatch {
+  Table,
+  Id,
+  UnknownUserId,
+  TwitchUserId,
+  Score,
+  Status,
+  CreatedAt,
+  ReviewedAt,
+}
+
+#[derive(Iden)]
+enum TwitchUserUnknownUserAssociation {
+  Table,
+  UnknownUserId,
+  TwitchUserId,
+  CreatedAt,
+}
+
+#[derive(Iden)]
+enum UnknownUser {
+  Table,
+  Id,
+  Name,
+  _CreatedAt,
+}
+
+#[derive(Iden)]
+enum DonationEvent {
+  Table,
+  EventType,
+  Timestamp,
+  DonatorTwitchUserId,
+  DonationReceiverTwitchUserId,
+  UnknownUserId,
+}
+
+#[derive(Iden)]
+enum StreamMessage {
+  Table,
+  ChannelId,
+  Timestamp,
+  Contents,
+}
+
+#[derive(Iden)]
+enum TwitchUser {
+  Table,
+  Id,
+  _TwitchId,
+  _DisplayName,
+  _LoginName,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Iden, EnumIter, DeriveActiveEnum, DeriveDisplay)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "match_status")]
+enum MatchStatus {
+  #[sea_orm(string_value = "pending")]
+  Pending,
+  #[sea_orm(string_value = "confirmed")]
+  Confirmed,
+  #[sea_orm(string_value = "rejected")]
+  Rejected,
+}
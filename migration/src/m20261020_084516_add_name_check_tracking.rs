@@ -0,0 +1,142 @@
+use sea_orm::{DeriveActiveEnum, DeriveDisplay, EnumIter};
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let add_last_verified_column = Table::alter()
+      .table(TwitchUser::Table)
+      .add_column(timestamp_null(TwitchUser::LastVerifiedAt))
+      .to_owned();
+
+    manager.alter_table(add_last_verified_column).await?;
+
+    manager
+      .create_index(
+        Index::create()
+          .name("idx-twitch_user-last_verified_at")
+          .table(TwitchUser::Table)
+          .col(TwitchUser::LastVerifiedAt)
+          .to_owned(),
+      )
+      .await?;
+
+    manager
+      .create_table(
+        Table::create()
+          .table(AccountStatusChange::Table)
+          .if_not_exists()
+          .col(
+            ColumnDef::new(AccountStatusChange::Id)
+              .integer()
+              .not_null()
+              .primary_key()
+              .auto_increment(),
+          )
+          .col(
+            ColumnDef::new(AccountStatusChange::TwitchUserId)
+              .integer()
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(AccountStatusChange::Status)
+              .enumeration(
+                AccountStatusChange::Status,
+                [
+                  AccountStatus::Active,
+                  AccountStatus::Suspended,
+                  AccountStatus::Deleted,
+                  AccountStatus::Renamed,
+                ],
+              )
+              .not_null(),
+          )
+          .col(
+            ColumnDef::new(AccountStatusChange::CreatedAt)
+              .timestamp()
+              .not_null()
+              .default(Expr::current_timestamp()),
+          )
+          .foreign_key(
+            ForeignKey::create()
+              .name("fk-account_status_change-twitch_user_id")
+              .from(
+                AccountStatusChange::Table,
+                AccountStatusChange::TwitchUserId,
+              )
+              .to(TwitchUser::Table, TwitchUser::Id)
+              .on_delete(ForeignKeyAction::Cascade),
+          )
+          .to_owned(),
+      )
+      .await?;
+
+    manager
+      .create_index(
+        Index::create()
+          .name("idx-account_status_change-twitch_user-created_at")
+          .table(AccountStatusChange::Table)
+          .col(AccountStatusChange::TwitchUserId)
+          .col(AccountStatusChange::CreatedAt)
+          .to_owned(),
+      )
+      .await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_table(Table::drop().table(AccountStatusChange::Table).to_owned())
+      .await?;
+
+    manager
+      .drop_index(
+        Index::drop()
+          .name("idx-twitch_user-last_verified_at")
+          .table(TwitchUser::Table)
+          .to_owned(),
+      )
+      .await?;
+
+    let remove_last_verified_column = Table::alter()
+      .table(TwitchUser::Table)
+      .drop_column(TwitchUser::LastVerifiedAt)
+      .to_owned();
+
+    manager.alter_table(remove_last_verified_column).await
+  }
+}
+
+#[derive(Iden)]
+enum AccountStatusChange {
+  Table,
+  Id,
+  TwitchUserId,
+  Status,
+  CreatedAt,
+}
+
+#[derive(Iden)]
+enum TwitchUser {
+  Table,
+  Id,
+  _TwitchId,
+  _DisplayName,
+  _LoginName,
+  LastVerifiedAt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Iden, EnumIter, DeriveActiveEnum, DeriveDisplay)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "account_status")]
+enum AccountStatus {
+  #[sea_orm(string_value = "active")]
+  Active,
+  #[sea_orm(string_value = "suspended")]
+  Suspended,
+  #[sea_orm(string_value = "deleted")]
+  Deleted,
+  #[sea_orm(string_value = "renamed")]
+  Renamed,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .create_table(
+        Table::create()
+          .table(JobRun::Table)
+          .if_not_exists()
+          .col(pk_auto(JobRun::Id))
+          .col(string(JobRun::JobName))
+          .col(timestamp(JobRun::StartedAt))
+          .col(timestamp_null(JobRun::FinishedAt))
+          .col(boolean_null(JobRun::Succeeded))
+          .col(big_integer(JobRun::ItemsProcessed).default(0))
+          .col(integer(JobRun::ErrorCount).default(0))
+          .col(text_null(JobRun::Errors))
+          .to_owned(),
+      )
+      .await?;
+
+    manager
+      .create_index(
+        Index::create()
+          .name("idx-job_run-job_name-started_at")
+          .table(JobRun::Table)
+          .col(JobRun::JobName)
+          .col(JobRun::StartedAt)
+          .to_owned(),
+      )
+      .await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_table(Table::drop().table(JobRun::Table).to_owned())
+      .await
+  }
+}
+
+#[derive(DeriveIden)]
+enum JobRun {
+  Table,
+  Id,
+  JobName,
+  StartedAt,
+  FinishedAt,
+  Succeeded,
+  ItemsProcessed,
+  ErrorCount,
+  Errors,
+}
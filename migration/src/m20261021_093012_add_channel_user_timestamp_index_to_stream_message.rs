@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+const INDEX_NAME: &str = "idx-stream_message-channel_id-twitch_user_id-timestamp";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    // Imported messages are deduplicated by who sent them, where, and when.
+    let create_index = Index::create()
+      .name(INDEX_NAME)
+      .table(StreamMessage::Table)
+      .col(StreamMessage::ChannelId)
+      .col(StreamMessage::TwitchUserId)
+      .col(StreamMessage::Timestamp)
+      .to_owned();
+
+    manager.create_index(create_index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let drop_index = Index::drop()
+      .name(INDEX_NAME)
+      .table(StreamMessage::Table)
+      .to_owned();
+
+    manager.drop_index(drop_index).await
+  }
+}
+
+#[derive(Iden)]
+enum StreamMessage {
+  Table,
+  ChannelId,
+  TwitchUserId,
+  Timestamp,
+}
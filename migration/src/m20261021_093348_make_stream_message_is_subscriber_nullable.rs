@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    // Some imported logs don't record badges, so whether the sender was subscribed is unknown.
+    let make_is_subscriber_nullable = Table::alter()
+      .table(StreamMessage::Table)
+      .modify_column(ColumnDef::new(StreamMessage::IsSubscriber).boolean().null())
+      .to_owned();
+
+    manager.alter_table(make_is_subscriber_nullable).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let clear_unknown_subscriber_statuses = Query::update()
+      .table(StreamMessage::Table)
+      .value(StreamMessage::IsSubscriber, false)
+      .and_where(Expr::col(StreamMessage::IsSubscriber).is_null())
+      .to_owned();
+    let make_is_subscriber_required = Table::alter()
+      .table(StreamMessage::Table)
+      .modify_column(
+        ColumnDef::new(StreamMessage::IsSubscriber)
+          .boolean()
+          .not_null(),
+      )
+      .to_owned();
+
+    manager.exec_stmt(clear_unknown_subscriber_statuses).await?;
+    manager.alter_table(make_is_subscriber_required).await
+  }
+}
+
+#[derive(Iden)]
+enum StreamMessage {
+  Table,
+  IsSubscriber,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let add_donation_bot_login_column = Table::alter()
+      .table(DonationEvent::Table)
+      .add_column(
+        ColumnDef::new(DonationEvent::DonationBotLogin)
+          .string_len(25)
+          .null(),
+      )
+      .to_owned();
+
+    // Every chat donation before configurable parsers were added was announced by StreamElements.
+    let set_existing_donation_bot_login = Query::update()
+      .table(DonationEvent::Table)
+      .value(DonationEvent::DonationBotLogin, "streamelements")
+      .and_where(Expr::col(DonationEvent::EventType).eq("streamlabs_donation"))
+      .to_owned();
+
+    manager.alter_table(add_donation_bot_login_column).await?;
+    manager.exec_stmt(set_existing_donation_bot_login).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let drop_donation_bot_login_column = Table::alter()
+      .table(DonationEvent::Table)
+      .drop_column(DonationEvent::DonationBotLogin)
+      .to_owned();
+
+    manager.alter_table(drop_donation_bot_login_column).await
+  }
+}
+
+#[derive(Iden)]
+enum DonationEvent {
+  Table,
+  EventType,
+  DonationBotLogin,
+}
@@ -0,0 +1,72 @@
+use sea_orm::{DeriveActiveEnum, DeriveDisplay, EnumIter};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let add_content_mode_column = Table::alter()
+      .table(StreamMessage::Table)
+      .add_column(
+        ColumnDef::new(StreamMessage::ContentMode)
+          .enumeration(
+            StreamMessage::ContentMode,
+            [ContentMode::Raw, ContentMode::Hashed, ContentMode::Redacted],
+          )
+          .not_null()
+          .default(ContentMode::Raw),
+      )
+      .to_owned();
+
+    let mark_redacted_messages = Query::update()
+      .table(StreamMessage::Table)
+      .value(StreamMessage::ContentMode, ContentMode::Redacted)
+      .and_where(Expr::col(StreamMessage::Contents).is_null())
+      .to_owned();
+
+    // A hash is always 64 hex characters, while the stored length is of the message that was hashed.
+    let mark_hashed_messages = Query::update()
+      .table(StreamMessage::Table)
+      .value(StreamMessage::ContentMode, ContentMode::Hashed)
+      .and_where(Expr::cust(format!(
+        "`{}` REGEXP '^[0-9a-f]{{64}}$'",
+        StreamMessage::Contents.to_string()
+      )))
+      .and_where(Expr::col(StreamMessage::ContentLength).ne(64))
+      .to_owned();
+
+    manager.alter_table(add_content_mode_column).await?;
+    manager.exec_stmt(mark_redacted_messages).await?;
+    manager.exec_stmt(mark_hashed_messages).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let drop_content_mode_column = Table::alter()
+      .table(StreamMessage::Table)
+      .drop_column(StreamMessage::ContentMode)
+      .to_owned();
+
+    manager.alter_table(drop_content_mode_column).await
+  }
+}
+
+#[derive(Iden)]
+enum StreamMessage {
+  Table,
+  Contents,
+  ContentLength,
+  ContentMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Iden, EnumIter, DeriveActiveEnum, DeriveDisplay)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "content_mode")]
+enum ContentMode {
+  #[sea_orm(string_value = "raw")]
+  Raw,
+  #[sea_orm(string_value = "hashed")]
+  Hashed,
+  #[sea_orm(string_value = "redacted")]
+  Redacted,
+}
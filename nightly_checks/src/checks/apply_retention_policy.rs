@@ -0,0 +1,32 @@
+use crate::jobs::{JobOutcome, NightlyJob};
+use database_connection::get_database_connection;
+use entity_extensions::retention::RetentionPolicy;
+
+/// Clears and deletes data older than the configured retention periods.
+pub struct ApplyRetentionPolicyJob;
+
+impl NightlyJob for ApplyRetentionPolicyJob {
+  /// Counts every cleared message content and deleted chatter presence record as processed.
+  async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
+    let retention_policy = RetentionPolicy::from_config();
+
+    if retention_policy.is_empty() {
+      return Ok(());
+    }
+
+    let database_connection = get_database_connection().await;
+    let summary = retention_policy
+      .apply(chrono::Utc::now(), database_connection)
+      .await?;
+
+    tracing::info!(
+      "Applied the retention policy. Cleared {} message contents and deleted {} chatter presence records.",
+      summary.cleared_message_contents,
+      summary.deleted_chatter_presences
+    );
+
+    outcome.items_processed += summary.cleared_message_contents + summary.deleted_chatter_presences;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,369 @@
+use crate::checks::data_integrity::{Finding, IntegrityCheckReport};
+use crate::checks::update_vod_data::twitch_objects::vod_response::TwitchVodResponse;
+use crate::jobs::JobOutcome;
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use entities::{stream, stream_message, stream_viewer_sample, twitch_user};
+use entity_extensions::stream::StreamExtensions;
+use helix_client::get_helix_client;
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+
+/// Streams that started within this many hours are left alone, as they may have only just ended.
+const MIN_STREAM_AGE_HOURS: i64 = 6;
+/// How many channels or vods Helix takes per request.
+const HELIX_BATCH_SIZE: usize = 100;
+const HELIX_VOD_QUERY_PATH: &str = "videos";
+
+/// Finds streams without an end that aren't live anymore, ending them when their vod or last recorded activity was.
+pub async fn check(
+  repair: bool,
+  database_connection: &DatabaseConnection,
+  outcome: &mut JobOutcome,
+) -> anyhow::Result<IntegrityCheckReport> {
+  let started_before = Utc::now() - Duration::hours(MIN_STREAM_AGE_HOURS);
+  let open_streams = stream::Entity::find()
+    .filter(stream::Column::EndTimestamp.is_null())
+    .filter(stream::Column::StartTimestamp.lt(started_before))
+    .all(database_connection)
+    .await?;
+  let mut report = IntegrityCheckReport {
+    name: "Dangling streams",
+    findings: vec![],
+  };
+
+  if open_streams.is_empty() {
+    return Ok(report);
+  }
+
+  let live_stream_ids = get_live_stream_ids(&open_streams, database_connection).await?;
+  let dangling_streams: Vec<stream::Model> = open_streams
+    .into_iter()
+    .filter(|stream| !live_stream_ids.contains(&stream.twitch_stream_id.to_string()))
+    .collect();
+  let vod_durations = get_vod_durations(&dangling_streams, outcome).await;
+
+  report.findings = end_streams(
+    repair,
+    dangling_streams,
+    &vod_durations,
+    database_connection,
+    outcome,
+  )
+  .await?;
+
+  Ok(report)
+}
+
+/// Ends each of the given streams when it most likely ended, returning a finding for each of them.
+async fn end_streams(
+  repair: bool,
+  dangling_streams: Vec<stream::Model>,
+  vod_durations: &HashMap<String, Duration>,
+  database_connection: &DatabaseConnection,
+  outcome: &mut JobOutcome,
+) -> Result<Vec<Finding>, DbErr> {
+  let mut findings = vec![];
+
+  for stream in dangling_streams {
+    let description = format!(
+      "Stream {} (Twitch ID {}) started at {:?} isn't live, but has no end.",
+      stream.id, stream.twitch_stream_id, stream.start_timestamp
+    );
+    let Some((end_timestamp, source)) =
+      estimate_end(&stream, vod_durations, database_connection).await?
+    else {
+      findings.push(Finding {
+        description,
+        repair: None,
+        needs_review: false,
+      });
+
+      continue;
+    };
+
+    if repair {
+      let stream_id = stream.id;
+      let result = stream::ActiveModel {
+        end_timestamp: Set(Some(end_timestamp)),
+        ..stream.into_active_model()
+      }
+      .update(database_connection)
+      .await;
+
+      if let Err(error) = result {
+        outcome.record_error(format!(
+          "Failed to end stream {stream_id}. Reason: `{error}`"
+        ));
+
+        continue;
+      }
+    }
+
+    findings.push(Finding {
+      description,
+      repair: Some(format!("Ending it at {end_timestamp}, from {source}.")),
+      needs_review: false,
+    });
+  }
+
+  Ok(findings)
+}
+
+/// Returns the Twitch IDs of the streams that are live for the channels the given streams belong to.
+async fn get_live_stream_ids(
+  streams: &[stream::Model],
+  database_connection: &DatabaseConnection,
+) -> anyhow::Result<HashSet<String>> {
+  let channel_ids: HashSet<i32> = streams.iter().map(|stream| stream.twitch_user_id).collect();
+  let channels = twitch_user::Entity::find()
+    .filter(twitch_user::Column::Id.is_in(channel_ids))
+    .all(database_connection)
+    .await?;
+  let mut live_stream_ids = HashSet::new();
+
+  for channel_batch in channels.chunks(HELIX_BATCH_SIZE) {
+    let live_streams = stream::Model::get_active_livestreams(channel_batch).await?;
+
+    live_stream_ids.extend(
+      live_streams
+        .into_values()
+        .map(|(_, stream_twitch_id)| stream_twitch_id),
+    );
+  }
+
+  Ok(live_stream_ids)
+}
+
+/// Returns how long the vods of the given streams are, keyed by vod ID. Vods that can't be found are left out.
+async fn get_vod_durations(
+  streams: &[stream::Model],
+  outcome: &mut JobOutcome,
+) -> HashMap<String, Duration> {
+  let vod_ids: Vec<&str> = streams
+    .iter()
+    .filter_map(|stream| stream.twitch_vod_id.as_deref())
+    .collect();
+  let mut vod_durations = HashMap::new();
+
+  for vod_id_batch in vod_ids.chunks(HELIX_BATCH_SIZE) {
+    match query_vods(vod_id_batch).await {
+      Ok(vod_response) => {
+        vod_durations.extend(vod_response.vod_list.into_iter().filter_map(|vod| {
+          let duration = vod.duration()?;
+
+          Some((vod.vod_id().to_string(), duration))
+        }))
+      }
+      Err(error) => outcome.record_error(format!(
+        "Failed to get the vods of dangling streams. Reason: `{error}`"
+      )),
+    }
+  }
+
+  vod_durations
+}
+
+async fn query_vods(vod_ids: &[&str]) -> anyhow::Result<TwitchVodResponse> {
+  let mut request = get_helix_client().get(HELIX_VOD_QUERY_PATH);
+
+  for vod_id in vod_ids {
+    request = request.query("id", *vod_id);
+  }
+
+  let response = request.send().await?;
+  let status = response.status();
+
+  if !status.is_success() {
+    return Err(anyhow!(
+      "Failed to get vod response. Error code `{}`",
+      status.as_u16()
+    ));
+  }
+
+  serde_json::from_str(&response.text().await?).map_err(Into::into)
+}
+
+/// Returns when the stream most likely ended, along with where that came from.
+///
+/// The vod's length is preferred, followed by the last message or viewer count sample recorded during the stream.
+async fn estimate_end(
+  stream: &stream::Model,
+  vod_durations: &HashMap<String, Duration>,
+  database_connection: &DatabaseConnection,
+) -> Result<Option<(DateTime<Utc>, &'static str)>, DbErr> {
+  let vod_duration = stream
+    .twitch_vod_id
+    .as_ref()
+    .and_then(|vod_id| vod_durations.get(vod_id));
+
+  if let (Some(start_timestamp), Some(vod_duration)) = (stream.start_timestamp, vod_duration) {
+    return Ok(Some((start_timestamp + *vod_duration, "the vod's length")));
+  }
+
+  let last_message: Option<Option<DateTime<Utc>>> = stream_message::Entity::find()
+    .select_only()
+    .column_as(stream_message::Column::Timestamp.max(), "last_timestamp")
+    .filter(stream_message::Column::StreamId.eq(stream.id))
+    .into_tuple()
+    .one(database_connection)
+    .await?;
+  let last_viewer_sample: Option<Option<DateTime<Utc>>> = stream_viewer_sample::Entity::find()
+    .select_only()
+    .column_as(
+      stream_viewer_sample::Column::Timestamp.max(),
+      "last_timestamp",
+    )
+    .filter(stream_viewer_sample::Column::StreamId.eq(stream.id))
+    .into_tuple()
+    .one(database_connection)
+    .await?;
+  let last_activity = last_message.flatten().max(last_viewer_sample.flatten());
+
+  Ok(last_activity.map(|last_activity| (last_activity, "the last recorded activity")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+  use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value};
+  use std::collections::BTreeMap;
+
+  fn stream(id: i32, twitch_vod_id: Option<&str>) -> stream::Model {
+    stream::Model {
+      id,
+      twitch_stream_id: 40000 + id as u64,
+      start_timestamp: Some(Utc.with_ymd_and_hms(2026, 10, 1, 18, 0, 0).unwrap()),
+      end_timestamp: None,
+      twitch_user_id: 1,
+      twitch_vod_id: twitch_vod_id.map(Into::into),
+      title: None,
+    }
+  }
+
+  fn last_timestamp(timestamp: Option<DateTime<Utc>>) -> Vec<BTreeMap<&'static str, Value>> {
+    vec![BTreeMap::from([(
+      "last_timestamp",
+      Value::ChronoDateTimeUtc(timestamp.map(Box::new)),
+    )])]
+  }
+
+  fn logged_statements(database_connection: DatabaseConnection) -> Vec<String> {
+    database_connection
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  fn finding_repairs(findings: &[Finding]) -> Vec<Option<&str>> {
+    findings
+      .iter()
+      .map(|finding| finding.repair.as_deref())
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn nothing_is_checked_without_open_streams() {
+    let database_connection = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([Vec::<stream::Model>::new()])
+      .into_connection();
+    let mut outcome = JobOutcome::default();
+
+    let report = check(true, &database_connection, &mut outcome)
+      .await
+      .unwrap();
+    let statements = logged_statements(database_connection);
+
+    assert!(report.findings.is_empty());
+    assert_eq!(statements.len(), 1);
+    assert!(statements[0].contains("`stream`.`end_timestamp` IS NULL"));
+  }
+
+  #[tokio::test]
+  async fn dry_run_estimates_ends_without_ending_the_streams() {
+    let last_message = Utc.with_ymd_and_hms(2026, 10, 1, 20, 30, 0).unwrap();
+    let database_connection = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([last_timestamp(Some(last_message)), last_timestamp(None)])
+      .into_connection();
+    let vod_durations = HashMap::from([("v1".to_string(), Duration::hours(3))]);
+    let mut outcome = JobOutcome::default();
+
+    let findings = end_streams(
+      false,
+      vec![stream(1, Some("v1")), stream(2, Some("v2"))],
+      &vod_durations,
+      &database_connection,
+      &mut outcome,
+    )
+    .await
+    .unwrap();
+    let statements = logged_statements(database_connection);
+
+    assert_eq!(
+      finding_repairs(&findings),
+      vec![
+        Some("Ending it at 2026-10-01 21:00:00 UTC, from the vod's length."),
+        Some("Ending it at 2026-10-01 20:30:00 UTC, from the last recorded activity."),
+      ]
+    );
+    assert_eq!(statements.len(), 2);
+    assert!(
+      statements
+        .iter()
+        .all(|statement| statement.starts_with("SELECT"))
+    );
+  }
+
+  #[tokio::test]
+  async fn repair_ends_streams_and_leaves_unknown_ends_to_be_looked_at() {
+    let last_message = Utc.with_ymd_and_hms(2026, 10, 1, 20, 30, 0).unwrap();
+    let last_viewer_sample = Utc.with_ymd_and_hms(2026, 10, 1, 20, 45, 0).unwrap();
+    let ended_stream = stream::Model {
+      end_timestamp: Some(last_viewer_sample),
+      ..stream(1, None)
+    };
+    let database_connection = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([
+        last_timestamp(Some(last_message)),
+        last_timestamp(Some(last_viewer_sample)),
+      ])
+      .append_exec_results([MockExecResult {
+        last_insert_id: 0,
+        rows_affected: 1,
+      }])
+      .append_query_results([vec![ended_stream]])
+      .append_query_results([last_timestamp(None), last_timestamp(None)])
+      .into_connection();
+    let mut outcome = JobOutcome::default();
+
+    let findings = end_streams(
+      true,
+      vec![stream(1, None), stream(2, None)],
+      &HashMap::new(),
+      &database_connection,
+      &mut outcome,
+    )
+    .await
+    .unwrap();
+    let statements = logged_statements(database_connection);
+    let updates: Vec<&String> = statements
+      .iter()
+      .filter(|statement| statement.starts_with("UPDATE"))
+      .collect();
+
+    assert_eq!(
+      finding_repairs(&findings),
+      vec![
+        Some("Ending it at 2026-10-01 20:45:00 UTC, from the last recorded activity."),
+        None,
+      ]
+    );
+    assert_eq!(updates.len(), 1);
+    assert!(updates[0].contains("`end_timestamp` = '2026-10-01 20:45:00"));
+    assert!(updates[0].contains("WHERE `stream`.`id` = 1"));
+    assert!(outcome.errors.is_empty());
+  }
+}
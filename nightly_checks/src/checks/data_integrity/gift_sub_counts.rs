@@ -0,0 +1,289 @@
+use crate::checks::data_integrity::{Finding, IntegrityCheckReport};
+use crate::jobs::JobOutcome;
+use entities::sea_orm_active_enums::EventType;
+use entities::{donation_event, gift_sub_recipient};
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+
+/// A gift sub donation and how many recipients were recorded for it.
+#[derive(Debug, FromQueryResult)]
+struct GiftSubDonation {
+  id: i32,
+  amount: f32,
+  recipient_count: i64,
+}
+
+/// Finds gift sub donations whose amount doesn't match how many recipients were recorded for them.
+///
+/// A recipient recorded more than once for the same donation is a duplicate, and those are removed. Any other mismatch,
+/// including one that's left after removing the duplicates, has to be looked at by hand, as the amount is what Twitch
+/// reported and which recipients are missing or extra is unknown. Donations without any recipients are skipped, as recipients weren't recorded for older gift subs.
+pub async fn check(
+  repair: bool,
+  database_connection: &DatabaseConnection,
+  outcome: &mut JobOutcome,
+) -> anyhow::Result<IntegrityCheckReport> {
+  let gift_sub_donations = donation_event::Entity::find()
+    .select_only()
+    .column(donation_event::Column::Id)
+    .column(donation_event::Column::Amount)
+    .column_as(gift_sub_recipient::Column::Id.count(), "recipient_count")
+    .join(
+      JoinType::InnerJoin,
+      donation_event::Relation::GiftSubRecipient.def(),
+    )
+    .filter(donation_event::Column::EventType.eq(EventType::GiftSubs))
+    .group_by(donation_event::Column::Id)
+    .into_model::<GiftSubDonation>()
+    .all(database_connection)
+    .await?;
+  let over_counted_donation_ids: Vec<i32> = gift_sub_donations
+    .iter()
+    .filter(|donation| donation.recipient_count > donation.amount.round() as i64)
+    .map(|donation| donation.id)
+    .collect();
+  let duplicate_recipients =
+    get_duplicate_recipients(&over_counted_donation_ids, database_connection).await?;
+  let mut report = IntegrityCheckReport {
+    name: "Gift subs with mismatched recipients",
+    findings: vec![],
+  };
+
+  for donation in gift_sub_donations {
+    let donation_event_id = donation.id;
+    let recipient_count = donation.recipient_count;
+    let gift_count = donation.amount.round() as i64;
+
+    if gift_count == recipient_count {
+      continue;
+    }
+
+    let description = format!(
+      "Gift sub donation {donation_event_id} is for {gift_count} subs, but has {recipient_count} recipients."
+    );
+    let Some(duplicate_recipient_ids) = duplicate_recipients.get(&donation_event_id) else {
+      report.findings.push(Finding {
+        description,
+        repair: None,
+        needs_review: false,
+      });
+
+      continue;
+    };
+
+    let remaining_recipient_count = recipient_count - duplicate_recipient_ids.len() as i64;
+
+    if repair {
+      let result = gift_sub_recipient::Entity::delete_many()
+        .filter(gift_sub_recipient::Column::Id.is_in(duplicate_recipient_ids.iter().copied()))
+        .exec(database_connection)
+        .await;
+
+      if let Err(error) = result {
+        outcome.record_error(format!(
+          "Failed to remove the duplicate recipients of gift sub donation {donation_event_id}. Reason: `{error}`"
+        ));
+
+        continue;
+      }
+    }
+
+    report.findings.push(Finding {
+      description,
+      repair: Some(format!(
+        "Removing its {} duplicate recipients.",
+        duplicate_recipient_ids.len()
+      )),
+      needs_review: remaining_recipient_count != gift_count,
+    });
+  }
+
+  Ok(report)
+}
+
+/// Returns the IDs of the recipients recorded more than once for the same donation, keyed by the donation.
+///
+/// The first time each user was recorded as a recipient is kept out, as is anyone without a known user.
+async fn get_duplicate_recipients(
+  donation_event_ids: &[i32],
+  database_connection: &DatabaseConnection,
+) -> Result<HashMap<i32, Vec<i32>>, DbErr> {
+  if donation_event_ids.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  let recipients = gift_sub_recipient::Entity::find()
+    .filter(gift_sub_recipient::Column::DonationEventId.is_in(donation_event_ids.iter().copied()))
+    .filter(gift_sub_recipient::Column::TwitchUserId.is_not_null())
+    .order_by_asc(gift_sub_recipient::Column::Id)
+    .all(database_connection)
+    .await?;
+  let mut recorded_recipients = HashSet::new();
+  let mut duplicate_recipients: HashMap<i32, Vec<i32>> = HashMap::new();
+
+  for recipient in recipients {
+    if !recorded_recipients.insert((recipient.donation_event_id, recipient.twitch_user_id)) {
+      duplicate_recipients
+        .entry(recipient.donation_event_id)
+        .or_default()
+        .push(recipient.id);
+    }
+  }
+
+  Ok(duplicate_recipients)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value};
+  use std::collections::BTreeMap;
+
+  fn gift_sub_donation(
+    donation_event_id: i32,
+    amount: f32,
+    recipient_count: i64,
+  ) -> BTreeMap<&'static str, Value> {
+    BTreeMap::from([
+      ("id", Value::Int(Some(donation_event_id))),
+      ("amount", Value::Float(Some(amount))),
+      ("recipient_count", Value::BigInt(Some(recipient_count))),
+    ])
+  }
+
+  fn recipient(id: i32, donation_event_id: i32, twitch_user_id: i32) -> gift_sub_recipient::Model {
+    gift_sub_recipient::Model {
+      id,
+      recipient_months_subscribed: 1,
+      twitch_user_id: Some(twitch_user_id),
+      donation_event_id,
+    }
+  }
+
+  /// A matching donation, one with too few recipients, one with duplicated recipients, one with an extra recipient
+  /// that isn't a duplicate, and one that still has an extra recipient once its duplicate is removed.
+  fn mock_database(repair: bool) -> DatabaseConnection {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![
+        gift_sub_donation(1, 3.0, 3),
+        gift_sub_donation(2, 5.0, 3),
+        gift_sub_donation(3, 2.0, 4),
+        gift_sub_donation(4, 1.0, 2),
+        gift_sub_donation(5, 2.0, 4),
+      ]])
+      .append_query_results([vec![
+        recipient(10, 3, 100),
+        recipient(11, 3, 100),
+        recipient(12, 3, 101),
+        recipient(13, 3, 101),
+        recipient(20, 4, 200),
+        recipient(21, 4, 201),
+        recipient(30, 5, 300),
+        recipient(31, 5, 300),
+        recipient(32, 5, 301),
+        recipient(33, 5, 302),
+      ]]);
+
+    match repair {
+      true => mock_database.append_exec_results([
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 2,
+        },
+        MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 1,
+        },
+      ]),
+      false => mock_database,
+    }
+    .into_connection()
+  }
+
+  fn logged_statements(database_connection: DatabaseConnection) -> Vec<String> {
+    database_connection
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  fn finding_repairs(report: &IntegrityCheckReport) -> Vec<(&str, Option<&str>, bool)> {
+    report
+      .findings
+      .iter()
+      .map(|finding| {
+        (
+          finding.description.as_str(),
+          finding.repair.as_deref(),
+          finding.needs_review,
+        )
+      })
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn dry_run_reports_duplicates_without_removing_them() {
+    let database_connection = mock_database(false);
+    let mut outcome = JobOutcome::default();
+
+    let report = check(false, &database_connection, &mut outcome)
+      .await
+      .unwrap();
+    let statements = logged_statements(database_connection);
+
+    assert_eq!(
+      finding_repairs(&report),
+      vec![
+        (
+          "Gift sub donation 2 is for 5 subs, but has 3 recipients.",
+          None,
+          false
+        ),
+        (
+          "Gift sub donation 3 is for 2 subs, but has 4 recipients.",
+          Some("Removing its 2 duplicate recipients."),
+          false
+        ),
+        (
+          "Gift sub donation 4 is for 1 subs, but has 2 recipients.",
+          None,
+          false
+        ),
+        (
+          "Gift sub donation 5 is for 2 subs, but has 4 recipients.",
+          Some("Removing its 1 duplicate recipients."),
+          true
+        ),
+      ]
+    );
+    assert_eq!(statements.len(), 2);
+    assert!(statements[1].contains("`gift_sub_recipient`.`donation_event_id` IN (3, 4, 5)"));
+    assert!(outcome.errors.is_empty());
+  }
+
+  #[tokio::test]
+  async fn repair_removes_duplicates_and_leaves_amounts_alone() {
+    let database_connection = mock_database(true);
+    let mut outcome = JobOutcome::default();
+
+    let report = check(true, &database_connection, &mut outcome)
+      .await
+      .unwrap();
+    let statements = logged_statements(database_connection);
+
+    assert_eq!(report.findings.len(), 4);
+    assert_eq!(statements.len(), 4);
+    assert!(statements[2].starts_with("DELETE FROM `gift_sub_recipient`"));
+    assert!(statements[2].contains("`gift_sub_recipient`.`id` IN (11, 13)"));
+    assert!(statements[3].contains("`gift_sub_recipient`.`id` IN (31)"));
+    assert!(report.findings[3].needs_review);
+    assert!(
+      !statements
+        .iter()
+        .any(|statement| statement.contains("`donation_event`") && statement.starts_with("UPDATE"))
+    );
+    assert!(outcome.errors.is_empty());
+  }
+}
@@ -0,0 +1,219 @@
+use crate::jobs::{JobOutcome, NightlyJob};
+use app_config::nightly_job_config::NightlyJobConfig;
+use database_connection::get_database_connection;
+use sea_orm::DatabaseConnection;
+use std::fmt;
+
+pub mod dangling_streams;
+pub mod gift_sub_counts;
+pub mod unlinked_messages;
+
+/// How many findings of each kind are listed in the report. The rest are only counted.
+const MAX_LISTED_FINDINGS: usize = 25;
+
+/// Looks for data that's inconsistent with the rest of the database, repairing what it can if asked to.
+///
+/// Without repairing, nothing is changed and the report describes what would be done.
+pub struct DataIntegrityJob {
+  repair: bool,
+}
+
+impl DataIntegrityJob {
+  pub fn new(repair: bool) -> Self {
+    Self { repair }
+  }
+
+  pub fn from_config(config: &NightlyJobConfig) -> Self {
+    Self::new(config.repair)
+  }
+}
+
+impl NightlyJob for DataIntegrityJob {
+  /// Counts every finding as processed, whether or not it was repaired.
+  async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
+    let database_connection = get_database_connection().await;
+    let report = run_checks(self.repair, database_connection, outcome).await;
+
+    outcome.items_processed += report
+      .checks
+      .iter()
+      .map(|check| check.findings.len() as u64)
+      .sum::<u64>();
+
+    tracing::info!("{report}");
+
+    Ok(())
+  }
+}
+
+/// Runs every check, recording the ones that failed as errors rather than stopping the rest.
+async fn run_checks(
+  repair: bool,
+  database_connection: &DatabaseConnection,
+  outcome: &mut JobOutcome,
+) -> IntegrityReport {
+  let mut report = IntegrityReport {
+    repair,
+    checks: vec![],
+  };
+
+  // Streams are closed first, so the messages sent during them can be linked to them afterwards.
+  let checks = [
+    dangling_streams::check(repair, database_connection, outcome).await,
+    unlinked_messages::check(repair, database_connection, outcome).await,
+    gift_sub_counts::check(repair, database_connection, outcome).await,
+  ];
+
+  for check in checks {
+    match check {
+      Ok(check) => report.checks.push(check),
+      Err(error) => outcome.record_error(format!("An integrity check failed. Reason: `{error}`")),
+    }
+  }
+
+  report
+}
+
+/// A problem found in the stored data.
+#[derive(Debug)]
+pub struct Finding {
+  pub description: String,
+  /// What's done to repair it. None if it has to be looked at by hand.
+  pub repair: Option<String>,
+  /// Set when the repair doesn't fully fix it, so it still has to be looked at by hand afterwards.
+  pub needs_review: bool,
+}
+
+/// The findings of a single integrity check.
+#[derive(Debug)]
+pub struct IntegrityCheckReport {
+  pub name: &'static str,
+  pub findings: Vec<Finding>,
+}
+
+#[derive(Debug)]
+pub struct IntegrityReport {
+  /// False for a dry run.
+  pub repair: bool,
+  pub checks: Vec<IntegrityCheckReport>,
+}
+
+impl fmt::Display for IntegrityReport {
+  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (mode, repair_verb) = match self.repair {
+      true => ("repairing", "repaired"),
+      false => ("dry run, nothing was changed", "repairable"),
+    };
+
+    writeln!(formatter, "Data integrity report ({mode}):")?;
+
+    for check in &self.checks {
+      let repairable_count = check
+        .findings
+        .iter()
+        .filter(|finding| finding.repair.is_some() && !finding.needs_review)
+        .count();
+
+      writeln!(
+        formatter,
+        "{}: {} found, {repairable_count} {repair_verb}.",
+        check.name,
+        check.findings.len()
+      )?;
+
+      for finding in check.findings.iter().take(MAX_LISTED_FINDINGS) {
+        match &finding.repair {
+          Some(repair) if finding.needs_review => writeln!(
+            formatter,
+            "  - {} {repair} It still needs to be looked at by hand.",
+            finding.description
+          )?,
+          Some(repair) => writeln!(formatter, "  - {} {repair}", finding.description)?,
+          None => writeln!(
+            formatter,
+            "  - {} Needs to be looked at by hand.",
+            finding.description
+          )?,
+        }
+      }
+
+      if check.findings.len() > MAX_LISTED_FINDINGS {
+        writeln!(
+          formatter,
+          "  ...and {} more.",
+          check.findings.len() - MAX_LISTED_FINDINGS
+        )?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use entities::stream;
+  use sea_orm::{DatabaseBackend, DbErr, MockDatabase, Value};
+  use std::collections::BTreeMap;
+
+  #[tokio::test]
+  async fn a_failed_check_is_recorded_without_stopping_the_rest() {
+    let database_connection = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([Vec::<stream::Model>::new()])
+      .append_query_errors([DbErr::Custom("The connection was lost.".into())])
+      .append_query_results([Vec::<BTreeMap<&str, Value>>::new()])
+      .into_connection();
+    let mut outcome = JobOutcome::default();
+
+    let report = run_checks(false, &database_connection, &mut outcome).await;
+    let check_names: Vec<&str> = report.checks.iter().map(|check| check.name).collect();
+
+    assert_eq!(
+      check_names,
+      vec!["Dangling streams", "Gift subs with mismatched recipients"]
+    );
+    assert_eq!(
+      outcome.errors,
+      vec!["An integrity check failed. Reason: `Custom Error: The connection was lost.`"]
+    );
+  }
+
+  #[test]
+  fn report_lists_findings_and_what_is_done_about_them() {
+    let report = IntegrityReport {
+      repair: false,
+      checks: vec![IntegrityCheckReport {
+        name: "Dangling streams",
+        findings: vec![
+          Finding {
+            description: "Stream 1 has no end.".into(),
+            repair: Some("Ending it.".into()),
+            needs_review: false,
+          },
+          Finding {
+            description: "Stream 2 has no end.".into(),
+            repair: None,
+            needs_review: false,
+          },
+          Finding {
+            description: "Stream 3 has no end.".into(),
+            repair: Some("Ending it.".into()),
+            needs_review: true,
+          },
+        ],
+      }],
+    };
+
+    assert_eq!(
+      report.to_string(),
+      "\
+Data integrity report (dry run, nothing was changed):
+Dangling streams: 3 found, 1 repairable.
+  - Stream 1 has no end. Ending it.
+  - Stream 2 has no end. Needs to be looked at by hand.
+  - Stream 3 has no end. Ending it. It still needs to be looked at by hand.
+"
+    );
+  }
+}
@@ -0,0 +1,248 @@
+use crate::checks::data_integrity::{Finding, IntegrityCheckReport};
+use crate::jobs::JobOutcome;
+use chrono::{DateTime, Utc};
+use entities::{stream, stream_message};
+use sea_orm::sea_query::{Expr, Query, SelectStatement, SimpleExpr};
+use sea_orm::*;
+
+/// How many unlinked messages were sent during a stream.
+#[derive(Debug, FromQueryResult)]
+struct UnlinkedStreamMessages {
+  stream_id: i32,
+  start_timestamp: DateTime<Utc>,
+  end_timestamp: DateTime<Utc>,
+  message_count: i64,
+}
+
+/// Finds messages without a stream that were sent in a channel while it was live, linking them to that stream.
+///
+/// Each channel's messages are linked with a single update, rather than one per stream.
+pub async fn check(
+  repair: bool,
+  database_connection: &DatabaseConnection,
+  outcome: &mut JobOutcome,
+) -> anyhow::Result<IntegrityCheckReport> {
+  let channel_ids: Vec<i32> = stream_message::Entity::find()
+    .select_only()
+    .column(stream_message::Column::ChannelId)
+    .filter(stream_message::Column::StreamId.is_null())
+    .distinct()
+    .into_tuple()
+    .all(database_connection)
+    .await?;
+  let mut report = IntegrityCheckReport {
+    name: "Messages missing their stream",
+    findings: vec![],
+  };
+
+  for channel_id in channel_ids {
+    let unlinked_stream_messages = stream_message::Entity::find()
+      .select_only()
+      .column_as(stream::Column::Id, "stream_id")
+      .column(stream::Column::StartTimestamp)
+      .column(stream::Column::EndTimestamp)
+      .column_as(stream_message::Column::Id.count(), "message_count")
+      .join(JoinType::InnerJoin, messages_during_streams())
+      .filter(stream_message::Column::ChannelId.eq(channel_id))
+      .filter(stream_message::Column::StreamId.is_null())
+      .group_by(stream::Column::Id)
+      .group_by(stream::Column::StartTimestamp)
+      .group_by(stream::Column::EndTimestamp)
+      .into_model::<UnlinkedStreamMessages>()
+      .all(database_connection)
+      .await?;
+
+    if unlinked_stream_messages.is_empty() {
+      continue;
+    }
+
+    if repair {
+      let result = stream_message::Entity::update_many()
+        .col_expr(
+          stream_message::Column::StreamId,
+          SimpleExpr::SubQuery(
+            None,
+            Box::new(stream_during_message().into_sub_query_statement()),
+          ),
+        )
+        .filter(stream_message::Column::ChannelId.eq(channel_id))
+        .filter(stream_message::Column::StreamId.is_null())
+        .filter(Expr::exists(stream_during_message()))
+        .exec(database_connection)
+        .await;
+
+      if let Err(error) = result {
+        outcome.record_error(format!(
+          "Failed to link the messages in channel {channel_id} to their streams. Reason: `{error}`"
+        ));
+
+        continue;
+      }
+    }
+
+    report.findings.extend(
+      unlinked_stream_messages
+        .into_iter()
+        .map(|stream_messages| Finding {
+          description: format!(
+            "{} messages were sent during stream {} ({} to {}) without being linked to it.",
+            stream_messages.message_count,
+            stream_messages.stream_id,
+            stream_messages.start_timestamp,
+            stream_messages.end_timestamp
+          ),
+          repair: Some("Linking them to the stream.".to_string()),
+          needs_review: false,
+        }),
+    );
+  }
+
+  Ok(report)
+}
+
+/// Joins each message to the streams its channel was live for when it was sent.
+fn messages_during_streams() -> RelationDef {
+  stream_message::Entity::belongs_to(stream::Entity)
+    .from(stream_message::Column::ChannelId)
+    .to(stream::Column::TwitchUserId)
+    .on_condition(|message_table, stream_table| {
+      Condition::all().add(
+        Expr::col((message_table, stream_message::Column::Timestamp)).between(
+          Expr::col((stream_table.clone(), stream::Column::StartTimestamp)),
+          Expr::col((stream_table, stream::Column::EndTimestamp)),
+        ),
+      )
+    })
+    .into()
+}
+
+/// Selects the ID of the stream the message's channel was live for when it was sent.
+///
+/// Only the first stream is taken if the channel's streams overlap.
+fn stream_during_message() -> SelectStatement {
+  Query::select()
+    .column((stream::Entity, stream::Column::Id))
+    .from(stream::Entity)
+    .and_where(
+      Expr::col((stream::Entity, stream::Column::TwitchUserId))
+        .equals((stream_message::Entity, stream_message::Column::ChannelId)),
+    )
+    .and_where(
+      Expr::col((stream_message::Entity, stream_message::Column::Timestamp)).between(
+        Expr::col((stream::Entity, stream::Column::StartTimestamp)),
+        Expr::col((stream::Entity, stream::Column::EndTimestamp)),
+      ),
+    )
+    .order_by((stream::Entity, stream::Column::StartTimestamp), Order::Asc)
+    .limit(1)
+    .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+  use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value};
+  use std::collections::BTreeMap;
+
+  fn channel(channel_id: i32) -> BTreeMap<&'static str, Value> {
+    BTreeMap::from([("channel_id", Value::Int(Some(channel_id)))])
+  }
+
+  fn stream_messages(stream_id: i32, message_count: i64) -> BTreeMap<&'static str, Value> {
+    let start_timestamp = Utc.with_ymd_and_hms(2026, 10, 1, 18, 0, 0).unwrap();
+    let end_timestamp = Utc.with_ymd_and_hms(2026, 10, 1, 22, 0, 0).unwrap();
+
+    BTreeMap::from([
+      ("stream_id", Value::Int(Some(stream_id))),
+      (
+        "start_timestamp",
+        Value::ChronoDateTimeUtc(Some(Box::new(start_timestamp))),
+      ),
+      (
+        "end_timestamp",
+        Value::ChronoDateTimeUtc(Some(Box::new(end_timestamp))),
+      ),
+      ("message_count", Value::BigInt(Some(message_count))),
+    ])
+  }
+
+  /// Channel 1 has unlinked messages sent during two of its streams, and channel 2 has some sent while offline.
+  fn mock_database(repair: bool) -> DatabaseConnection {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![channel(1), channel(2)]])
+      .append_query_results([vec![stream_messages(10, 4), stream_messages(11, 2)]])
+      .append_query_results([Vec::<BTreeMap<&str, Value>>::new()]);
+
+    match repair {
+      true => mock_database.append_exec_results([MockExecResult {
+        last_insert_id: 0,
+        rows_affected: 6,
+      }]),
+      false => mock_database,
+    }
+    .into_connection()
+  }
+
+  fn logged_statements(database_connection: DatabaseConnection) -> Vec<String> {
+    database_connection
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn dry_run_counts_the_messages_of_each_stream_without_linking_them() {
+    let database_connection = mock_database(false);
+    let mut outcome = JobOutcome::default();
+
+    let report = check(false, &database_connection, &mut outcome)
+      .await
+      .unwrap();
+    let statements = logged_statements(database_connection);
+
+    assert_eq!(report.findings.len(), 2);
+    assert_eq!(
+      report.findings[0].description,
+      "4 messages were sent during stream 10 (2026-10-01 18:00:00 UTC to 2026-10-01 22:00:00 UTC) without being linked to it."
+    );
+    assert_eq!(statements.len(), 3);
+    assert!(statements[1].contains(
+      "INNER JOIN `stream` ON `stream_message`.`channel_id` = `stream`.`twitch_user_id` AND (`stream_message`.`timestamp` BETWEEN `stream`.`start_timestamp` AND `stream`.`end_timestamp`)"
+    ));
+    assert!(statements[1].contains("WHERE `stream_message`.`channel_id` = 1 AND"));
+    assert!(
+      statements[1]
+        .ends_with("GROUP BY `stream`.`id`, `stream`.`start_timestamp`, `stream`.`end_timestamp`")
+    );
+    assert!(statements[2].contains("WHERE `stream_message`.`channel_id` = 2 AND"));
+    assert!(outcome.errors.is_empty());
+  }
+
+  #[tokio::test]
+  async fn repair_links_each_channels_messages_with_one_update() {
+    let database_connection = mock_database(true);
+    let mut outcome = JobOutcome::default();
+
+    let report = check(true, &database_connection, &mut outcome)
+      .await
+      .unwrap();
+    let statements = logged_statements(database_connection);
+    let updates: Vec<&String> = statements
+      .iter()
+      .filter(|statement| statement.starts_with("UPDATE"))
+      .collect();
+
+    assert_eq!(report.findings.len(), 2);
+    assert_eq!(updates.len(), 1);
+    assert!(updates[0].starts_with(
+      "UPDATE `stream_message` SET `stream_id` = (SELECT `stream`.`id` FROM `stream` WHERE `stream`.`twitch_user_id` = `stream_message`.`channel_id`"
+    ));
+    assert!(updates[0].contains(
+      "WHERE `stream_message`.`channel_id` = 1 AND `stream_message`.`stream_id` IS NULL AND EXISTS("
+    ));
+    assert!(outcome.errors.is_empty());
+  }
+}
@@ -0,0 +1,38 @@
+use crate::jobs::{JobOutcome, NightlyJob};
+use app_config::AppConfig;
+use database_connection::get_database_connection;
+use entity_extensions::exchange_rate::{
+  EXCHANGE_RATE_BASE_CURRENCY, fetch_latest_exchange_rates, store_exchange_rates,
+};
+
+/// Stores today's exchange rates so donations made today can later be converted at today's rate.
+pub struct StoreExchangeRatesJob;
+
+impl NightlyJob for StoreExchangeRatesJob {
+  /// Counts every stored rate as processed.
+  async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
+    if AppConfig::exchange_rate_api_key().is_none() {
+      tracing::warn!("No exchange rate API key is set. Skipping today's exchange rates.");
+
+      return Ok(());
+    }
+
+    let database_connection = get_database_connection().await;
+    let rates =
+      fetch_latest_exchange_rates(EXCHANGE_RATE_BASE_CURRENCY, &reqwest::Client::new()).await?;
+
+    store_exchange_rates(
+      chrono::Utc::now().date_naive(),
+      EXCHANGE_RATE_BASE_CURRENCY,
+      &rates,
+      database_connection,
+    )
+    .await?;
+
+    tracing::info!("Stored {} exchange rates for today.", rates.len());
+
+    outcome.items_processed += rates.len() as u64;
+
+    Ok(())
+  }
+}
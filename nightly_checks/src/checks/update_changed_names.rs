@@ -1,147 +1,231 @@
-use crate::rate_limiter::RateLimiter;
+use crate::jobs::{JobOutcome, NightlyJob};
 use anyhow::anyhow;
+use app_config::{AppConfig, nightly_job_config::NightlyJobConfig};
+use chrono::{DateTime, Utc};
 use database_connection::get_database_connection;
-use entities::{twitch_user, twitch_user_name_change};
-use entity_extensions::{prelude::TwitchUserExtensions, twitch_user::ChannelIdentifier};
-use human_time::ToHumanTimeString;
+use entities::{stream_message, twitch_user, twitch_user_name_change};
+use entity_extensions::account_status_change::record_rename;
+use entity_extensions::prelude::TwitchUserExtensions;
+use helix_client::get_helix_client;
+use sea_orm::sea_query::{Expr, Query};
 use sea_orm::*;
 use std::collections::{HashMap, HashSet};
 
+/// How many users are sent to Helix at once.
+const DEFAULT_BATCH_SIZE: usize = 100;
+/// The most users Helix takes in one request.
+const MAX_BATCH_SIZE: usize = 100;
+/// Users who sent a message within this many days are verified every night.
+const RECENT_ACTIVITY_DAYS: i64 = 7;
+/// Users verified within this many hours are skipped, so a rerun on the same night doesn't check them again.
+const VERIFIED_RECENTLY_HOURS: i64 = 20;
+
+/// Verifies users' names with Helix, recording any name changes.
+pub struct UpdateChangedNamesJob {
+  batch_size: usize,
+  sweep_nights: u32,
+}
+
+impl UpdateChangedNamesJob {
+  /// Batch sizes outside of what Helix takes are brought within it.
+  pub fn from_config(config: &NightlyJobConfig) -> Self {
+    let batch_size = config.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+    let clamped_batch_size = batch_size.clamp(1, MAX_BATCH_SIZE);
+
+    if clamped_batch_size != batch_size {
+      tracing::warn!(
+        "The batch size of {batch_size} is outside of the 1 to {MAX_BATCH_SIZE} users Helix takes. Using {clamped_batch_size}."
+      );
+    }
+
+    Self {
+      batch_size: clamped_batch_size,
+      sweep_nights: AppConfig::name_check_sweep_nights(),
+    }
+  }
+}
+
+impl NightlyJob for UpdateChangedNamesJob {
+  async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
+    let database_connection = get_database_connection().await;
+
+    DatabaseNameUpdateConfig::new(self.batch_size, self.sweep_nights, database_connection)
+      .await?
+      .run(outcome)
+      .await;
+
+    Ok(())
+  }
+}
+
 pub struct DatabaseNameUpdateConfig<'a> {
-  rate_limiter: RateLimiter,
   database_connection: &'a DatabaseConnection,
-  all_login_and_display_names: HashSet<String>,
-  user_list_by_twitch_ids: HashMap<i32, twitch_user::Model>,
+  users_to_check: Vec<twitch_user::Model>,
   chunk_limit: usize,
   total_batches: usize,
 }
 
-impl DatabaseNameUpdateConfig<'_> {
-  pub async fn new(max_requests_per_minute: usize, chunk_limit: usize) -> anyhow::Result<Self> {
-    let database_connection = get_database_connection().await;
-    let all_users = twitch_user::Entity::find().all(database_connection).await?;
-    let rate_limiter = RateLimiter::new(max_requests_per_minute);
-    let total_batches = all_users.len() / chunk_limit;
-    let all_login_and_display_names: HashSet<String> = all_users
-      .iter()
-      .flat_map(|user| [user.login_name.to_owned(), user.display_name.to_owned()])
-      .collect();
-    let user_list_by_twitch_ids: HashMap<i32, twitch_user::Model> = all_users
+impl<'a> DatabaseNameUpdateConfig<'a> {
+  /// Picks the users to verify tonight. Every recently active user, followed by the users verified longest ago.
+  ///
+  /// Enough of the latter are picked for every user to be verified over `sweep_nights` nights.
+  pub async fn new(
+    chunk_limit: usize,
+    sweep_nights: u32,
+    database_connection: &'a DatabaseConnection,
+  ) -> anyhow::Result<Self> {
+    let now = Utc::now();
+    let verified_recently = now - chrono::Duration::hours(VERIFIED_RECENTLY_HOURS);
+    let recently_active = now - chrono::Duration::days(RECENT_ACTIVITY_DAYS);
+
+    let total_users = twitch_user::Entity::find()
+      .count(database_connection)
+      .await?;
+    let sweep_size = total_users.div_ceil(sweep_nights.max(1) as u64);
+
+    let active_users = get_unverified_users(verified_recently)
+      .filter(
+        twitch_user::Column::Id.in_subquery(
+          Query::select()
+            .distinct()
+            .column(stream_message::Column::TwitchUserId)
+            .from(stream_message::Entity)
+            .and_where(stream_message::Column::Timestamp.gte(recently_active))
+            .to_owned(),
+        ),
+      )
+      .all(database_connection)
+      .await?;
+    let active_user_ids: HashSet<i32> = active_users.iter().map(|user| user.id).collect();
+    let stalest_users = get_unverified_users(verified_recently)
+      .order_by_asc(twitch_user::Column::LastVerifiedAt)
+      .order_by_asc(twitch_user::Column::Id)
+      .limit(sweep_size)
+      .all(database_connection)
+      .await?;
+
+    tracing::info!(
+      "Verifying {} recently active users and {} of the {} users in the sweep.",
+      active_users.len(),
+      stalest_users.len(),
+      total_users
+    );
+
+    let users_to_check: Vec<twitch_user::Model> = active_users
       .into_iter()
-      .map(|user| (user.twitch_id, user))
+      .chain(
+        stalest_users
+          .into_iter()
+          .filter(|user| !active_user_ids.contains(&user.id)),
+      )
       .collect();
+    let total_batches = users_to_check.len().div_ceil(chunk_limit);
 
     Ok(Self {
-      rate_limiter,
       database_connection,
-      all_login_and_display_names,
-      user_list_by_twitch_ids,
+      users_to_check,
       chunk_limit,
       total_batches,
     })
   }
 
-  pub async fn run(mut self) {
+  /// Counts every user in a batch that was verified as processed.
+  pub async fn run(mut self, outcome: &mut JobOutcome) {
     tracing::info!("Total batch count: {}", self.total_batches);
 
-    let user_list: Vec<&twitch_user::Model> = self.user_list_by_twitch_ids.values().collect();
+    let users_to_check = std::mem::take(&mut self.users_to_check);
 
-    for (batch_number, user_batch) in user_list.chunks(self.chunk_limit).enumerate() {
+    for (batch_number, user_batch) in users_to_check.chunks(self.chunk_limit).enumerate() {
       tracing::info!(
-        "Processing batch number {}. Current tokens: {}",
+        "Processing batch number {}. Remaining Helix requests: {:?}",
         batch_number,
-        self.rate_limiter.tokens()
+        get_helix_client().rate_limit().remaining
       );
-      let channel_identifiers: Vec<ChannelIdentifier<String>> = user_batch
-        .iter()
-        .map(|user| ChannelIdentifier::TwitchID(user.twitch_id.to_string()))
-        .collect();
-
-      if let Some(refresh_wait_time) = self.rate_limiter.request_tokens(self.chunk_limit) {
-        tracing::info!(
-          "Rate limit reached. Waiting for next refresh in {}.",
-          refresh_wait_time.to_human_time_string()
-        );
-
-        tokio::time::sleep(refresh_wait_time).await;
-
-        self.rate_limiter.reset_tokens();
-      }
 
       let channel_list_query_result =
-        twitch_user::Model::query_helix_for_channels_from_list(channel_identifiers.as_slice())
-          .await;
-      let mut update_channel_list = match channel_list_query_result {
+        twitch_user::Model::query_helix_for_users(user_batch, self.database_connection).await;
+      let channel_list = match channel_list_query_result {
         Ok(channel_list) => channel_list,
         Err(error) => {
-          tracing::error!(
+          outcome.record_error(format!(
             "Failed to process batch number {}/{}. Reason: {}",
-            batch_number,
-            self.total_batches,
-            error
-          );
+            batch_number, self.total_batches, error
+          ));
 
           continue;
         }
       };
 
-      self.remove_unchanged_names(&mut update_channel_list);
-
-      for channel in update_channel_list {
-        let result = self
-          .update_channel_and_insert_name_change(channel, batch_number)
-          .await;
-
-        if let Err(error) = result {
-          tracing::error!("Failed to update a channel's name change. Reason: `{error}`");
-        }
+      match self
+        .process_batch(user_batch, channel_list, batch_number, outcome)
+        .await
+      {
+        Ok(()) => outcome.items_processed += user_batch.len() as u64,
+        Err(error) => outcome.record_error(format!(
+          "Failed to update batch number {}/{}. Reason: {}",
+          batch_number, self.total_batches, error
+        )),
       }
     }
   }
 
-  /// Removes any names from the list that have not changed on Twitch compared to their current database entry.
-  fn remove_unchanged_names(&self, batch: &mut Vec<twitch_user::ActiveModel>) {
-    batch.retain(|channel| {
-      let Some(login_name) = channel.login_name.try_as_ref() else {
-        tracing::error!("Channel {:?} is missing a login name", channel.id);
+  /// Updates the names that changed and marks the batch as verified.
+  async fn process_batch(
+    &self,
+    user_batch: &[twitch_user::Model],
+    channel_list: Vec<twitch_user::ActiveModel>,
+    batch_number: usize,
+    outcome: &mut JobOutcome,
+  ) -> anyhow::Result<()> {
+    let mut channels_by_twitch_id: HashMap<i32, twitch_user::ActiveModel> = channel_list
+      .into_iter()
+      .filter_map(|channel| Some((*channel.twitch_id.try_as_ref()?, channel)))
+      .collect();
 
-        return false;
+    for user in user_batch {
+      let Some(channel) = channels_by_twitch_id.remove(&user.twitch_id) else {
+        continue;
       };
-      let Some(display_name) = channel.display_name.try_as_ref() else {
-        tracing::error!("Channel {:?} is missing a display name", channel.id);
 
-        return false;
-      };
+      if !has_changed_name(user, &channel) {
+        continue;
+      }
+
+      let result = self
+        .update_channel_and_insert_name_change(user, channel, batch_number)
+        .await;
+
+      if let Err(error) = result {
+        outcome.record_error(format!(
+          "Failed to update a channel's name change. Reason: `{error}`"
+        ));
+      }
+    }
+
+    twitch_user::Entity::update_many()
+      .col_expr(
+        twitch_user::Column::LastVerifiedAt,
+        Expr::value(Some(Utc::now())),
+      )
+      .filter(twitch_user::Column::Id.is_in(user_batch.iter().map(|user| user.id)))
+      .exec(self.database_connection)
+      .await?;
 
-      !self
-        .all_login_and_display_names
-        .contains(login_name.as_str())
-        || !self
-          .all_login_and_display_names
-          .contains(display_name.as_str())
-    });
+    Ok(())
   }
 
   async fn update_channel_and_insert_name_change(
     &self,
+    corresponding_channel: &twitch_user::Model,
     mut channel_name_change: twitch_user::ActiveModel,
     current_batch_number: usize,
   ) -> anyhow::Result<()> {
-    let Some(channel_twitch_id) = channel_name_change.twitch_id.try_as_ref() else {
-      return Err(anyhow!("Missing twitch id: {:?}", channel_name_change));
-    };
     let Some(new_login_name) = channel_name_change.login_name.try_as_ref().cloned() else {
       return Err(anyhow!("Missing login name: {:?}", channel_name_change));
     };
     let Some(new_display_name) = channel_name_change.display_name.try_as_ref().cloned() else {
       return Err(anyhow!("Missing display name: {:?}", channel_name_change));
     };
-    let Some(corresponding_channel) = self.user_list_by_twitch_ids.get(channel_twitch_id) else {
-      return Err(anyhow!(
-        "Missing channel from user list: {:?}",
-        channel_name_change
-      ));
-    };
 
     channel_name_change.id = Set(corresponding_channel.id);
     channel_name_change.twitch_id = Unchanged(corresponding_channel.twitch_id);
@@ -173,7 +257,9 @@ impl DatabaseNameUpdateConfig<'_> {
             error
           ))
         } else {
-          Ok(())
+          record_rename(corresponding_channel.id, self.database_connection)
+            .await
+            .map_err(Into::into)
         }
       }
       Err(error) => Err(anyhow!(
@@ -185,3 +271,185 @@ impl DatabaseNameUpdateConfig<'_> {
     }
   }
 }
+
+/// Returns the users who haven't been verified since the given time, including those never verified.
+fn get_unverified_users(verified_since: DateTime<Utc>) -> Select<twitch_user::Entity> {
+  twitch_user::Entity::find().filter(
+    Condition::any()
+      .add(twitch_user::Column::LastVerifiedAt.is_null())
+      .add(twitch_user::Column::LastVerifiedAt.lt(verified_since)),
+  )
+}
+
+/// Returns true if the login or display name from Helix differs from the user's current one.
+fn has_changed_name(user: &twitch_user::Model, channel: &twitch_user::ActiveModel) -> bool {
+  let Some(login_name) = channel.login_name.try_as_ref() else {
+    tracing::error!("Channel {:?} is missing a login name", channel.twitch_id);
+
+    return false;
+  };
+  let Some(display_name) = channel.display_name.try_as_ref() else {
+    tracing::error!("Channel {:?} is missing a display name", channel.twitch_id);
+
+    return false;
+  };
+
+  *login_name != user.login_name || *display_name != user.display_name
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::BTreeMap;
+
+  fn user(id: i32, login_name: &str, display_name: &str) -> twitch_user::Model {
+    twitch_user::Model {
+      id,
+      twitch_id: id * 100,
+      login_name: login_name.into(),
+      display_name: display_name.into(),
+      last_verified_at: None,
+    }
+  }
+
+  fn helix_channel(
+    twitch_id: i32,
+    login_name: &str,
+    display_name: &str,
+  ) -> twitch_user::ActiveModel {
+    twitch_user::ActiveModel {
+      twitch_id: Set(twitch_id),
+      login_name: Set(login_name.into()),
+      display_name: Set(display_name.into()),
+      ..Default::default()
+    }
+  }
+
+  fn logged_statements(mock_database: DatabaseConnection) -> Vec<String> {
+    mock_database
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn new_picks_active_users_then_the_stalest_users_once() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![BTreeMap::from([(
+        "num_items".to_string(),
+        Value::Int(Some(5)),
+      )])]])
+      .append_query_results([
+        vec![
+          user(1, "moose", "Moose"),
+          user(2, "fallenshadow", "FallenShadow"),
+        ],
+        vec![
+          user(2, "fallenshadow", "FallenShadow"),
+          user(3, "shadowchama", "ShadowChama"),
+        ],
+      ])
+      .into_connection();
+
+    let name_update_config = DatabaseNameUpdateConfig::new(2, 3, &mock_database)
+      .await
+      .unwrap();
+    let users_to_check: Vec<i32> = name_update_config
+      .users_to_check
+      .iter()
+      .map(|user| user.id)
+      .collect();
+    let total_batches = name_update_config.total_batches;
+    let statements = logged_statements(mock_database);
+
+    assert_eq!(users_to_check, vec![1, 2, 3]);
+    assert_eq!(total_batches, 2);
+    assert!(statements[1].contains(
+      "`twitch_user`.`id` IN (SELECT DISTINCT `twitch_user_id` FROM `stream_message` WHERE `stream_message`.`timestamp` >="
+    ));
+    assert!(statements[1].contains("`twitch_user`.`last_verified_at` IS NULL"));
+    assert!(
+      statements[2]
+        .ends_with("ORDER BY `twitch_user`.`last_verified_at` ASC, `twitch_user`.`id` ASC LIMIT 2")
+    );
+  }
+
+  #[tokio::test]
+  async fn process_batch_records_name_changes_and_marks_the_batch_verified() {
+    let renamed_user = user(1, "moose_renamed", "Moose_Renamed");
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![renamed_user]])
+      .append_query_results([vec![twitch_user_name_change::Model {
+        id: 1,
+        twitch_user_id: 1,
+        previous_login_name: Some("moose".into()),
+        previous_display_name: Some("Moose".into()),
+        new_login_name: Some("moose_renamed".into()),
+        new_display_name: Some("Moose_Renamed".into()),
+        created_at: Utc::now(),
+      }]])
+      .append_exec_results((1..=4).map(|id| MockExecResult {
+        last_insert_id: id,
+        rows_affected: 1,
+      }))
+      .into_connection();
+    let user_batch = vec![
+      user(1, "moose", "Moose"),
+      user(2, "fallenshadow", "FallenShadow"),
+      user(3, "shadowchama", "ShadowChama"),
+    ];
+    let channel_list = vec![
+      helix_channel(100, "moose_renamed", "Moose_Renamed"),
+      helix_channel(200, "fallenshadow", "FallenShadow"),
+    ];
+    let name_update_config = DatabaseNameUpdateConfig {
+      database_connection: &mock_database,
+      users_to_check: vec![],
+      chunk_limit: 100,
+      total_batches: 1,
+    };
+    let mut outcome = JobOutcome::default();
+
+    name_update_config
+      .process_batch(&user_batch, channel_list, 0, &mut outcome)
+      .await
+      .unwrap();
+    let statements = logged_statements(mock_database);
+
+    assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("UPDATE `twitch_user` SET")
+        && statement.contains("`login_name` = 'moose_renamed'")
+        && statement.ends_with("WHERE `twitch_user`.`id` = 1")
+    }));
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("INSERT INTO `twitch_user_name_change`")
+        && statement.contains("'moose', 'Moose', 'moose_renamed', 'Moose_Renamed'")
+    }));
+    assert!(statements.iter().any(|statement| {
+      statement.starts_with("INSERT INTO `account_status_change`")
+        && statement.contains("'renamed'")
+    }));
+    assert_eq!(
+      statements
+        .iter()
+        .filter(|statement| statement.starts_with("UPDATE `twitch_user` SET"))
+        .count(),
+      2
+    );
+    assert!(
+      statements
+        .last()
+        .unwrap()
+        .starts_with("UPDATE `twitch_user` SET `last_verified_at` =")
+    );
+    assert!(
+      statements
+        .last()
+        .unwrap()
+        .ends_with("WHERE `twitch_user`.`id` IN (1, 2, 3)")
+    );
+  }
+}
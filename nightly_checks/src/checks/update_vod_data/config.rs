@@ -2,20 +2,20 @@ use crate::checks::update_vod_data::{
   config::vod_stream_pair::{VodAndStream, VodStreamPairs},
   twitch_objects::vod_response::TwitchVodResponse,
 };
+use crate::jobs::JobOutcome;
 use anyhow::anyhow;
-use app_config::{AppConfig, secret_string::Secret};
 use chrono::{Duration, Utc};
 use database_connection::get_database_connection;
 use entities::{stream, twitch_user};
 use entity_extensions::stream::StreamExtensions;
+use helix_client::get_helix_client;
 use sea_orm::*;
 use std::collections::{HashMap, HashSet};
-use url::Url;
 
 mod vod_stream_pair;
 
 const VOD_AGE_DAYS_MAX_RANGE: (usize, usize) = (1, 60);
-const HELIX_VOD_QUERY_URL: &str = "https://api.twitch.tv/helix/videos";
+const HELIX_VOD_QUERY_PATH: &str = "videos";
 
 pub struct UpdateVodDataConfig<'a> {
   /// How many days old should vods be to be checked.
@@ -37,14 +37,17 @@ impl UpdateVodDataConfig<'_> {
     }
   }
 
-  pub async fn run(self) -> anyhow::Result<()> {
+  /// Counts every stream updated with its vod data as processed.
+  pub async fn run(self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
     let streams = self.get_streams().await?;
     let users = self.get_users_from_stream_list(&streams).await?;
 
-    let vods = Self::query_for_vods_from_users(&users, &streams).await;
+    let vods = Self::query_for_vods_from_users(&users, &streams, outcome).await;
     let vod_stream_pairs = Self::build_vod_stream_pairs(users, streams, vods).await;
 
-    self.update_streams_with_vod_data(vod_stream_pairs).await;
+    self
+      .update_streams_with_vod_data(vod_stream_pairs, outcome)
+      .await;
 
     Ok(())
   }
@@ -88,6 +91,7 @@ impl UpdateVodDataConfig<'_> {
   async fn query_for_vods_from_users(
     users: &[twitch_user::Model],
     desired_streams: &[stream::Model],
+    outcome: &mut JobOutcome,
   ) -> HashMap<i32, TwitchVodResponse> {
     tracing::info!("Getting vods for users list.");
     let desired_stream_ids: HashSet<String> = desired_streams
@@ -100,7 +104,9 @@ impl UpdateVodDataConfig<'_> {
       let mut vod_response = match Self::get_vods_for_user(user).await {
         Ok(vod_response) => vod_response,
         Err(error) => {
-          tracing::error!("Failed to get vods for user `{user:?}`. Reason: `{error}`");
+          outcome.record_error(format!(
+            "Failed to get vods for user `{user:?}`. Reason: `{error}`"
+          ));
           continue;
         }
       };
@@ -135,28 +141,9 @@ impl UpdateVodDataConfig<'_> {
 
   async fn get_vods_for_user(user: &twitch_user::Model) -> anyhow::Result<TwitchVodResponse> {
     tracing::info!("Getting vods for `{}`-`{}`", user.id, user.login_name);
-    let mut query_url = Url::parse(HELIX_VOD_QUERY_URL)?;
-    let reqwest_client = reqwest::Client::new();
-
-    {
-      let mut query_pairs = query_url.query_pairs_mut();
-
-      query_pairs.append_pair("user_id", &user.twitch_id.to_string());
-    }
-
-    let request = reqwest_client
-      .get(query_url)
-      .header(
-        "Authorization",
-        format!(
-          "Bearer {}",
-          Secret::read_secret_string(AppConfig::access_token().read_value())
-        ),
-      )
-      .header(
-        "Client-Id",
-        Secret::read_secret_string(AppConfig::client_id().read_value()),
-      );
+    let request = get_helix_client()
+      .get(HELIX_VOD_QUERY_PATH)
+      .query("user_id", user.twitch_id.to_string());
 
     let response = request.send().await?;
     let status = response.status();
@@ -215,7 +202,11 @@ impl UpdateVodDataConfig<'_> {
       .collect()
   }
 
-  async fn update_streams_with_vod_data(&self, vod_stream_pairs: Vec<VodStreamPairs>) {
+  async fn update_streams_with_vod_data(
+    &self,
+    vod_stream_pairs: Vec<VodStreamPairs>,
+    outcome: &mut JobOutcome,
+  ) {
     for VodStreamPairs {
       vods_and_streams, ..
     } in vod_stream_pairs
@@ -236,7 +227,9 @@ impl UpdateVodDataConfig<'_> {
         let stream = match result {
           Ok(stream_model) => stream_model,
           Err(error) => {
-            tracing::error!("Failed to update stream of ID `{stream_id}`. Reason: `{error}`");
+            outcome.record_error(format!(
+              "Failed to update stream of ID `{stream_id}`. Reason: `{error}`"
+            ));
             continue;
           }
         };
@@ -246,12 +239,14 @@ impl UpdateVodDataConfig<'_> {
           .await;
 
         if let Err(error) = result {
-          tracing::error!(
+          outcome.record_error(format!(
             "Failed to insert muted segments for stream of ID `{}`. Reason: `{error}`",
             stream.id
-          );
+          ));
           continue;
         }
+
+        outcome.items_processed += 1;
       }
     }
   }
@@ -1,2 +1,31 @@
-mod twitch_objects;
+use crate::checks::update_vod_data::config::UpdateVodDataConfig;
+use crate::jobs::{JobOutcome, NightlyJob};
+use app_config::nightly_job_config::NightlyJobConfig;
+
+pub(crate) mod twitch_objects;
 pub mod config;
+
+/// How many days back streams are checked for vods by default.
+const DEFAULT_MAX_VOD_AGE_DAYS: usize = 30;
+
+/// Fills in the titles, vod IDs, and muted segments of recent streams from their vods.
+pub struct UpdateVodDataJob {
+  max_vod_age_days: usize,
+}
+
+impl UpdateVodDataJob {
+  pub fn from_config(config: &NightlyJobConfig) -> Self {
+    Self {
+      max_vod_age_days: config.max_age_days.unwrap_or(DEFAULT_MAX_VOD_AGE_DAYS),
+    }
+  }
+}
+
+impl NightlyJob for UpdateVodDataJob {
+  async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
+    UpdateVodDataConfig::new(self.max_vod_age_days)
+      .await
+      .run(outcome)
+      .await
+  }
+}
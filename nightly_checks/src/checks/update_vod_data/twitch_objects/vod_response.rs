@@ -1,3 +1,4 @@
+use chrono::Duration;
 use entities::muted_vod_segment;
 use sea_orm::{NotSet, Set};
 use serde::Deserialize;
@@ -19,6 +20,10 @@ pub struct TwitchVodData {
 
   title: String,
 
+  /// Formatted like `3h8m33s`.
+  #[serde(default)]
+  duration: Option<String>,
+
   #[serde(default)]
   muted_segments: Option<Vec<MutedStreamSegment>>,
 }
@@ -52,6 +57,31 @@ impl TwitchVodData {
   pub fn muted_segments(&self) -> &[MutedStreamSegment] {
     self.muted_segments.as_deref().unwrap_or(&[])
   }
+
+  /// Returns how long the vod is, which is how long the stream lasted for.
+  pub fn duration(&self) -> Option<Duration> {
+    let mut duration = Duration::zero();
+    let mut value = String::new();
+
+    for character in self.duration.as_deref()?.chars() {
+      if character.is_ascii_digit() {
+        value.push(character);
+
+        continue;
+      }
+
+      let amount = std::mem::take(&mut value).parse::<i64>().ok()?;
+
+      duration += match character {
+        'h' => Duration::hours(amount),
+        'm' => Duration::minutes(amount),
+        's' => Duration::seconds(amount),
+        _ => return None,
+      };
+    }
+
+    value.is_empty().then_some(duration)
+  }
 }
 
 impl MutedStreamSegment {
@@ -75,3 +105,53 @@ impl From<&MutedStreamSegment> for muted_vod_segment::ActiveModel {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn vod_with_duration(duration: Option<&str>) -> TwitchVodData {
+    TwitchVodData {
+      id: "1".into(),
+      stream_id: None,
+      user_id: "2".into(),
+      title: "A stream".into(),
+      duration: duration.map(Into::into),
+      muted_segments: None,
+    }
+  }
+
+  #[test]
+  fn durations_are_parsed_from_hours_minutes_and_seconds() {
+    let duration = vod_with_duration(Some("3h8m33s")).duration();
+
+    assert_eq!(
+      duration,
+      Some(Duration::hours(3) + Duration::minutes(8) + Duration::seconds(33))
+    );
+  }
+
+  #[test]
+  fn durations_without_every_unit_are_parsed() {
+    assert_eq!(
+      vod_with_duration(Some("45m2s")).duration(),
+      Some(Duration::seconds(45 * 60 + 2))
+    );
+    assert_eq!(
+      vod_with_duration(Some("59s")).duration(),
+      Some(Duration::seconds(59))
+    );
+    assert_eq!(
+      vod_with_duration(Some("12h0m0s")).duration(),
+      Some(Duration::hours(12))
+    );
+  }
+
+  #[test]
+  fn malformed_durations_are_rejected() {
+    assert_eq!(vod_with_duration(None).duration(), None);
+    assert_eq!(vod_with_duration(Some("3h8m33")).duration(), None);
+    assert_eq!(vod_with_duration(Some("3d")).duration(), None);
+    assert_eq!(vod_with_duration(Some("h")).duration(), None);
+  }
+}
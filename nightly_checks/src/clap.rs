@@ -0,0 +1,43 @@
+use crate::jobs::JobName;
+use clap::{Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(name = "NightlyChecks")]
+pub struct ClapArgs {
+  /// Runs every enabled job once if left out.
+  #[command(subcommand)]
+  pub command: Option<JobCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum JobCommand {
+  /// Runs every enabled job once.
+  RunAll,
+  /// Runs the given jobs once, even if they're disabled.
+  Run {
+    #[arg(required = true, value_enum)]
+    jobs: Vec<JobName>,
+  },
+  /// Checks the stored data for inconsistencies and prints a report. Nothing is changed unless repairing.
+  CheckIntegrity {
+    /// Fixes the problems that can be fixed, rather than only reporting them.
+    #[arg(long)]
+    repair: bool,
+  },
+  /// Keeps running, running the enabled jobs on their schedules.
+  Schedule,
+  /// Lists every job with its settings and last run.
+  List,
+}
+
+impl ClapArgs {
+  pub fn new() -> Self {
+    ClapArgs::parse()
+  }
+}
+
+impl Default for ClapArgs {
+  fn default() -> Self {
+    Self::new()
+  }
+}
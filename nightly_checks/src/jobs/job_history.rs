@@ -0,0 +1,52 @@
+use crate::jobs::{JobName, JobOutcome};
+use chrono::Utc;
+use entities::job_run;
+use sea_orm::*;
+
+/// Records that the job has started. The run is left unfinished until [`record_finish`] is called with it.
+pub async fn record_start(
+  job_name: JobName,
+  database_connection: &DatabaseConnection,
+) -> Result<job_run::Model, DbErr> {
+  job_run::ActiveModel {
+    job_name: Set(job_name.as_str().to_string()),
+    started_at: Set(Utc::now()),
+    ..Default::default()
+  }
+  .insert(database_connection)
+  .await
+}
+
+/// Records the end of a run. `finished` is false if the job failed partway through.
+pub async fn record_finish(
+  job_run: job_run::Model,
+  outcome: &JobOutcome,
+  finished: bool,
+  database_connection: &DatabaseConnection,
+) -> Result<job_run::Model, DbErr> {
+  let errors = (!outcome.errors.is_empty()).then(|| outcome.errors.join("\n"));
+
+  job_run::ActiveModel {
+    finished_at: Set(Some(Utc::now())),
+    succeeded: Set(Some(finished)),
+    items_processed: Set(outcome.items_processed as i64),
+    error_count: Set(outcome.errors.len() as i32),
+    errors: Set(errors),
+    ..job_run.into_active_model()
+  }
+  .update(database_connection)
+  .await
+}
+
+/// Returns the most recent run of the job, including one that's still going.
+pub async fn last_run(
+  job_name: JobName,
+  database_connection: &DatabaseConnection,
+) -> Result<Option<job_run::Model>, DbErr> {
+  job_run::Entity::find()
+    .filter(job_run::Column::JobName.eq(job_name.as_str()))
+    .order_by_desc(job_run::Column::StartedAt)
+    .order_by_desc(job_run::Column::Id)
+    .one(database_connection)
+    .await
+}
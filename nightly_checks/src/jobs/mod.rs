@@ -0,0 +1,339 @@
+use crate::checks::{
+  apply_retention_policy::ApplyRetentionPolicyJob, data_integrity::DataIntegrityJob,
+  store_exchange_rates::StoreExchangeRatesJob, update_changed_names::UpdateChangedNamesJob,
+  update_vod_data::UpdateVodDataJob,
+};
+use app_config::{AppConfig, cron_schedule::CronSchedule, nightly_job_config::NightlyJobConfig};
+use database_connection::get_database_connection;
+use sea_orm::DatabaseConnection;
+
+pub mod job_history;
+pub mod scheduler;
+
+/// A check that can be run on its own, with its runs recorded in the `job_run` table.
+pub trait NightlyJob {
+  /// Runs the job, recording how many items it processed and any errors it recovered from.
+  ///
+  /// An error is returned if the job couldn't finish.
+  async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()>;
+}
+
+/// What a job got through during a run.
+#[derive(Debug, Default)]
+pub struct JobOutcome {
+  pub items_processed: u64,
+  pub errors: Vec<String>,
+}
+
+impl JobOutcome {
+  /// Logs an error the job recovered from, keeping it for the run history.
+  pub fn record_error(&mut self, error: String) {
+    tracing::error!("{error}");
+
+    self.errors.push(error);
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum JobName {
+  UpdateChangedNames,
+  UpdateVodData,
+  ApplyRetentionPolicy,
+  StoreExchangeRates,
+  CheckDataIntegrity,
+}
+
+impl JobName {
+  /// Every job, in the order they're run.
+  pub const ALL: &[JobName] = &[
+    Self::UpdateChangedNames,
+    Self::UpdateVodData,
+    Self::ApplyRetentionPolicy,
+    Self::StoreExchangeRates,
+    Self::CheckDataIntegrity,
+  ];
+
+  /// The name used for the job in the config, on the command line, and in the run history.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::UpdateChangedNames => "update-changed-names",
+      Self::UpdateVodData => "update-vod-data",
+      Self::ApplyRetentionPolicy => "apply-retention-policy",
+      Self::StoreExchangeRates => "store-exchange-rates",
+      Self::CheckDataIntegrity => "check-data-integrity",
+    }
+  }
+
+  pub fn config(&self) -> NightlyJobConfig {
+    AppConfig::nightly_job(self.as_str())
+  }
+
+  /// The job's own schedule if it has one, otherwise the shared one.
+  pub fn schedule(&self) -> CronSchedule {
+    self
+      .config()
+      .schedule
+      .unwrap_or_else(AppConfig::nightly_checks_schedule)
+  }
+
+  /// Runs the job and records the run. Returns true if the job finished, even if it recovered from errors on the way.
+  pub async fn run(self) -> bool {
+    let config = self.config();
+
+    match self {
+      Self::UpdateChangedNames => run_job(self, UpdateChangedNamesJob::from_config(&config)).await,
+      Self::UpdateVodData => run_job(self, UpdateVodDataJob::from_config(&config)).await,
+      Self::ApplyRetentionPolicy => run_job(self, ApplyRetentionPolicyJob).await,
+      Self::StoreExchangeRates => run_job(self, StoreExchangeRatesJob).await,
+      Self::CheckDataIntegrity => run_job(self, DataIntegrityJob::from_config(&config)).await,
+    }
+  }
+}
+
+impl std::fmt::Display for JobName {
+  fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    formatter.write_str(self.as_str())
+  }
+}
+
+impl std::str::FromStr for JobName {
+  type Err = String;
+
+  fn from_str(job_name: &str) -> Result<Self, Self::Err> {
+    Self::ALL
+      .iter()
+      .find(|job| job.as_str() == job_name)
+      .copied()
+      .ok_or_else(|| format!("Unknown job `{job_name}`."))
+  }
+}
+
+/// Runs every enabled job once.
+///
+/// Returns true if every job finished.
+pub async fn run_enabled_jobs() -> bool {
+  run_jobs_if_enabled(JobName::config, JobName::run).await
+}
+
+/// Runs each job whose config has it enabled, in order. Returns true if every job that ran finished.
+async fn run_jobs_if_enabled<C, R, F>(get_config: C, mut run: R) -> bool
+where
+  C: Fn(&JobName) -> NightlyJobConfig,
+  R: FnMut(JobName) -> F,
+  F: Future<Output = bool>,
+{
+  let mut process_succeeded = true;
+
+  for job_name in JobName::ALL {
+    if !get_config(job_name).enabled {
+      tracing::info!("Skipping the disabled job `{job_name}`.");
+
+      continue;
+    }
+
+    process_succeeded &= run(*job_name).await;
+  }
+
+  process_succeeded
+}
+
+/// Warns about jobs in the config that don't exist, as their settings would otherwise be silently ignored.
+pub fn warn_about_unknown_jobs() {
+  for job_name in AppConfig::configured_nightly_jobs() {
+    if job_name.parse::<JobName>().is_err() {
+      tracing::warn!("The config has settings for the unknown job `{job_name}`. They're ignored.");
+    }
+  }
+}
+
+/// Runs the job under the given name and records the run. Returns true if the job finished.
+pub async fn run_job<J: NightlyJob>(job_name: JobName, job: J) -> bool {
+  run_and_record_job(job_name, job, get_database_connection().await).await
+}
+
+async fn run_and_record_job<J: NightlyJob>(
+  job_name: JobName,
+  job: J,
+  database_connection: &DatabaseConnection,
+) -> bool {
+  tracing::info!("Running the job `{job_name}`.");
+
+  let job_run = job_history::record_start(job_name, database_connection).await;
+  let mut outcome = JobOutcome::default();
+  let result = job.run(&mut outcome).await;
+
+  if let Err(error) = &result {
+    outcome.record_error(format!("The job `{job_name}` failed. Reason: `{error}`"));
+  }
+
+  tracing::info!(
+    "Finished the job `{job_name}`. Processed {} items with {} errors.",
+    outcome.items_processed,
+    outcome.errors.len()
+  );
+
+  let finished = result.is_ok();
+
+  match job_run {
+    Ok(job_run) => {
+      if let Err(error) =
+        job_history::record_finish(job_run, &outcome, finished, database_connection).await
+      {
+        tracing::error!("Failed to record the finished run of `{job_name}`. Reason: `{error}`");
+      }
+    }
+    Err(error) => {
+      tracing::error!("Failed to record the run of `{job_name}`. Reason: `{error}`");
+    }
+  }
+
+  finished
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+  use entities::job_run;
+  use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+  struct TestJob {
+    items_processed: u64,
+    recovered_errors: Vec<String>,
+    fails: bool,
+  }
+
+  impl NightlyJob for TestJob {
+    async fn run(&self, outcome: &mut JobOutcome) -> anyhow::Result<()> {
+      outcome.items_processed = self.items_processed;
+
+      for error in &self.recovered_errors {
+        outcome.record_error(error.clone());
+      }
+
+      if self.fails {
+        return Err(anyhow::anyhow!("Helix is down"));
+      }
+
+      Ok(())
+    }
+  }
+
+  fn job_run(id: i32, job_name: JobName) -> job_run::Model {
+    job_run::Model {
+      id,
+      job_name: job_name.as_str().into(),
+      started_at: Utc::now(),
+      finished_at: None,
+      succeeded: None,
+      items_processed: 0,
+      error_count: 0,
+      errors: None,
+    }
+  }
+
+  /// A database that takes the start and finish of each of the given job runs.
+  fn mock_database_for_runs(job_runs: &[job_run::Model]) -> DatabaseConnection {
+    let mut mock_database = MockDatabase::new(DatabaseBackend::MySql);
+
+    for job_run in job_runs {
+      mock_database = mock_database
+        .append_exec_results([MockExecResult {
+          last_insert_id: job_run.id as u64,
+          rows_affected: 1,
+        }])
+        .append_query_results([vec![job_run.clone()]])
+        .append_exec_results([MockExecResult {
+          last_insert_id: 0,
+          rows_affected: 1,
+        }])
+        .append_query_results([vec![job_run.clone()]]);
+    }
+
+    mock_database.into_connection()
+  }
+
+  fn logged_statements(database_connection: DatabaseConnection) -> Vec<String> {
+    database_connection
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn finished_runs_are_recorded_with_their_outcome() {
+    let mock_database = mock_database_for_runs(&[job_run(1, JobName::UpdateVodData)]);
+    let job = TestJob {
+      items_processed: 3,
+      recovered_errors: vec!["A VOD was missing.".into()],
+      fails: false,
+    };
+
+    let finished = run_and_record_job(JobName::UpdateVodData, job, &mock_database).await;
+    let statements = logged_statements(mock_database);
+
+    assert!(finished);
+    assert!(statements[0].starts_with("INSERT INTO `job_run`"));
+    assert!(statements[0].contains("'update-vod-data'"));
+    assert!(statements[2].starts_with("UPDATE `job_run`"));
+    assert!(statements[2].contains("`succeeded` = TRUE"));
+    assert!(statements[2].contains("`items_processed` = 3"));
+    assert!(statements[2].contains("`error_count` = 1"));
+    assert!(statements[2].contains("`errors` = 'A VOD was missing.'"));
+  }
+
+  #[tokio::test]
+  async fn failed_runs_are_recorded_as_unsuccessful() {
+    let mock_database = mock_database_for_runs(&[job_run(1, JobName::UpdateVodData)]);
+    let job = TestJob {
+      items_processed: 0,
+      recovered_errors: vec![],
+      fails: true,
+    };
+
+    let finished = run_and_record_job(JobName::UpdateVodData, job, &mock_database).await;
+    let statements = logged_statements(mock_database);
+
+    assert!(!finished);
+    assert!(statements[2].contains("`succeeded` = FALSE"));
+    assert!(statements[2].contains("`error_count` = 1"));
+    assert!(statements[2].contains("Helix is down"));
+  }
+
+  #[tokio::test]
+  async fn disabled_jobs_are_skipped() {
+    let enabled_jobs = [JobName::UpdateChangedNames, JobName::CheckDataIntegrity];
+    let mock_database = mock_database_for_runs(&[
+      job_run(1, JobName::UpdateChangedNames),
+      job_run(2, JobName::CheckDataIntegrity),
+    ]);
+
+    let process_succeeded = run_jobs_if_enabled(
+      |job_name| NightlyJobConfig {
+        enabled: enabled_jobs.contains(job_name),
+        ..Default::default()
+      },
+      |job_name| {
+        let job = TestJob {
+          items_processed: 1,
+          recovered_errors: vec![],
+          fails: false,
+        };
+
+        run_and_record_job(job_name, job, &mock_database)
+      },
+    )
+    .await;
+    let started_jobs: Vec<String> = logged_statements(mock_database)
+      .into_iter()
+      .filter(|statement| statement.starts_with("INSERT INTO `job_run`"))
+      .collect();
+
+    assert!(process_succeeded);
+    assert_eq!(started_jobs.len(), 2);
+    assert!(started_jobs[0].contains("'update-changed-names'"));
+    assert!(started_jobs[1].contains("'check-data-integrity'"));
+  }
+}
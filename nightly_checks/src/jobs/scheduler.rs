@@ -0,0 +1,54 @@
+use crate::jobs::JobName;
+use chrono::{DateTime, Utc};
+
+/// Runs the enabled jobs whenever their schedules come up, so the nightly checks don't rely on an external cron.
+///
+/// Jobs that come up together run one after another. Runs missed while another job was going are skipped rather
+/// than caught up on. Returns if none of the jobs are scheduled to run.
+pub async fn run_on_schedule() {
+  let now = Utc::now();
+  let mut next_runs: Vec<(JobName, DateTime<Utc>)> = JobName::ALL
+    .iter()
+    .filter(|job_name| job_name.config().enabled)
+    .filter_map(|job_name| next_run(*job_name, now))
+    .collect();
+
+  loop {
+    let Some(next_run_time) = next_runs.iter().map(|(_, run_time)| *run_time).min() else {
+      tracing::error!("None of the enabled jobs are scheduled to run.");
+
+      return;
+    };
+    let wait_time = (next_run_time - Utc::now()).to_std().unwrap_or_default();
+
+    tracing::info!("Waiting until {next_run_time} for the next job run.");
+
+    tokio::time::sleep(wait_time).await;
+
+    let (due_jobs, upcoming_jobs): (Vec<_>, Vec<_>) = next_runs
+      .into_iter()
+      .partition(|(_, run_time)| *run_time <= next_run_time);
+    next_runs = upcoming_jobs;
+
+    for (job_name, _) in due_jobs {
+      if !job_name.run().await {
+        tracing::error!("The scheduled run of `{job_name}` failed.");
+      }
+
+      next_runs.extend(next_run(job_name, Utc::now()));
+    }
+  }
+}
+
+fn next_run(job_name: JobName, after: DateTime<Utc>) -> Option<(JobName, DateTime<Utc>)> {
+  let schedule = job_name.schedule();
+  let Some(run_time) = schedule.next_after(after) else {
+    tracing::warn!("The schedule `{schedule}` for `{job_name}` never comes up.");
+
+    return None;
+  };
+
+  tracing::info!("`{job_name}` is next scheduled for {run_time}.");
+
+  Some((job_name, run_time))
+}
@@ -1,3 +1,6 @@
+#![allow(async_fn_in_trait)]
+
 pub mod logging;
-pub mod rate_limiter;
 pub mod checks;
+pub mod clap;
+pub mod jobs;
@@ -1,10 +1,97 @@
+use app_config::AppConfig;
+use database_connection::get_database_connection;
+use nightly_checks::checks::data_integrity::DataIntegrityJob;
+use nightly_checks::clap::{ClapArgs, JobCommand};
+use nightly_checks::jobs::{JobName, job_history, scheduler};
+
 #[tokio::main]
 async fn main() {
   nightly_checks::logging::setup_logging_config().unwrap();
 
-  let process_succeeded = nightly_checks::checks::run().await;
+  let args = ClapArgs::new();
+
+  nightly_checks::jobs::warn_about_unknown_jobs();
+
+  let process_succeeded = match args.command.unwrap_or(JobCommand::RunAll) {
+    JobCommand::List => {
+      list_jobs().await;
+
+      true
+    }
+    JobCommand::RunAll => {
+      start_token_lifecycle().await;
+
+      nightly_checks::jobs::run_enabled_jobs().await
+    }
+    JobCommand::Run { jobs } => {
+      start_token_lifecycle().await;
+
+      let mut process_succeeded = true;
+
+      for job_name in jobs {
+        process_succeeded &= job_name.run().await;
+      }
+
+      process_succeeded
+    }
+    JobCommand::CheckIntegrity { repair } => {
+      start_token_lifecycle().await;
+
+      nightly_checks::jobs::run_job(JobName::CheckDataIntegrity, DataIntegrityJob::new(repair))
+        .await
+    }
+    JobCommand::Schedule => {
+      start_token_lifecycle().await;
+      scheduler::run_on_schedule().await;
+
+      false
+    }
+  };
 
   if !process_succeeded {
     std::process::exit(1)
   }
 }
+
+/// Validates the Twitch access token and keeps it valid, exiting if it can't be validated.
+///
+/// Only needed by the commands that run jobs, so listing them works without a valid token.
+async fn start_token_lifecycle() {
+  if let Err(error) = helix_client::start_token_lifecycle(&AppConfig::channels()).await {
+    tracing::error!("Failed to validate the Twitch access token. Reason: {error}");
+
+    std::process::exit(1);
+  }
+}
+
+async fn list_jobs() {
+  let database_connection = get_database_connection().await;
+
+  for job_name in JobName::ALL {
+    let status = if job_name.config().enabled {
+      "enabled"
+    } else {
+      "disabled"
+    };
+    let last_run = match job_history::last_run(*job_name, database_connection).await {
+      Ok(Some(job_run)) => match (job_run.finished_at, job_run.succeeded) {
+        (Some(finished_at), Some(true)) => format!(
+          "finished at {finished_at}. {} items, {} errors",
+          job_run.items_processed, job_run.error_count
+        ),
+        (Some(finished_at), _) => format!(
+          "failed at {finished_at}. {} items, {} errors",
+          job_run.items_processed, job_run.error_count
+        ),
+        (None, _) => format!("started at {} and hasn't finished", job_run.started_at),
+      },
+      Ok(None) => "never run".to_string(),
+      Err(error) => format!("unknown. Reason: {error}"),
+    };
+
+    println!(
+      "{job_name}: {status}, schedule `{}`, last run {last_run}.",
+      job_name.schedule()
+    );
+  }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use database_connection::get_database_connection;
+use entities::twitch_user;
+use report_generator::clap::parse_date;
+use report_generator::conditions::query_conditions_builder::AppQueryConditionsBuilder;
+use report_generator::errors::AppError;
+use report_generator::export::format::ExportFormat;
+use report_generator::export::{export_entity, ExportEntity};
+use sea_orm::*;
+use std::path::PathBuf;
+use tokio::{fs, io::BufWriter};
+
+const EXPORTS_DIR: &str = "file_reports/exports";
+
+/// Dumps rows of an entity to a file for analysis outside of the reports.
+#[derive(Parser)]
+#[command(name = "TwitchStreamDataExport")]
+struct ExportArgs {
+  /// The entity to export. One of `stream_message`, `donation_event`, `subscription_event`, `raid`, `user_timeout`, or `emote_usage`.
+  #[arg(short = 'e', long)]
+  entity: ExportEntity,
+
+  /// The format to write. One of `csv`, `ndjson`, or `parquet`.
+  #[arg(short = 'f', long, default_value = "csv")]
+  format: ExportFormat,
+
+  /// Only exports rows from this stream.
+  #[arg(short = 's', long)]
+  stream_id: Option<i32>,
+
+  /// Only exports rows from this streamer's channel.
+  #[arg(short = 'n', long)]
+  streamer_name: Option<String>,
+
+  /// Only exports rows from this date onwards. Requires `end_date`.
+  #[arg(long, value_parser = parse_date, requires = "end_date")]
+  start_date: Option<DateTime<Utc>>,
+  /// Only exports rows up to this date. Requires `start_date`.
+  #[arg(long, value_parser = parse_date, requires = "start_date")]
+  end_date: Option<DateTime<Utc>>,
+
+  /// Where to write the export. Defaults to `file_reports/exports/{entity}.{format}`.
+  #[arg(short = 'o', long)]
+  output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+  report_generator::logging::setup_logging_config().unwrap();
+
+  let args = ExportArgs::parse();
+
+  if let Err(error) = run_export(args).await {
+    tracing::error!("Failed to export data. Reason: {:?}", error);
+
+    std::process::exit(1);
+  }
+}
+
+async fn run_export(args: ExportArgs) -> Result<(), AppError> {
+  let database_connection = get_database_connection().await;
+  let mut condition_builder = AppQueryConditionsBuilder::new();
+
+  if let Some(stream_id) = args.stream_id {
+    condition_builder = condition_builder.set_stream_id(stream_id);
+  }
+
+  if let Some(streamer_name) = &args.streamer_name {
+    let Some(streamer) = twitch_user::Entity::find()
+      .filter(twitch_user::Column::LoginName.eq(streamer_name.to_lowercase()))
+      .one(database_connection)
+      .await?
+    else {
+      return Err(AppError::FailedToFindUser(streamer_name.clone()));
+    };
+
+    condition_builder = condition_builder.set_streamer_twitch_user_id(streamer.id);
+  }
+
+  if let (Some(start_date), Some(end_date)) = (args.start_date, args.end_date) {
+    condition_builder = condition_builder.set_time_range(start_date, end_date)?;
+  }
+
+  let query_conditions = condition_builder.build()?;
+  let output_path = match args.output {
+    Some(output_path) => output_path,
+    None => PathBuf::from(EXPORTS_DIR).join(format!(
+      "{}.{}",
+      args.entity.table_name(),
+      args.format.file_extension()
+    )),
+  };
+
+  if let Some(parent) = output_path.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+
+  let mut writer = BufWriter::new(fs::File::create(&output_path).await?);
+  let rows_written = export_entity(
+    args.entity,
+    args.format,
+    &query_conditions,
+    &mut writer,
+    database_connection,
+  )
+  .await?;
+
+  println!(
+    "Exported {rows_written} rows of {} to `{}`",
+    args.entity.table_name(),
+    output_path.display()
+  );
+
+  Ok(())
+}
@@ -0,0 +1,251 @@
+//! Renders a chat replay in the JSON layout used by TwitchDownloader, which most chat render tools accept.
+
+use crate::chat_replay::{ChatReplay, ReplayMessage};
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use entities::emote;
+use entities::sea_orm_active_enums::ExternalService;
+use entity_extensions::external_service::ExternalServiceExtensions;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Serialize)]
+struct ChatReplayJson {
+  streamer: Streamer,
+  video: Video,
+  comments: Vec<Comment>,
+  /// Every emote used in the replay. Not part of the TwitchDownloader layout, so render tools will ignore it.
+  emotes: Vec<EmoteReference>,
+}
+
+#[derive(Debug, Serialize)]
+struct Streamer {
+  name: String,
+  id: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct Video {
+  id: Option<String>,
+  title: Option<String>,
+  created_at: Option<DateTime<Utc>>,
+  start: f64,
+  end: f64,
+  length: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Comment {
+  _id: String,
+  created_at: DateTime<Utc>,
+  content_offset_seconds: f64,
+  commenter: Commenter,
+  message: Message,
+}
+
+#[derive(Debug, Serialize)]
+struct Commenter {
+  display_name: String,
+  _id: String,
+  name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+  body: String,
+  bits_spent: i32,
+  fragments: Vec<Fragment>,
+  user_badges: Vec<()>,
+  user_color: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Fragment {
+  pub text: String,
+  /// Only set for Twitch emotes. Render tools look up third party emotes by name.
+  pub emoticon: Option<Emoticon>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Emoticon {
+  pub emoticon_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EmoteReference {
+  id: String,
+  name: String,
+  provider: ExternalService,
+  url: String,
+}
+
+pub fn render_json(chat_replay: &ChatReplay) -> Result<String, AppError> {
+  let length = chat_replay
+    .messages
+    .last()
+    .map(|message| message.offset)
+    .unwrap_or_default();
+  let emotes: BTreeMap<&str, &emote::Model> = chat_replay
+    .messages
+    .iter()
+    .flat_map(|message| &message.emotes)
+    .map(|emote| (emote.external_id.as_str(), emote))
+    .collect();
+
+  let chat_replay_json = ChatReplayJson {
+    streamer: Streamer {
+      name: chat_replay.channel.login_name.clone(),
+      id: chat_replay.channel.twitch_id,
+    },
+    video: Video {
+      id: chat_replay.stream.twitch_vod_id.clone(),
+      title: chat_replay.stream.title.clone(),
+      created_at: chat_replay.stream.start_timestamp,
+      start: 0.0,
+      end: length,
+      length,
+    },
+    comments: chat_replay.messages.iter().map(Comment::from).collect(),
+    emotes: emotes
+      .into_values()
+      .map(|emote| EmoteReference {
+        id: emote.external_id.clone(),
+        name: emote.name.clone(),
+        provider: emote.external_service.clone(),
+        url: emote.external_service.to_fetch_url(&emote.external_id),
+      })
+      .collect(),
+  };
+
+  serde_json::to_string(&chat_replay_json).map_err(Into::into)
+}
+
+impl From<&ReplayMessage> for Comment {
+  fn from(message: &ReplayMessage) -> Self {
+    Self {
+      _id: message.id.to_string(),
+      created_at: message.timestamp,
+      content_offset_seconds: message.offset,
+      commenter: Commenter {
+        display_name: message.user.display_name.clone(),
+        _id: message.user.twitch_id.to_string(),
+        name: message.user.login_name.clone(),
+      },
+      message: Message {
+        body: message.contents.clone(),
+        bits_spent: 0,
+        fragments: build_fragments(&message.contents, &message.emotes),
+        user_badges: vec![],
+        user_color: None,
+      },
+    }
+  }
+}
+
+/// Splits the message into runs of plain text and the Twitch emotes between them.
+pub fn build_fragments(contents: &str, emotes: &[emote::Model]) -> Vec<Fragment> {
+  let twitch_emotes: HashMap<&str, &emote::Model> = emotes
+    .iter()
+    .filter(|emote| emote.external_service == ExternalService::Twitch)
+    .map(|emote| (emote.name.as_str(), emote))
+    .collect();
+  let mut fragments = vec![];
+  let mut text = String::new();
+
+  for (index, word) in contents.split(' ').enumerate() {
+    if index > 0 {
+      text.push(' ');
+    }
+
+    let Some(emote) = twitch_emotes.get(word) else {
+      text.push_str(word);
+      continue;
+    };
+
+    if !text.is_empty() {
+      fragments.push(Fragment {
+        text: std::mem::take(&mut text),
+        emoticon: None,
+      });
+    }
+
+    fragments.push(Fragment {
+      text: word.to_string(),
+      emoticon: Some(Emoticon {
+        emoticon_id: emote.external_id.clone(),
+      }),
+    });
+  }
+
+  if !text.is_empty() {
+    fragments.push(Fragment {
+      text,
+      emoticon: None,
+    });
+  }
+
+  fragments
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn build_fragments_splits_twitch_emotes() {
+    let emotes = vec![
+      emote::Model {
+        id: 1,
+        external_id: "25".into(),
+        name: "Kappa".into(),
+        external_service: ExternalService::Twitch,
+      },
+      emote::Model {
+        id: 2,
+        external_id: "60ae958e229664e8667aea38".into(),
+        name: "catJAM".into(),
+        external_service: ExternalService::SevenTv,
+      },
+    ];
+
+    let fragments = build_fragments("hello Kappa catJAM Kappa", &emotes);
+
+    let expected_fragments = vec![
+      Fragment {
+        text: "hello ".into(),
+        emoticon: None,
+      },
+      Fragment {
+        text: "Kappa".into(),
+        emoticon: Some(Emoticon {
+          emoticon_id: "25".into(),
+        }),
+      },
+      Fragment {
+        text: " catJAM ".into(),
+        emoticon: None,
+      },
+      Fragment {
+        text: "Kappa".into(),
+        emoticon: Some(Emoticon {
+          emoticon_id: "25".into(),
+        }),
+      },
+    ];
+
+    assert_eq!(fragments, expected_fragments);
+  }
+
+  #[test]
+  fn build_fragments_without_emotes_is_single_text_fragment() {
+    let fragments = build_fragments("no emotes here", &[]);
+
+    assert_eq!(
+      fragments,
+      vec![Fragment {
+        text: "no emotes here".into(),
+        emoticon: None,
+      }]
+    );
+  }
+}
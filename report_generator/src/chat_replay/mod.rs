@@ -0,0 +1,186 @@
+pub mod json;
+pub mod subtitles;
+
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use entities::sea_orm_active_enums::ContentMode;
+use entities::{emote, emote_usage, stream, stream_message, twitch_user};
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tokio::fs;
+
+const CHAT_REPLAY_DIR: &str = "file_reports";
+
+/// A stream's chat with each message aligned to its offset in the VOD.
+#[derive(Debug, Clone)]
+pub struct ChatReplay {
+  pub stream: stream::Model,
+  pub channel: twitch_user::Model,
+  pub messages: Vec<ReplayMessage>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayMessage {
+  pub id: i32,
+  /// Seconds since the start of the stream.
+  pub offset: f64,
+  pub timestamp: DateTime<Utc>,
+  pub user: twitch_user::Model,
+  pub contents: String,
+  /// The emotes used in the message.
+  pub emotes: Vec<emote::Model>,
+}
+
+impl ChatReplay {
+  /// Loads every message sent during the stream along with its sender and emotes.
+  ///
+  /// Messages sent before the stream started are skipped since they have no place in the VOD. Hashed and redacted
+  /// messages are skipped too, as they have no text to show.
+  pub async fn from_stream(
+    stream: stream::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Self, AppError> {
+    let Some(start_timestamp) = stream.start_timestamp else {
+      return Err(AppError::MissingStreamStartTimestamp(stream.id));
+    };
+    let Some(channel) = twitch_user::Entity::find_by_id(stream.twitch_user_id)
+      .one(database_connection)
+      .await?
+    else {
+      return Err(AppError::FailedToFindStream(stream.id));
+    };
+
+    tracing::info!(
+      "Getting messages for the chat replay of stream {}.",
+      stream.id
+    );
+    let messages = stream_message::Entity::find()
+      .filter(stream_message::Column::StreamId.eq(stream.id))
+      .filter(stream_message::Column::Timestamp.gte(start_timestamp))
+      .filter(stream_message::Column::ContentMode.eq(ContentMode::Raw))
+      .order_by_asc(stream_message::Column::Timestamp)
+      .all(database_connection)
+      .await?;
+    let users = get_message_senders(&messages, database_connection).await?;
+    let mut message_emotes = get_message_emotes(&stream, database_connection).await?;
+
+    let messages = messages
+      .into_iter()
+      .filter_map(|message| {
+        let Some(user) = users.get(&message.twitch_user_id).cloned() else {
+          tracing::error!("Failed to find the sender of message {}", message.id);
+          return None;
+        };
+        let offset = (message.timestamp - start_timestamp).num_milliseconds() as f64 / 1000.0;
+
+        Some(ReplayMessage {
+          id: message.id,
+          offset,
+          timestamp: message.timestamp,
+          user,
+          contents: message.contents.unwrap_or_default(),
+          emotes: message_emotes.remove(&message.id).unwrap_or_default(),
+        })
+      })
+      .collect();
+
+    Ok(Self {
+      stream,
+      channel,
+      messages,
+    })
+  }
+}
+
+/// Writes the stream's chat replay as JSON, `.ass`, and `.srt` files.
+///
+/// Returns the paths of the written files.
+pub async fn export_chat_replay(
+  stream: stream::Model,
+  database_connection: &DatabaseConnection,
+) -> Result<Vec<PathBuf>, AppError> {
+  let chat_replay = ChatReplay::from_stream(stream, database_connection).await?;
+
+  let mut chat_replay_dir = PathBuf::from(CHAT_REPLAY_DIR);
+  chat_replay_dir.push(chat_replay.stream.id.to_string());
+  fs::create_dir_all(&chat_replay_dir).await?;
+
+  let exports = [
+    ("chat_replay.json", json::render_json(&chat_replay)?),
+    ("chat_replay.ass", subtitles::render_ass(&chat_replay)),
+    ("chat_replay.srt", subtitles::render_srt(&chat_replay)),
+  ];
+  let mut written_paths = vec![];
+
+  for (file_name, contents) in exports {
+    let file_path = chat_replay_dir.join(file_name);
+
+    fs::write(&file_path, contents).await?;
+
+    written_paths.push(file_path);
+  }
+
+  Ok(written_paths)
+}
+
+async fn get_message_senders(
+  messages: &[stream_message::Model],
+  database_connection: &DatabaseConnection,
+) -> Result<HashMap<i32, twitch_user::Model>, AppError> {
+  let user_ids: HashSet<i32> = messages
+    .iter()
+    .map(|message| message.twitch_user_id)
+    .collect();
+
+  Ok(
+    twitch_user::Entity::find()
+      .filter(twitch_user::Column::Id.is_in(user_ids))
+      .all(database_connection)
+      .await?
+      .into_iter()
+      .map(|user| (user.id, user))
+      .collect(),
+  )
+}
+
+/// Returns the emotes used in each message of the stream, keyed by message ID.
+async fn get_message_emotes(
+  stream: &stream::Model,
+  database_connection: &DatabaseConnection,
+) -> Result<HashMap<i32, Vec<emote::Model>>, AppError> {
+  let emote_usages: Vec<(i32, i32)> = emote_usage::Entity::find()
+    .join(
+      JoinType::InnerJoin,
+      emote_usage::Relation::StreamMessage.def(),
+    )
+    .filter(stream_message::Column::StreamId.eq(stream.id))
+    .select_only()
+    .column(emote_usage::Column::StreamMessageId)
+    .column(emote_usage::Column::EmoteId)
+    .into_tuple()
+    .all(database_connection)
+    .await?;
+  let emote_ids: HashSet<i32> = emote_usages.iter().map(|(_, emote_id)| *emote_id).collect();
+  let emotes: HashMap<i32, emote::Model> = emote::Entity::find()
+    .filter(emote::Column::Id.is_in(emote_ids))
+    .all(database_connection)
+    .await?
+    .into_iter()
+    .map(|emote| (emote.id, emote))
+    .collect();
+
+  Ok(emote_usages.into_iter().fold(
+    HashMap::new(),
+    |mut message_emotes, (message_id, emote_id)| {
+      if let Some(emote) = emotes.get(&emote_id) {
+        message_emotes
+          .entry(message_id)
+          .or_insert_with(Vec::new)
+          .push(emote.clone());
+      }
+
+      message_emotes
+    },
+  ))
+}
@@ -0,0 +1,208 @@
+//! Renders a chat replay as a subtitle track that can be loaded alongside the VOD.
+//!
+//! Each message starts a new cue showing the most recent messages, which stays up until the next message or
+//! until [`MAXIMUM_CUE_DURATION`] passes.
+
+use crate::chat_replay::{ChatReplay, ReplayMessage};
+use std::fmt::Write;
+
+/// The amount of messages shown on screen at once.
+const VISIBLE_MESSAGES: usize = 5;
+
+/// The longest a cue stays on screen when chat goes quiet, in seconds.
+const MAXIMUM_CUE_DURATION: f64 = 10.0;
+
+const ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+PlayResX: 1920
+PlayResY: 1080
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Chat,Arial,32,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,2,0,7,20,20,20,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+struct Cue<'a> {
+  start: f64,
+  end: f64,
+  messages: &'a [ReplayMessage],
+}
+
+pub fn render_srt(chat_replay: &ChatReplay) -> String {
+  let mut srt = String::new();
+
+  for (index, cue) in build_cues(&chat_replay.messages).into_iter().enumerate() {
+    let lines: Vec<String> = cue.messages.iter().map(format_message).collect();
+
+    let _ = writeln!(
+      srt,
+      "{}\n{} --> {}\n{}\n",
+      index + 1,
+      format_srt_timestamp(cue.start),
+      format_srt_timestamp(cue.end),
+      lines.join("\n")
+    );
+  }
+
+  srt
+}
+
+pub fn render_ass(chat_replay: &ChatReplay) -> String {
+  let mut ass = String::from(ASS_HEADER);
+
+  for cue in build_cues(&chat_replay.messages) {
+    let lines: Vec<String> = cue
+      .messages
+      .iter()
+      .map(|message| escape_ass_text(&format_message(message)))
+      .collect();
+
+    let _ = writeln!(
+      ass,
+      "Dialogue: 0,{},{},Chat,,0,0,0,,{}",
+      format_ass_timestamp(cue.start),
+      format_ass_timestamp(cue.end),
+      lines.join("\\N")
+    );
+  }
+
+  ass
+}
+
+fn build_cues(messages: &[ReplayMessage]) -> Vec<Cue<'_>> {
+  messages
+    .iter()
+    .enumerate()
+    .map(|(index, message)| {
+      let maximum_end = message.offset + MAXIMUM_CUE_DURATION;
+      let end = messages
+        .get(index + 1)
+        .map(|next_message| next_message.offset.min(maximum_end))
+        .unwrap_or(maximum_end);
+
+      Cue {
+        start: message.offset,
+        end,
+        messages: &messages[(index + 1).saturating_sub(VISIBLE_MESSAGES)..=index],
+      }
+    })
+    .filter(|cue| cue.end > cue.start)
+    .collect()
+}
+
+fn format_message(message: &ReplayMessage) -> String {
+  // Subtitle lines can't contain line breaks of their own.
+  let contents = message.contents.replace(['\r', '\n'], " ");
+
+  format!("{}: {}", message.user.display_name, contents)
+}
+
+/// Formats seconds as `HH:MM:SS,mmm`.
+fn format_srt_timestamp(seconds: f64) -> String {
+  let milliseconds = (seconds * 1000.0).round() as i64;
+
+  format!(
+    "{:02}:{:02}:{:02},{:03}",
+    milliseconds / 3_600_000,
+    (milliseconds % 3_600_000) / 60_000,
+    (milliseconds % 60_000) / 1000,
+    milliseconds % 1000
+  )
+}
+
+/// Formats seconds as `H:MM:SS.cc`.
+fn format_ass_timestamp(seconds: f64) -> String {
+  let centiseconds = (seconds * 100.0).round() as i64;
+
+  format!(
+    "{}:{:02}:{:02}.{:02}",
+    centiseconds / 360_000,
+    (centiseconds % 360_000) / 6000,
+    (centiseconds % 6000) / 100,
+    centiseconds % 100
+  )
+}
+
+/// Escapes the characters ASS would otherwise treat as override tags.
+fn escape_ass_text(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('{', "\\{")
+    .replace('}', "\\}")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+  use entities::twitch_user;
+
+  fn replay_message(offset: f64, contents: &str) -> ReplayMessage {
+    ReplayMessage {
+      id: 1,
+      offset,
+      timestamp: Utc::now(),
+      user: twitch_user::Model {
+        id: 1,
+        twitch_id: 1,
+        display_name: "Chatter".into(),
+        login_name: "chatter".into(),
+        last_verified_at: None,
+      },
+      contents: contents.into(),
+      emotes: vec![],
+    }
+  }
+
+  #[test]
+  fn format_srt_timestamp_expected_value() {
+    assert_eq!(format_srt_timestamp(3725.5), "01:02:05,500");
+    assert_eq!(format_srt_timestamp(0.0), "00:00:00,000");
+  }
+
+  #[test]
+  fn format_ass_timestamp_expected_value() {
+    assert_eq!(format_ass_timestamp(3725.5), "1:02:05.50");
+    assert_eq!(format_ass_timestamp(0.0), "0:00:00.00");
+  }
+
+  #[test]
+  fn escape_ass_text_escapes_override_tags() {
+    assert_eq!(escape_ass_text("{\\b1}hi"), "\\{\\\\b1\\}hi");
+  }
+
+  #[test]
+  fn build_cues_ends_at_next_message_or_maximum_duration() {
+    let messages = vec![
+      replay_message(0.0, "first"),
+      replay_message(2.0, "second"),
+      replay_message(30.0, "third"),
+    ];
+
+    let cues = build_cues(&messages);
+
+    assert_eq!(cues.len(), 3);
+    assert_eq!((cues[0].start, cues[0].end), (0.0, 2.0));
+    assert_eq!((cues[1].start, cues[1].end), (2.0, 12.0));
+    assert_eq!((cues[2].start, cues[2].end), (30.0, 40.0));
+    assert_eq!(cues[1].messages.len(), 2);
+  }
+
+  #[test]
+  fn build_cues_only_shows_recent_messages() {
+    let messages: Vec<ReplayMessage> = (0..8)
+      .map(|index| replay_message(index as f64, &index.to_string()))
+      .collect();
+
+    let cues = build_cues(&messages);
+    let last_cue = cues.last().unwrap();
+
+    assert_eq!(last_cue.messages.len(), VISIBLE_MESSAGES);
+    assert_eq!(last_cue.messages[0].contents, "3");
+  }
+}
@@ -99,7 +99,7 @@ impl Args {
 }
 
 /// Custom parser for to convert "yyyy-mm-dd" string to DateTime<Utc> at midnight.
-fn parse_date(s: &str) -> Result<DateTime<Utc>, String> {
+pub fn parse_date(s: &str) -> Result<DateTime<Utc>, String> {
   NaiveDate::parse_from_str(s, "%Y-%m-%d")
     .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
     .map_err(|e| e.to_string())
@@ -12,6 +12,7 @@ pub struct AppQueryConditions {
   pub subscriptions: Condition,
   pub raids: Condition,
   pub streams: Condition,
+  pub presence: Condition,
 
   pub stream_id: Option<i32>,
   pub date_start: Option<DateTime<Utc>>,
@@ -28,6 +29,7 @@ impl AppQueryConditions {
       subscriptions: Condition::all().add(subscription_event::Column::StreamId.eq(Some(stream_id))),
       raids: Condition::all().add(raid::Column::StreamId.eq(Some(stream_id))),
       streams: Condition::all().add(stream::Column::Id.eq(stream_id)),
+      presence: Condition::all().add(chatter_presence::Column::StreamId.eq(Some(stream_id))),
 
       stream_id: Some(stream_id),
       date_start: None,
@@ -64,6 +66,10 @@ impl AppQueryConditions {
         .add(stream::Column::StartTimestamp.gte(start_date))
         .add(stream::Column::EndTimestamp.gte(end_date)),
 
+      presence: Condition::all()
+        .add(chatter_presence::Column::JoinedAt.between(start_date, end_date))
+        .add(chatter_presence::Column::ChannelId.eq(streamer_twitch_user_id)),
+
       stream_id: None,
       date_start: Some(start_date),
       date_end: Some(end_date),
@@ -94,6 +100,10 @@ impl AppQueryConditions {
   pub fn streams(&self) -> &Condition {
     &self.streams
   }
+
+  pub fn presence(&self) -> &Condition {
+    &self.presence
+  }
 }
 
 /// Returns the start and end times for the given month. The current month is used if `None` is passed in.
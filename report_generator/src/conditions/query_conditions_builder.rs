@@ -155,6 +155,7 @@ impl AppQueryConditionsBuilder {
       subscriptions: self.subscription_event(),
       raids: self.raid(),
       streams: self.stream(),
+      presence: self.chatter_presence(),
 
       stream_id: self.stream_id,
       date_start: self.start_time,
@@ -198,6 +199,13 @@ impl AppQueryConditionsBuilder {
     get_user_column: TwitchUserId,
   }
 
+  generate_condition_getter! {
+    module: chatter_presence,
+    get_stream_column: StreamId,
+    get_timestamp_column: JoinedAt,
+    get_user_column: ChannelId,
+  }
+
   fn stream(&self) -> sea_orm::Condition {
     let mut condition = sea_orm::Condition::all();
 
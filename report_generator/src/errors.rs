@@ -1,5 +1,3 @@
-use reqwest::StatusCode;
-
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
   #[error("{}", .0)]
@@ -18,32 +16,19 @@ pub enum AppError {
   SerdeError(#[from] serde_json::Error),
 
   #[error("{}", .0)]
-  TeraError(#[from] tera::Error),
-
-  #[error("Failed to generate a pastebin. Reason: {:?}", .0)]
-  IncorrectPastebinResponse(String),
-
-  #[error(
-    "Could not convert currency rates. Missing API key for https://app.exchangerate-api.com/"
-  )]
-  MissingEchangeRateApiKey,
+  EntityExtensionError(#[from] entity_extensions::errors::EntityExtensionError),
 
-  #[error("Received an unknown response body structure when querying. Body location: {:?}", .0)]
-  UnknownResponseBody(&'static str),
+  #[error("{}", .0)]
+  TeraError(#[from] tera::Error),
 
-  #[error("Attempted to retrieve currency exchange rates, but received an errored response. Error code: {:?}", .0)]
-  FailedToRetrieveCurrenyExchangeRates(StatusCode),
+  #[error("{}", .0)]
+  ArrowError(#[from] arrow_schema::ArrowError),
 
-  #[error(
-    "Failed to convert currency from {} to {} because {} didn't exist.",
-    from,
-    to,
-    to
-  )]
-  FailedToFindCurrencyValueInConversionRates { from: String, to: String },
+  #[error("{}", .0)]
+  ParquetError(#[from] parquet::errors::ParquetError),
 
-  #[error("Failed to convert json number. Value: {:?}", .0)]
-  FailedToConvertJsonNumber(serde_json::Number),
+  #[error("Failed to generate a pastebin. Reason: {:?}", .0)]
+  IncorrectPastebinResponse(String),
 
   #[error("Attempted to generate a report for donation rankings with an invalid month of {:?}", .0)]
   InvalidMonthValue(i32),
@@ -74,4 +59,13 @@ pub enum AppError {
 
   #[error("Tried to generate subathon report without a subathon start time.")]
   MissingSubathonStartTime,
+
+  #[error("Stream {} has no start timestamp to align the chat replay with.", .0)]
+  MissingStreamStartTimestamp(i32),
+
+  #[error("Could not find a user with the login {:?}", .0)]
+  FailedToFindUser(String),
+
+  #[error("The export format `{}` is written in batches, not line by line.", .0)]
+  ExportFormatIsNotLineBased(&'static str),
 }
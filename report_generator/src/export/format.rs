@@ -0,0 +1,153 @@
+use crate::errors::AppError;
+use serde::Serialize;
+use serde_json::Value;
+use std::str::FromStr;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+  #[default]
+  Csv,
+  /// One JSON object per line.
+  Ndjson,
+  /// Columnar, with the column types taken from the entity. Written in row groups rather than line by line.
+  Parquet,
+}
+
+impl ExportFormat {
+  pub fn file_extension(&self) -> &'static str {
+    match self {
+      Self::Csv => "csv",
+      Self::Ndjson => "ndjson",
+      Self::Parquet => "parquet",
+    }
+  }
+
+  /// Whether rows are written one line at a time through [`header`](Self::header) and
+  /// [`format_row`](Self::format_row).
+  pub fn is_line_based(&self) -> bool {
+    !matches!(self, Self::Parquet)
+  }
+
+  /// Returns the line written before any rows, if the format has one.
+  pub fn header(&self, columns: &[String]) -> Option<String> {
+    match self {
+      Self::Csv => Some(format!(
+        "{}\n",
+        columns
+          .iter()
+          .map(|column| escape_csv_field(column))
+          .collect::<Vec<String>>()
+          .join(",")
+      )),
+      Self::Ndjson | Self::Parquet => None,
+    }
+  }
+
+  /// Formats a row as a single line, including the trailing newline.
+  ///
+  /// `columns` decides the order of the fields for CSV. JSON objects keep the field order of the model.
+  pub fn format_row<T: Serialize>(&self, row: &T, columns: &[String]) -> Result<String, AppError> {
+    match self {
+      Self::Csv => {
+        let row = serde_json::to_value(row)?;
+        let fields: Vec<String> = columns
+          .iter()
+          .map(|column| csv_field(row.get(column.as_str()).unwrap_or(&Value::Null)))
+          .collect();
+
+        Ok(format!("{}\n", fields.join(",")))
+      }
+      Self::Ndjson => Ok(format!("{}\n", serde_json::to_string(row)?)),
+      Self::Parquet => Err(AppError::ExportFormatIsNotLineBased(self.file_extension())),
+    }
+  }
+}
+
+impl FromStr for ExportFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().trim() {
+      "csv" => Ok(Self::Csv),
+      "ndjson" | "jsonl" => Ok(Self::Ndjson),
+      "parquet" => Ok(Self::Parquet),
+      _ => Err(format!("Invalid variant: {}", s)),
+    }
+  }
+}
+
+/// Converts a JSON value into a CSV field. Null values become empty fields.
+fn csv_field(value: &Value) -> String {
+  match value {
+    Value::Null => String::new(),
+    Value::String(string) => escape_csv_field(string),
+    Value::Bool(_) | Value::Number(_) => value.to_string(),
+    Value::Array(_) | Value::Object(_) => escape_csv_field(&value.to_string()),
+  }
+}
+
+/// Quotes the field if it contains characters that would break the row apart.
+fn escape_csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Serialize)]
+  struct TestRow {
+    id: i32,
+    contents: Option<String>,
+    stream_id: Option<i32>,
+  }
+
+  #[test]
+  fn escape_csv_field_expected_value() {
+    assert_eq!(escape_csv_field("plain"), "plain");
+    assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+    assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(escape_csv_field("two\nlines"), "\"two\nlines\"");
+  }
+
+  #[test]
+  fn csv_row_follows_column_order() {
+    let row = TestRow {
+      id: 1,
+      contents: Some("hello, chat".into()),
+      stream_id: None,
+    };
+    let columns = [
+      "stream_id".to_string(),
+      "contents".to_string(),
+      "id".to_string(),
+    ];
+
+    let header = ExportFormat::Csv.header(&columns);
+    let line = ExportFormat::Csv.format_row(&row, &columns).unwrap();
+
+    assert_eq!(header, Some("stream_id,contents,id\n".to_string()));
+    assert_eq!(line, ",\"hello, chat\",1\n");
+  }
+
+  #[test]
+  fn ndjson_row_is_single_line() {
+    let row = TestRow {
+      id: 1,
+      contents: Some("two\nlines".into()),
+      stream_id: Some(5),
+    };
+
+    let line = ExportFormat::Ndjson.format_row(&row, &[]).unwrap();
+
+    assert_eq!(ExportFormat::Ndjson.header(&["id".to_string()]), None);
+    assert_eq!(
+      line,
+      "{\"id\":1,\"contents\":\"two\\nlines\",\"stream_id\":5}\n"
+    );
+  }
+}
@@ -0,0 +1,171 @@
+//! Dumps raw entity rows to files for analysis outside of the reports.
+//!
+//! Rows are fetched from the database a page at a time so large exports never have to fit in memory.
+//!
+//! Messages are exported with their `content_mode`, so hashed contents can be told apart from message text.
+
+pub mod format;
+pub mod parquet_file;
+
+use crate::conditions::query_conditions::AppQueryConditions;
+use crate::errors::AppError;
+use entities::*;
+use format::ExportFormat;
+use sea_orm::*;
+use serde::Serialize;
+use std::str::FromStr;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// How many rows are fetched at once. Parquet exports write a row group per page.
+const EXPORT_PAGE_SIZE: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportEntity {
+  StreamMessage,
+  DonationEvent,
+  SubscriptionEvent,
+  Raid,
+  UserTimeout,
+  EmoteUsage,
+}
+
+impl ExportEntity {
+  pub fn table_name(&self) -> &'static str {
+    match self {
+      Self::StreamMessage => stream_message::Entity.table_name(),
+      Self::DonationEvent => donation_event::Entity.table_name(),
+      Self::SubscriptionEvent => subscription_event::Entity.table_name(),
+      Self::Raid => raid::Entity.table_name(),
+      Self::UserTimeout => user_timeout::Entity.table_name(),
+      Self::EmoteUsage => emote_usage::Entity.table_name(),
+    }
+  }
+}
+
+impl FromStr for ExportEntity {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().trim() {
+      "stream_message" => Ok(Self::StreamMessage),
+      "donation_event" => Ok(Self::DonationEvent),
+      "subscription_event" => Ok(Self::SubscriptionEvent),
+      "raid" => Ok(Self::Raid),
+      "user_timeout" => Ok(Self::UserTimeout),
+      "emote_usage" => Ok(Self::EmoteUsage),
+      _ => Err(format!("Invalid variant: {}", s)),
+    }
+  }
+}
+
+/// Writes every row of the entity matching the query conditions into the writer.
+///
+/// Emote usages are filtered by the conditions of the messages they belong to.
+///
+/// Returns the amount of rows written.
+pub async fn export_entity<W>(
+  export_entity: ExportEntity,
+  export_format: ExportFormat,
+  query_conditions: &AppQueryConditions,
+  writer: &mut W,
+  database_connection: &DatabaseConnection,
+) -> Result<usize, AppError>
+where
+  W: AsyncWrite + Unpin,
+{
+  match export_entity {
+    ExportEntity::StreamMessage => {
+      let query = stream_message::Entity::find()
+        .filter(query_conditions.messages().clone())
+        .order_by_asc(stream_message::Column::Id);
+
+      write_rows(query, export_format, writer, database_connection).await
+    }
+    ExportEntity::DonationEvent => {
+      let query = donation_event::Entity::find()
+        .filter(query_conditions.donations().clone())
+        .order_by_asc(donation_event::Column::Id);
+
+      write_rows(query, export_format, writer, database_connection).await
+    }
+    ExportEntity::SubscriptionEvent => {
+      let query = subscription_event::Entity::find()
+        .filter(query_conditions.subscriptions().clone())
+        .order_by_asc(subscription_event::Column::Id);
+
+      write_rows(query, export_format, writer, database_connection).await
+    }
+    ExportEntity::Raid => {
+      let query = raid::Entity::find()
+        .filter(query_conditions.raids().clone())
+        .order_by_asc(raid::Column::Id);
+
+      write_rows(query, export_format, writer, database_connection).await
+    }
+    ExportEntity::UserTimeout => {
+      let query = user_timeout::Entity::find()
+        .filter(query_conditions.timeouts().clone())
+        .order_by_asc(user_timeout::Column::Id);
+
+      write_rows(query, export_format, writer, database_connection).await
+    }
+    ExportEntity::EmoteUsage => {
+      let query = emote_usage::Entity::find()
+        .join(
+          JoinType::InnerJoin,
+          emote_usage::Relation::StreamMessage.def(),
+        )
+        .filter(query_conditions.messages().clone())
+        .order_by_asc(emote_usage::Column::StreamMessageId)
+        .order_by_asc(emote_usage::Column::EmoteId);
+
+      write_rows(query, export_format, writer, database_connection).await
+    }
+  }
+}
+
+/// Writes the rows of the query into the writer a page at a time.
+///
+/// The columns are written in the order the entity defines them so the schema of an export stays the same between
+/// runs.
+async fn write_rows<E, W>(
+  query: Select<E>,
+  export_format: ExportFormat,
+  writer: &mut W,
+  database_connection: &DatabaseConnection,
+) -> Result<usize, AppError>
+where
+  E: EntityTrait,
+  E::Model: Serialize + Send + Sync,
+  W: AsyncWrite + Unpin,
+{
+  if !export_format.is_line_based() {
+    return parquet_file::write_parquet_rows(query, EXPORT_PAGE_SIZE, writer, database_connection)
+      .await;
+  }
+
+  let columns: Vec<String> = E::Column::iter()
+    .map(|column| column.as_str().to_owned())
+    .collect();
+
+  if let Some(header) = export_format.header(&columns) {
+    writer.write_all(header.as_bytes()).await?;
+  }
+
+  let mut row_pages = query.paginate(database_connection, EXPORT_PAGE_SIZE);
+  let mut rows_written = 0;
+
+  while let Some(rows) = row_pages.fetch_and_next().await? {
+    for row in &rows {
+      let line = export_format.format_row(row, &columns)?;
+
+      writer.write_all(line.as_bytes()).await?;
+    }
+
+    rows_written += rows.len();
+  }
+
+  writer.flush().await?;
+
+  Ok(rows_written)
+}
@@ -0,0 +1,192 @@
+//! Writes exports as Parquet files, with a row group per page of rows.
+
+use crate::errors::AppError;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use sea_orm::*;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Builds the Arrow schema of the entity, with a field for each column in the order the entity defines them.
+pub fn entity_schema<E: EntityTrait>() -> Schema {
+  let fields: Vec<Field> = E::Column::iter()
+    .map(|column| {
+      let column_def = column.def();
+
+      Field::new(
+        column.as_str(),
+        arrow_data_type(column_def.get_column_type()),
+        column_def.is_null(),
+      )
+    })
+    .collect();
+
+  Schema::new(fields)
+}
+
+/// The Arrow type a column is written as. Types without a direct equivalent, such as enums, are written as strings.
+fn arrow_data_type(column_type: &ColumnType) -> DataType {
+  match column_type {
+    ColumnType::TinyInteger => DataType::Int8,
+    ColumnType::SmallInteger => DataType::Int16,
+    ColumnType::Integer => DataType::Int32,
+    ColumnType::BigInteger => DataType::Int64,
+    ColumnType::Float => DataType::Float32,
+    ColumnType::Double => DataType::Float64,
+    ColumnType::Boolean => DataType::Boolean,
+    ColumnType::Timestamp | ColumnType::TimestampWithTimeZone | ColumnType::DateTime => {
+      DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into()))
+    }
+    _ => DataType::Utf8,
+  }
+}
+
+/// Writes the rows of the query into the writer as a Parquet file.
+///
+/// Each page of rows becomes a row group, which is handed to the writer as soon as it's encoded.
+///
+/// Returns the amount of rows written.
+pub async fn write_parquet_rows<E, W>(
+  query: Select<E>,
+  page_size: u64,
+  writer: &mut W,
+  database_connection: &DatabaseConnection,
+) -> Result<usize, AppError>
+where
+  E: EntityTrait,
+  E::Model: Serialize + Send + Sync,
+  W: AsyncWrite + Unpin,
+{
+  let schema = Arc::new(entity_schema::<E>());
+  let writer_properties = WriterProperties::builder()
+    .set_compression(Compression::SNAPPY)
+    .build();
+  let mut parquet_writer =
+    ArrowWriter::try_new(Vec::new(), schema.clone(), Some(writer_properties))?;
+  let mut row_pages = query.paginate(database_connection, page_size);
+  let mut rows_written = 0;
+
+  while let Some(rows) = row_pages.fetch_and_next().await? {
+    let mut decoder = ReaderBuilder::new(schema.clone())
+      .with_batch_size(rows.len())
+      .with_coerce_primitive(true)
+      .build_decoder()?;
+
+    decoder.serialize(&rows)?;
+
+    if let Some(record_batch) = decoder.flush()? {
+      parquet_writer.write(&record_batch)?;
+    }
+
+    parquet_writer.flush()?;
+    writer
+      .write_all(&std::mem::take(parquet_writer.inner_mut()))
+      .await?;
+
+    rows_written += rows.len();
+  }
+
+  writer.write_all(&parquet_writer.into_inner()?).await?;
+  writer.flush().await?;
+
+  Ok(rows_written)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow_array::{Array, StringArray, TimestampMillisecondArray};
+  use chrono::{TimeZone, Utc};
+  use entities::sea_orm_active_enums::ContentMode;
+  use entities::stream_message;
+  use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+  fn message(id: i32, contents: Option<&str>) -> stream_message::Model {
+    stream_message::Model {
+      id,
+      is_first_message: 0,
+      timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, id as u32).unwrap(),
+      emote_only: 0,
+      contents: contents.map(str::to_string),
+      twitch_user_id: 1,
+      channel_id: 2,
+      stream_id: Some(3),
+      is_subscriber: Some(1),
+      origin_id: None,
+      word_count: None,
+      content_length: None,
+      content_mode: ContentMode::Raw,
+    }
+  }
+
+  #[test]
+  fn entity_schema_follows_the_column_definitions() {
+    let schema = entity_schema::<stream_message::Entity>();
+
+    assert_eq!(schema.field(0).name(), "id");
+    assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+    assert!(!schema.field(0).is_nullable());
+    assert_eq!(schema.field(1).data_type(), &DataType::Int8);
+    assert_eq!(
+      schema.field(2).data_type(),
+      &DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into()))
+    );
+    assert_eq!(schema.field(4).name(), "contents");
+    assert_eq!(schema.field(4).data_type(), &DataType::Utf8);
+    assert!(schema.field(4).is_nullable());
+  }
+
+  #[tokio::test]
+  async fn parquet_rows_can_be_read_back() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([
+        vec![message(1, Some("hello, chat")), message(2, None)],
+        vec![message(3, Some("bye"))],
+        vec![],
+      ])
+      .into_connection();
+    let mut output = vec![];
+
+    let rows_written = write_parquet_rows(
+      stream_message::Entity::find(),
+      2,
+      &mut output,
+      &mock_database,
+    )
+    .await
+    .unwrap();
+
+    let reader_builder =
+      ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(output)).unwrap();
+    let row_group_count = reader_builder.metadata().num_row_groups();
+    let reader = reader_builder.build().unwrap();
+    let record_batches: Vec<_> = reader.map(Result::unwrap).collect();
+    let rows_read: usize = record_batches.iter().map(|batch| batch.num_rows()).sum();
+    let contents = record_batches[0]
+      .column_by_name("contents")
+      .unwrap()
+      .as_any()
+      .downcast_ref::<StringArray>()
+      .unwrap();
+    let timestamps = record_batches[0]
+      .column_by_name("timestamp")
+      .unwrap()
+      .as_any()
+      .downcast_ref::<TimestampMillisecondArray>()
+      .unwrap();
+
+    assert_eq!(rows_written, 3);
+    assert_eq!(rows_read, 3);
+    assert_eq!(row_group_count, 2);
+    assert_eq!(contents.value(0), "hello, chat");
+    assert!(contents.is_null(1));
+    assert_eq!(
+      timestamps.value(0),
+      message(1, None).timestamp.timestamp_millis()
+    );
+  }
+}
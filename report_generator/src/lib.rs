@@ -1,7 +1,8 @@
+pub mod chat_replay;
 pub mod clap;
 pub mod conditions;
-pub mod currency_exchangerate;
 pub mod errors;
+pub mod export;
 pub mod logging;
 pub mod pastebin;
 pub mod query_result_models;
@@ -34,6 +34,20 @@ async fn main() {
 
       println!("Total points for subathon: `{points}`");
 
+      std::process::exit(0);
+    }
+    ChosenReport::ChatReplay => {
+      match report_generator::chat_replay::export_chat_replay(stream, database_connection).await {
+        Ok(file_paths) => {
+          for file_path in file_paths {
+            println!("Wrote the chat replay to `{}`", file_path.display());
+          }
+        }
+        Err(error) => {
+          tracing::error!("Failed to export the chat replay. Reason: {:?}", error);
+        }
+      }
+
       std::process::exit(0);
     }
   };
@@ -6,6 +6,7 @@ pub struct EmoteUsageWithContents {
   pub emote_id: i32,
   pub stream_message_id: i32,
   pub contents: Option<String>,
+  pub word_count: Option<i32>,
 }
 
 impl EmoteUsageWithContents {
@@ -20,6 +21,7 @@ impl EmoteUsageWithContents {
         sea_orm::Value::from(self.stream_message_id),
       ),
       ("contents".into(), sea_orm::Value::from(self.contents)),
+      ("word_count".into(), sea_orm::Value::from(self.word_count)),
     ])
   }
 }
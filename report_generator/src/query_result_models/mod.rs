@@ -1 +1,2 @@
 pub mod emote_usage_contents;
+pub mod viewer_count_statistics;
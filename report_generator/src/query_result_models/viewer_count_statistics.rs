@@ -0,0 +1,8 @@
+use sea_orm::entity::prelude::Decimal;
+use sea_orm::*;
+
+#[derive(Debug, FromQueryResult)]
+pub struct ViewerCountStatistics {
+  pub peak_viewers: Option<i32>,
+  pub average_viewers: Option<Decimal>,
+}
@@ -3,6 +3,7 @@ use crate::errors::AppError;
 use crate::EMOTE_DOMINANCE;
 use database_connection::get_database_connection;
 use entities::{emote_usage, stream_message, twitch_user};
+use entity_extensions::stream_message::message_word_count;
 use messages_with_word_counts::{MessageWithWordCount, UserMessages};
 use num_traits::cast::ToPrimitive;
 use ranking_table::*;
@@ -117,7 +118,7 @@ async fn calculate_rankings(
   tracing::info!("Sorting unfiltered chats sent.");
 
   unfiltered_chats_sent
-    .sort_by(|(_, lhs), (_, rhs)| rhs.all_messages.len().cmp(&lhs.all_messages.len()));
+    .sort_by_key(|(_, user_messages)| std::cmp::Reverse(user_messages.all_messages.len()));
 
   tracing::info!("Sorting and removing users with no messages from emote filtered chats sent.");
 
@@ -223,18 +224,16 @@ async fn is_emote_message(
   message: &stream_message::Model,
   database_connection: &DatabaseConnection,
 ) -> Result<Option<(usize, bool)>, AppError> {
-  let Some(contents) = &message.contents else {
+  // Hashed contents are a single word, so the stored word count is used instead.
+  let Some(word_count) = message_word_count(message) else {
     tracing::error!(
-      "Failed to get message with null contents. Message ID: {}",
+      "Failed to get the word count of message {}, as it has neither a word count nor contents.",
       message.id
     );
 
     return Ok(None);
   };
-  let word_count = contents
-    .split_whitespace()
-    .filter(|word| !word.is_empty())
-    .count() as f32;
+  let word_count = word_count as f32;
 
   let sum_usage_query = format!(
     "SELECT COALESCE(SUM({}), 0) AS total FROM {} WHERE {} = {}",
@@ -304,18 +303,21 @@ mod tests {
         twitch_id: 1,
         login_name: "user1".into(),
         display_name: "user1".into(),
+        last_verified_at: None,
       },
       twitch_user::Model {
         id: 2,
         twitch_id: 2,
         login_name: "user2".into(),
         display_name: "user2".into(),
+        last_verified_at: None,
       },
       twitch_user::Model {
         id: 3,
         twitch_id: 3,
         login_name: "user3".into(),
         display_name: "user3".into(),
+        last_verified_at: None,
       },
     ];
     let mock_database = MockDatabase::new(DatabaseBackend::MySql)
@@ -407,9 +409,9 @@ mod tests {
   fn get_fake_stream_chat_logs() -> Vec<stream_message::Model> {
     let mut first_time_message_not_subbed = generate_message(7, 3, "emote emote");
     first_time_message_not_subbed.is_first_message = 1;
-    first_time_message_not_subbed.is_subscriber = 0;
+    first_time_message_not_subbed.is_subscriber = Some(0);
     let mut second_message_not_subbed = generate_message(8, 3, "word in message");
-    second_message_not_subbed.is_subscriber = 0;
+    second_message_not_subbed.is_subscriber = Some(0);
 
     vec![
       generate_message(1, 1, "This is message"),
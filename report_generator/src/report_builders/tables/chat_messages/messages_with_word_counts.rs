@@ -24,8 +24,8 @@ impl<'a> UserMessages<'a> {
   pub fn insert_message(&mut self, message: MessageWithWordCount<'a>) {
     self.total_words_sent += message.word_count;
 
-    if message.stream_message.is_subscriber == 1 && !self.user_is_subscribed {
-      self.user_is_subscribed = message.stream_message.is_subscriber == 1;
+    if message.stream_message.is_subscriber == Some(1) && !self.user_is_subscribed {
+      self.user_is_subscribed = true;
     }
     if message.stream_message.is_first_message == 1 {
       self.first_message_sent_this_stream = message.stream_message.is_first_message == 1
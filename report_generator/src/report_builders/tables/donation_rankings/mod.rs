@@ -1,8 +1,10 @@
 use crate::errors::AppError;
+use app_config::AppConfig;
 use chrono::*;
 use database_connection::get_database_connection;
 use donator_identifier::DonatorIdentifier;
 use entities::*;
+use entity_extensions::exchange_rate::DonationCurrencyConverter;
 use sea_orm::*;
 use sea_orm_active_enums::EventType;
 use top_donators::*;
@@ -78,6 +80,12 @@ async fn get_top_donators(
     return Ok(None);
   }
 
+  let currency_converter = DonationCurrencyConverter::new(
+    &donations,
+    AppConfig::report_currency(),
+    database_connection,
+  )
+  .await?;
   let mut donators = TopDonators::default();
 
   tracing::info!("Building top donators list.");
@@ -119,12 +127,15 @@ async fn get_top_donators(
       }
 
       EventType::StreamlabsDonation => {
+        let Some(converted_amount) = currency_converter.convert_or_warn(&donation) else {
+          continue;
+        };
         let amount = donators
           .streamlabs_donations
           .entry(donator_identifier)
           .or_default();
 
-        *amount += donation.amount;
+        *amount += converted_amount;
       }
     };
   }
@@ -0,0 +1,83 @@
+use crate::{conditions::query_conditions::AppQueryConditions, errors::AppError};
+use entities::stream;
+use entity_extensions::prelude::StreamExtensions;
+use entity_extensions::stream_highlights::{HighlightOptions, StreamHighlight};
+use sea_orm::*;
+
+const HEADER: &str = "= Highlights =";
+
+/// Builds a ranked list of the moments chat spiked in each stream, linking to the VOD where possible.
+pub async fn get_highlights_table(
+  query_conditions: &AppQueryConditions,
+  database_connection: &DatabaseConnection,
+) -> Result<String, AppError> {
+  tracing::info!("Building highlights table.");
+
+  let streams = stream::Entity::find()
+    .filter(query_conditions.streams().clone())
+    .order_by_asc(stream::Column::StartTimestamp)
+    .all(database_connection)
+    .await?;
+  let highlight_options = HighlightOptions::default();
+  let mut highlights =
+    stream::Model::get_highlights_for_streams(&streams, &highlight_options, database_connection)
+      .await?;
+  let mut stream_highlight_lists = vec![];
+
+  for stream in &streams {
+    let Some(highlights) = highlights.remove(&stream.id) else {
+      continue;
+    };
+
+    let highlight_list = build_highlight_list(stream, highlights);
+
+    if streams.len() > 1 {
+      let stream_name = stream
+        .title
+        .clone()
+        .unwrap_or_else(|| stream.twitch_stream_id.to_string());
+
+      stream_highlight_lists.push(format!("{stream_name}\n{highlight_list}"));
+    } else {
+      stream_highlight_lists.push(highlight_list);
+    }
+  }
+
+  tracing::info!("Finished.");
+
+  if stream_highlight_lists.is_empty() {
+    return Ok(String::default());
+  }
+
+  Ok(format!(
+    "{HEADER}\n{}\n",
+    stream_highlight_lists.join("\n\n")
+  ))
+}
+
+fn build_highlight_list(stream: &stream::Model, highlights: Vec<StreamHighlight>) -> String {
+  highlights
+    .into_iter()
+    .enumerate()
+    .map(|(index, highlight)| {
+      let rank = index + 1;
+      let position = highlight
+        .formatted_vod_offset()
+        .unwrap_or_else(|| highlight.timestamp.format("%H:%M:%S UTC").to_string());
+      let vod_link = stream
+        .twitch_vod_id
+        .as_deref()
+        .and_then(|twitch_vod_id| highlight.vod_url(twitch_vod_id))
+        .map(|vod_url| format!(" {vod_url}"))
+        .unwrap_or_default();
+      let muted = if highlight.is_muted { " (muted)" } else { "" };
+
+      format!(
+        "{rank}: {position} - {} messages, {:.1}x the usual activity{vod_link}{muted}",
+        highlight.activity,
+        highlight.activity as f64 / highlight.baseline.max(1.0),
+      )
+    })
+    .collect::<Vec<String>>()
+    .join("\n")
+}
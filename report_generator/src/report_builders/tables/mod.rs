@@ -1,5 +1,6 @@
 pub mod chat_messages;
 pub mod donation_rankings;
+pub mod highlights;
 pub mod raids;
 pub mod timeouts;
 pub mod top_emotes;
@@ -1,7 +1,8 @@
 use crate::{conditions::query_conditions::AppQueryConditions, errors::AppError};
 use entities::{emote, emote_usage};
+use sea_orm::entity::prelude::Decimal;
+use sea_orm::sea_query::{Alias, Expr};
 use sea_orm::*;
-use std::collections::HashMap;
 
 pub async fn get_top_n_emotes_table(
   query_conditions: &AppQueryConditions,
@@ -24,45 +25,42 @@ async fn get_top_n_emotes(
   database_connection: &DatabaseConnection,
   amount: Option<usize>,
 ) -> Result<Vec<(String, usize)>, AppError> {
-  tracing::info!("Getting emotes used.");
+  tracing::info!("Getting total usage for each emote.");
 
-  let emotes_used: Vec<(emote_usage::Model, Option<emote::Model>)> = emote_usage::Entity::find()
+  let mut top_emotes_query = emote_usage::Entity::find()
     .join(
-      JoinType::LeftJoin,
+      JoinType::InnerJoin,
       emote_usage::Relation::StreamMessage.def(),
     )
+    .join(JoinType::InnerJoin, emote_usage::Relation::Emote.def())
     .filter(query_conditions.messages().clone()) // Filter for the messages wanted because it's joined
-    .find_also_related(emote::Entity)
+    .select_only()
+    .column(emote::Column::Name)
+    .column_as(
+      Expr::col((emote_usage::Entity, emote_usage::Column::UsageCount)).sum(),
+      "usage_count",
+    )
+    .group_by(emote::Column::Name)
+    .order_by_desc(Expr::col(Alias::new("usage_count")));
+
+  if let Some(amount) = amount {
+    top_emotes_query = top_emotes_query.limit(amount as u64);
+  }
+
+  // MySQL returns `SUM` over integer columns as a decimal.
+  let emote_uses: Vec<(String, Decimal)> = top_emotes_query
+    .into_tuple()
     .all(database_connection)
     .await?;
 
-  tracing::info!("Calculating total usage for each emote.");
-  let emotes_used_totals: HashMap<String, usize> =
-    emotes_used
+  Ok(
+    emote_uses
       .into_iter()
-      .fold(HashMap::new(), |mut usage_totals, (emote_usage, emote)| {
-        let Some(emote) = emote else {
-          tracing::error!(
-            "Failed to get an emote from an emote_usage relation. Message id: {} | Emote id: {}",
-            emote_usage.stream_message_id,
-            emote_usage.emote_id,
-          );
-          return usage_totals;
-        };
-
-        let entry = usage_totals.entry(emote.name).or_default();
-        *entry += emote_usage.usage_count as usize;
-
-        usage_totals
-      });
-
-  tracing::info!("Sorting emote usage by usage amount.");
-  let mut emote_uses: Vec<(String, usize)> = emotes_used_totals.into_iter().collect();
-  emote_uses.sort_by(|(_, uses_lhs), (_, uses_rhs)| uses_rhs.cmp(uses_lhs));
-
-  let amount = amount.unwrap_or(emote_uses.len());
-
-  Ok(emote_uses.into_iter().take(amount).collect())
+      .map(|(emote_name, usage_count)| {
+        (emote_name, usize::try_from(usage_count).unwrap_or_default())
+      })
+      .collect(),
+  )
 }
 
 fn build_emote_ranking_table(top_emotes: Vec<(String, usize)>) -> String {
@@ -1,12 +1,19 @@
 use crate::conditions::query_conditions::AppQueryConditions;
 use crate::errors::AppError;
 use crate::query_result_models::emote_usage_contents::EmoteUsageWithContents;
+use crate::query_result_models::viewer_count_statistics::ViewerCountStatistics;
 use crate::EMOTE_DOMINANCE;
+use app_config::AppConfig;
+use chrono::{DateTime, Utc};
 use database_connection::get_database_connection;
 use entities::sea_orm_active_enums::EventType;
 use entities::*;
+use entity_extensions::exchange_rate::DonationCurrencyConverter;
+use entity_extensions::stream_message as stream_message_extensions;
+use num_traits::cast::ToPrimitive;
+use sea_orm::sea_query::{Expr, Func, SimpleExpr};
 use sea_orm::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use subscriptions::Subscriptions;
 
 #[derive(Default, serde::Serialize)]
@@ -28,6 +35,13 @@ pub struct ChatStatistics {
   pub tier_1_gift_subs: i32,
   pub tier_2_gift_subs: i32,
   pub tier_3_gift_subs: i32,
+  /// Unique users seen through JOIN/PART membership.
+  pub chatters_present: i32,
+  /// Users that were present but never sent a message.
+  pub lurkers: i32,
+  pub peak_concurrent_chatters: i32,
+  pub peak_viewers: i32,
+  pub average_viewers: f32,
 }
 
 impl ChatStatistics {
@@ -47,6 +61,15 @@ impl ChatStatistics {
     let subscriptions = Subscriptions::new(query_conditions).await?;
     let emote_dominant_chats =
       Self::emote_dominant_chats(query_conditions, database_connection).await?;
+    tracing::info!("Gathering chatter presence.");
+    let chatter_presence = chatter_presence::Entity::find()
+      .filter(query_conditions.presence().clone())
+      .all(database_connection)
+      .await?;
+    let chatters_present = Self::chatters_present(&chatter_presence);
+    let stream_ends = Self::stream_ends(&chatter_presence, database_connection).await?;
+    let viewer_count_statistics =
+      Self::viewer_count_statistics(query_conditions, database_connection).await?;
 
     Ok(Self {
       emote_message_threshold: (EMOTE_DOMINANCE * 100.0).floor() as f64,
@@ -69,6 +92,14 @@ impl ChatStatistics {
       tier_1_gift_subs: subscriptions.tier_1_gifted,
       tier_2_gift_subs: subscriptions.tier_2_gifted,
       tier_3_gift_subs: subscriptions.tier_3_gifted,
+      chatters_present: chatters_present.len() as i32,
+      lurkers: Self::lurkers(&chatters_present, &stream_messages),
+      peak_concurrent_chatters: Self::peak_concurrent_chatters(&chatter_presence, &stream_ends),
+      peak_viewers: viewer_count_statistics.peak_viewers.unwrap_or_default(),
+      average_viewers: viewer_count_statistics
+        .average_viewers
+        .and_then(|average_viewers| average_viewers.to_f32())
+        .unwrap_or_default(),
     })
   }
 
@@ -138,6 +169,20 @@ impl ChatStatistics {
       "{total_tier_3_subs}".into(),
       (self.tier_3_subs + self.tier_3_gift_subs).to_string(),
     );
+    end_pairs.insert(
+      "{chatters_present}".into(),
+      self.chatters_present.to_string(),
+    );
+    end_pairs.insert("{lurkers}".into(), self.lurkers.to_string());
+    end_pairs.insert(
+      "{peak_concurrent_chatters}".into(),
+      self.peak_concurrent_chatters.to_string(),
+    );
+    end_pairs.insert("{peak_viewers}".into(), self.peak_viewers.to_string());
+    end_pairs.insert(
+      "{average_viewers}".into(),
+      format!("{:.2}", self.average_viewers),
+    );
 
     end_pairs
   }
@@ -151,6 +196,132 @@ impl ChatStatistics {
       .count() as i32
   }
 
+  fn chatters_present(chatter_presence: &[chatter_presence::Model]) -> HashSet<i32> {
+    chatter_presence
+      .iter()
+      .map(|presence| presence.twitch_user_id)
+      .collect()
+  }
+
+  fn lurkers(chatters_present: &HashSet<i32>, messages: &[stream_message::Model]) -> i32 {
+    tracing::info!("Calculating lurkers.");
+
+    let chatters: HashSet<i32> = messages
+      .iter()
+      .map(|message| message.twitch_user_id)
+      .collect();
+
+    chatters_present.difference(&chatters).count() as i32
+  }
+
+  /// Returns when each ended stream the presence intervals belong to ended, keyed by stream ID.
+  async fn stream_ends(
+    chatter_presence: &[chatter_presence::Model],
+    database_connection: &DatabaseConnection,
+  ) -> Result<HashMap<i32, DateTime<Utc>>, AppError> {
+    let stream_ids: HashSet<i32> = chatter_presence
+      .iter()
+      .filter_map(|presence| presence.stream_id)
+      .collect();
+
+    if stream_ids.is_empty() {
+      return Ok(HashMap::new());
+    }
+
+    let streams = stream::Entity::find()
+      .filter(stream::Column::Id.is_in(stream_ids))
+      .all(database_connection)
+      .await?;
+
+    Ok(
+      streams
+        .into_iter()
+        .filter_map(|stream| Some((stream.id, stream.end_timestamp?)))
+        .collect(),
+    )
+  }
+
+  /// Returns the highest number of presence intervals that overlapped at any point.
+  ///
+  /// Intervals are cut off at the end of their stream. Ones without a part timestamp in a stream that hasn't ended are
+  /// treated as lasting until the end of the set.
+  fn peak_concurrent_chatters(
+    chatter_presence: &[chatter_presence::Model],
+    stream_ends: &HashMap<i32, DateTime<Utc>>,
+  ) -> i32 {
+    tracing::info!("Calculating peak concurrent chatters.");
+
+    let mut changes: Vec<(DateTime<Utc>, i32)> = chatter_presence
+      .iter()
+      .filter_map(|presence| {
+        let stream_end = presence
+          .stream_id
+          .and_then(|stream_id| stream_ends.get(&stream_id))
+          .copied();
+        let parted_at = match (presence.parted_at, stream_end) {
+          (Some(parted_at), Some(stream_end)) => Some(parted_at.min(stream_end)),
+          (parted_at, stream_end) => parted_at.or(stream_end),
+        };
+
+        if parted_at.is_some_and(|parted_at| parted_at <= presence.joined_at) {
+          return None;
+        }
+
+        let part = parted_at.map(|parted_at| (parted_at, -1));
+
+        Some(std::iter::once((presence.joined_at, 1)).chain(part))
+      })
+      .flatten()
+      .collect();
+
+    // Parts are sorted before joins on the same timestamp so a rejoin doesn't count twice.
+    changes.sort();
+
+    let mut current = 0;
+    let mut peak = 0;
+
+    for (_, change) in changes {
+      current += change;
+      peak = peak.max(current);
+    }
+
+    peak
+  }
+
+  async fn viewer_count_statistics(
+    query_conditions: &AppQueryConditions,
+    database_connection: &DatabaseConnection,
+  ) -> Result<ViewerCountStatistics, AppError> {
+    tracing::info!("Calculating peak and average viewers.");
+
+    let viewer_count_statistics = stream_viewer_sample::Entity::find()
+      .join(
+        JoinType::InnerJoin,
+        stream_viewer_sample::Relation::Stream.def(),
+      )
+      .filter(query_conditions.streams().clone())
+      .select_only()
+      .column_as(
+        Expr::col(stream_viewer_sample::Column::ViewerCount).max(),
+        "peak_viewers",
+      )
+      .column_as(
+        SimpleExpr::from(Func::avg(Expr::col((
+          stream_viewer_sample::Entity,
+          stream_viewer_sample::Column::ViewerCount,
+        )))),
+        "average_viewers",
+      )
+      .into_model::<ViewerCountStatistics>()
+      .one(database_connection)
+      .await?;
+
+    Ok(viewer_count_statistics.unwrap_or(ViewerCountStatistics {
+      peak_viewers: None,
+      average_viewers: None,
+    }))
+  }
+
   async fn emote_dominant_chats(
     query_conditions: &AppQueryConditions,
     database_connection: &DatabaseConnection,
@@ -171,21 +342,28 @@ impl ChatStatistics {
         emote_usage::Column::StreamMessageId,
       ])
       .column(stream_message::Column::Contents)
+      .column(stream_message::Column::WordCount)
       .into_model::<EmoteUsageWithContents>()
       .all(database_connection)
       .await?;
 
     tracing::info!("Building messages with total emote usage.");
-    // id: (contents, total)
-    let messages_with_totals: HashMap<i32, (String, i32)> = emote_usage_with_contents
+    // id: (word count, total)
+    let messages_with_totals: HashMap<i32, (i32, i32)> = emote_usage_with_contents
       .into_iter()
       .fold(HashMap::new(), |mut end_list, emote_usage| {
-        let Some(contents) = emote_usage.contents else {
+        // Messages with redacted contents only have their stored word count.
+        let Some(word_count) = emote_usage.word_count.or_else(|| {
+          emote_usage
+            .contents
+            .as_deref()
+            .map(stream_message_extensions::count_words)
+        }) else {
           return end_list;
         };
         let entry = end_list
           .entry(emote_usage.stream_message_id)
-          .or_insert((contents, 0));
+          .or_insert((word_count, 0));
 
         entry.1 += emote_usage.usage_count;
 
@@ -196,11 +374,8 @@ impl ChatStatistics {
     Ok(
       messages_with_totals
         .into_iter()
-        .filter(|(_id, (contents, emote_usage))| {
-          let word_count = contents
-            .split_whitespace()
-            .filter(|word| !word.is_empty())
-            .count() as f32;
+        .filter(|(_id, (word_count, emote_usage))| {
+          let word_count = *word_count as f32;
           let emote_usage = *emote_usage as f32;
 
           emote_usage / word_count > EMOTE_DOMINANCE
@@ -214,8 +389,8 @@ impl ChatStatistics {
 
     messages
       .iter()
-      .filter_map(|message| Some(message.contents.as_ref()?.split(' ').count()))
-      .sum::<usize>() as f32
+      .filter_map(stream_message_extensions::message_word_count)
+      .sum::<i32>() as f32
       / messages.len() as f32
   }
 
@@ -227,16 +402,22 @@ impl ChatStatistics {
 
     let database_connection = get_database_connection().await;
 
-    let streamlabs_donation_events = donation_event::Entity::find()
+    let donation_events = donation_event::Entity::find()
       .filter(query_conditions.donations().clone())
       .filter(donation_event::Column::EventType.eq(event_type))
       .all(database_connection)
       .await?;
+    let currency_converter = DonationCurrencyConverter::new(
+      &donation_events,
+      AppConfig::report_currency(),
+      database_connection,
+    )
+    .await?;
 
     Ok(
-      streamlabs_donation_events
+      donation_events
         .iter()
-        .map(|donation| donation.amount)
+        .filter_map(|donation| currency_converter.convert_or_warn(donation))
         .sum::<f32>()
         .max(0.0),
     )
@@ -245,13 +426,17 @@ impl ChatStatistics {
   fn subscribed_chat_percentage(messages: &[stream_message::Model]) -> f32 {
     tracing::info!("Calculating subscribed chat messages to unsubscribed chat messages ratio.");
 
-    let total_chats = messages.len();
-    let subscriber_message_count = messages
+    // Messages imported without badges are left out, as it's unknown whether their sender was subscribed.
+    let known_statuses: Vec<i8> = messages
+      .iter()
+      .filter_map(|message| message.is_subscriber)
+      .collect();
+    let subscriber_message_count = known_statuses
       .iter()
-      .filter(|message| message.is_subscriber == 1)
+      .filter(|is_subscriber| **is_subscriber == 1)
       .count();
 
-    (subscriber_message_count as f32 / total_chats as f32) * 100.0
+    (subscriber_message_count as f32 / known_statuses.len() as f32) * 100.0
   }
 
   async fn get_new_subscribers(query_conditions: &AppQueryConditions) -> Result<i32, AppError> {
@@ -362,6 +547,7 @@ mod subscriptions {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::testing_helper_methods::generate_message;
 
   #[tokio::test]
   async fn emote_dominant_chats_method_returns_expected_sum() {
@@ -373,6 +559,7 @@ mod tests {
           emote_id: 1,
           stream_message_id: 1,
           contents: Some(String::from("e1, e1, e2")),
+          word_count: None,
         }
         .to_queryable_result(),
         // 100% emotes
@@ -381,6 +568,7 @@ mod tests {
           emote_id: 2,
           stream_message_id: 1,
           contents: Some(String::from("e1, e1, e2")),
+          word_count: None,
         }
         .to_queryable_result(),
         // 70% emotes
@@ -389,6 +577,7 @@ mod tests {
           emote_id: 1,
           stream_message_id: 2,
           contents: Some(String::from("e1, e1, e1, e1, e1, e1, e1, w1, w2, w3")),
+          word_count: None,
         }
         .to_queryable_result(),
         // 50% emotes
@@ -397,6 +586,7 @@ mod tests {
           emote_id: 1,
           stream_message_id: 3,
           contents: Some(String::from("e1, e1, w1, w1")),
+          word_count: None,
         }
         .to_queryable_result(),
         // 25% emotes
@@ -405,6 +595,7 @@ mod tests {
           emote_id: 2,
           stream_message_id: 4,
           contents: Some(String::from("e2, w1, w2, w3")),
+          word_count: None,
         }
         .to_queryable_result(),
       ]])
@@ -419,4 +610,100 @@ mod tests {
 
     assert_eq!(emote_dominant_chats_sum, expected_sum);
   }
+
+  #[tokio::test]
+  async fn emote_dominant_chats_uses_word_count_for_redacted_messages() {
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![
+        // 100% emotes
+        EmoteUsageWithContents {
+          usage_count: 3,
+          emote_id: 1,
+          stream_message_id: 1,
+          contents: None,
+          word_count: Some(3),
+        }
+        .to_queryable_result(),
+        // 25% emotes
+        EmoteUsageWithContents {
+          usage_count: 1,
+          emote_id: 1,
+          stream_message_id: 2,
+          contents: None,
+          word_count: Some(4),
+        }
+        .to_queryable_result(),
+      ]])
+      .into_connection();
+    let query_conditions = AppQueryConditions::from_stream_id(0);
+
+    let emote_dominant_chats_sum =
+      ChatStatistics::emote_dominant_chats(&query_conditions, &mock_database)
+        .await
+        .unwrap();
+
+    assert_eq!(emote_dominant_chats_sum, 1);
+  }
+
+  #[test]
+  fn average_word_length_uses_stored_word_count() {
+    let mut redacted_message = generate_message(1, 1, "");
+    redacted_message.contents = None;
+    redacted_message.word_count = Some(6);
+    let messages = vec![redacted_message, generate_message(2, 1, "hi chat")];
+
+    let average_words = ChatStatistics::average_word_length(&messages);
+
+    assert_eq!(average_words, 4.0);
+  }
+
+  #[test]
+  fn peak_concurrent_chatters_counts_overlapping_intervals() {
+    let base_time = Utc::now();
+    let presence =
+      |id: i32, user_id: i32, joined: i64, parted: Option<i64>| chatter_presence::Model {
+        id,
+        twitch_user_id: user_id,
+        channel_id: 1,
+        stream_id: Some(1),
+        joined_at: base_time + chrono::Duration::minutes(joined),
+        parted_at: parted.map(|parted| base_time + chrono::Duration::minutes(parted)),
+      };
+    let chatter_presence = vec![
+      presence(1, 1, 0, Some(10)),
+      presence(2, 2, 5, Some(15)),
+      presence(3, 3, 10, None),
+      presence(4, 1, 10, Some(20)),
+      presence(5, 4, 30, None),
+    ];
+
+    let peak = ChatStatistics::peak_concurrent_chatters(&chatter_presence, &HashMap::new());
+
+    assert_eq!(peak, 3);
+  }
+
+  #[test]
+  fn peak_concurrent_chatters_cuts_open_intervals_off_at_the_stream_end() {
+    let base_time = Utc::now();
+    let presence =
+      |id: i32, stream_id: i32, joined: i64, parted: Option<i64>| chatter_presence::Model {
+        id,
+        twitch_user_id: id,
+        channel_id: 1,
+        stream_id: Some(stream_id),
+        joined_at: base_time + chrono::Duration::minutes(joined),
+        parted_at: parted.map(|parted| base_time + chrono::Duration::minutes(parted)),
+      };
+    let chatter_presence = vec![
+      presence(1, 1, 0, None),
+      presence(2, 1, 5, Some(120)),
+      presence(3, 2, 70, None),
+      presence(4, 2, 75, Some(80)),
+    ];
+    let stream_ends = HashMap::from([(1, base_time + chrono::Duration::minutes(60))]);
+
+    let peak = ChatStatistics::peak_concurrent_chatters(&chatter_presence, &stream_ends);
+
+    assert_eq!(peak, 2);
+  }
 }
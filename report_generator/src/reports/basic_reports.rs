@@ -3,6 +3,7 @@ use crate::conditions::query_conditions::AppQueryConditions;
 use crate::errors::AppError;
 use crate::report_builders::tables::chat_messages::get_messages_sent_ranking;
 use crate::report_builders::tables::donation_rankings::get_donation_rankings_for_streamer_and_date;
+use crate::report_builders::tables::highlights::get_highlights_table;
 use crate::report_builders::tables::raids::get_raids_table;
 use crate::report_builders::tables::timeouts::get_timeouts_table;
 use crate::report_builders::tables::top_emotes::get_top_n_emotes_table;
@@ -65,6 +66,7 @@ async fn get_baseline_reports(
     get_top_n_emotes_table(&query_conditions, database_connection, Some(15)).await?;
   let raids = get_raids_table(&query_conditions, database_connection).await?;
   let timeouts = get_timeouts_table(&query_conditions, database_connection).await?;
+  let highlights = get_highlights_table(&query_conditions, database_connection).await?;
 
   template_renderer.add_context(ChatStatistics::NAME, &monthly_general_chat_statistics);
 
@@ -78,6 +80,7 @@ async fn get_baseline_reports(
     &[
       &raids,
       &timeouts,
+      &highlights,
       &top_emotes_table,
       &rendered_chat_statistics,
     ],
@@ -93,6 +96,7 @@ async fn get_baseline_reports(
     &[
       &raids,
       &timeouts,
+      &highlights,
       &top_emotes_table,
       &rendered_chat_statistics,
       &rendered_donation_statistics,
@@ -6,6 +6,7 @@ pub enum ChosenReport {
   Basic,
   Subathon,
   CalculateSubathonPoints,
+  ChatReplay,
 }
 
 impl FromStr for ChosenReport {
@@ -16,6 +17,7 @@ impl FromStr for ChosenReport {
       "basic" => Ok(Self::Basic),
       "subathon" => Ok(Self::Subathon),
       "calculate_subathon_points" => Ok(Self::CalculateSubathonPoints),
+      "chat_replay" => Ok(Self::ChatReplay),
       _ => Err(format!("Invalid variant: {}", s)),
     }
   }
@@ -1,5 +1,7 @@
 use chrono::{DateTime, TimeZone, Utc};
+use entities::sea_orm_active_enums::ContentMode;
 use entities::stream_message;
+use entity_extensions::stream_message::count_words;
 
 /// Creates a message with the given data, and every other value being set to 0. Except for the `is_subscribed` column which is set to true.
 pub fn generate_message(message_id: i32, user_id: i32, contents: &str) -> stream_message::Model {
@@ -12,8 +14,11 @@ pub fn generate_message(message_id: i32, user_id: i32, contents: &str) -> stream
     twitch_user_id: user_id,
     channel_id: 1,
     stream_id: None,
-    is_subscriber: 1_i8,
+    is_subscriber: Some(1_i8),
     origin_id: Some("0".into()),
+    word_count: Some(count_words(contents)),
+    content_length: Some(contents.chars().count() as i32),
+    content_mode: ContentMode::Raw,
   }
 }
 
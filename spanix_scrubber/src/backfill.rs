@@ -0,0 +1,351 @@
+use crate::log_month::{LogMonth, MonthRange};
+use crate::providers::LogProvider;
+use chrono::Utc;
+use entities::{backfill_progress, twitch_user};
+use entity_extensions::twitch_user::*;
+use irc::proto::Message as IrcMessage;
+use irc::proto::message::Tag as IrcTag;
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use twitch_chat_tracker::channel::third_party_emote_list_storage::EmoteListStorage;
+use twitch_chat_tracker::errors::AppError;
+use twitch_chat_tracker::irc_chat::message_parser::MessageParser;
+
+const LOGIN_NAME_TAG: &str = "login";
+
+/// Pulls the chat history of users in a channel from a log provider into the database.
+///
+/// Progress is stored per user and month in the `backfill_progress` table, so an interrupted backfill picks up where it
+/// left off. Only finished months are backfilled, since the logs of the current month are still growing. Months with
+/// messages that failed to store aren't marked as complete, so they're retried on the next run.
+pub struct Backfill<P: LogProvider> {
+  provider: P,
+  channel: twitch_user::Model,
+  month_range: MonthRange,
+  /// The minimum time between requests to the provider.
+  request_interval: Duration,
+  third_party_emote_lists: EmoteListStorage,
+  database_connection: &'static DatabaseConnection,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillSummary {
+  pub months_backfilled: usize,
+  pub messages_inserted: usize,
+  pub failed_messages: usize,
+  pub failed_months: usize,
+}
+
+impl<P: LogProvider> Backfill<P> {
+  pub async fn new(
+    provider: P,
+    channel_login: &str,
+    month_range: MonthRange,
+    request_interval: Duration,
+    database_connection: &'static DatabaseConnection,
+  ) -> Result<Self, AppError> {
+    let channel =
+      twitch_user::Model::get_or_set_by_name(channel_login, database_connection).await?;
+    let third_party_emote_lists = EmoteListStorage::new(
+      std::slice::from_ref(&channel.login_name),
+      database_connection,
+    )
+    .await?;
+
+    Ok(Self {
+      provider,
+      channel,
+      month_range,
+      request_interval,
+      third_party_emote_lists,
+      database_connection,
+    })
+  }
+
+  /// Backfills every month of logs for each of the users.
+  pub async fn run(&self, users: Vec<twitch_user::Model>) -> Result<BackfillSummary, AppError> {
+    let mut completed_months = self.get_completed_months().await?;
+    let mut summary = BackfillSummary::default();
+    let total_user_count = users.len();
+
+    for (iteration, user) in users.into_iter().enumerate() {
+      tracing::info!(
+        "Backfilling messages for user {} | {iteration}/{total_user_count}",
+        user.login_name
+      );
+
+      let available_months = self
+        .rate_limited(
+          self
+            .provider
+            .get_available_months(&self.channel.login_name, &user.login_name),
+        )
+        .await;
+      let available_months = match available_months {
+        Ok(available_months) => available_months,
+        Err(error) => {
+          tracing::error!(
+            "Failed to get available logs for user `{}`. Reason: {error}",
+            user.login_name
+          );
+          continue;
+        }
+      };
+      let user_completed_months = completed_months.remove(&user.id).unwrap_or_default();
+      let pending_months = get_pending_months(
+        available_months,
+        self.month_range,
+        &user_completed_months,
+        LogMonth::current(),
+      );
+
+      for month in pending_months {
+        match self.backfill_month(&user, month).await {
+          Ok((inserted, failed)) => {
+            summary.messages_inserted += inserted;
+            summary.failed_messages += failed;
+
+            if failed == 0 {
+              summary.months_backfilled += 1;
+            } else {
+              summary.failed_months += 1;
+            }
+          }
+          Err(error) => {
+            tracing::error!(
+              "Failed to backfill {month} for user `{}`. Reason: {error}",
+              user.login_name
+            );
+
+            summary.failed_months += 1;
+          }
+        }
+      }
+    }
+
+    Ok(summary)
+  }
+
+  /// Inserts the user's messages for the month, returning the amount inserted and the amount that failed.
+  ///
+  /// The month is only marked as complete if every message was stored.
+  async fn backfill_month(
+    &self,
+    user: &twitch_user::Model,
+    month: LogMonth,
+  ) -> Result<(usize, usize), AppError> {
+    tracing::info!("Getting messages on {month} for {}", user.login_name);
+
+    let messages = self
+      .rate_limited(self.provider.get_user_messages(
+        &self.channel.login_name,
+        &user.login_name,
+        month,
+      ))
+      .await?;
+    let message_count = messages.len();
+    let failed_message_count = self.process_messages(messages, &user.login_name).await;
+
+    if failed_message_count > 0 {
+      tracing::warn!(
+        "{failed_message_count} of {message_count} messages on {month} for {} failed. The month will be retried on the next run.",
+        user.login_name
+      );
+
+      return Ok((message_count - failed_message_count, failed_message_count));
+    }
+
+    backfill_progress::ActiveModel {
+      provider: Set(self.provider.name().to_owned()),
+      channel_id: Set(self.channel.id),
+      twitch_user_id: Set(user.id),
+      year: Set(month.year),
+      month: Set(month.month as i32),
+      message_count: Set(message_count as i32),
+      failed_message_count: Set(failed_message_count as i32),
+      completed_at: Set(Utc::now()),
+      ..Default::default()
+    }
+    .insert(self.database_connection)
+    .await?;
+
+    Ok((message_count - failed_message_count, failed_message_count))
+  }
+
+  /// Processes all messages for a user, returning the amount that failed.
+  async fn process_messages(&self, messages: Vec<String>, user_login: &str) -> usize {
+    let mut failed_message_count = 0;
+
+    for message in messages {
+      let mut irc_message = match message.parse::<IrcMessage>() {
+        Ok(irc_message) => irc_message,
+        Err(error) => {
+          tracing::error!("Failed to parse IRC message {message:?}. Reason: {error}");
+
+          failed_message_count += 1;
+
+          continue;
+        }
+      };
+
+      set_irc_login_tag_if_names_differ(&mut irc_message, user_login);
+
+      let message_parser = match MessageParser::new(&irc_message, &self.third_party_emote_lists) {
+        Ok(message_parser) => message_parser,
+        Err(error) => {
+          tracing::error!(
+            "Failed to create message parser for message. Message: {message:?}. Reason: {error}"
+          );
+
+          failed_message_count += 1;
+
+          continue;
+        }
+      };
+
+      let Some(message_parser) = message_parser else {
+        continue;
+      };
+
+      match message_parser.parse(self.database_connection).await {
+        Ok(_) => (),
+        // Messages stored by an earlier attempt at the month.
+        Err(error) if error.is_unique_constraint_violation() => (),
+        Err(error) => {
+          tracing::error!("Failed to parse a message for user `{user_login}`. Reason: {error}");
+
+          failed_message_count += 1;
+        }
+      }
+    }
+
+    failed_message_count
+  }
+
+  /// Returns the months already backfilled from this provider for the channel, grouped by user ID.
+  async fn get_completed_months(&self) -> Result<HashMap<i32, HashSet<LogMonth>>, AppError> {
+    let progress = backfill_progress::Entity::find()
+      .filter(backfill_progress::Column::Provider.eq(self.provider.name()))
+      .filter(backfill_progress::Column::ChannelId.eq(self.channel.id))
+      .all(self.database_connection)
+      .await?;
+
+    Ok(progress.into_iter().fold(
+      HashMap::new(),
+      |mut completed_months: HashMap<i32, HashSet<LogMonth>>, progress| {
+        if let Some(month) = LogMonth::new(progress.year, progress.month as u32) {
+          completed_months
+            .entry(progress.twitch_user_id)
+            .or_default()
+            .insert(month);
+        }
+
+        completed_months
+      },
+    ))
+  }
+
+  /// Runs the request, then waits out the rest of the request interval.
+  async fn rate_limited<T>(&self, request: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let result = request.await;
+    let run_time = start.elapsed();
+
+    if run_time < self.request_interval {
+      tokio::time::sleep(self.request_interval - run_time).await;
+    }
+
+    result
+  }
+}
+
+/// Returns the months in the range that haven't been backfilled yet, oldest first.
+///
+/// Months from `current_month` onwards are skipped since their logs aren't complete.
+pub fn get_pending_months(
+  available_months: Vec<LogMonth>,
+  month_range: MonthRange,
+  completed_months: &HashSet<LogMonth>,
+  current_month: LogMonth,
+) -> Vec<LogMonth> {
+  let mut pending_months: Vec<LogMonth> = available_months
+    .into_iter()
+    .filter(|month| {
+      *month < current_month && month_range.contains(month) && !completed_months.contains(month)
+    })
+    .collect();
+
+  pending_months.sort();
+  pending_months.dedup();
+
+  pending_months
+}
+
+/// Replaces the login tag value if the login name of the IRC message differs from the one given.
+///
+/// This is for when someone changes their username at some point.
+fn set_irc_login_tag_if_names_differ(irc_message: &mut IrcMessage, user_login: &str) {
+  let Some(tags) = &mut irc_message.tags else {
+    return;
+  };
+
+  for IrcTag(tag_name, tag_value) in tags {
+    if tag_name != LOGIN_NAME_TAG {
+      continue;
+    }
+
+    if let Some(login) = tag_value
+      && login != user_login
+    {
+      *login = user_login.to_string();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn month(year: i32, month: u32) -> LogMonth {
+    LogMonth::new(year, month).unwrap()
+  }
+
+  #[test]
+  fn get_pending_months_skips_completed_and_current_months() {
+    let available_months = vec![
+      month(2025, 4),
+      month(2025, 3),
+      month(2025, 2),
+      month(2025, 1),
+      month(2024, 12),
+    ];
+    let completed_months = HashSet::from([month(2025, 2)]);
+    let month_range = MonthRange {
+      start: Some(month(2025, 1)),
+      end: None,
+    };
+
+    let pending_months = get_pending_months(
+      available_months,
+      month_range,
+      &completed_months,
+      month(2025, 4),
+    );
+
+    assert_eq!(pending_months, vec![month(2025, 1), month(2025, 3)]);
+  }
+
+  #[test]
+  fn set_irc_login_tag_if_names_differ_replaces_login() {
+    let mut irc_message: IrcMessage =
+      "@login=oldname;tmi-sent-ts=1 :oldname!oldname@oldname.tmi.twitch.tv PRIVMSG #channel :hi"
+        .parse()
+        .unwrap();
+
+    set_irc_login_tag_if_names_differ(&mut irc_message, "newname");
+
+    let tags = irc_message.tags.unwrap();
+    assert!(tags.contains(&IrcTag("login".into(), Some("newname".into()))));
+  }
+}
@@ -1,29 +1,32 @@
+use crate::log_month::LogMonth;
+use crate::providers::justlog::SPANIX_LOGS_URL;
 use clap::Parser;
 
 #[derive(Parser, Debug)]
-#[command(name = "SpanixScrubber")]
+#[command(name = "LogBackfill")]
 pub struct ClapArgs {
   #[arg(short = 'n', long = "streamer_name", required = true)]
   pub streamer_name: String,
 
-  #[clap(flatten)]
-  pub mode: Mode,
+  /// The base URL of a justlog compatible log service.
+  #[arg(short = 'p', long, default_value = SPANIX_LOGS_URL)]
+  pub provider_url: String,
 
-  #[arg(short = 'd', long)]
-  pub data_set: Option<String>,
-}
+  /// The first month to backfill, formatted as `yyyy-mm`.
+  #[arg(long)]
+  pub start_month: Option<LogMonth>,
 
-#[derive(Parser, Debug)]
-#[clap(group(
-    clap::ArgGroup::new("mode")
-        .required(true)
-))]
-pub struct Mode {
-  #[clap(short = 's', long, group = "mode")]
-  pub scrub_data: bool,
-
-  #[clap(short = 'p', long, group = "mode")]
-  pub process_data: bool,
+  /// The last month to backfill, formatted as `yyyy-mm`. Defaults to the last finished month.
+  #[arg(long)]
+  pub end_month: Option<LogMonth>,
+
+  /// Only backfills this user instead of every user in the database.
+  #[arg(short = 'u', long = "user_login")]
+  pub user_login: Option<String>,
+
+  /// The minimum amount of milliseconds between requests to the log service.
+  #[arg(long, default_value_t = 1000)]
+  pub request_interval_ms: u64,
 }
 
 impl ClapArgs {
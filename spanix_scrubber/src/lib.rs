@@ -1,6 +1,6 @@
-pub mod clap;
-pub mod config;
-pub mod response_models;
+#![allow(async_fn_in_trait)]
 
-mod process_messages;
-mod scrub_messages;
+pub mod backfill;
+pub mod clap;
+pub mod log_month;
+pub mod providers;
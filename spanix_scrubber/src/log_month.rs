@@ -0,0 +1,105 @@
+use chrono::{Datelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// A month of logs from a log provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LogMonth {
+  pub year: i32,
+  pub month: u32,
+}
+
+impl LogMonth {
+  pub fn new(year: i32, month: u32) -> Option<Self> {
+    (1..=12).contains(&month).then_some(Self { year, month })
+  }
+
+  pub fn current() -> Self {
+    let now = Utc::now();
+
+    Self {
+      year: now.year(),
+      month: now.month(),
+    }
+  }
+}
+
+impl fmt::Display for LogMonth {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}-{:02}", self.year, self.month)
+  }
+}
+
+/// Parses `yyyy-mm`.
+impl FromStr for LogMonth {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid_month = || format!("Invalid month `{s}`. Expected `yyyy-mm`.");
+    let (year, month) = s.trim().split_once('-').ok_or_else(invalid_month)?;
+    let year = year.parse().map_err(|_| invalid_month())?;
+    let month = month.parse().map_err(|_| invalid_month())?;
+
+    Self::new(year, month).ok_or_else(invalid_month)
+  }
+}
+
+/// An inclusive range of months. Either end being None leaves that side unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonthRange {
+  pub start: Option<LogMonth>,
+  pub end: Option<LogMonth>,
+}
+
+impl MonthRange {
+  pub fn contains(&self, month: &LogMonth) -> bool {
+    self.start.is_none_or(|start| start <= *month) && self.end.is_none_or(|end| *month <= end)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn log_month_from_str_expected_value() {
+    assert_eq!(
+      "2025-03".parse(),
+      Ok(LogMonth {
+        year: 2025,
+        month: 3
+      })
+    );
+    assert!("2025-13".parse::<LogMonth>().is_err());
+    assert!("march".parse::<LogMonth>().is_err());
+  }
+
+  #[test]
+  fn month_range_contains_expected_value() {
+    let range = MonthRange {
+      start: LogMonth::new(2024, 11),
+      end: LogMonth::new(2025, 2),
+    };
+
+    assert!(range.contains(&LogMonth {
+      year: 2024,
+      month: 11
+    }));
+    assert!(range.contains(&LogMonth {
+      year: 2025,
+      month: 2
+    }));
+    assert!(!range.contains(&LogMonth {
+      year: 2024,
+      month: 10
+    }));
+    assert!(!range.contains(&LogMonth {
+      year: 2025,
+      month: 3
+    }));
+    assert!(MonthRange::default().contains(&LogMonth {
+      year: 1999,
+      month: 1
+    }));
+  }
+}
@@ -1,26 +1,53 @@
+use database_connection::get_database_connection;
+use entities::twitch_user;
+use entity_extensions::twitch_user::*;
+use sea_orm::*;
+use spanix_scrubber::backfill::Backfill;
 use spanix_scrubber::clap::ClapArgs;
-use spanix_scrubber::config::SpanixScrubberConfig;
+use spanix_scrubber::log_month::MonthRange;
+use spanix_scrubber::providers::justlog::JustlogProvider;
+use std::time::Duration;
+use twitch_chat_tracker::errors::AppError;
 
 #[tokio::main]
 async fn main() {
   twitch_chat_tracker::logging::setup_logging_config().unwrap();
 
   let args = ClapArgs::new();
-  let scrubber = SpanixScrubberConfig::new(&args.streamer_name).await;
-
-  match () {
-    _ if args.mode.scrub_data => scrubber.scrub_for_all_users_in_database_for_channel().await,
-    _ if args.mode.process_data => {
-      if let Some(data_set) = &args.data_set {
-        scrubber.insert_user_messages_into_database(data_set).await;
-      } else {
-        tracing::error!("Attempted to process data without a given data set.");
-
-        std::process::exit(1);
-      }
-    }
-    _ => {
-      unreachable!()
-    }
+
+  if let Err(error) = run_backfill(args).await {
+    tracing::error!("Failed to run the backfill. Reason: {error}");
+
+    std::process::exit(1);
   }
 }
+
+async fn run_backfill(args: ClapArgs) -> Result<(), AppError> {
+  let database_connection = get_database_connection().await;
+  let provider = JustlogProvider::new(&args.provider_url)?;
+  let month_range = MonthRange {
+    start: args.start_month,
+    end: args.end_month,
+  };
+  let backfill = Backfill::new(
+    provider,
+    &args.streamer_name,
+    month_range,
+    Duration::from_millis(args.request_interval_ms),
+    database_connection,
+  )
+  .await?;
+
+  let users = match &args.user_login {
+    Some(user_login) => {
+      vec![twitch_user::Model::get_or_set_by_name(user_login, database_connection).await?]
+    }
+    None => twitch_user::Entity::find().all(database_connection).await?,
+  };
+
+  let summary = backfill.run(users).await?;
+
+  tracing::info!("Backfill finished. {summary:?}");
+
+  Ok(())
+}
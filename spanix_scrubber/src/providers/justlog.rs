@@ -0,0 +1,257 @@
+use super::LogProvider;
+use crate::log_month::LogMonth;
+use reqwest::{StatusCode, Url};
+use twitch_chat_tracker::errors::AppError;
+
+/// The instance the backfill was originally written for.
+pub const SPANIX_LOGS_URL: &str = "https://logs.spanix.team";
+
+/// A provider for any instance of [justlog](https://github.com/gempir/justlog) or a service with the same API.
+#[derive(Debug, Clone)]
+pub struct JustlogProvider {
+  base_url: Url,
+  reqwest_client: reqwest::Client,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AvailableLogs {
+  #[serde(rename = "availableLogs")]
+  logs: Vec<LogEntry>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LogEntry {
+  year: String,
+  month: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UserMessages {
+  messages: Vec<UserMessage>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UserMessage {
+  raw: String,
+}
+
+impl JustlogProvider {
+  pub fn new(base_url: &str) -> Result<Self, AppError> {
+    let mut base_url = Url::parse(base_url)?;
+
+    // Without the trailing slash, joining paths would replace the last segment of the base URL.
+    if !base_url.path().ends_with('/') {
+      base_url.set_path(&format!("{}/", base_url.path()));
+    }
+
+    Ok(Self {
+      base_url,
+      reqwest_client: reqwest::Client::new(),
+    })
+  }
+}
+
+impl LogProvider for JustlogProvider {
+  fn name(&self) -> &str {
+    self.base_url.host_str().unwrap_or(self.base_url.as_str())
+  }
+
+  async fn get_available_months(
+    &self,
+    channel_login: &str,
+    user_login: &str,
+  ) -> Result<Vec<LogMonth>, AppError> {
+    let mut url = self.base_url.join("list")?;
+    url
+      .query_pairs_mut()
+      .append_pair("channel", channel_login)
+      .append_pair("user", user_login);
+
+    let response = self.reqwest_client.get(url).send().await?;
+    let status = response.status();
+
+    if status == StatusCode::NOT_FOUND {
+      return Ok(vec![]);
+    }
+
+    if !status.is_success() {
+      return Err(AppError::FailedResponse {
+        location: "get available logs from justlog",
+        code: status.as_u16(),
+      });
+    }
+
+    let available_logs: AvailableLogs = response.json().await?;
+
+    Ok(
+      available_logs
+        .logs
+        .into_iter()
+        .filter_map(|LogEntry { year, month }| {
+          let log_month = LogMonth::new(year.parse().ok()?, month.parse().ok()?);
+
+          if log_month.is_none() {
+            tracing::error!(
+              "Received an invalid log month from {}: {year}-{month}",
+              self.name()
+            );
+          }
+
+          log_month
+        })
+        .collect(),
+    )
+  }
+
+  async fn get_user_messages(
+    &self,
+    channel_login: &str,
+    user_login: &str,
+    month: LogMonth,
+  ) -> Result<Vec<String>, AppError> {
+    let mut url = self.base_url.join(&format!(
+      "channel/{channel_login}/user/{user_login}/{}/{}",
+      month.year, month.month
+    ))?;
+    url.query_pairs_mut().append_pair("json", "1");
+
+    let response = self.reqwest_client.get(url).send().await?;
+    let status = response.status();
+
+    if !status.is_success() {
+      return Err(AppError::FailedResponse {
+        location: "get user messages from justlog",
+        code: status.as_u16(),
+      });
+    }
+
+    let user_messages: UserMessages = response.json().await?;
+
+    Ok(
+      user_messages
+        .messages
+        .into_iter()
+        .map(|user_message| user_message.raw)
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum::extract::{Path, Query};
+  use axum::http::StatusCode as AxumStatusCode;
+  use axum::routing::get;
+  use axum::{Json, Router};
+  use serde_json::{Value, json};
+  use std::collections::HashMap;
+
+  const RAW_MESSAGE: &str = "@tmi-sent-ts=1740956922774 :linkthedot!linkthedot@linkthedot.tmi.twitch.tv PRIVMSG #fallenshadow :waaa";
+
+  async fn list_logs(
+    Query(query): Query<HashMap<String, String>>,
+  ) -> Result<Json<Value>, AxumStatusCode> {
+    if query.get("user").map(String::as_str) != Some("linkthedot") {
+      return Err(AxumStatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(json!({
+      "availableLogs": [
+        { "year": "2025", "month": "3" },
+        { "year": "2025", "month": "2" },
+        { "year": "2025", "month": "13" },
+      ]
+    })))
+  }
+
+  async fn user_logs(
+    Path((channel, user, year, month)): Path<(String, String, i32, u32)>,
+  ) -> Json<Value> {
+    assert_eq!(
+      (channel.as_str(), user.as_str()),
+      ("fallenshadow", "linkthedot")
+    );
+    assert_eq!((year, month), (2025, 3));
+
+    Json(json!({
+      "messages": [{ "text": "waaa", "raw": RAW_MESSAGE }]
+    }))
+  }
+
+  /// Serves a minimal justlog API on a random local port, returning its URL.
+  async fn start_mock_justlog_server() -> String {
+    let router = Router::new().route("/logs/list", get(list_logs)).route(
+      "/logs/channel/{channel}/user/{user}/{year}/{month}",
+      get(user_logs),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    format!("http://{address}/logs")
+  }
+
+  #[tokio::test]
+  async fn get_available_months_expected_value() {
+    let provider = JustlogProvider::new(&start_mock_justlog_server().await).unwrap();
+
+    let available_months = provider
+      .get_available_months("fallenshadow", "linkthedot")
+      .await
+      .unwrap();
+
+    assert_eq!(
+      available_months,
+      vec![
+        LogMonth {
+          year: 2025,
+          month: 3
+        },
+        LogMonth {
+          year: 2025,
+          month: 2
+        },
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn get_available_months_is_empty_for_unknown_user() {
+    let provider = JustlogProvider::new(&start_mock_justlog_server().await).unwrap();
+
+    let available_months = provider
+      .get_available_months("fallenshadow", "nobody")
+      .await
+      .unwrap();
+
+    assert!(available_months.is_empty());
+  }
+
+  #[tokio::test]
+  async fn get_user_messages_expected_value() {
+    let provider = JustlogProvider::new(&start_mock_justlog_server().await).unwrap();
+
+    let messages = provider
+      .get_user_messages(
+        "fallenshadow",
+        "linkthedot",
+        LogMonth {
+          year: 2025,
+          month: 3,
+        },
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(messages, vec![RAW_MESSAGE.to_string()]);
+  }
+
+  #[test]
+  fn name_is_host_of_base_url() {
+    let provider = JustlogProvider::new(SPANIX_LOGS_URL).unwrap();
+
+    assert_eq!(provider.name(), "logs.spanix.team");
+  }
+}
@@ -0,0 +1,25 @@
+pub mod justlog;
+
+use crate::log_month::LogMonth;
+use twitch_chat_tracker::errors::AppError;
+
+/// A service that stores chat logs per user and month.
+pub trait LogProvider {
+  /// The name progress is stored under. Changing it restarts the backfill for this provider.
+  fn name(&self) -> &str;
+
+  /// Returns the months the provider has logs for. An empty list is returned if the user has no logs.
+  async fn get_available_months(
+    &self,
+    channel_login: &str,
+    user_login: &str,
+  ) -> Result<Vec<LogMonth>, AppError>;
+
+  /// Returns the user's messages in the channel for the month as raw IRC lines.
+  async fn get_user_messages(
+    &self,
+    channel_login: &str,
+    user_login: &str,
+    month: LogMonth,
+  ) -> Result<Vec<String>, AppError>;
+}
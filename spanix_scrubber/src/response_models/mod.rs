@@ -1,2 +0,0 @@
-pub mod avaiable_logs;
-pub mod user_messages;
@@ -0,0 +1,83 @@
+use clap::Parser;
+use database_connection::get_database_connection;
+use entities::twitch_user;
+use entity_extensions::prelude::*;
+use entity_extensions::twitch_user::ChannelIdentifier;
+use entity_extensions::user_erasure::{erase_user, ErasureMode};
+use twitch_chat_tracker::errors::AppError;
+
+/// Deletes or anonymizes everything stored about a user.
+#[derive(Parser, Debug)]
+#[command(name = "TwitchUserErasure")]
+struct EraseUserArgs {
+  /// The login name of the user to erase.
+  #[arg(short = 'u', long = "user_login", conflicts_with = "twitch_id")]
+  user_login: Option<String>,
+
+  /// The Twitch ID of the user to erase.
+  #[arg(
+    short = 'i',
+    long = "twitch_id",
+    required_unless_present = "user_login"
+  )]
+  twitch_id: Option<String>,
+
+  /// Either `anonymize` to keep the user's activity without anything identifying them, or `delete` to remove it all.
+  #[arg(short = 'm', long, default_value = "anonymize")]
+  mode: ErasureMode,
+
+  /// Erasing can't be undone, so nothing is changed unless this is passed.
+  #[arg(long)]
+  confirm: bool,
+}
+
+#[tokio::main]
+async fn main() {
+  twitch_chat_tracker::logging::setup_logging_config().unwrap();
+
+  let args = EraseUserArgs::parse();
+
+  if let Err(error) = run_erasure(args).await {
+    tracing::error!("Failed to erase the user. Reason: {error}");
+
+    std::process::exit(1);
+  }
+}
+
+async fn run_erasure(args: EraseUserArgs) -> Result<(), AppError> {
+  let database_connection = get_database_connection().await;
+  let identifier = match (&args.user_login, &args.twitch_id) {
+    (Some(user_login), _) => ChannelIdentifier::Login(user_login.as_str()),
+    (None, Some(twitch_id)) => ChannelIdentifier::TwitchID(twitch_id.as_str()),
+    (None, None) => unreachable!("clap requires one of the identifiers"),
+  };
+  let Some(user) =
+    twitch_user::Model::get_by_identifier(identifier.clone(), database_connection).await?
+  else {
+    return Err(AppError::UserDoesNotExist(
+      <&str>::from(identifier).to_owned(),
+    ));
+  };
+
+  if !args.confirm {
+    println!(
+      "Would {} user `{}` (ID {}). Run again with `--confirm` to erase them.",
+      args.mode, user.login_name, user.id
+    );
+
+    return Ok(());
+  }
+
+  let summary = erase_user(user.id, args.mode, database_connection).await?;
+
+  println!(
+    "Erased user `{}` (ID {}) with mode `{}`.",
+    user.login_name, user.id, summary.mode
+  );
+
+  for (table_name, rows_affected) in summary.affected_rows {
+    println!("  {table_name}: {rows_affected} rows");
+  }
+
+  Ok(())
+}
@@ -0,0 +1,108 @@
+use chrono::{FixedOffset, NaiveDate};
+use clap::Parser;
+use database_connection::get_database_connection;
+use entities::twitch_user;
+use entity_extensions::prelude::*;
+use std::path::PathBuf;
+use tokio::fs;
+use twitch_chat_tracker::channel::third_party_emote_list_storage::EmoteListStorage;
+use twitch_chat_tracker::errors::AppError;
+use twitch_chat_tracker::importers::*;
+
+/// Imports chat logs recorded by other tools.
+#[derive(Parser, Debug)]
+#[command(name = "TwitchChatLogImporter")]
+struct ImportArgs {
+  /// The channel the logs were recorded in.
+  #[arg(short = 'n', long = "channel_name")]
+  channel_name: String,
+
+  /// The format of the logs. One of `chatterino`, `irc`, or `justlog`.
+  #[arg(short = 'f', long)]
+  format: ImportFormat,
+
+  /// The date of a Chatterino log. Taken from the file name if not given.
+  #[arg(short = 'd', long, value_parser = parse_date)]
+  date: Option<NaiveDate>,
+
+  /// The offset from UTC in hours that a Chatterino log was recorded in, such as `-5` for EST.
+  #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+  utc_offset: i32,
+
+  /// The log files to import.
+  #[arg(required = true)]
+  files: Vec<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+  twitch_chat_tracker::logging::setup_logging_config().unwrap();
+
+  let args = ImportArgs::parse();
+
+  if let Err(error) = run_import(args).await {
+    tracing::error!("Failed to import chat logs. Reason: {error}");
+
+    std::process::exit(1);
+  }
+}
+
+async fn run_import(args: ImportArgs) -> Result<(), AppError> {
+  let database_connection = get_database_connection().await;
+  let Some(utc_offset) = FixedOffset::east_opt(args.utc_offset * 3600) else {
+    return Err(AppError::FailedToParseValue {
+      value_name: "utc offset",
+      location: "chat log import",
+      value: args.utc_offset.to_string(),
+    });
+  };
+  let channel =
+    twitch_user::Model::get_or_set_by_name(&args.channel_name, database_connection).await?;
+  let third_party_emote_lists = EmoteListStorage::new(
+    std::slice::from_ref(&channel.login_name),
+    database_connection,
+  )
+  .await?;
+  let mut importer =
+    ChatLogImporter::new(channel, &third_party_emote_lists, database_connection).await?;
+  let mut total_summary = ImportSummary::default();
+
+  for file_path in &args.files {
+    let contents = fs::read_to_string(file_path).await?;
+    let messages = match args.format {
+      ImportFormat::Chatterino => {
+        let Some(date) = args
+          .date
+          .or_else(|| chatterino::date_from_file_name(file_path))
+        else {
+          tracing::error!(
+            "Could not determine the date of {file_path:?}. Pass one in with `--date`."
+          );
+          continue;
+        };
+
+        chatterino::parse_chatterino_log(&contents, date, utc_offset)
+      }
+      ImportFormat::RawIrc => raw_irc::parse_raw_irc_log(&contents),
+      ImportFormat::Justlog => justlog::parse_justlog_log(&contents)?,
+    };
+
+    let summary = importer.import(messages).await?;
+
+    println!("{file_path:?}: {summary:?}");
+
+    total_summary.imported += summary.imported;
+    total_summary.duplicates += summary.duplicates;
+    total_summary.skipped += summary.skipped;
+    total_summary.failed += summary.failed;
+  }
+
+  println!("Finished importing. {total_summary:?}");
+
+  Ok(())
+}
+
+/// Converts "yyyy-mm-dd" into a date.
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+  NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| e.to_string())
+}
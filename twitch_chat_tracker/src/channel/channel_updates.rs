@@ -0,0 +1,26 @@
+use app_config::AppConfig;
+use std::sync::LazyLock;
+use tokio::sync::watch;
+
+static TRACKED_CHANNEL_UPDATES: LazyLock<watch::Sender<Vec<String>>> = LazyLock::new(|| {
+  let (sender, _) = watch::channel(AppConfig::channels());
+
+  AppConfig::on_reload(|reloadable_settings| {
+    TRACKED_CHANNEL_UPDATES.send_if_modified(|channels| {
+      if *channels == reloadable_settings.channels {
+        return false;
+      }
+
+      *channels = reloadable_settings.channels.clone();
+
+      true
+    });
+  });
+
+  sender
+});
+
+/// Returns a receiver that's marked as changed whenever the tracked channels are changed in the config.
+pub fn watch_tracked_channels() -> watch::Receiver<Vec<String>> {
+  TRACKED_CHANNEL_UPDATES.subscribe()
+}
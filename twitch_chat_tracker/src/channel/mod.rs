@@ -1,3 +1,4 @@
+pub mod channel_updates;
 pub mod third_party_emote_list;
 pub mod third_party_emote_list_storage;
 pub mod tracked_channels;
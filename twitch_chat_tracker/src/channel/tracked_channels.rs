@@ -14,7 +14,7 @@ pub struct TrackedChannels {
 
 impl TrackedChannels {
   pub async fn new() -> Result<Self, AppError> {
-    let connected_channels = Self::get_channels_from_list(AppConfig::channels()).await?;
+    let connected_channels = Self::get_channels_from_list(&AppConfig::channels()).await?;
 
     Ok(TrackedChannels {
       channels: connected_channels,
@@ -69,8 +69,11 @@ impl TrackedChannels {
     );
 
     let channels_missing_from_database_active_models =
-      twitch_user::Model::query_helix_for_channels_from_list(&channels_missing_from_database)
-        .await?;
+      twitch_user::Model::query_helix_for_channels_from_list(
+        &channels_missing_from_database,
+        database_connection,
+      )
+      .await?;
     let _insert_result = TwitchUser::insert_many(channels_missing_from_database_active_models)
       .exec(database_connection)
       .await?;
@@ -17,6 +17,9 @@ pub enum AppError {
   #[error("{0}")]
   SerdeError(#[from] serde_json::Error),
 
+  #[error("{0}")]
+  HelixError(#[from] helix_client::HelixError),
+
   #[error("{0}")]
   EntityExtensionError(#[from] entity_extensions::errors::EntityExtensionError),
 
@@ -122,11 +125,8 @@ pub enum AppError {
     value: String,
   },
 
-  #[error(
-    "Expected `streamelements` when parsing a donation, got `{}` instead",
-    got_user
-  )]
-  IncorrectUserWhenParsingStreamlabsDonation { got_user: String },
+  #[error("Invalid donation parser for bot `{}`. {}", bot_login, reason)]
+  InvalidDonationParser { bot_login: String, reason: String },
 
   #[error(
     "Incorrect message format received at {}. Got command: {:?}",
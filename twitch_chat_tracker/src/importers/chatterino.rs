@@ -0,0 +1,135 @@
+use super::{ImportedMessage, MessageSender};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Chatterino names its log files `{channel}-{yyyy-mm-dd}.log`.
+static LOG_FILE_DATE_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"(\d{4}-\d{2}-\d{2})\.log$").unwrap());
+
+/// Retrieves the date from the name of a Chatterino log file.
+pub fn date_from_file_name<P: AsRef<Path>>(file_path: P) -> Option<NaiveDate> {
+  let file_name = file_path.as_ref().file_name()?.to_str()?;
+  let date = LOG_FILE_DATE_REGEX.captures(file_name)?.get(1)?;
+
+  NaiveDate::parse_from_str(date.as_str(), "%Y-%m-%d").ok()
+}
+
+/// Parses a Chatterino log where each message is formatted as:
+///
+/// ```text
+/// [hh:mm:ss] login_name: text content here
+/// ```
+///
+/// Chatterino logs times in the local time of whoever recorded them, so `utc_offset` is used to convert them to UTC.
+///
+/// Lines that aren't user messages, such as timeouts and the `# Start logging` lines, are skipped.
+pub fn parse_chatterino_log(
+  contents: &str,
+  date: NaiveDate,
+  utc_offset: FixedOffset,
+) -> Vec<ImportedMessage> {
+  contents
+    .lines()
+    .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+    .filter_map(|line| {
+      let message = parse_line(line, date, utc_offset);
+
+      if message.is_none() {
+        tracing::debug!("Skipping Chatterino log line {:?}", line);
+      }
+
+      message
+    })
+    .collect()
+}
+
+fn parse_line(line: &str, date: NaiveDate, utc_offset: FixedOffset) -> Option<ImportedMessage> {
+  let (timestamp, rest) = line.strip_prefix('[')?.split_once(']')?;
+  let (name, message_contents) = rest.trim_start().split_once(": ")?;
+
+  if name.contains(' ') && !name.ends_with(')') {
+    // System messages such as "user has been timed out for 10s: reason" contain spaces before the colon.
+    return None;
+  }
+
+  Some(ImportedMessage {
+    timestamp: parse_timestamp(timestamp, date, utc_offset)?,
+    sender: MessageSender::Login(extract_login(name).to_lowercase()),
+    contents: message_contents.to_owned(),
+    room_twitch_id: None,
+    twitch_emote_data: None,
+    // Chatterino doesn't log badges.
+    is_subscriber: None,
+    is_first_message: false,
+    is_emote_only: None,
+    origin_id: None,
+  })
+}
+
+fn parse_timestamp(
+  timestamp: &str,
+  date: NaiveDate,
+  utc_offset: FixedOffset,
+) -> Option<DateTime<Utc>> {
+  let time = NaiveTime::parse_from_str(timestamp, "%H:%M:%S").ok()?;
+
+  utc_offset
+    .from_local_datetime(&date.and_time(time))
+    .single()
+    .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+/// Users with localized display names are logged as `DisplayName (login)`.
+fn extract_login(name: &str) -> &str {
+  name
+    .split_once(" (")
+    .and_then(|(_, login)| login.strip_suffix(')'))
+    .unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_chatterino_log_expected_value() {
+    let log = "# Start logging at 2025-04-18 19:00:00 Eastern Daylight Time
+[19:00:05] linkthedot: hello chat
+[19:00:06] 링크 (linkthedot2): time: 7pm
+[19:00:07] someone has been timed out for 10s.
+[19:00:08] Mod: someone has been timed out for 10s: reason
+";
+    let date = NaiveDate::from_ymd_opt(2025, 4, 18).unwrap();
+    let utc_offset = FixedOffset::west_opt(4 * 3600).unwrap();
+
+    let messages = parse_chatterino_log(log, date, utc_offset);
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(
+      messages[0].timestamp,
+      Utc.with_ymd_and_hms(2025, 4, 18, 23, 0, 5).unwrap()
+    );
+    assert_eq!(
+      messages[0].sender,
+      MessageSender::Login("linkthedot".into())
+    );
+    assert_eq!(messages[0].contents, "hello chat");
+    assert_eq!(
+      messages[1].sender,
+      MessageSender::Login("linkthedot2".into())
+    );
+    assert_eq!(messages[1].contents, "time: 7pm");
+    assert_eq!(messages[2].sender, MessageSender::Login("mod".into()));
+  }
+
+  #[test]
+  fn date_from_file_name_expected_value() {
+    assert_eq!(
+      date_from_file_name("logs/Twitch/Channels/fallenshadow/fallenshadow-2025-04-18.log"),
+      NaiveDate::from_ymd_opt(2025, 4, 18)
+    );
+    assert_eq!(date_from_file_name("fallenshadow.log"), None);
+  }
+}
@@ -0,0 +1,124 @@
+use super::{ImportedMessage, MessageSender};
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The message type justlog gives to messages typed in chat.
+const PRIVMSG_MESSAGE_TYPE: i32 = 1;
+
+#[derive(Debug, Deserialize)]
+struct JustlogResponse {
+  messages: Vec<JustlogMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JustlogMessage {
+  text: String,
+  username: String,
+  timestamp: DateTime<Utc>,
+  #[serde(rename = "type")]
+  message_type: i32,
+  #[serde(default)]
+  tags: HashMap<String, String>,
+}
+
+/// Parses the JSON returned by a justlog instance when requesting logs with `?json`.
+///
+/// Only messages typed in chat are returned.
+pub fn parse_justlog_log(contents: &str) -> Result<Vec<ImportedMessage>, AppError> {
+  let response: JustlogResponse = serde_json::from_str(contents)?;
+
+  Ok(
+    response
+      .messages
+      .into_iter()
+      .filter(|message| message.message_type == PRIVMSG_MESSAGE_TYPE)
+      .map(ImportedMessage::from)
+      .collect(),
+  )
+}
+
+impl From<JustlogMessage> for ImportedMessage {
+  fn from(mut message: JustlogMessage) -> Self {
+    let mut take_tag = |tag_name: &str| {
+      message
+        .tags
+        .remove(tag_name)
+        .filter(|value| !value.is_empty())
+    };
+    let sender = match take_tag("user-id") {
+      Some(user_id) => MessageSender::TwitchId(user_id),
+      None => MessageSender::Login(message.username.to_lowercase()),
+    };
+
+    Self {
+      timestamp: message.timestamp,
+      sender,
+      contents: message.text,
+      room_twitch_id: take_tag("room-id"),
+      twitch_emote_data: take_tag("emotes"),
+      is_subscriber: Some(take_tag("subscriber").as_deref() == Some("1")),
+      is_first_message: take_tag("first-msg").as_deref() == Some("1"),
+      is_emote_only: Some(take_tag("emote-only").as_deref() == Some("1")),
+      origin_id: take_tag("source-id"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn parse_justlog_log_expected_value() {
+    let log = r#"{
+      "messages": [
+        {
+          "text": "waaa <3",
+          "username": "linkthedot",
+          "displayName": "LinkTheDot",
+          "channel": "fallenshadow",
+          "timestamp": "2025-03-02T23:08:42.774Z",
+          "id": "8d4ea5c5-f0c6-4a36-9d2d-46b4ce9e1fd9",
+          "type": 1,
+          "raw": "",
+          "tags": {
+            "emotes": "555555584:5-6",
+            "room-id": "578762718",
+            "subscriber": "0",
+            "user-id": "128831052"
+          }
+        },
+        {
+          "text": "linkthedot has been timed out for 10 seconds",
+          "username": "",
+          "displayName": "",
+          "channel": "fallenshadow",
+          "timestamp": "2025-03-02T23:09:00Z",
+          "id": "",
+          "type": 2,
+          "raw": "",
+          "tags": {}
+        }
+      ]
+    }"#;
+
+    let messages = parse_justlog_log(log).unwrap();
+
+    let expected_messages = vec![ImportedMessage {
+      timestamp: Utc.timestamp_millis_opt(1740956922774).single().unwrap(),
+      sender: MessageSender::TwitchId("128831052".into()),
+      contents: "waaa <3".into(),
+      room_twitch_id: Some("578762718".into()),
+      twitch_emote_data: Some("555555584:5-6".into()),
+      is_subscriber: Some(false),
+      is_first_message: false,
+      is_emote_only: Some(false),
+      origin_id: None,
+    }];
+
+    assert_eq!(messages, expected_messages);
+  }
+}
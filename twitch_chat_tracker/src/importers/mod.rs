@@ -0,0 +1,410 @@
+//! Imports chat logs recorded by other tools into the database.
+//!
+//! Each log format is parsed into a list of [`ImportedMessage`](ImportedMessage)s, which the
+//! [`ChatLogImporter`](ChatLogImporter) then stores the same way the tracker stores live messages.
+
+pub mod chatterino;
+pub mod justlog;
+pub mod raw_irc;
+
+use crate::channel::third_party_emote_list_storage::EmoteListStorage;
+use crate::errors::AppError;
+use crate::irc_chat::message_content::StoredMessageContents;
+use crate::irc_chat::parse_results::stream_message::ParsedStreamMessage;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use entities::*;
+use entity_extensions::errors::EntityExtensionError;
+use entity_extensions::prelude::*;
+use entity_extensions::stream_message::StreamMessageExtensions;
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// The amount of origin IDs checked against the database in a single query.
+const ORIGIN_ID_QUERY_CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+  Chatterino,
+  RawIrc,
+  Justlog,
+}
+
+impl FromStr for ImportFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().trim() {
+      "chatterino" => Ok(Self::Chatterino),
+      "irc" | "raw_irc" => Ok(Self::RawIrc),
+      "justlog" => Ok(Self::Justlog),
+      _ => Err(format!("Invalid variant: {}", s)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageSender {
+  TwitchId(String),
+  Login(String),
+}
+
+/// A chat message read from a log file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMessage {
+  pub timestamp: DateTime<Utc>,
+  pub sender: MessageSender,
+  pub contents: String,
+  /// The Twitch ID of the channel the message was sent in, if the log contains it.
+  pub room_twitch_id: Option<String>,
+  /// Formatted as `emote_id:0-1,2-3/` as Twitch's IRC tags provide it.
+  pub twitch_emote_data: Option<String>,
+  /// None if the log doesn't record badges.
+  pub is_subscriber: Option<bool>,
+  pub is_first_message: bool,
+  /// None if the log doesn't say, in which case it's determined from the third party emotes in the message.
+  pub is_emote_only: Option<bool>,
+  pub origin_id: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+  pub imported: usize,
+  /// Messages that were already in the database.
+  pub duplicates: usize,
+  /// Messages sent in a different channel than the one being imported.
+  pub skipped: usize,
+  pub failed: usize,
+}
+
+pub struct ChatLogImporter<'a> {
+  channel: twitch_user::Model,
+  /// Every stream of the channel, used to attach messages to the stream they were sent during.
+  streams: Vec<stream::Model>,
+  users: HashMap<MessageSender, twitch_user::Model>,
+  third_party_emote_lists: &'a EmoteListStorage,
+  database_connection: &'a DatabaseConnection,
+}
+
+impl<'a> ChatLogImporter<'a> {
+  pub async fn new(
+    channel: twitch_user::Model,
+    third_party_emote_lists: &'a EmoteListStorage,
+    database_connection: &'a DatabaseConnection,
+  ) -> Result<Self, AppError> {
+    let streams = stream::Entity::find()
+      .filter(stream::Column::TwitchUserId.eq(channel.id))
+      .order_by_asc(stream::Column::StartTimestamp)
+      .all(database_connection)
+      .await?;
+
+    Ok(Self {
+      channel,
+      streams,
+      users: HashMap::new(),
+      third_party_emote_lists,
+      database_connection,
+    })
+  }
+
+  /// Stores every message that isn't already in the database.
+  ///
+  /// Messages are deduplicated by their origin ID, or by sender and the second they were sent in when the message has
+  /// no origin ID.
+  pub async fn import(
+    &mut self,
+    messages: Vec<ImportedMessage>,
+  ) -> Result<ImportSummary, AppError> {
+    let mut summary = ImportSummary::default();
+    let mut known_origin_ids = self.get_existing_origin_ids(&messages).await?;
+
+    self.load_login_senders(&messages).await?;
+
+    tracing::info!(
+      "Importing {} messages into channel {}.",
+      messages.len(),
+      self.channel.login_name
+    );
+
+    for message in messages {
+      if message
+        .room_twitch_id
+        .as_ref()
+        .is_some_and(|room_twitch_id| *room_twitch_id != self.channel.twitch_id.to_string())
+      {
+        summary.skipped += 1;
+        continue;
+      }
+
+      if let Some(origin_id) = &message.origin_id {
+        if !known_origin_ids.insert(origin_id.clone()) {
+          summary.duplicates += 1;
+          continue;
+        }
+      }
+
+      match self.import_message(&message).await {
+        Ok(true) => summary.imported += 1,
+        Ok(false) => summary.duplicates += 1,
+        Err(error) => {
+          tracing::error!(
+            "Failed to import a message. Message: {:?}. Reason: {error}",
+            message
+          );
+
+          summary.failed += 1;
+        }
+      }
+    }
+
+    Ok(summary)
+  }
+
+  /// Returns false if the message was already stored.
+  async fn import_message(&mut self, message: &ImportedMessage) -> Result<bool, AppError> {
+    let sender = self.get_sender(&message.sender).await?;
+
+    if self.message_exists(message, &sender).await? {
+      return Ok(false);
+    }
+
+    let stored_contents = StoredMessageContents::from_config(&message.contents);
+
+    let is_emote_only = message
+      .is_emote_only
+      .unwrap_or_else(|| self.is_third_party_emote_only(&message.contents));
+    let message_active_model = stream_message::ActiveModel {
+      is_first_message: Set(message.is_first_message as i8),
+      timestamp: Set(message.timestamp),
+      emote_only: Set(is_emote_only as i8),
+      contents: Set(stored_contents.contents),
+      word_count: Set(Some(stored_contents.word_count)),
+      content_length: Set(Some(stored_contents.content_length)),
+      content_mode: Set(stored_contents.content_mode),
+      twitch_user_id: Set(sender.id),
+      channel_id: Set(self.channel.id),
+      stream_id: Set(find_stream_for_timestamp(&self.streams, message.timestamp)),
+      is_subscriber: Set(
+        message
+          .is_subscriber
+          .map(|is_subscriber| is_subscriber as i8),
+      ),
+      origin_id: Set(message.origin_id.clone()),
+      ..Default::default()
+    };
+
+    let parsed_stream_message = ParsedStreamMessage::new(
+      message_active_model,
+      &message.contents,
+      message.twitch_emote_data.as_deref().unwrap_or(""),
+      self.channel.clone(),
+    )
+    .insert_message(self.database_connection)
+    .await?;
+    let message_emote_usage = parsed_stream_message
+      .parse_emote_usage(self.third_party_emote_lists, self.database_connection)
+      .await?;
+
+    if !message_emote_usage.is_empty() {
+      stream_message::Model::insert_many_emote_usages(
+        message_emote_usage,
+        self.database_connection,
+      )
+      .await?;
+    }
+
+    Ok(true)
+  }
+
+  /// Looks up every sender only known by their login at once, in batches of up to 100 per Helix request.
+  ///
+  /// Logs from big channels have thousands of chatters, so looking each one up as they're reached would send a Helix
+  /// request per new chatter.
+  async fn load_login_senders(&mut self, messages: &[ImportedMessage]) -> Result<(), AppError> {
+    let logins: HashSet<&str> = messages
+      .iter()
+      .filter(|message| !self.users.contains_key(&message.sender))
+      .filter_map(|message| match &message.sender {
+        MessageSender::Login(login) => Some(login.as_str()),
+        MessageSender::TwitchId(_) => None,
+      })
+      .collect();
+
+    if logins.is_empty() {
+      return Ok(());
+    }
+
+    let logins: Vec<&str> = logins.into_iter().collect();
+    let users = twitch_user::Model::get_or_set_by_names(&logins, self.database_connection).await?;
+
+    for user in users {
+      self
+        .users
+        .insert(MessageSender::Login(user.login_name.clone()), user);
+    }
+
+    Ok(())
+  }
+
+  async fn get_sender(&mut self, sender: &MessageSender) -> Result<twitch_user::Model, AppError> {
+    if let Some(user) = self.users.get(sender) {
+      return Ok(user.clone());
+    }
+
+    let user = match sender {
+      MessageSender::TwitchId(twitch_id) => {
+        twitch_user::Model::get_or_set_by_twitch_id(twitch_id, self.database_connection).await?
+      }
+      // Senders known by their login were all looked up before importing, so Helix doesn't have this one.
+      MessageSender::Login(login) => {
+        return Err(
+          EntityExtensionError::FailedToGetValue {
+            value_name: "user",
+            location: "ChatLogImporter::get_sender",
+            additional_data: login.clone(),
+          }
+          .into(),
+        );
+      }
+    };
+
+    self.users.insert(sender.clone(), user.clone());
+
+    Ok(user)
+  }
+
+  /// Checks for a message from the same user in the channel sent within the same second.
+  ///
+  /// Some logs only store timestamps to the second, so this is the closest match available. The lookup is covered by
+  /// the `(channel_id, twitch_user_id, timestamp)` index on `stream_message`.
+  async fn message_exists(
+    &self,
+    message: &ImportedMessage,
+    sender: &twitch_user::Model,
+  ) -> Result<bool, AppError> {
+    let second_start = message
+      .timestamp
+      .duration_trunc(Duration::seconds(1))
+      .unwrap_or(message.timestamp);
+    let second_end = second_start + Duration::seconds(1);
+
+    let existing_message_count = stream_message::Entity::find()
+      .filter(stream_message::Column::ChannelId.eq(self.channel.id))
+      .filter(stream_message::Column::TwitchUserId.eq(sender.id))
+      .filter(stream_message::Column::Timestamp.gte(second_start))
+      .filter(stream_message::Column::Timestamp.lt(second_end))
+      .count(self.database_connection)
+      .await?;
+
+    Ok(existing_message_count > 0)
+  }
+
+  async fn get_existing_origin_ids(
+    &self,
+    messages: &[ImportedMessage],
+  ) -> Result<HashSet<String>, AppError> {
+    let origin_ids: Vec<&str> = messages
+      .iter()
+      .filter_map(|message| message.origin_id.as_deref())
+      .collect();
+    let mut existing_origin_ids = HashSet::new();
+
+    for origin_id_chunk in origin_ids.chunks(ORIGIN_ID_QUERY_CHUNK_SIZE) {
+      let existing_chunk: Vec<Option<String>> = stream_message::Entity::find()
+        .select_only()
+        .column(stream_message::Column::OriginId)
+        .filter(stream_message::Column::OriginId.is_in(origin_id_chunk.iter().copied()))
+        .into_tuple()
+        .all(self.database_connection)
+        .await?;
+
+      existing_origin_ids.extend(existing_chunk.into_iter().flatten());
+    }
+
+    Ok(existing_origin_ids)
+  }
+
+  fn is_third_party_emote_only(&self, contents: &str) -> bool {
+    !contents.trim().is_empty()
+      && contents.split_whitespace().all(|word| {
+        self
+          .third_party_emote_lists
+          .get_channel_emote(&self.channel, word)
+          .is_some()
+      })
+  }
+}
+
+/// Returns the ID of the stream that was live at the given time.
+///
+/// Streams without an end timestamp are treated as still live.
+pub fn find_stream_for_timestamp(
+  streams: &[stream::Model],
+  timestamp: DateTime<Utc>,
+) -> Option<i32> {
+  streams
+    .iter()
+    .find(|stream| {
+      let Some(start_timestamp) = stream.start_timestamp else {
+        return false;
+      };
+
+      start_timestamp <= timestamp
+        && stream
+          .end_timestamp
+          .is_none_or(|end_timestamp| timestamp <= end_timestamp)
+    })
+    .map(|stream| stream.id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  fn stream_model(
+    id: i32,
+    start_timestamp: DateTime<Utc>,
+    end_timestamp: Option<DateTime<Utc>>,
+  ) -> stream::Model {
+    stream::Model {
+      id,
+      twitch_stream_id: id as u64,
+      start_timestamp: Some(start_timestamp),
+      end_timestamp,
+      twitch_user_id: 1,
+      twitch_vod_id: None,
+      title: None,
+    }
+  }
+
+  #[test]
+  fn find_stream_for_timestamp_expected_value() {
+    let streams = vec![
+      stream_model(
+        1,
+        Utc.with_ymd_and_hms(2025, 4, 18, 12, 0, 0).unwrap(),
+        Some(Utc.with_ymd_and_hms(2025, 4, 18, 16, 0, 0).unwrap()),
+      ),
+      stream_model(
+        2,
+        Utc.with_ymd_and_hms(2025, 4, 19, 12, 0, 0).unwrap(),
+        None,
+      ),
+    ];
+
+    let during_first_stream = Utc.with_ymd_and_hms(2025, 4, 18, 13, 0, 0).unwrap();
+    let between_streams = Utc.with_ymd_and_hms(2025, 4, 18, 20, 0, 0).unwrap();
+    let during_live_stream = Utc.with_ymd_and_hms(2025, 4, 20, 1, 0, 0).unwrap();
+
+    assert_eq!(
+      find_stream_for_timestamp(&streams, during_first_stream),
+      Some(1)
+    );
+    assert_eq!(find_stream_for_timestamp(&streams, between_streams), None);
+    assert_eq!(
+      find_stream_for_timestamp(&streams, during_live_stream),
+      Some(2)
+    );
+  }
+}
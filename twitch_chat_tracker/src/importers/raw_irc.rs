@@ -0,0 +1,104 @@
+use super::{ImportedMessage, MessageSender};
+use crate::errors::AppError;
+use crate::irc_chat::mirrored_twitch_objects::message::TwitchIrcMessage;
+use irc::proto::{Command, Message as IrcMessage};
+
+/// Parses a log of raw Twitch IRC lines, one message per line.
+///
+/// Only messages a user typed in chat are returned. Lines that fail to parse are logged and skipped.
+pub fn parse_raw_irc_log(contents: &str) -> Vec<ImportedMessage> {
+  contents
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .filter_map(|line| match parse_raw_irc_line(line) {
+      Ok(message) => message,
+      Err(error) => {
+        tracing::error!("Failed to parse IRC line {:?}. Reason: {error}", line);
+
+        None
+      }
+    })
+    .collect()
+}
+
+/// Returns None if the line isn't a user message.
+pub fn parse_raw_irc_line(line: &str) -> Result<Option<ImportedMessage>, AppError> {
+  let irc_message =
+    line
+      .trim()
+      .parse::<IrcMessage>()
+      .map_err(|_| AppError::FailedToParseValue {
+        value_name: "irc message",
+        location: "raw irc import",
+        value: line.to_owned(),
+      })?;
+
+  imported_message_from_irc(&irc_message)
+}
+
+pub fn imported_message_from_irc(
+  irc_message: &IrcMessage,
+) -> Result<Option<ImportedMessage>, AppError> {
+  let Some(twitch_message) = TwitchIrcMessage::new(irc_message)? else {
+    return Ok(None);
+  };
+
+  if !twitch_message.message_type_has_user_message_attached() {
+    return Ok(None);
+  }
+
+  let Command::PRIVMSG(_, message_contents) = twitch_message.command() else {
+    return Ok(None);
+  };
+  let sender = match (twitch_message.user_id(), twitch_message.login_name()) {
+    (Some(user_id), _) => MessageSender::TwitchId(user_id.to_owned()),
+    (None, Some(login)) => MessageSender::Login(login.to_owned()),
+    (None, None) => {
+      return Err(AppError::MissingExpectedValue {
+        expected_value_name: "user id",
+        location: "raw irc import",
+      });
+    }
+  };
+
+  Ok(Some(ImportedMessage {
+    timestamp: *twitch_message.timestamp(),
+    sender,
+    contents: message_contents.to_owned(),
+    room_twitch_id: twitch_message.room_id().map(str::to_owned),
+    twitch_emote_data: twitch_message.emotes().map(str::to_owned),
+    is_subscriber: Some(twitch_message.is_subscriber()),
+    is_first_message: twitch_message.is_first_message(),
+    is_emote_only: Some(twitch_message.message_is_only_emotes()),
+    origin_id: twitch_message.message_source_id().map(str::to_owned),
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing_helper_methods::timestamp_from_string;
+
+  #[test]
+  fn parse_raw_irc_log_expected_value() {
+    let log = "@display-name=LinkTheDot;emote-only=0;emotes=555555584:4-5;first-msg=0;login=linkthedot;room-id=578762718;source-id=159ba37c-c6aa-4fdd-bc62-c5fadbab0770;subscriber=1;tmi-sent-ts=1740956922774;user-id=128831052 :linkthedot!linkthedot@linkthedot.tmi.twitch.tv PRIVMSG #fallenshadow :waaa <3
+:tmi.twitch.tv PING
+";
+
+    let messages = parse_raw_irc_log(log);
+
+    let expected_messages = vec![ImportedMessage {
+      timestamp: timestamp_from_string("1740956922774"),
+      sender: MessageSender::TwitchId("128831052".into()),
+      contents: "waaa <3".into(),
+      room_twitch_id: Some("578762718".into()),
+      twitch_emote_data: Some("555555584:4-5".into()),
+      is_subscriber: Some(true),
+      is_first_message: false,
+      is_emote_only: Some(false),
+      origin_id: Some("159ba37c-c6aa-4fdd-bc62-c5fadbab0770".into()),
+    }];
+
+    assert_eq!(messages, expected_messages);
+  }
+}
@@ -0,0 +1,333 @@
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use entities::*;
+use entity_extensions::prelude::*;
+use irc::proto::{Command, Message as IrcMessage};
+use sea_orm::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipChange {
+  Join,
+  Part,
+}
+
+/// Parses the JOIN and PART commands sent with the `twitch.tv/membership` capability.
+///
+/// These commands carry no tags, so they can't be handled by the [`MessageParser`](super::message_parser::MessageParser).
+/// Twitch batches these and only sends them for channels with fewer than 1000 chatters,
+/// which means the resulting presence intervals are an estimate rather than an exact record.
+#[derive(Debug)]
+pub struct MembershipParser {
+  user_login: String,
+  channel_login: String,
+  change: MembershipChange,
+  timestamp: DateTime<Utc>,
+}
+
+impl MembershipParser {
+  /// Returns None if the message was not a JOIN or PART command.
+  pub fn new(message: &IrcMessage) -> Result<Option<Self>, AppError> {
+    let (channel_list, change) = match &message.command {
+      Command::JOIN(channel_list, _, _) => (channel_list, MembershipChange::Join),
+      Command::PART(channel_list, _) => (channel_list, MembershipChange::Part),
+      _ => return Ok(None),
+    };
+    let Some(user_login) = message.source_nickname() else {
+      return Err(AppError::MissingExpectedValue {
+        expected_value_name: "source nickname",
+        location: "membership parsing",
+      });
+    };
+    let channel_login = channel_list.trim_start_matches('#');
+
+    if channel_login.is_empty() {
+      return Err(AppError::MissingExpectedValue {
+        expected_value_name: "channel name",
+        location: "membership parsing",
+      });
+    }
+
+    Ok(Some(Self {
+      user_login: user_login.to_lowercase(),
+      channel_login: channel_login.to_lowercase(),
+      change,
+      timestamp: Utc::now(),
+    }))
+  }
+
+  pub fn user_login(&self) -> &str {
+    &self.user_login
+  }
+
+  pub fn change(&self) -> MembershipChange {
+    self.change
+  }
+
+  /// Records the membership change.
+  ///
+  /// The parser is handed back if the user isn't in the database yet, so their lookup can be batched with other
+  /// unknown chatters by [`resolve_unknown_chatters`](crate::processes::resolve_unknown_chatters) rather than querying
+  /// Helix for each one.
+  pub async fn parse(
+    self,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<Self>, AppError> {
+    let Some(user) = twitch_user::Entity::find()
+      .filter(twitch_user::Column::LoginName.eq(&self.user_login))
+      .one(database_connection)
+      .await?
+    else {
+      return Ok(Some(self));
+    };
+    let channel =
+      twitch_user::Model::get_or_set_by_name(&self.channel_login, database_connection).await?;
+
+    let presence = match self.change {
+      MembershipChange::Join => {
+        self
+          .parse_join(&user, &channel, database_connection)
+          .await?
+      }
+      MembershipChange::Part => {
+        self
+          .parse_part(&user, &channel, database_connection)
+          .await?
+      }
+    };
+
+    if let Some(presence) = presence {
+      presence.save(database_connection).await?;
+    }
+
+    Ok(None)
+  }
+
+  /// Returns a new open presence interval for the user.
+  ///
+  /// None is returned if the user already has an open interval in the channel.
+  pub async fn parse_join(
+    &self,
+    user: &twitch_user::Model,
+    channel: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<chatter_presence::ActiveModel>, AppError> {
+    let maybe_stream =
+      stream::Model::get_active_stream_for_user(channel, database_connection).await?;
+
+    if let Some(open_presence) = Self::get_open_presence(user, channel, database_connection).await?
+    {
+      let is_same_stream = open_presence.stream_id == maybe_stream.as_ref().map(|stream| stream.id);
+
+      if is_same_stream {
+        tracing::debug!(
+          "Received a JOIN for `{}` in `{}` while already present.",
+          self.user_login,
+          self.channel_login
+        );
+
+        return Ok(None);
+      }
+
+      // The previous interval belongs to a different stream. Close it off so the new one can begin.
+      let mut open_presence = open_presence.into_active_model();
+      open_presence.parted_at = Set(Some(self.timestamp));
+      open_presence.update(database_connection).await?;
+    }
+
+    Ok(Some(chatter_presence::ActiveModel {
+      twitch_user_id: Set(user.id),
+      channel_id: Set(channel.id),
+      stream_id: Set(maybe_stream.map(|stream| stream.id)),
+      joined_at: Set(self.timestamp),
+      parted_at: Set(None),
+      ..Default::default()
+    }))
+  }
+
+  /// Returns the user's open presence interval with the part timestamp set.
+  ///
+  /// None is returned if no open interval exists for the user in the channel.
+  pub async fn parse_part(
+    &self,
+    user: &twitch_user::Model,
+    channel: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<chatter_presence::ActiveModel>, AppError> {
+    let Some(open_presence) = Self::get_open_presence(user, channel, database_connection).await?
+    else {
+      tracing::debug!(
+        "Received a PART for `{}` in `{}` with no matching JOIN.",
+        self.user_login,
+        self.channel_login
+      );
+
+      return Ok(None);
+    };
+
+    let mut open_presence = open_presence.into_active_model();
+    open_presence.parted_at = Set(Some(self.timestamp));
+
+    Ok(Some(open_presence))
+  }
+
+  async fn get_open_presence(
+    user: &twitch_user::Model,
+    channel: &twitch_user::Model,
+    database_connection: &DatabaseConnection,
+  ) -> Result<Option<chatter_presence::Model>, AppError> {
+    chatter_presence::Entity::find()
+      .filter(chatter_presence::Column::TwitchUserId.eq(user.id))
+      .filter(chatter_presence::Column::ChannelId.eq(channel.id))
+      .filter(chatter_presence::Column::PartedAt.is_null())
+      .order_by_desc(chatter_presence::Column::JoinedAt)
+      .one(database_connection)
+      .await
+      .map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use irc::proto::Prefix;
+
+  #[test]
+  fn new_ignores_non_membership_commands() {
+    let message = IrcMessage {
+      tags: None,
+      prefix: Some(Prefix::ServerName("tmi.twitch.tv".into())),
+      command: Command::PING("tmi.twitch.tv".into(), None),
+    };
+
+    assert!(MembershipParser::new(&message).unwrap().is_none());
+  }
+
+  #[tokio::test]
+  async fn parse_join_expected_value() {
+    let message = get_membership_message(Command::JOIN("#fallenshadow".into(), None, None));
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![get_stream()]])
+      .append_query_results::<chatter_presence::Model, _, _>([vec![]])
+      .into_connection();
+    let parser = MembershipParser::new(&message).unwrap().unwrap();
+
+    let result = parser
+      .parse_join(&get_user(), &get_channel(), &mock_database)
+      .await
+      .unwrap()
+      .unwrap();
+
+    let expected_active_model = chatter_presence::ActiveModel {
+      id: ActiveValue::NotSet,
+      twitch_user_id: Set(3),
+      channel_id: Set(1),
+      stream_id: Set(Some(1)),
+      joined_at: Set(parser.timestamp),
+      parted_at: Set(None),
+    };
+
+    assert_eq!(parser.change(), MembershipChange::Join);
+    assert_eq!(parser.user_login(), "linkthedot");
+    assert_eq!(result, expected_active_model);
+  }
+
+  #[tokio::test]
+  async fn parse_part_closes_open_presence() {
+    let message = get_membership_message(Command::PART("#fallenshadow".into(), None));
+    let open_presence = chatter_presence::Model {
+      id: 5,
+      twitch_user_id: 3,
+      channel_id: 1,
+      stream_id: Some(1),
+      joined_at: Utc::now(),
+      parted_at: None,
+    };
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![open_presence.clone()]])
+      .into_connection();
+    let parser = MembershipParser::new(&message).unwrap().unwrap();
+
+    let result = parser
+      .parse_part(&get_user(), &get_channel(), &mock_database)
+      .await
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(result.id, Unchanged(open_presence.id));
+    assert_eq!(result.parted_at, Set(Some(parser.timestamp)));
+  }
+
+  #[tokio::test]
+  async fn parse_part_without_join_returns_none() {
+    let message = get_membership_message(Command::PART("#fallenshadow".into(), None));
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results::<chatter_presence::Model, _, _>([vec![]])
+      .into_connection();
+    let parser = MembershipParser::new(&message).unwrap().unwrap();
+
+    let result = parser
+      .parse_part(&get_user(), &get_channel(), &mock_database)
+      .await
+      .unwrap();
+
+    assert!(result.is_none());
+  }
+
+  #[tokio::test]
+  async fn parse_hands_back_changes_for_unknown_users() {
+    let message = get_membership_message(Command::JOIN("#fallenshadow".into(), None, None));
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results::<twitch_user::Model, _, _>([vec![]])
+      .into_connection();
+    let parser = MembershipParser::new(&message).unwrap().unwrap();
+
+    let deferred_parser = parser.parse(&mock_database).await.unwrap().unwrap();
+
+    assert_eq!(deferred_parser.user_login(), "linkthedot");
+    assert_eq!(mock_database.into_transaction_log().len(), 1);
+  }
+
+  fn get_membership_message(command: Command) -> IrcMessage {
+    IrcMessage {
+      tags: None,
+      prefix: Some(Prefix::Nickname(
+        "linkthedot".into(),
+        "linkthedot".into(),
+        "linkthedot.tmi.twitch.tv".into(),
+      )),
+      command,
+    }
+  }
+
+  fn get_user() -> twitch_user::Model {
+    twitch_user::Model {
+      id: 3,
+      twitch_id: 128831052,
+      login_name: "linkthedot".into(),
+      display_name: "LinkTheDot".into(),
+      last_verified_at: None,
+    }
+  }
+
+  fn get_channel() -> twitch_user::Model {
+    twitch_user::Model {
+      id: 1,
+      twitch_id: 578762718,
+      login_name: "fallenshadow".into(),
+      display_name: "fallenshadow".into(),
+      last_verified_at: None,
+    }
+  }
+
+  fn get_stream() -> stream::Model {
+    stream::Model {
+      id: 1,
+      twitch_stream_id: 1,
+      start_timestamp: Some(Utc::now()),
+      end_timestamp: None,
+      twitch_user_id: 1,
+      twitch_vod_id: None,
+      title: None,
+    }
+  }
+}
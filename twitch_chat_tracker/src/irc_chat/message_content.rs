@@ -0,0 +1,142 @@
+use app_config::message_content_mode::MessageContentMode;
+use app_config::secret_string::Secret;
+use app_config::AppConfig;
+use entities::sea_orm_active_enums::ContentMode;
+use entity_extensions::stream_message::count_words;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Once;
+
+type HmacSha256 = Hmac<Sha256>;
+
+static MISSING_HASH_KEY_WARNING: Once = Once::new();
+
+/// The values stored for a message's text based on the configured [`MessageContentMode`](MessageContentMode).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMessageContents {
+  pub contents: Option<String>,
+  /// How the contents were stored, so hashes aren't mistaken for message text.
+  pub content_mode: ContentMode,
+  pub word_count: i32,
+  /// In characters.
+  pub content_length: i32,
+}
+
+impl StoredMessageContents {
+  /// `hash_key` is only used by the hashed mode. Without one, no contents are stored at all, as a hash that isn't
+  /// keyed can be reversed by hashing guesses.
+  pub fn new(message_contents: &str, mode: MessageContentMode, hash_key: Option<&[u8]>) -> Self {
+    let (contents, content_mode) = match (mode, hash_key) {
+      (MessageContentMode::Raw, _) => (Some(message_contents.to_owned()), ContentMode::Raw),
+      (MessageContentMode::Hashed, Some(hash_key)) => (
+        Some(hash_contents(message_contents, hash_key)),
+        ContentMode::Hashed,
+      ),
+      (MessageContentMode::Hashed, None) => {
+        MISSING_HASH_KEY_WARNING.call_once(|| {
+          tracing::error!(
+            "Message contents are set to be hashed, but there's no `tracker.messageHashKey`. Contents won't be stored."
+          )
+        });
+
+        (None, ContentMode::Redacted)
+      }
+      (MessageContentMode::Redacted, _) => (None, ContentMode::Redacted),
+    };
+
+    Self {
+      contents,
+      content_mode,
+      word_count: count_words(message_contents),
+      content_length: message_contents.chars().count() as i32,
+    }
+  }
+
+  /// Uses the mode and hash key from the config.
+  pub fn from_config(message_contents: &str) -> Self {
+    let hash_key = AppConfig::message_hash_key()
+      .map(|hash_key| Secret::read_secret_string(hash_key.read_value()).as_bytes());
+
+    Self::new(
+      message_contents,
+      AppConfig::message_content_mode(),
+      hash_key,
+    )
+  }
+}
+
+fn hash_contents(message_contents: &str, hash_key: &[u8]) -> String {
+  let mut hasher = HmacSha256::new_from_slice(hash_key).expect("HMAC accepts keys of any length.");
+  hasher.update(message_contents.as_bytes());
+
+  hex::encode(hasher.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MESSAGE: &str = "waaa <3 syadouStanding";
+  const HASH_KEY: &[u8] = b"test key";
+
+  #[test]
+  fn raw_mode_keeps_contents() {
+    let stored_contents = StoredMessageContents::new(MESSAGE, MessageContentMode::Raw, None);
+
+    let expected_contents = StoredMessageContents {
+      contents: Some(MESSAGE.to_owned()),
+      content_mode: ContentMode::Raw,
+      word_count: 3,
+      content_length: 22,
+    };
+
+    assert_eq!(stored_contents, expected_contents);
+  }
+
+  #[test]
+  fn hashed_mode_replaces_contents_with_hash() {
+    let stored_contents =
+      StoredMessageContents::new(MESSAGE, MessageContentMode::Hashed, Some(HASH_KEY));
+    let hashed_contents = stored_contents.contents.unwrap();
+
+    assert_eq!(stored_contents.content_mode, ContentMode::Hashed);
+    assert_eq!(hashed_contents.len(), 64);
+    assert_ne!(hashed_contents, MESSAGE);
+    assert_eq!(
+      Some(hashed_contents.clone()),
+      StoredMessageContents::new(MESSAGE, MessageContentMode::Hashed, Some(HASH_KEY)).contents
+    );
+    assert_ne!(
+      Some(hashed_contents),
+      StoredMessageContents::new(MESSAGE, MessageContentMode::Hashed, Some(b"other key")).contents
+    );
+    assert_eq!(
+      (stored_contents.word_count, stored_contents.content_length),
+      (3, 22)
+    );
+  }
+
+  #[test]
+  fn redacted_mode_keeps_only_metrics() {
+    let stored_contents =
+      StoredMessageContents::new(MESSAGE, MessageContentMode::Redacted, Some(HASH_KEY));
+
+    let expected_contents = StoredMessageContents {
+      contents: None,
+      content_mode: ContentMode::Redacted,
+      word_count: 3,
+      content_length: 22,
+    };
+
+    assert_eq!(stored_contents, expected_contents);
+  }
+
+  #[test]
+  fn hashed_mode_without_a_key_stores_no_contents() {
+    let stored_contents = StoredMessageContents::new(MESSAGE, MessageContentMode::Hashed, None);
+
+    assert_eq!(stored_contents.contents, None);
+    assert_eq!(stored_contents.content_mode, ContentMode::Redacted);
+    assert_eq!(stored_contents.word_count, 3);
+  }
+}
@@ -6,6 +6,7 @@ use irc::proto::Message as IrcMessage;
 use sea_orm::*;
 
 mod bits_message_parsing;
+pub mod donation_bot_parser;
 mod gift_sub_message_parsing;
 mod raid_message_parsing;
 mod stream_message_parsing;
@@ -90,7 +91,7 @@ mod tests {
   use super::*;
   use crate::channel::third_party_emote_list_storage::EmoteListStorage;
   use crate::testing_helper_methods::timestamp_from_string;
-  use entities::sea_orm_active_enums::EventType;
+  use entities::sea_orm_active_enums::{ContentMode, EventType};
   use entities::{donation_event, stream_message, twitch_user};
   use irc::proto::message::Tag as IrcTag;
   use irc::proto::Message as IrcMessage;
@@ -140,6 +141,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -147,6 +149,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .append_query_results([vec![stream_message::Model {
@@ -158,8 +161,11 @@ mod tests {
         twitch_user_id: 3,
         channel_id: 1,
         stream_id: None,
-        is_subscriber: 1_i8,
+        is_subscriber: Some(1_i8),
         origin_id: None,
+        word_count: Some(1),
+        content_length: Some(11),
+        content_mode: ContentMode::Raw,
       }]])
       .append_exec_results([MockExecResult {
         last_insert_id: 1,
@@ -171,6 +177,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -178,6 +185,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .append_query_results([vec![donation_event::Model {
@@ -192,6 +200,8 @@ mod tests {
         unknown_user_id: None,
         origin_id: None,
         source_id: None,
+        currency: None,
+        donation_bot_login: None,
       }]])
       .append_exec_results([MockExecResult {
         last_insert_id: 1,
@@ -99,6 +99,8 @@ mod tests {
       unknown_user_id: ActiveValue::NotSet,
       origin_id: ActiveValue::NotSet,
       source_id: Set(None),
+      currency: ActiveValue::NotSet,
+      donation_bot_login: ActiveValue::NotSet,
     };
 
     assert_eq!(result, expected_active_model);
@@ -131,6 +133,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -138,6 +141,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .into_connection();
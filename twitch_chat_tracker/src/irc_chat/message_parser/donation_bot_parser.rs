@@ -0,0 +1,358 @@
+use crate::errors::AppError;
+use crate::irc_chat::message_parser::streamlabs_donation::{
+  currency_code, parse_donation_amount, StreamlabsDonation,
+};
+use crate::irc_chat::mirrored_twitch_objects::message::TwitchIrcMessage;
+use app_config::donation_parser_config::DonationParserConfig;
+use app_config::AppConfig;
+use regex::Regex;
+use std::sync::OnceLock;
+
+const TEMPLATE_PLACEHOLDER_PATTERN: &str = r"\{(name|amount|currency|message)\}";
+
+static DONATION_BOT_PARSERS: OnceLock<DonationBotParsers> = OnceLock::new();
+
+/// The bot that posted a chat message, and the channel it was posted in.
+#[derive(Debug, Clone, Copy)]
+pub struct DonationMessageSource<'a> {
+  pub channel_login: &'a str,
+  pub user_id: Option<&'a str>,
+  pub user_login: Option<&'a str>,
+}
+
+/// Every parser for donations announced in chat by bots.
+///
+/// The configured parsers are checked in order, followed by the built in Streamlabs parser.
+#[derive(Debug, Default)]
+pub struct DonationBotParsers {
+  parsers: Vec<DonationBotParser>,
+}
+
+#[derive(Debug)]
+struct DonationBotParser {
+  channels: Vec<String>,
+  bot_login: String,
+  pattern: Regex,
+  default_currency: Option<String>,
+}
+
+impl DonationBotParsers {
+  /// Returns the parsers from the [`AppConfig`](AppConfig).
+  ///
+  /// Parsers with an invalid pattern or template are logged and skipped.
+  pub fn get() -> &'static Self {
+    DONATION_BOT_PARSERS.get_or_init(|| Self::new(AppConfig::donation_parsers()))
+  }
+
+  pub fn new(parser_configs: &[DonationParserConfig]) -> Self {
+    let parsers = parser_configs
+      .iter()
+      .filter_map(
+        |parser_config| match DonationBotParser::new(parser_config) {
+          Ok(parser) => Some(parser),
+          Err(error) => {
+            tracing::error!("Skipping a donation parser. Reason: {error}");
+
+            None
+          }
+        },
+      )
+      .collect();
+
+    Self { parsers }
+  }
+
+  /// Parses the message contents as a donation if the source is a known donation bot.
+  pub fn parse<'a>(
+    &self,
+    source: DonationMessageSource,
+    message_contents: &'a str,
+  ) -> Option<StreamlabsDonation<'a>> {
+    let configured_donation = self
+      .parsers
+      .iter()
+      .filter(|parser| parser.applies_to(source))
+      .find_map(|parser| parser.parse(message_contents));
+
+    if configured_donation.is_some() {
+      return configured_donation;
+    }
+
+    if source.user_id != Some(TwitchIrcMessage::STREAMELEMENTS_TWITCH_ID) {
+      return None;
+    }
+
+    StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(message_contents)
+  }
+}
+
+impl DonationBotParser {
+  fn new(parser_config: &DonationParserConfig) -> Result<Self, AppError> {
+    let pattern = match (&parser_config.pattern, &parser_config.template) {
+      (Some(pattern), _) => pattern.to_owned(),
+      (None, Some(template)) => template_to_pattern(template),
+      (None, None) => {
+        return Err(AppError::InvalidDonationParser {
+          bot_login: parser_config.bot_login.clone(),
+          reason: "Neither a pattern nor a template was set.".into(),
+        });
+      }
+    };
+    let pattern = Regex::new(&pattern).map_err(|error| AppError::InvalidDonationParser {
+      bot_login: parser_config.bot_login.clone(),
+      reason: error.to_string(),
+    })?;
+
+    for required_capture in ["name", "amount"] {
+      if !pattern
+        .capture_names()
+        .any(|capture_name| capture_name == Some(required_capture))
+      {
+        return Err(AppError::InvalidDonationParser {
+          bot_login: parser_config.bot_login.clone(),
+          reason: format!("Missing the `{required_capture}` capture."),
+        });
+      }
+    }
+
+    let has_currency_capture = pattern
+      .capture_names()
+      .any(|capture_name| capture_name == Some("currency"));
+
+    if !has_currency_capture && parser_config.default_currency.is_none() {
+      return Err(AppError::InvalidDonationParser {
+        bot_login: parser_config.bot_login.clone(),
+        reason: "Neither a `currency` capture nor a default currency was set.".into(),
+      });
+    }
+
+    Ok(Self {
+      channels: parser_config.channels.clone(),
+      bot_login: parser_config.bot_login.clone(),
+      pattern,
+      default_currency: parser_config.default_currency.clone(),
+    })
+  }
+
+  fn applies_to(&self, source: DonationMessageSource) -> bool {
+    let is_bot = source
+      .user_login
+      .is_some_and(|user_login| user_login.eq_ignore_ascii_case(&self.bot_login));
+    let is_channel = self.channels.is_empty()
+      || self
+        .channels
+        .iter()
+        .any(|channel| channel.eq_ignore_ascii_case(source.channel_login));
+
+    is_bot && is_channel
+  }
+
+  fn parse<'a>(&self, message_contents: &'a str) -> Option<StreamlabsDonation<'a>> {
+    let captures = self.pattern.captures(message_contents)?;
+    let donator_name = captures.name("name")?.as_str().trim();
+    let amount = parse_donation_amount(captures.name("amount")?.as_str())?;
+    let currency = match captures.name("currency") {
+      Some(currency) => currency_code(currency.as_str())?,
+      None => currency_code(self.default_currency.as_deref()?)?,
+    };
+    let donation_message = captures
+      .name("message")
+      .map(|message| message.as_str().trim())
+      .unwrap_or_default();
+
+    if donator_name.is_empty() {
+      return None;
+    }
+
+    Some(StreamlabsDonation {
+      amount,
+      currency,
+      donation_message,
+      donator_name,
+    })
+  }
+}
+
+/// Converts a message template into a regex that matches the whole message.
+///
+/// `{name}`, `{amount}`, `{currency}`, and `{message}` become their named captures, everything else is matched exactly.
+fn template_to_pattern(template: &str) -> String {
+  let placeholder_regex = Regex::new(TEMPLATE_PLACEHOLDER_PATTERN).unwrap();
+  let mut pattern = String::from("^");
+  let mut last_end = 0;
+
+  for placeholder in placeholder_regex.captures_iter(template) {
+    let (Some(full_match), Some(placeholder_name)) = (placeholder.get(0), placeholder.get(1))
+    else {
+      continue;
+    };
+
+    pattern.push_str(&regex::escape(&template[last_end..full_match.start()]));
+    pattern.push_str(match placeholder_name.as_str() {
+      "name" => r"(?P<name>.+?)",
+      "amount" => r"(?P<amount>\d[\d,]*(?:\.\d+)?)",
+      "currency" => r"(?P<currency>[$€£]|[A-Za-z]{3})",
+      _ => r"(?P<message>.*)",
+    });
+
+    last_end = full_match.end();
+  }
+
+  pattern.push_str(&regex::escape(&template[last_end..]));
+  pattern.push('$');
+
+  pattern
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const STREAMELEMENTS_TIP: &str = "anon y moose just tipped $12.50 PogChamp";
+  const KOFI_DONATION: &str =
+    "anon y moose bought 3 coffees (€9.00) on Ko-fi! Message: Love the streams";
+  const FOURTHWALL_DONATION: &str = "anon y moose donated 1,000 JPY: ganbatte!";
+
+  fn parser_config(bot_login: &str) -> DonationParserConfig {
+    DonationParserConfig {
+      channels: vec![],
+      bot_login: bot_login.into(),
+      pattern: None,
+      template: None,
+      default_currency: None,
+    }
+  }
+
+  fn source<'a>(channel_login: &'a str, user_login: &'a str) -> DonationMessageSource<'a> {
+    DonationMessageSource {
+      channel_login,
+      user_id: None,
+      user_login: Some(user_login),
+    }
+  }
+
+  #[test]
+  fn template_parsers_read_configured_bot_messages() {
+    let parsers = DonationBotParsers::new(&[
+      DonationParserConfig {
+        template: Some("{name} just tipped {currency}{amount} PogChamp".into()),
+        ..parser_config("streamelements")
+      },
+      DonationParserConfig {
+        template: Some("{name} donated {amount} {currency}: {message}".into()),
+        ..parser_config("fourthwall")
+      },
+    ]);
+
+    let streamelements_tip = parsers
+      .parse(source("channel", "StreamElements"), STREAMELEMENTS_TIP)
+      .unwrap();
+    let fourthwall_donation = parsers
+      .parse(source("channel", "fourthwall"), FOURTHWALL_DONATION)
+      .unwrap();
+
+    assert_eq!(
+      streamelements_tip,
+      StreamlabsDonation {
+        amount: 12.5,
+        currency: "USD".into(),
+        donation_message: "",
+        donator_name: "anon y moose",
+      }
+    );
+    assert_eq!(
+      fourthwall_donation,
+      StreamlabsDonation {
+        amount: 1000.0,
+        currency: "JPY".into(),
+        donation_message: "ganbatte!",
+        donator_name: "anon y moose",
+      }
+    );
+  }
+
+  #[test]
+  fn pattern_parsers_read_configured_bot_messages() {
+    let parsers = DonationBotParsers::new(&[DonationParserConfig {
+      pattern: Some(
+        r"^(?P<name>.+) bought \d+ coffees? \((?P<currency>.)(?P<amount>[\d.]+)\) on Ko-fi! Message: (?P<message>.*)$"
+          .into(),
+      ),
+      ..parser_config("kofibot")
+    }]);
+
+    let donation = parsers
+      .parse(source("channel", "kofibot"), KOFI_DONATION)
+      .unwrap();
+
+    assert_eq!(
+      donation,
+      StreamlabsDonation {
+        amount: 9.0,
+        currency: "EUR".into(),
+        donation_message: "Love the streams",
+        donator_name: "anon y moose",
+      }
+    );
+  }
+
+  #[test]
+  fn parsers_only_apply_to_their_bot_and_channels() {
+    let parsers = DonationBotParsers::new(&[DonationParserConfig {
+      channels: vec!["FallenShadow".into()],
+      template: Some("{name} donated {amount}: {message}".into()),
+      default_currency: Some("gbp".into()),
+      ..parser_config("fourthwall")
+    }]);
+    let message = "anon y moose donated 5: hi";
+
+    let donation = parsers
+      .parse(source("fallenshadow", "fourthwall"), message)
+      .unwrap();
+
+    assert_eq!(donation.currency, "GBP");
+    assert!(parsers
+      .parse(source("shadowchama", "fourthwall"), message)
+      .is_none());
+    assert!(parsers
+      .parse(source("fallenshadow", "anon_y_moose"), message)
+      .is_none());
+  }
+
+  #[test]
+  fn streamlabs_parser_is_built_in() {
+    let parsers = DonationBotParsers::default();
+    let message = "anon y moose just tipped £120.00! thanks here's what they say: hi";
+    let streamelements_source = DonationMessageSource {
+      channel_login: "channel",
+      user_id: Some(TwitchIrcMessage::STREAMELEMENTS_TWITCH_ID),
+      user_login: Some("streamelements"),
+    };
+
+    assert!(parsers.parse(streamelements_source, message).is_some());
+    assert!(parsers
+      .parse(source("channel", "streamelements"), message)
+      .is_none());
+  }
+
+  #[test]
+  fn invalid_parsers_are_skipped() {
+    let parsers = DonationBotParsers::new(&[
+      parser_config("no_pattern"),
+      DonationParserConfig {
+        pattern: Some(r"(?P<name>.+) gave".into()),
+        ..parser_config("missing_amount")
+      },
+      DonationParserConfig {
+        pattern: Some(r"(?P<name>.+".into()),
+        ..parser_config("invalid_regex")
+      },
+      DonationParserConfig {
+        template: Some("{name} donated {amount}".into()),
+        ..parser_config("missing_currency")
+      },
+    ]);
+
+    assert!(parsers.parsers.is_empty());
+  }
+}
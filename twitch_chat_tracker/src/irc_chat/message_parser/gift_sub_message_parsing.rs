@@ -210,6 +210,8 @@ mod tests {
       unknown_user_id: ActiveValue::NotSet,
       origin_id: Set(Some("1000".into())),
       source_id: NotSet,
+      currency: NotSet,
+      donation_bot_login: NotSet,
     };
 
     assert_eq!(result, Some(expected_active_model));
@@ -239,6 +241,8 @@ mod tests {
       unknown_user_id: ActiveValue::NotSet,
       origin_id: Set(Some("1000".into())),
       source_id: NotSet,
+      currency: NotSet,
+      donation_bot_login: NotSet,
     };
 
     assert_eq!(result, Some(expected_active_model));
@@ -276,6 +280,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -283,6 +288,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .append_exec_results(vec![MockExecResult {
@@ -301,6 +307,8 @@ mod tests {
         unknown_user_id: None,
         origin_id: Some("1000".into()),
         source_id: None,
+        currency: None,
+        donation_bot_login: None,
       }]]);
 
     for iteration in 0..sub_count.unwrap_or(0) {
@@ -309,6 +317,7 @@ mod tests {
         twitch_id: 100 + iteration,
         login_name: iteration.to_string(),
         display_name: iteration.to_string(),
+        last_verified_at: None,
       }]]);
     }
 
@@ -436,6 +445,8 @@ mod tests {
       unknown_user_id: None,
       origin_id: Some("1000".into()),
       source_id: None,
+      currency: None,
+      donation_bot_login: None,
     };
     let expected_active_model = donation_event::ActiveModel {
       id: ActiveValue::NotSet,
@@ -449,6 +460,8 @@ mod tests {
       unknown_user_id: ActiveValue::NotSet,
       origin_id: Set(Some("1000".into())),
       source_id: NotSet,
+      currency: NotSet,
+      donation_bot_login: NotSet,
     };
     let (bulk_message, _) = get_gift_subs_template(None);
     let bulk_message_parser = MessageParser::new(&bulk_message, &third_party_emote_storage)
@@ -463,6 +476,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -470,6 +484,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .append_query_results([
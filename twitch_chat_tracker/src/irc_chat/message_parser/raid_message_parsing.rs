@@ -121,6 +121,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -128,6 +129,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .into_connection();
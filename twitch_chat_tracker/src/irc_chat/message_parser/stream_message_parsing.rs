@@ -1,5 +1,6 @@
 use super::MessageParser;
 use crate::errors::AppError;
+use crate::irc_chat::message_content::StoredMessageContents;
 use crate::irc_chat::mirrored_twitch_objects::twitch_message_type::TwitchMessageType;
 use crate::irc_chat::parse_results::stream_message::ParsedStreamMessage;
 use entities::*;
@@ -70,21 +71,29 @@ impl<'a> MessageParser<'a> {
     let sender_twitch_user_model =
       twitch_user::Model::get_or_set_by_twitch_id(sender_twitch_id, database_connection).await?;
 
+    let stored_contents = StoredMessageContents::from_config(message_contents);
     let message_active_model = stream_message::ActiveModel {
       is_first_message: Set(self.message.is_first_message() as i8),
       timestamp: Set(*self.message.timestamp()),
       emote_only: Set(self.message.message_is_only_emotes() as i8),
-      contents: Set(Some(message_contents.to_owned())),
+      contents: Set(stored_contents.contents),
+      word_count: Set(Some(stored_contents.word_count)),
+      content_length: Set(Some(stored_contents.content_length)),
+      content_mode: Set(stored_contents.content_mode),
       twitch_user_id: Set(sender_twitch_user_model.id),
       channel_id: Set(streamer_twitch_user_model.id),
       stream_id: Set(maybe_stream.map(|stream| stream.id)),
-      is_subscriber: Set(self.message.is_subscriber() as i8),
+      is_subscriber: Set(Some(self.message.is_subscriber() as i8)),
       origin_id: Set(self.message.message_source_id().map(str::to_owned)),
       ..Default::default()
     };
 
-    let parsed_stream_message =
-      ParsedStreamMessage::new(message_active_model, emotes, streamer_twitch_user_model);
+    let parsed_stream_message = ParsedStreamMessage::new(
+      message_active_model,
+      message_contents,
+      emotes,
+      streamer_twitch_user_model,
+    );
 
     Ok(parsed_stream_message)
   }
@@ -98,7 +107,7 @@ mod tests {
   use irc::proto::message::Tag as IrcTag;
   use irc::proto::Message as IrcMessage;
   use irc::proto::{Command, Prefix};
-  use sea_orm_active_enums::ExternalService;
+  use sea_orm_active_enums::{ContentMode, ExternalService};
 
   #[tokio::test]
   async fn parse_user_message_expected_value() {
@@ -184,6 +193,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -191,6 +201,7 @@ mod tests {
           twitch_id: 128831052,
           login_name: "linkthedot".into(),
           display_name: "LinkTheDot".into(),
+          last_verified_at: None,
         }],
       ])
       .append_exec_results([MockExecResult {
@@ -206,8 +217,11 @@ mod tests {
         twitch_user_id: 3,
         channel_id: 1,
         stream_id: None,
-        is_subscriber: 1_i8,
+        is_subscriber: Some(1_i8),
         origin_id: Some("159ba37c-c6aa-4fdd-bc62-c5fadbab0770".into()),
+        word_count: Some(4),
+        content_length: Some(37),
+        content_mode: ContentMode::Raw,
       }]])
       .append_exec_results([MockExecResult {
         last_insert_id: 1,
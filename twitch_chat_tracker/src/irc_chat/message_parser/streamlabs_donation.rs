@@ -1,14 +1,18 @@
-use regex::Regex;
+use regex::{Captures, Regex};
+
+const DONATION_AMOUNT_PATTERN: &str = r"(?:(?P<symbol>[$€£])|(?P<prefix_code>[A-Z]{3}) ?)(?P<amount>\d[\d,]*(?:\.\d+)?)!|(?P<suffix_amount>\d[\d,]*(?:\.\d+)?) ?(?P<suffix_code>[A-Z]{3})!";
 
 #[derive(Debug, PartialEq)]
 pub struct StreamlabsDonation<'a> {
   pub amount: f32,
+  /// The ISO 4217 code of the currency donated in.
+  pub currency: String,
   pub donation_message: &'a str,
   pub donator_name: &'a str,
 }
 
 // Take every character from start to the last instance of the pattern "just tipped" to get the name.
-//   Take the last instance of £[0-9]*.[0-9]*! before the first `here's what they say:` to get the amount.
+//   Take the last instance of a currency symbol or code next to an amount followed by `!` before the first `here's what they say:` to get the amount.
 //   Take everything after the first `here's what they say:` to get the message.
 impl<'a> StreamlabsDonation<'a> {
   pub fn parse_streamlabs_donation_value_from_message_content(
@@ -23,18 +27,56 @@ impl<'a> StreamlabsDonation<'a> {
 
     let donator_name = name_and_amount_content[..just_tipped_position].trim();
 
-    let amount_regex = Regex::new(r"£(\d+(?:\.\d+)?)!").ok()?;
-    let amount_match = amount_regex.find_iter(name_and_amount_content).last()?;
-    let amount_captures =
-      amount_regex.captures(&name_and_amount_content[amount_match.start()..amount_match.end()])?;
-    let amount: f32 = amount_captures.get(1)?.as_str().parse().ok()?;
+    let amount_regex = Regex::new(DONATION_AMOUNT_PATTERN).ok()?;
+    let amount_captures = amount_regex.captures_iter(name_and_amount_content).last()?;
+    let (amount, currency) = Self::amount_and_currency(&amount_captures)?;
 
     Some(StreamlabsDonation {
       amount,
+      currency,
       donation_message,
       donator_name,
     })
   }
+
+  fn amount_and_currency(amount_captures: &Captures) -> Option<(f32, String)> {
+    let amount = parse_donation_amount(
+      amount_captures
+        .name("amount")
+        .or_else(|| amount_captures.name("suffix_amount"))?
+        .as_str(),
+    )?;
+    let currency = amount_captures
+      .name("symbol")
+      .or_else(|| amount_captures.name("prefix_code"))
+      .or_else(|| amount_captures.name("suffix_code"))?
+      .as_str();
+
+    Some((amount, currency_code(currency)?))
+  }
+}
+
+/// Parses a donation amount, ignoring thousands separators.
+pub fn parse_donation_amount(amount: &str) -> Option<f32> {
+  amount.replace(',', "").parse().ok()
+}
+
+/// Returns the ISO 4217 code for a currency symbol, or the code itself if one was given.
+pub fn currency_code(symbol_or_code: &str) -> Option<String> {
+  match symbol_or_code.trim() {
+    "$" => Some("USD".to_string()),
+    "€" => Some("EUR".to_string()),
+    "£" => Some("GBP".to_string()),
+    code
+      if code.len() == 3
+        && code
+          .chars()
+          .all(|character| character.is_ascii_alphabetic()) =>
+    {
+      Some(code.to_uppercase())
+    }
+    _ => None,
+  }
 }
 
 #[cfg(test)]
@@ -63,6 +105,7 @@ mod tests {
 
     assert_eq!(result.donator_name, "anon y moose");
     assert_eq!(result.amount, 120.0);
+    assert_eq!(result.currency, "GBP");
     assert_eq!(result.donation_message, "This is a message");
   }
 
@@ -127,6 +170,42 @@ mod tests {
     assert_eq!(result.donation_message, "Short tip");
   }
 
+  #[test]
+  fn test_dollar_amount() {
+    let input = "user123 just tipped $1,250.50! here's what they say: Big tip";
+
+    let result =
+      StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(input).unwrap();
+
+    assert_eq!(result.amount, 1250.5);
+    assert_eq!(result.currency, "USD");
+  }
+
+  #[test]
+  fn test_euro_amount() {
+    let input = "user123 just tipped €7.50! here's what they say: Hello";
+
+    let result =
+      StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(input).unwrap();
+
+    assert_eq!(result.amount, 7.5);
+    assert_eq!(result.currency, "EUR");
+  }
+
+  #[test]
+  fn test_currency_code_amount() {
+    let prefixed = "user123 just tipped CAD 20.00! here's what they say: Hello";
+    let suffixed = "user123 just tipped 15 SEK! here's what they say: Hello";
+
+    let prefixed =
+      StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(prefixed).unwrap();
+    let suffixed =
+      StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(suffixed).unwrap();
+
+    assert_eq!((prefixed.amount, prefixed.currency.as_str()), (20.0, "CAD"));
+    assert_eq!((suffixed.amount, suffixed.currency.as_str()), (15.0, "SEK"));
+  }
+
   #[test]
   fn test_invalid_input() {
     assert!(
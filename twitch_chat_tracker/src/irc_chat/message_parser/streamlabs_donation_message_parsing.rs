@@ -1,7 +1,8 @@
 use super::MessageParser;
 use crate::errors::AppError;
-use crate::irc_chat::message_parser::streamlabs_donation::StreamlabsDonation;
-use crate::irc_chat::mirrored_twitch_objects::message::TwitchIrcMessage;
+use crate::irc_chat::message_parser::donation_bot_parser::{
+  DonationBotParsers, DonationMessageSource,
+};
 use crate::irc_chat::mirrored_twitch_objects::twitch_message_type::TwitchMessageType;
 use entities::sea_orm_active_enums::EventType;
 use entities::*;
@@ -21,28 +22,19 @@ impl MessageParser<'_> {
       });
     }
 
-    let Some(user_id) = self.message.user_id() else {
-      return Err(AppError::MissingExpectedValue {
-        expected_value_name: "user id",
-        location: "streamlabs donation parsing",
-      });
-    };
-
-    if user_id != TwitchIrcMessage::STREAMELEMENTS_TWITCH_ID {
-      return Err(AppError::IncorrectUserWhenParsingStreamlabsDonation {
-        got_user: user_id.to_string(),
-      });
-    }
-
-    let Command::PRIVMSG(_, message_contents) = &self.message.command() else {
+    let Command::PRIVMSG(channel, message_contents) = &self.message.command() else {
       return Err(AppError::IncorrectCommandWhenParsingMessage {
         location: "streamlabs parser",
         command_string: format!("{:?}", self.message.command()),
       });
     };
+    let source = DonationMessageSource {
+      channel_login: channel.trim_start_matches('#'),
+      user_id: self.message.user_id(),
+      user_login: self.message.login_name(),
+    };
 
-    let Some(parsed_donation_contents) =
-      StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(message_contents)
+    let Some(parsed_donation_contents) = DonationBotParsers::get().parse(source, message_contents)
     else {
       return Err(AppError::FailedToParseValue {
         value_name: "donation contents",
@@ -51,30 +43,30 @@ impl MessageParser<'_> {
       });
     };
 
-    let donator = match twitch_user::Model::get_or_set_by_name(
+    // Donations from unrecognized names keep their unknown user so they can be reattributed when a match is reviewed.
+    let (donator, unknown_user) = match twitch_user::Model::get_or_set_by_name(
       parsed_donation_contents.donator_name,
       database_connection,
     )
     .await
     {
-      Ok(donator) => Some(donator),
+      Ok(donator) => (Some(donator), None),
       Err(error) => {
         tracing::error!("Failed to get donator from a streamlabs donation. Reason: {:?}. Attempting guess based on known users.", error);
 
-        twitch_user::Model::guess_name(parsed_donation_contents.donator_name, database_connection)
-          .await?
-      }
-    };
-    let unknown_user = if donator.is_none() {
-      Some(
-        unknown_user::Model::get_or_set_by_name(
+        let donator = twitch_user::Model::guess_name(
+          parsed_donation_contents.donator_name,
+          database_connection,
+        )
+        .await?;
+        let unknown_user = unknown_user::Model::get_or_set_by_name(
           parsed_donation_contents.donator_name,
           database_connection,
         )
-        .await?,
-      )
-    } else {
-      None
+        .await?;
+
+        (donator, Some(unknown_user))
+      }
     };
 
     let Some(streamer_twitch_id) = self.message.room_id() else {
@@ -91,12 +83,14 @@ impl MessageParser<'_> {
     let donation_event = donation_event::ActiveModel {
       event_type: Set(EventType::StreamlabsDonation),
       amount: Set(parsed_donation_contents.amount),
+      currency: Set(Some(parsed_donation_contents.currency)),
       timestamp: Set(*self.message.timestamp()),
       donator_twitch_user_id: Set(donator.map(|donator| donator.id)),
       unknown_user_id: Set(unknown_user.map(|user| user.id)),
       donation_receiver_twitch_user_id: Set(streamer_model.id),
       stream_id: Set(maybe_stream.map(|stream| stream.id)),
       source_id: Set(self.message.message_source_id().map(str::to_owned)),
+      donation_bot_login: Set(source.user_login.map(str::to_lowercase)),
       ..Default::default()
     };
 
@@ -139,6 +133,8 @@ mod tests {
       unknown_user_id: Set(None),
       origin_id: ActiveValue::NotSet,
       source_id: Set(None),
+      currency: Set(Some("GBP".into())),
+      donation_bot_login: Set(Some("streamelements".into())),
     };
 
     assert_eq!(result, expected_active_model);
@@ -171,12 +167,14 @@ mod tests {
           twitch_id: 246216923,
           login_name: "5even5".into(),
           display_name: "5EVEN5".into(),
+          last_verified_at: None,
         }],
         vec![twitch_user::Model {
           id: 1,
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
       ])
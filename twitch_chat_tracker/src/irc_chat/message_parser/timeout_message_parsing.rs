@@ -115,6 +115,7 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![],
         vec![twitch_user::Model {
@@ -122,6 +123,7 @@ mod tests {
           twitch_id: 795025340,
           login_name: "shadowchama".into(),
           display_name: "shadowchama".into(),
+          last_verified_at: None,
         }],
       ])
       .into_connection();
@@ -1,5 +1,7 @@
 use super::twitch_message_type::TwitchMessageType;
-use crate::irc_chat::message_parser::streamlabs_donation::StreamlabsDonation;
+use crate::irc_chat::message_parser::donation_bot_parser::{
+  DonationBotParsers, DonationMessageSource,
+};
 use crate::irc_chat::mirrored_twitch_objects::tag_values::TwitchIrcTagValues;
 use crate::{errors::AppError, irc_chat::sub_tier::SubTier};
 use chrono::{DateTime, Utc};
@@ -98,20 +100,19 @@ impl TwitchIrcMessage {
     tags.bits().is_some()
   }
 
+  /// Returns true if the message is a donation announced by a bot with a [`donation parser`](DonationBotParsers).
   fn is_streamlabs_donation(tags: &TwitchIrcTagValues, message: &IrcMessage) -> bool {
-    let Some(user_id) = tags.user_id() else {
+    let Command::PRIVMSG(channel, contents) = &message.command else {
       return false;
     };
 
-    if user_id != Self::STREAMELEMENTS_TWITCH_ID {
-      return false;
-    }
-
-    let Command::PRIVMSG(_, contents) = &message.command else {
-      return false;
+    let source = DonationMessageSource {
+      channel_login: channel.trim_start_matches('#'),
+      user_id: tags.user_id(),
+      user_login: tags.login_name(),
     };
 
-    StreamlabsDonation::parse_streamlabs_donation_value_from_message_content(contents).is_some()
+    DonationBotParsers::get().parse(source, contents).is_some()
   }
 
   fn is_raid(tags: &TwitchIrcTagValues) -> bool {
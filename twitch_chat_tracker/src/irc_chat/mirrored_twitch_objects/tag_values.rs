@@ -124,8 +124,15 @@ impl TwitchIrcTagValues {
       })
       .collect();
     let serialized_tag_map = serde_json::to_string(&tag_map)?;
+    let source_nickname = message.source_nickname();
 
     let mut message: Self = serde_json::from_str(&serialized_tag_map)?;
+
+    // Only USERNOTICEs have a `login` tag. The sender of a PRIVMSG is in its prefix instead.
+    if message.login_name.is_none() {
+      message.login_name = source_nickname.map(str::to_owned);
+    }
+
     message.set_timestamp()?;
     message.check_resub_after_giftsub();
 
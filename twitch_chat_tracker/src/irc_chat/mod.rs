@@ -1,3 +1,5 @@
+pub mod membership_parser;
+pub mod message_content;
 pub mod message_parser;
 pub mod mirrored_twitch_objects;
 pub mod parse_results;
@@ -12,6 +12,8 @@ pub struct ParsedStreamMessage<'a, ModelState = ActiveModel> {
   ///
   /// Returned as a list of the emote models and the positions they were used in the message.
   pub twitch_emote_data: &'a str,
+  /// The message as it was sent, since the stored contents may be hashed or redacted.
+  pub message_contents: &'a str,
   pub channel: twitch_user::Model,
   stream_message_model: StoredMessageModel,
 
@@ -38,12 +40,14 @@ struct ParsedTwitchEmote {
 impl<'a> ParsedStreamMessage<'a, ActiveModel> {
   pub fn new(
     stream_message_active_model: stream_message::ActiveModel,
+    message_contents: &'a str,
     twitch_emote_data: &'a str,
     channel: twitch_user::Model,
   ) -> Self {
     Self {
       stream_message_model: StoredMessageModel::ActiveModel(stream_message_active_model),
       twitch_emote_data,
+      message_contents,
       channel,
 
       model_state: PhantomData,
@@ -66,6 +70,7 @@ impl<'a> ParsedStreamMessage<'a, ActiveModel> {
     Ok(ParsedStreamMessage {
       stream_message_model: self.stream_message_model,
       twitch_emote_data: self.twitch_emote_data,
+      message_contents: self.message_contents,
       channel: self.channel,
       model_state: PhantomData,
     })
@@ -98,10 +103,8 @@ impl ParsedStreamMessage<'_, Model> {
 
       return vec![];
     };
-    let Some(message_contents) = &stream_message.contents else {
-      return vec![];
-    };
-    let emote_usage: HashMap<i32, i32> = message_contents
+    let emote_usage: HashMap<i32, i32> = self
+      .message_contents
       .split(' ')
       .filter_map(|word| third_party_emote_list_storage.get_channel_emote(&self.channel, word))
       .fold(HashMap::new(), |mut emote_and_frequency, emote| {
@@ -135,11 +138,7 @@ impl ParsedStreamMessage<'_, Model> {
 
       return Ok(vec![]);
     };
-    let Some(message_contents) = &stream_message.contents else {
-      return Ok(vec![]);
-    };
-
-    let emote_usage_data = parse_twitch_emotes(message_contents, self.twitch_emote_data);
+    let emote_usage_data = parse_twitch_emotes(self.message_contents, self.twitch_emote_data);
     let mut parsed_emotes = vec![];
 
     for ParsedTwitchEmote {
@@ -1,12 +1,21 @@
+use crate::channel::channel_updates::watch_tracked_channels;
 use crate::channel::third_party_emote_list_storage::EmoteListStorage;
 use crate::errors::AppError;
+use crate::irc_chat::membership_parser::MembershipParser;
 use crate::irc_chat::message_parser::MessageParser;
-use app_config::{secret_string::Secret, AppConfig};
+use crate::processes::resolve_unknown_chatters::UnknownChatterQueue;
+use crate::processes::SubProcessSenders;
+use app_config::AppConfig;
 use database_connection::get_database_connection;
+use helix_client::get_helix_client;
 use irc::client::{prelude::*, ClientStream};
 use irc::proto::{CapSubCommand, Message as IrcMessage};
 use std::{sync::Arc, time::Duration};
-use tokio::{sync::mpsc, task::JoinHandle, time::timeout};
+use tokio::{
+  sync::{mpsc, watch},
+  task::JoinHandle,
+  time::timeout,
+};
 use tokio_stream::StreamExt;
 
 const MESSAGE_WAIT_TIME: Duration = Duration::new(10, 0);
@@ -25,31 +34,41 @@ pub struct TwitchIrc {
   irc_client_stream: Option<ClientStream>,
   third_party_emote_lists: Arc<EmoteListStorage>,
   message_result_processor_sender: mpsc::UnboundedSender<JoinHandle<Result<(), AppError>>>,
+  unknown_chatter_queue: UnknownChatterQueue,
+  /// The channels to track, updated when the config changes.
+  tracked_channels: watch::Receiver<Vec<String>>,
+  /// The channels last joined, formatted for IRC. Changes to the tracked channels are applied against these.
+  joined_channels: Vec<String>,
 }
 
 impl TwitchIrc {
-  pub async fn new(
-    message_result_processor_sender: mpsc::UnboundedSender<JoinHandle<Result<(), AppError>>>,
-  ) -> Result<Self, AppError> {
+  pub async fn new(sub_process_senders: SubProcessSenders) -> Result<Self, AppError> {
     tracing::info!("Initializing Twitch IRC client.");
-    let mut irc_client = Self::get_irc_client().await?;
+    let mut tracked_channels = watch_tracked_channels();
+    let channels = tracked_channels.borrow_and_update().clone();
+    let mut irc_client = Self::get_irc_client(&channels).await?;
     let irc_client_stream = irc_client.stream()?;
     let database_connection = get_database_connection().await;
-    let third_party_emote_lists =
-      EmoteListStorage::new(AppConfig::channels(), database_connection).await?;
+    let third_party_emote_lists = EmoteListStorage::new(&channels, database_connection).await?;
 
     Ok(Self {
       irc_client,
       irc_client_stream: Some(irc_client_stream),
       third_party_emote_lists: Arc::new(third_party_emote_lists),
-      message_result_processor_sender,
+      message_result_processor_sender: sub_process_senders.message_result_processor_sender,
+      unknown_chatter_queue: sub_process_senders.unknown_chatter_queue,
+      tracked_channels,
+      joined_channels: Self::format_channels(&channels),
     })
   }
 
   pub async fn reconnect(&mut self) -> Result<(), AppError> {
     tracing::warn!("Reconnecting the IRC client.");
 
-    self.irc_client = Self::get_irc_client().await?;
+    let channels = self.tracked_channels.borrow_and_update().clone();
+
+    self.irc_client = Self::get_irc_client(&channels).await?;
+    self.joined_channels = Self::format_channels(&channels);
 
     let irc_client_stream = self.irc_client.stream()?;
 
@@ -60,8 +79,39 @@ impl TwitchIrc {
     Ok(())
   }
 
-  async fn get_irc_client() -> Result<Client, AppError> {
-    let config = Self::get_config()?;
+  /// Joins and leaves channels to match the tracked channels if they were changed in the config.
+  async fn update_tracked_channels(&mut self) -> Result<(), AppError> {
+    if !self.tracked_channels.has_changed().unwrap_or(false) {
+      return Ok(());
+    }
+
+    let channels = self.tracked_channels.borrow_and_update().clone();
+    let formatted_channels = Self::format_channels(&channels);
+    let (joined_channels, parted_channels) =
+      Self::diff_channels(&self.joined_channels, &formatted_channels);
+
+    tracing::info!("The tracked channels changed to {:?}.", channels);
+
+    for channel in parted_channels {
+      self.irc_client.send_part(channel)?;
+    }
+
+    if !joined_channels.is_empty() {
+      self.irc_client.send_join(joined_channels.join(","))?;
+    }
+
+    self.joined_channels = formatted_channels;
+
+    let database_connection = get_database_connection().await;
+    let third_party_emote_lists = EmoteListStorage::new(&channels, database_connection).await?;
+
+    self.third_party_emote_lists = Arc::new(third_party_emote_lists);
+
+    Ok(())
+  }
+
+  async fn get_irc_client(channels: &[String]) -> Result<Client, AppError> {
+    let config = Self::get_config(channels).await?;
     let irc_client = Client::from_config(config).await?;
     irc_client.identify()?;
 
@@ -75,9 +125,10 @@ impl TwitchIrc {
     Ok(irc_client)
   }
 
-  fn get_config() -> Result<Config, AppError> {
-    let password = AppConfig::access_token().read_value();
-    let password = Some("oauth:".to_string() + Secret::read_secret_string(password));
+  /// Takes the access token from the Helix client, so that reconnects use the latest refreshed token.
+  async fn get_config(channels: &[String]) -> Result<Config, AppError> {
+    let access_token = get_helix_client().access_token().await?;
+    let password = Some(format!("oauth:{access_token}"));
 
     Ok(Config {
       server: Some(TWITCH_IRC_URL.to_string()),
@@ -85,15 +136,34 @@ impl TwitchIrc {
       port: Some(TWITCH_IRC_PORT),
       password,
       use_tls: Some(USE_TLS),
-      channels: Self::get_channels(),
+      channels: Self::format_channels(channels),
       ping_timeout: Some(PING_TIMEOUT),
       ping_time: Some(PING_TIME),
       ..Default::default()
     })
   }
 
-  fn get_channels() -> Vec<String> {
-    AppConfig::channels()
+  /// Returns the channels to join and the channels to leave to get from the previous channels to the new ones.
+  fn diff_channels<'a>(
+    previous_channels: &'a [String],
+    channels: &'a [String],
+  ) -> (Vec<&'a str>, Vec<&'a str>) {
+    let joined_channels = channels
+      .iter()
+      .filter(|channel| !previous_channels.contains(channel))
+      .map(String::as_str)
+      .collect();
+    let parted_channels = previous_channels
+      .iter()
+      .filter(|channel| !channels.contains(channel))
+      .map(String::as_str)
+      .collect();
+
+    (joined_channels, parted_channels)
+  }
+
+  fn format_channels(channels: &[String]) -> Vec<String> {
+    channels
       .iter()
       .map(|channel_name| {
         if !channel_name.starts_with("#") {
@@ -129,6 +199,8 @@ impl TwitchIrc {
   /// Checks for the next message from the irc client stream.
   /// If no message is received within 10 seconds the function ends without doing anything.
   pub async fn next_message(&mut self) -> Result<(), AppError> {
+    self.update_tracked_channels().await?;
+
     let future = self.get_mut_client_stream()?.next();
     let message_result = timeout(MESSAGE_WAIT_TIME, future).await;
 
@@ -150,9 +222,10 @@ impl TwitchIrc {
       return Ok(());
     };
     let third_party_emote_lists = self.third_party_emote_lists.clone();
+    let unknown_chatter_queue = self.unknown_chatter_queue.clone();
 
     let process_message_future =
-      Self::create_and_run_mesage_parser(message, third_party_emote_lists);
+      Self::create_and_run_mesage_parser(message, third_party_emote_lists, unknown_chatter_queue);
     let process_message_handle = tokio::spawn(process_message_future);
 
     if let Err(error) = self
@@ -170,9 +243,12 @@ impl TwitchIrc {
   async fn create_and_run_mesage_parser(
     message: IrcMessage,
     third_party_emote_lists: Arc<EmoteListStorage>,
+    unknown_chatter_queue: UnknownChatterQueue,
   ) -> std::result::Result<(), AppError> {
     match message.command {
-      Command::JOIN(_, _, _) | Command::PART(_, _) => return Ok(()),
+      Command::JOIN(_, _, _) | Command::PART(_, _) => {
+        return Self::run_membership_parser(message, unknown_chatter_queue).await;
+      }
       Command::Response(_, _) => return Ok(()),
       Command::Raw(command, _) if &command == "USERSTATE" => return Ok(()),
       Command::Raw(command, _) if &command == "ROOMSTATE" => return Ok(()),
@@ -202,13 +278,65 @@ impl TwitchIrc {
 
     result
   }
+
+  /// Changes for chatters that aren't in the database yet are sent to be looked up with other unknown chatters, as are
+  /// any changes received while a chatter still has changes waiting to be looked up.
+  async fn run_membership_parser(
+    message: IrcMessage,
+    unknown_chatter_queue: UnknownChatterQueue,
+  ) -> std::result::Result<(), AppError> {
+    let Some(membership_parser) = MembershipParser::new(&message)? else {
+      return Ok(());
+    };
+
+    // The client's own JOIN/PART isn't a chatter being present.
+    if membership_parser.user_login() == AppConfig::twitch_nickname().to_lowercase() {
+      return Ok(());
+    }
+
+    let Some(membership_parser) = unknown_chatter_queue.queue_behind_pending(membership_parser)?
+    else {
+      return Ok(());
+    };
+    let database_connection = get_database_connection().await;
+    let result = membership_parser.parse(database_connection).await;
+
+    match result {
+      Ok(Some(membership_parser)) => unknown_chatter_queue.queue(membership_parser),
+      Ok(None) => Ok(()),
+      Err(error) if error.is_unique_constraint_violation() => Ok(()),
+      Err(error) => {
+        tracing::error!(
+          "Failed to process a membership change. Dumping contents to log.\n{:?}",
+          message
+        );
+
+        Err(error)
+      }
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  // use super::*;
+  use super::*;
   // use irc::proto::message::Tag as IrcTag;
 
+  #[test]
+  fn channel_changes_are_diffed_against_the_joined_channels() {
+    let joined_channels = TwitchIrc::format_channels(&["fallenshadow".into(), "#moose".into()]);
+    let channels = TwitchIrc::format_channels(&["moose".into(), "shadowchama".into()]);
+
+    assert_eq!(
+      TwitchIrc::diff_channels(&joined_channels, &channels),
+      (vec!["#shadowchama"], vec!["#fallenshadow"])
+    );
+    assert_eq!(
+      TwitchIrc::diff_channels(&channels, &channels),
+      (vec![], vec![])
+    );
+  }
+
   /// Used to manually test raw IRC messages from Twitch to
   /// check if the parser is working as intended.
   #[tokio::test]
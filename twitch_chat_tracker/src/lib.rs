@@ -2,6 +2,7 @@
 
 pub mod channel;
 pub mod errors;
+pub mod importers;
 pub mod irc_chat;
 pub mod logging;
 pub mod processes;
@@ -9,6 +9,7 @@ mod manual_migrations;
 #[tokio::main]
 async fn main() {
   twitch_chat_tracker::logging::setup_logging_config().unwrap();
+  AppConfig::watch_for_changes();
 
   if AppConfig::channels().is_empty() {
     println!("No channels to track.");
@@ -18,8 +19,13 @@ async fn main() {
 
   tracing::info!("Tracking channels {:?}", AppConfig::channels());
 
-  let message_result_processor_sender =
-    twitch_chat_tracker::processes::create_sub_processes().await;
+  if let Err(error) = helix_client::start_token_lifecycle(&AppConfig::channels()).await {
+    tracing::error!("Failed to validate the Twitch access token. Reason: {error}");
 
-  twitch_chat_tracker::processes::run_main_process(message_result_processor_sender).await;
+    std::process::exit(1);
+  }
+
+  let sub_process_senders = twitch_chat_tracker::processes::create_sub_processes().await;
+
+  twitch_chat_tracker::processes::run_main_process(sub_process_senders).await;
 }
@@ -3,4 +3,3 @@ pub mod convert_message_third_party_emotes_into_emote_tables;
 pub mod fix_donations;
 pub mod other;
 pub mod parse_stream_names;
-pub mod store_chatterino_messages;
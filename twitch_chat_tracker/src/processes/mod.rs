@@ -1,10 +1,14 @@
 pub mod app_animation;
 pub mod main_process;
 pub mod message_results;
+pub mod resolve_unknown_chatters;
+pub mod sample_stream_viewers;
 pub mod sub_process_creation;
 pub mod update_channel_live_status;
 
 pub use main_process::run_main_process;
 pub use message_results::process_irc_message_results;
-pub use sub_process_creation::create_sub_processes;
+pub use resolve_unknown_chatters::resolve_unknown_chatters;
+pub use sample_stream_viewers::sample_stream_viewer_counts;
+pub use sub_process_creation::{create_sub_processes, SubProcessSenders};
 pub use update_channel_live_status::update_channel_live_statuses;
@@ -0,0 +1,236 @@
+use crate::errors::AppError;
+use crate::irc_chat::membership_parser::MembershipParser;
+use database_connection::get_database_connection;
+use entities::twitch_user;
+use entity_extensions::prelude::*;
+use sea_orm::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How long membership changes for unknown chatters are collected before they're looked up together.
+const RESOLVE_INTERVAL: Duration = Duration::new(30, 0);
+
+/// Queues the membership changes of chatters missing from the database until they've been looked up.
+///
+/// Once a chatter has a change queued, every change after it goes through the queue too until it's caught up, so
+/// their changes are recorded in the order they were received. Otherwise a PART could be recorded before the queued
+/// JOIN it ends, leaving that JOIN's interval open.
+#[derive(Debug, Clone)]
+pub struct UnknownChatterQueue {
+  sender: mpsc::UnboundedSender<MembershipParser>,
+  /// How many changes are waiting in the queue for each chatter.
+  queued_changes: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl UnknownChatterQueue {
+  pub fn new() -> (Self, mpsc::UnboundedReceiver<MembershipParser>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let unknown_chatter_queue = Self {
+      sender,
+      queued_changes: Arc::default(),
+    };
+
+    (unknown_chatter_queue, receiver)
+  }
+
+  pub fn queue(&self, membership_parser: MembershipParser) -> Result<(), AppError> {
+    let mut queued_changes = self.queued_changes.lock().unwrap();
+    let user_login = membership_parser.user_login().to_string();
+
+    if let Err(error) = self.sender.send(membership_parser) {
+      return Err(AppError::MpscConnectionClosed {
+        error: error.to_string(),
+      });
+    }
+
+    *queued_changes.entry(user_login).or_default() += 1;
+
+    Ok(())
+  }
+
+  /// Queues the change if its chatter already has changes waiting in the queue.
+  ///
+  /// The change is handed back if it can be recorded right away.
+  pub fn queue_behind_pending(
+    &self,
+    membership_parser: MembershipParser,
+  ) -> Result<Option<MembershipParser>, AppError> {
+    let has_queued_changes = self
+      .queued_changes
+      .lock()
+      .unwrap()
+      .contains_key(membership_parser.user_login());
+
+    if !has_queued_changes {
+      return Ok(Some(membership_parser));
+    }
+
+    self.queue(membership_parser).map(|_| None)
+  }
+
+  /// Marks one of the chatter's queued changes as handled.
+  fn finish(&self, user_login: &str) {
+    let mut queued_changes = self.queued_changes.lock().unwrap();
+
+    if let Some(change_count) = queued_changes.get_mut(user_login) {
+      *change_count -= 1;
+
+      if *change_count == 0 {
+        queued_changes.remove(user_login);
+      }
+    }
+  }
+}
+
+/// Collects the membership changes of chatters missing from the database, looking them up on Helix in batches.
+///
+/// Lurkers are often seen for the first time through a JOIN, so looking each one up as it arrives would send a Helix
+/// request per chatter whenever a busy channel is joined.
+pub async fn resolve_unknown_chatters(
+  unknown_chatter_queue: UnknownChatterQueue,
+  mut unknown_chatter_receiver: mpsc::UnboundedReceiver<MembershipParser>,
+) -> ! {
+  tracing::info!("Starting unknown chatter resolution process.");
+  let database_connection = get_database_connection().await;
+  let mut resolve_interval = tokio::time::interval(RESOLVE_INTERVAL);
+
+  loop {
+    resolve_interval.tick().await;
+
+    let mut pending_changes = vec![];
+
+    while let Ok(membership_parser) = unknown_chatter_receiver.try_recv() {
+      pending_changes.push(membership_parser);
+    }
+
+    if pending_changes.is_empty() {
+      continue;
+    }
+
+    let pending_logins: Vec<String> = pending_changes
+      .iter()
+      .map(|membership_parser| membership_parser.user_login().to_string())
+      .collect();
+
+    if let Err(error) = resolve_pending_changes(pending_changes, database_connection).await {
+      tracing::error!(
+        "Failed to look up the users of membership changes. Reason: {}",
+        error
+      );
+    }
+
+    for user_login in pending_logins {
+      unknown_chatter_queue.finish(&user_login);
+    }
+  }
+}
+
+/// Adds the users of the membership changes to the database, then records the changes in the order they were received.
+async fn resolve_pending_changes(
+  pending_changes: Vec<MembershipParser>,
+  database_connection: &DatabaseConnection,
+) -> Result<(), AppError> {
+  let user_logins: HashSet<&str> = pending_changes
+    .iter()
+    .map(|membership_parser| membership_parser.user_login())
+    .collect();
+  let user_logins: Vec<&str> = user_logins.into_iter().collect();
+
+  tracing::debug!(
+    "Looking up {} unknown chatters from membership changes.",
+    user_logins.len()
+  );
+
+  twitch_user::Model::get_or_set_by_names(&user_logins, database_connection).await?;
+
+  for membership_parser in pending_changes {
+    match membership_parser.parse(database_connection).await {
+      Ok(Some(membership_parser)) => tracing::debug!(
+        "Helix has no user `{}`. Dropping their membership change.",
+        membership_parser.user_login()
+      ),
+      Ok(None) => (),
+      Err(error) if error.is_unique_constraint_violation() => (),
+      Err(error) => tracing::error!("Failed to process a membership change. Reason: {}", error),
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::irc_chat::membership_parser::MembershipChange;
+  use irc::proto::{Command, Message as IrcMessage, Prefix};
+
+  fn membership_change(user_login: &str, command: Command) -> MembershipParser {
+    let message = IrcMessage {
+      tags: None,
+      prefix: Some(Prefix::Nickname(
+        user_login.into(),
+        user_login.into(),
+        format!("{user_login}.tmi.twitch.tv"),
+      )),
+      command,
+    };
+
+    MembershipParser::new(&message).unwrap().unwrap()
+  }
+
+  #[test]
+  fn changes_follow_a_chatters_queued_changes_until_they_are_handled() {
+    let (unknown_chatter_queue, mut unknown_chatter_receiver) = UnknownChatterQueue::new();
+
+    unknown_chatter_queue
+      .queue(membership_change(
+        "linkthedot",
+        Command::JOIN("#fallenshadow".into(), None, None),
+      ))
+      .unwrap();
+
+    let part = membership_change("linkthedot", Command::PART("#fallenshadow".into(), None));
+    let other_part = membership_change("fallenshadow", Command::PART("#fallenshadow".into(), None));
+
+    assert!(unknown_chatter_queue
+      .queue_behind_pending(part)
+      .unwrap()
+      .is_none());
+    assert!(unknown_chatter_queue
+      .queue_behind_pending(other_part)
+      .unwrap()
+      .is_some());
+
+    let queued_changes: Vec<MembershipChange> =
+      std::iter::from_fn(|| unknown_chatter_receiver.try_recv().ok())
+        .map(|membership_parser| membership_parser.change())
+        .collect();
+
+    assert_eq!(
+      queued_changes,
+      vec![MembershipChange::Join, MembershipChange::Part]
+    );
+
+    unknown_chatter_queue.finish("linkthedot");
+
+    let join = membership_change(
+      "linkthedot",
+      Command::JOIN("#fallenshadow".into(), None, None),
+    );
+    let join = unknown_chatter_queue.queue_behind_pending(join).unwrap();
+
+    assert!(join.is_none(), "The PART is still queued.");
+
+    unknown_chatter_queue.finish("linkthedot");
+    unknown_chatter_queue.finish("linkthedot");
+
+    let part = membership_change("linkthedot", Command::PART("#fallenshadow".into(), None));
+
+    assert!(unknown_chatter_queue
+      .queue_behind_pending(part)
+      .unwrap()
+      .is_some());
+  }
+}
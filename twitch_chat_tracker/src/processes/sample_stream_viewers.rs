@@ -0,0 +1,204 @@
+use crate::channel::{channel_updates::watch_tracked_channels, tracked_channels::TrackedChannels};
+use crate::errors::AppError;
+use chrono::Utc;
+use database_connection::get_database_connection;
+use entities::{stream, stream_viewer_sample, twitch_user};
+use entity_extensions::stream::StreamExtensions;
+use sea_orm::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const VIEWER_SAMPLE_INTERVAL: Duration = Duration::new(300, 0);
+
+/// Periodically records the viewer count of every tracked channel that's currently live.
+pub async fn sample_stream_viewer_counts(mut tracked_channels: TrackedChannels) -> ! {
+  tracing::info!("Starting stream viewer count sampling process.");
+  let database_connection = get_database_connection().await;
+  let mut sample_interval = tokio::time::interval(VIEWER_SAMPLE_INTERVAL);
+  let mut channel_updates = watch_tracked_channels();
+  channel_updates.mark_unchanged();
+
+  loop {
+    sample_interval.tick().await;
+
+    if channel_updates.has_changed().unwrap_or(false) {
+      channel_updates.mark_unchanged();
+
+      match TrackedChannels::new().await {
+        Ok(updated_channels) => tracked_channels = updated_channels,
+        Err(error) => tracing::error!(
+          "Failed to get the updated tracked channels for viewer sampling. Reason: {}",
+          error
+        ),
+      }
+    }
+
+    if let Err(error) =
+      sample_viewer_counts(tracked_channels.all_channels(), database_connection).await
+    {
+      tracing::error!("Failed to sample stream viewer counts. Reason: {}", error);
+    }
+  }
+}
+
+/// Records the viewer count of each channel's live stream.
+async fn sample_viewer_counts(
+  channels: Vec<&twitch_user::Model>,
+  database_connection: &DatabaseConnection,
+) -> Result<(), AppError> {
+  let channel_ids: Vec<i32> = channels.iter().map(|channel| channel.id).collect();
+  let live_streams = stream::Entity::find()
+    .filter(stream::Column::TwitchUserId.is_in(channel_ids))
+    .filter(stream::Column::EndTimestamp.is_null())
+    .all(database_connection)
+    .await?;
+
+  if live_streams.is_empty() {
+    tracing::debug!("No live streams to sample viewer counts for.");
+
+    return Ok(());
+  }
+
+  let live_channels: Vec<&twitch_user::Model> = channels
+    .into_iter()
+    .filter(|channel| {
+      live_streams
+        .iter()
+        .any(|stream| stream.twitch_user_id == channel.id)
+    })
+    .collect();
+  let viewer_counts: HashMap<u64, i32> = stream::Model::get_live_viewer_counts(live_channels)
+    .await?
+    .into_values()
+    .filter_map(|(stream_twitch_id, viewer_count)| {
+      let Ok(stream_twitch_id) = stream_twitch_id.parse::<u64>() else {
+        tracing::error!("Failed to parse a stream ID. Value: {:?}", stream_twitch_id);
+
+        return None;
+      };
+
+      Some((stream_twitch_id, viewer_count))
+    })
+    .collect();
+  let timestamp = Utc::now();
+
+  let viewer_samples: Vec<stream_viewer_sample::ActiveModel> = live_streams
+    .iter()
+    .filter_map(|stream| {
+      let viewer_count = viewer_counts.get(&stream.twitch_stream_id)?;
+
+      Some(stream_viewer_sample::ActiveModel {
+        stream_id: Set(stream.id),
+        timestamp: Set(timestamp),
+        viewer_count: Set(*viewer_count),
+        ..Default::default()
+      })
+    })
+    .collect();
+
+  if viewer_samples.is_empty() {
+    return Ok(());
+  }
+
+  tracing::info!("Recording {} viewer count samples.", viewer_samples.len());
+
+  stream_viewer_sample::Entity::insert_many(viewer_samples)
+    .exec(database_connection)
+    .await?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use axum::routing::get;
+  use axum::Router;
+  use helix_client::mock::use_mock_helix;
+  use serde_json::json;
+
+  /// Serves `streams` with `fallenshadow` live and every other channel offline.
+  fn mock_helix() {
+    use_mock_helix(|| {
+      Router::new().route(
+        "/streams",
+        get(|| async {
+          axum::Json(json!({
+            "data": [{
+              "id": "40952121085",
+              "user_login": "fallenshadow",
+              "type": "live",
+              "started_at": "2025-05-08T00:02:29Z",
+              "viewer_count": 1234,
+            }]
+          }))
+        }),
+      )
+    });
+  }
+
+  #[tokio::test]
+  async fn viewer_counts_are_recorded_for_streams_helix_reports_as_live() {
+    mock_helix();
+    let channels = [channel(1, "fallenshadow"), channel(2, "shadowchama")];
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results([vec![stream(1, 1, 40952121085), stream(2, 2, 40952121086)]])
+      .append_exec_results([MockExecResult {
+        last_insert_id: 1,
+        rows_affected: 1,
+      }])
+      .into_connection();
+
+    sample_viewer_counts(channels.iter().collect(), &mock_database)
+      .await
+      .unwrap();
+
+    let statements: Vec<String> = mock_database
+      .into_transaction_log()
+      .iter()
+      .flat_map(|transaction| transaction.statements())
+      .map(|statement| statement.to_string())
+      .collect();
+
+    assert_eq!(statements.len(), 2);
+    assert!(statements[1].starts_with("INSERT INTO `stream_viewer_sample`"));
+    assert!(statements[1].contains("1234"));
+    assert!(!statements[1].contains("), ("));
+  }
+
+  #[tokio::test]
+  async fn nothing_is_sampled_without_live_streams() {
+    let channels = [channel(1, "fallenshadow")];
+    let mock_database = MockDatabase::new(DatabaseBackend::MySql)
+      .append_query_results::<stream::Model, _, _>([vec![]])
+      .into_connection();
+
+    sample_viewer_counts(channels.iter().collect(), &mock_database)
+      .await
+      .unwrap();
+
+    assert_eq!(mock_database.into_transaction_log().len(), 1);
+  }
+
+  fn channel(id: i32, login_name: &str) -> twitch_user::Model {
+    twitch_user::Model {
+      id,
+      twitch_id: id,
+      login_name: login_name.into(),
+      display_name: login_name.into(),
+      last_verified_at: None,
+    }
+  }
+
+  fn stream(id: i32, twitch_user_id: i32, twitch_stream_id: u64) -> stream::Model {
+    stream::Model {
+      id,
+      twitch_stream_id,
+      start_timestamp: Some(Utc::now()),
+      end_timestamp: None,
+      twitch_user_id,
+      twitch_vod_id: None,
+      title: None,
+    }
+  }
+}
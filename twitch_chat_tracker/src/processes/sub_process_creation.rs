@@ -1,22 +1,55 @@
 use crate::channel::tracked_channels::TrackedChannels;
 use crate::errors::AppError;
+use crate::processes::resolve_unknown_chatters::UnknownChatterQueue;
 use crate::processes::{
-  app_animation::run_animation, process_irc_message_results, update_channel_live_statuses,
+  app_animation::run_animation, process_irc_message_results, resolve_unknown_chatters,
+  sample_stream_viewer_counts, update_channel_live_statuses,
 };
+use database_connection::get_database_connection;
+use entity_extensions::chatter_presence::close_open_presences;
 use tokio::{sync::mpsc, task::JoinHandle};
 
+/// The senders the main process uses to hand work off to the sub processes.
+pub struct SubProcessSenders {
+  pub message_result_processor_sender: mpsc::UnboundedSender<JoinHandle<Result<(), AppError>>>,
+  pub unknown_chatter_queue: UnknownChatterQueue,
+}
+
 /// Creates the necessary sub processes for running the app.
-/// These include the running animation, channel updator, and message parsing result manager.
+/// These include the running animation, channel updator, viewer count sampler, unknown chatter resolver, and message
+/// parsing result manager.
+///
+/// Presence intervals left open by the previous run are closed first, at the last activity recorded for their stream
+/// or channel, as it's unknown when those chatters left.
 ///
-/// Returns the sender to the message parsing result manager.
-pub async fn create_sub_processes() -> mpsc::UnboundedSender<JoinHandle<Result<(), AppError>>> {
+/// Returns the sender to the message parsing result manager and the unknown chatter resolver's queue.
+pub async fn create_sub_processes() -> SubProcessSenders {
   tracing::info!("Creating sub processes.");
+  let database_connection = get_database_connection().await;
+
+  match close_open_presences(database_connection).await {
+    Ok(closed_presences) => tracing::info!(
+      "Closed {} presence intervals left open by the last run.",
+      closed_presences
+    ),
+    Err(error) => tracing::error!("Failed to close open presence intervals. Reason: {}", error),
+  }
+
   let connected_channels = TrackedChannels::new().await.unwrap();
   let (irc_message_processing_sender, irc_message_processing_receiver) = mpsc::unbounded_channel();
+  let (unknown_chatter_queue, unknown_chatter_receiver) = UnknownChatterQueue::new();
 
   tokio::spawn(run_animation());
+  tokio::spawn(sample_stream_viewer_counts(connected_channels.clone()));
   tokio::spawn(update_channel_live_statuses(connected_channels));
   tokio::spawn(process_irc_message_results(irc_message_processing_receiver));
+  tokio::spawn(resolve_unknown_chatters(
+    unknown_chatter_queue.clone(),
+    unknown_chatter_receiver,
+  ));
 
-  irc_message_processing_sender
+  SubProcessSenders {
+    message_result_processor_sender: irc_message_processing_sender,
+    unknown_chatter_queue,
+  }
 }
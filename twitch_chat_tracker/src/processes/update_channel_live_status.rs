@@ -1,4 +1,4 @@
-use crate::channel::tracked_channels::TrackedChannels;
+use crate::channel::{channel_updates::watch_tracked_channels, tracked_channels::TrackedChannels};
 use crate::{errors::AppError, websocket_connection::config::TwitchWebsocketConfig};
 use database_connection::get_database_connection;
 use entities::stream;
@@ -11,6 +11,8 @@ const TIMEOUT_COUNT_UNTIL_RESET: usize = 5;
 pub async fn update_channel_live_statuses(tracked_channels: TrackedChannels) -> ! {
   tracing::info!("Starting channel status update process.");
   let database_connection = get_database_connection().await;
+  let mut channel_updates = watch_tracked_channels();
+  channel_updates.mark_unchanged();
 
   tracing::info!("Checking for active livestreams.");
   if let Err(error) = update_active_streams(&tracked_channels, database_connection).await {
@@ -29,6 +31,12 @@ pub async fn update_channel_live_statuses(tracked_channels: TrackedChannels) ->
   tracing::info!("Running channel status update process.");
 
   loop {
+    if channel_updates.has_changed().unwrap_or(false) {
+      channel_updates.mark_unchanged();
+
+      update_tracked_channels(&mut websocket_config, database_connection).await;
+    }
+
     match websocket_config.check_for_stream_message().await {
       Err(AppError::WebsocketTimeout) => {
         tracing::error!("{}", AppError::WebsocketTimeout);
@@ -65,6 +73,37 @@ pub async fn update_channel_live_statuses(tracked_channels: TrackedChannels) ->
   }
 }
 
+/// Subscribes to the channels in the reloaded config, checking any new ones for active livestreams.
+async fn update_tracked_channels(
+  websocket_config: &mut TwitchWebsocketConfig,
+  database_connection: &DatabaseConnection,
+) {
+  let tracked_channels = match TrackedChannels::new().await {
+    Ok(tracked_channels) => tracked_channels,
+    Err(error) => {
+      tracing::error!(
+        "Failed to get the updated tracked channels. Keeping the current ones. Reason: {}",
+        error
+      );
+
+      return;
+    }
+  };
+
+  tracing::info!("The tracked channels changed. Updating the websocket subscriptions.");
+
+  if let Err(error) = update_active_streams(&tracked_channels, database_connection).await {
+    tracing::error!(
+      "Failed to update active livestreams from the tracked channels list. Reason: {}",
+      error
+    );
+  }
+
+  websocket_config.set_tracked_channels(tracked_channels);
+
+  restart_connection(websocket_config, database_connection).await;
+}
+
 /// Attempts to restart the websocket connection.
 ///
 /// Exits the program with an error if the connection could not be re-established.
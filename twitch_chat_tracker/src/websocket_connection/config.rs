@@ -5,12 +5,13 @@ use crate::{
     message_parser::WebsocketMessageParser, subscriptions::EventSubscription,
   },
 };
-use app_config::{secret_string::Secret, AppConfig};
+use app_config::AppConfig;
 use database_connection::get_database_connection;
 use entities::twitch_user;
 use entity_extensions::prelude::TwitchUserExtensions;
 use futures_util::StreamExt;
-use reqwest::{RequestBuilder, Response};
+use helix_client::get_helix_client;
+use reqwest::Response;
 use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::time::{Duration, Instant};
@@ -19,20 +20,16 @@ use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use url::Url;
 
 const WEBSOCKET_URL: &str = "wss://eventsub.wss.twitch.tv/ws";
-const TWITCH_API_URL: &str = "https://api.twitch.tv";
 
 /// How long to wait between sending subscription events;
 const SUBSCRIPTION_WAIT_TIME: Duration = Duration::new(0, 250000000);
-const SUBSCRIPTION_PATH: &str = "helix/eventsub/subscriptions";
-// - Use the below constants for the Twitch CLI connection. -
+/// Relative to the Helix API URL.
+const SUBSCRIPTION_PATH: &str = "eventsub/subscriptions";
+// - Use the below constant for the Twitch CLI connection, and point the Helix client at `http://127.0.0.1:8080/`. -
 // const WEBSOCKET_URL: &str = "ws://127.0.0.1:8080/ws";
-// const TWITCH_API_URL: &str = "http://127.0.0.1:8080/";
-// const SUBSCRIPTION_PATH: &str = "eventsub/subscriptions";
 
 /// The amount of messages to go through at startup to retrieve the session ID.
 const GET_SESSION_ID_RETRY_ATTEMPTS: i32 = 5;
-/// The amount of times to attmempt to subscribe to an event for a given channel.
-pub const EVENT_SUBSCRIBE_RETRY_ATTEMPTS: i32 = 3;
 /// As per the [documentation](https://dev.twitch.tv/docs/eventsub/handling-websocket-events/#subscription-limits)
 const WEBSOCKET_SUBSCRIPTION_LIMIT: usize = 300;
 
@@ -43,7 +40,6 @@ const SUBSCRIPTIONS: &[EventSubscription] = &[
   // https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#streamoffline
   EventSubscription::new(None, "stream.offline", 1),
 ];
-const SUBSCRIPTION_FAIL_RETRY_BASE_DURATION: Duration = Duration::new(30, 0);
 
 /// In seconds.
 ///
@@ -110,25 +106,6 @@ impl TwitchWebsocketConfig {
     running_user: &twitch_user::Model,
     session_id: &str,
   ) -> Result<bool, AppError> {
-    let mut url = Url::parse(TWITCH_API_URL)?;
-    url.set_path(SUBSCRIPTION_PATH);
-
-    let reqwest_client = reqwest::Client::new();
-    let request = reqwest_client
-      .post(url)
-      .header(
-        "Authorization",
-        format!(
-          "Bearer {}",
-          Secret::read_secret_string(AppConfig::access_token().read_value())
-        ),
-      )
-      .header(
-        "Client-Id",
-        Secret::read_secret_string(AppConfig::client_id().read_value()),
-      )
-      .header("Content-Type", "application/json");
-
     let subscription_bodies = EventSubscription::create_subscription_bodies_from_list(
       SUBSCRIPTIONS,
       tracked_channels,
@@ -143,10 +120,9 @@ impl TwitchWebsocketConfig {
     let mut subscription_failed = false;
 
     for subscription in subscription_bodies {
-      let request = request.try_clone().unwrap();
       let subscription_type = &subscription["type"].clone();
 
-      let result = Self::send_subscription(subscription, request).await;
+      let result = Self::send_subscription(subscription).await;
 
       match result {
         Ok(_response) => {}
@@ -168,44 +144,22 @@ impl TwitchWebsocketConfig {
     Ok(subscription_failed)
   }
 
-  pub async fn send_subscription(
-    value: Value,
-    request_builder: RequestBuilder,
-  ) -> Result<Response, AppError> {
-    let mut attempts = EVENT_SUBSCRIBE_RETRY_ATTEMPTS;
-    let request = request_builder.json(&value);
-
-    while let Ok(response) = request.try_clone().unwrap().send().await {
-      if attempts <= 0 {
-        return Err(AppError::FailedToGetEventSubSubscription {
-          subscription_value: value,
-          response: Some(response.text().await?),
-        });
-      }
-
-      if !response.status().is_success() {
-        tracing::error!(
-          "Failed to subscribe to an EventSub event. {attempts} remain. Response: {:?}",
-          response
-        );
-
-        let sleep_multiplier = ((EVENT_SUBSCRIBE_RETRY_ATTEMPTS - attempts) + 1).max(0) as u32;
-        let sleep_duration = SUBSCRIPTION_FAIL_RETRY_BASE_DURATION * sleep_multiplier;
-
-        tokio::time::sleep(sleep_duration).await;
-
-        attempts -= 1;
-
-        continue;
-      } else {
-        return Ok(response);
-      }
+  /// Sends the subscription to Helix. Server errors and rate limited requests are retried by the Helix client.
+  pub async fn send_subscription(value: Value) -> Result<Response, AppError> {
+    let response = get_helix_client()
+      .post(SUBSCRIPTION_PATH)
+      .json(value.clone())
+      .send()
+      .await?;
+
+    if !response.status().is_success() {
+      return Err(AppError::FailedToGetEventSubSubscription {
+        subscription_value: value,
+        response: Some(response.text().await?),
+      });
     }
 
-    Err(AppError::FailedToGetEventSubSubscription {
-      subscription_value: value,
-      response: None,
-    })
+    Ok(response)
   }
 
   /// Extracts the session ID when connecting to Twitch's websocket servers.
@@ -314,6 +268,11 @@ impl TwitchWebsocketConfig {
     Ok(true)
   }
 
+  /// Replaces the channels subscribed to. Takes effect on the next [`restart`](Self::restart).
+  pub fn set_tracked_channels(&mut self, tracked_channels: TrackedChannels) {
+    self.tracked_channels = tracked_channels;
+  }
+
   pub async fn restart(
     &mut self,
     database_connection: &DatabaseConnection,
@@ -4,6 +4,7 @@ use crate::websocket_connection::twitch_objects::stream_status::{
   StreamUpdateEventType, TwitchStreamUpdateMessage,
 };
 use entities::*;
+use entity_extensions::chatter_presence::carry_over_to_stream;
 use entity_extensions::prelude::*;
 use sea_orm::*;
 
@@ -29,10 +30,13 @@ impl WebsocketMessageParser {
 
     match stream_update_message.get_subscription_event_type() {
       StreamUpdateEventType::Online => {
-        let _stream_model = Self::stream_update_online(stream_update_message, database_connection)
+        let stream_model = Self::stream_update_online(stream_update_message, database_connection)
           .await?
           .insert(database_connection)
           .await?;
+
+        // Chatters who joined before the stream started are present for it too.
+        carry_over_to_stream(&stream_model, database_connection).await?;
       }
 
       StreamUpdateEventType::Offline => {
@@ -124,12 +128,14 @@ mod tests {
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
         vec![twitch_user::Model {
           id: 1,
           twitch_id: 578762718,
           login_name: "fallenshadow".into(),
           display_name: "fallenshadow".into(),
+          last_verified_at: None,
         }],
       ])
       .append_query_results([vec![stream::Model {